@@ -0,0 +1,109 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+};
+
+use wordplay_core::dictionary::Dictionary;
+
+use crate::command::{parse_line, Command};
+use crate::config::Config;
+
+/// Runs each line of `input` as a search, as if typed at the prompt, and
+/// writes every result rather than the configured limit. Results go to `out`
+/// under a header per query, or to a numbered file per query in `out_dir`.
+/// Blank lines and lines starting with `#` are skipped; lines that are not
+/// searches are reported and skipped.
+pub fn run_batch<R: BufRead, W: Write>(
+    dict: &Dictionary,
+    config: &Config,
+    input: R,
+    out_dir: Option<&Path>,
+    mut out: W,
+) -> io::Result<()> {
+    if let Some(dir) = out_dir {
+        fs::create_dir_all(dir)?;
+    }
+    let mut queries = 0;
+    for (line_number, line) in input.lines().enumerate() {
+        let line = line?;
+        let query = line.trim();
+        if query.is_empty() || query.starts_with('#') {
+            continue;
+        }
+        let command = match parse_line(&config.expand_alias(query)) {
+            Ok(command) if is_search(&command) => command,
+            Ok(_) => {
+                writeln!(out, "line {}: not a search: {}", line_number + 1, query)?;
+                continue;
+            }
+            Err(err) => {
+                writeln!(out, "line {}: {}", line_number + 1, err)?;
+                continue;
+            }
+        };
+
+        queries += 1;
+        let results = crate::results(dict, &command, usize::MAX, None);
+        match out_dir {
+            Some(dir) => {
+                let path = dir.join(format!("{:03}.txt", queries));
+                let mut file = BufWriter::new(File::create(&path)?);
+                for x in &results {
+                    writeln!(file, "{}", x.original)?;
+                }
+                file.flush()?;
+                writeln!(
+                    out,
+                    "{}: {} results to {}",
+                    query,
+                    results.len(),
+                    path.display()
+                )?;
+            }
+            None => {
+                writeln!(out, "# {} ({} results)", query, results.len())?;
+                for x in &results {
+                    writeln!(out, "{}", x.original)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_search(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Find { .. }
+            | Command::Anagram(_)
+            | Command::Subanagram(_)
+            | Command::Superanagram(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_each_query() {
+        let dict = Dictionary::from_iter(vec!["cat", "cot", "act", "dog"]);
+        let input = "f p c?t\n\n# slot 2\na tac\nhelp\nzz\n";
+        let mut out = Vec::new();
+
+        run_batch(&dict, &Default::default(), input.as_bytes(), None, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "# f p c?t (2 results)\n\
+             cat\n\
+             cot\n\
+             # a tac (2 results)\n\
+             act\n\
+             cat\n\
+             line 5: not a search: help\n\
+             line 6: unknown command 'zz' (did you mean 'f'?)\n"
+        );
+    }
+}
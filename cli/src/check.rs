@@ -0,0 +1,90 @@
+use std::io::{self, BufRead, Write};
+
+use wordplay_core::{dictionary::Dictionary, normalized_word::NormalizedWord};
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict<'a> {
+    Word,
+    Anagram(Vec<&'a str>),
+    Neither,
+}
+
+pub fn check<'a>(dict: &'a Dictionary, candidate: &str) -> Verdict<'a> {
+    if dict
+        .find(&NormalizedWord::from_str_safe(candidate))
+        .is_some()
+    {
+        return Verdict::Word;
+    }
+    let anagrams: Vec<&str> = dict
+        .anagrams(candidate)
+        .filter(|x| !dict.is_blocked(&x.normalized))
+        .map(|x| &x.original[..])
+        .collect();
+    if anagrams.is_empty() {
+        Verdict::Neither
+    } else {
+        Verdict::Anagram(anagrams)
+    }
+}
+
+/// Checks each non-blank line of `input`, writing one verdict per line
+/// followed by a summary.
+pub fn check_lines<R: BufRead, W: Write>(
+    dict: &Dictionary,
+    input: R,
+    mut out: W,
+) -> io::Result<()> {
+    let (mut words, mut anagrams, mut neither) = (0, 0, 0);
+    for line in input.lines() {
+        let line = line?;
+        let candidate = line.trim();
+        if candidate.is_empty() {
+            continue;
+        }
+        match check(dict, candidate) {
+            Verdict::Word => {
+                words += 1;
+                writeln!(out, "{}: word", candidate)?;
+            }
+            Verdict::Anagram(of) => {
+                anagrams += 1;
+                writeln!(out, "{}: anagram of {}", candidate, of.join(", "))?;
+            }
+            Verdict::Neither => {
+                neither += 1;
+                writeln!(out, "{}: neither", candidate)?;
+            }
+        }
+    }
+    writeln!(
+        out,
+        "{} words, {} anagrams, {} neither",
+        words, anagrams, neither
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_words_and_anagrams() {
+        let mut dict = Dictionary::from_iter(vec!["listen", "silent", "tinsel", "inlets", "cat"]);
+        dict.block("inlets");
+        let input = "Listen\nen list\n\n  tac \ndog\nlis\n";
+        let mut out = Vec::new();
+
+        check_lines(&dict, input.as_bytes(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Listen: word\n\
+             en list: anagram of listen, silent, tinsel\n\
+             tac: anagram of cat\n\
+             dog: neither\n\
+             lis: neither\n\
+             1 words, 2 anagrams, 2 neither\n"
+        );
+    }
+}
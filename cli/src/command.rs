@@ -0,0 +1,1081 @@
+use std::fmt;
+
+use wordplay_core::{
+    anagram_number::AnagramNumber,
+    char_set::CharSet,
+    dictionary::{Sort, SortAspect, SortDirection, WordPredicate},
+    export::ExportFormat,
+    normalized_word::NormalizedWord,
+    trie::{PatternError, TriePrefix},
+};
+
+pub struct Keyword {
+    pub name: &'static str,
+    pub arg: &'static str,
+    pub description: &'static str,
+}
+
+pub const COMMANDS: [Keyword; 15] = [
+    Keyword {
+        name: "f",
+        arg: "<clause>, <clause>, ...",
+        description: "find words matching every clause",
+    },
+    Keyword {
+        name: "a",
+        arg: "<word>",
+        description: "list exact anagrams of <word>",
+    },
+    Keyword {
+        name: "a-",
+        arg: "<letters>",
+        description: "list words formable from <letters>, longest first",
+    },
+    Keyword {
+        name: "a+",
+        arg: "<letters>",
+        description: "list words containing all of <letters>, shortest first",
+    },
+    Keyword {
+        name: "ap",
+        arg: "<letters> +<word> -<word>",
+        description: "list phrases using all of <letters>, with each +word and no -word",
+    },
+    Keyword {
+        name: "all",
+        arg: "",
+        description: "print every result of the last search",
+    },
+    Keyword {
+        name: "info",
+        arg: "<n>",
+        description: "show details of result <n> of the last search",
+    },
+    Keyword {
+        name: "save",
+        arg: "<file>",
+        description: "write every result of the last search to <file>",
+    },
+    Keyword {
+        name: "export",
+        arg: "<format> <file>",
+        description: "save the last search as list (word;score), dict or json",
+    },
+    Keyword {
+        name: "group",
+        arg: "<len|off>",
+        description: "show results in buckets by length with counts, or not",
+    },
+    Keyword {
+        name: "explain",
+        arg: "<search>",
+        description: "show how a search would run, to help write faster ones",
+    },
+    Keyword {
+        name: "config",
+        arg: "",
+        description: "show the effective configuration",
+    },
+    Keyword {
+        name: "help",
+        arg: "",
+        description: "show this help",
+    },
+    Keyword {
+        name: "q",
+        arg: "",
+        description: "quit",
+    },
+    Keyword {
+        name: "quit",
+        arg: "",
+        description: "quit",
+    },
+];
+
+pub const CLAUSES: [Keyword; 12] = [
+    Keyword {
+        name: "p",
+        arg: "<pattern>",
+        description: "starts with pattern, '?' or '.' matching any letter, [abc] a rebus square",
+    },
+    Keyword {
+        name: "len",
+        arg: "<max>",
+        description: "at most <max> letters long",
+    },
+    Keyword {
+        name: "a",
+        arg: "<letters>",
+        description: "anagram of <letters>",
+    },
+    Keyword {
+        name: "a+",
+        arg: "<letters>",
+        description: "contains all of <letters>",
+    },
+    Keyword {
+        name: "a-",
+        arg: "<letters>",
+        description: "made only from <letters>",
+    },
+    Keyword {
+        name: "contains",
+        arg: "<letters>",
+        description: "contains <letters> together, in order",
+    },
+    Keyword {
+        name: "sym",
+        arg: "<kind>",
+        description: "only symmetric letters: v (mirror), h (upside down), rot (half turn)",
+    },
+    Keyword {
+        name: "common",
+        arg: "p<n>",
+        description: "scored at or above the <n>th percentile, e.g. p90",
+    },
+    Keyword {
+        name: "rare",
+        arg: "p<n>",
+        description: "scored below the <n>th percentile, e.g. p10",
+    },
+    Keyword {
+        name: "lang",
+        arg: "<code>/...",
+        description: "from word lists in any of these languages, e.g. en/fr",
+    },
+    Keyword {
+        name: "category",
+        arg: "<name>/...",
+        description: "in any of these configured categories, e.g. element/country",
+    },
+    Keyword {
+        name: "sort",
+        arg: "<order>",
+        description: "one of len, len-, alph, alph-, freq, freq- ('-' for descending)",
+    },
+];
+
+// Clauses that can be negated with '!' or 'not', those filtering words
+// rather than setting the pattern, length, percentile or order.
+const NEGATABLE: [&str; 7] = ["a", "a+", "a-", "contains", "sym", "lang", "category"];
+
+pub const SORTS: [&str; 6] = ["len", "len-", "alph", "alph-", "freq", "freq-"];
+
+pub const SYMMETRIES: [&str; 3] = ["v", "h", "rot"];
+
+pub const GROUPINGS: [&str; 2] = ["len", "off"];
+
+/// How search results are laid out.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Grouping {
+    #[default]
+    None,
+    /// Bucketed by word length, shortest first, each under a header.
+    Length,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Command {
+    Find {
+        prefix: String,
+        predicate: WordPredicate,
+        sort: Option<Sort>,
+        max_length: Option<usize>,
+        frequency: Option<Frequency>,
+    },
+    Anagram(String),
+    Subanagram(String),
+    Superanagram(String),
+    PhraseAnagram {
+        letters: String,
+        required: Vec<String>,
+        forbidden: Vec<String>,
+    },
+    All,
+    Info(usize),
+    Save(String),
+    Export {
+        format: ExportFormat,
+        path: String,
+    },
+    Group(Grouping),
+    /// How the search would run, without running it.
+    Explain(Box<Command>),
+    Config,
+    Help,
+    Quit,
+}
+
+/// A percentile filter on scores, resolved against the dictionary when the
+/// search runs.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Frequency {
+    Common(u8),
+    Rare(u8),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseError {
+    Empty,
+    UnterminatedQuote,
+    UnknownCommand {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    UnknownClause {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    EmptyClause,
+    DuplicateClause(&'static str),
+    NotNegatable(&'static str),
+    MissingValue(&'static str),
+    UnexpectedValue {
+        clause: &'static str,
+        value: String,
+    },
+    InvalidValue {
+        clause: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    InvalidPattern {
+        clause: &'static str,
+        value: String,
+        error: PatternError,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ParseError::*;
+        match self {
+            Empty => write!(f, "empty command; type 'help' for usage"),
+            UnterminatedQuote => write!(f, "unterminated quote"),
+            UnknownCommand { name, suggestion } => {
+                write!(f, "unknown command '{}'", name)?;
+                write_suggestion(f, *suggestion)
+            }
+            UnknownClause { name, suggestion } => {
+                write!(f, "unknown clause '{}'", name)?;
+                write_suggestion(f, *suggestion)
+            }
+            EmptyClause => write!(f, "empty clause between commas"),
+            DuplicateClause(clause) => write!(f, "clause '{}' given more than once", clause),
+            NotNegatable(clause) => write!(f, "clause '{}' cannot be negated", clause),
+            MissingValue(clause) => write!(f, "'{}' needs a value", clause),
+            UnexpectedValue { clause, value } => {
+                write!(f, "unexpected '{}' after '{}'", value, clause)
+            }
+            InvalidValue {
+                clause,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value '{}' for '{}': expected {}",
+                value, clause, expected
+            ),
+            InvalidPattern {
+                clause,
+                value,
+                error,
+            } => write!(f, "invalid pattern '{}' for '{}': {}", value, clause, error),
+        }
+    }
+}
+
+fn write_suggestion(f: &mut fmt::Formatter<'_>, suggestion: Option<&str>) -> fmt::Result {
+    match suggestion {
+        Some(s) => write!(f, " (did you mean '{}'?)", s),
+        None => write!(f, "; type 'help' for usage"),
+    }
+}
+
+pub fn help_text() -> String {
+    let mut text = String::from("Commands:\n");
+    for cmd in COMMANDS.iter() {
+        text += &format!("  {:<6} {:<24} {}\n", cmd.name, cmd.arg, cmd.description);
+    }
+    text += "Clauses for f:\n";
+    for clause in CLAUSES.iter() {
+        text += &format!(
+            "  {:<6} {:<24} {}\n",
+            clause.name, clause.arg, clause.description
+        );
+    }
+    text += "Prefix a clause with ! or not to exclude its matches, e.g. !a listen\n";
+    text += "Values containing spaces or commas can be quoted, e.g. a \"dormitory room\"";
+    text
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Word(String),
+    Comma,
+}
+
+fn tokenize(str: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = str.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(c) if c == ch => break,
+                        Some(c) => word.push(c),
+                        None => return Err(ParseError::UnterminatedQuote),
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ',' || c.is_whitespace() {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+pub fn parse_line(str: &str) -> Result<Command, ParseError> {
+    parse_tokens(&tokenize(str)?)
+}
+
+fn parse_tokens(tokens: &[Token]) -> Result<Command, ParseError> {
+    let (name, rest) = match tokens.split_first() {
+        None => return Err(ParseError::Empty),
+        Some((Token::Comma, _)) => return Err(ParseError::EmptyClause),
+        Some((Token::Word(name), rest)) => (name, rest),
+    };
+
+    match name.as_str() {
+        "f" => parse_find(rest),
+        "a" => Ok(Command::Anagram(parse_letters("a", rest)?)),
+        "a-" => Ok(Command::Subanagram(parse_letters("a-", rest)?)),
+        "a+" => Ok(Command::Superanagram(parse_letters("a+", rest)?)),
+        "ap" => parse_phrase_anagram(rest),
+        "all" => no_value("all", rest).map(|_| Command::All),
+        "info" => {
+            let value = single_value("info", rest)?;
+            Ok(Command::Info(parse_value(
+                "info",
+                value,
+                "a result number",
+            )?))
+        }
+        "save" => Ok(Command::Save(single_value("save", rest)?.to_string())),
+        "export" => parse_export(rest),
+        "group" => match single_value("group", rest)? {
+            "len" => Ok(Command::Group(Grouping::Length)),
+            "off" => Ok(Command::Group(Grouping::None)),
+            value => Err(ParseError::InvalidValue {
+                clause: "group",
+                value: value.to_string(),
+                expected: "one of len, off",
+            }),
+        },
+        "explain" => parse_explain(rest),
+        "config" => no_value("config", rest).map(|_| Command::Config),
+        "help" => no_value("help", rest).map(|_| Command::Help),
+        "q" | "quit" => Ok(Command::Quit),
+        _ => Err(ParseError::UnknownCommand {
+            name: name.clone(),
+            suggestion: suggest(name, COMMANDS.iter().map(|c| c.name)),
+        }),
+    }
+}
+
+fn parse_find(tokens: &[Token]) -> Result<Command, ParseError> {
+    let mut prefix: Option<String> = None;
+    let mut max_length: Option<usize> = None;
+    let mut predicates: Vec<WordPredicate> = vec![];
+    let mut sort: Option<Sort> = None;
+    let mut frequency: Option<Frequency> = None;
+
+    if !tokens.is_empty() {
+        for clause in tokens.split(|t| *t == Token::Comma) {
+            let (negated, clause) = strip_negation(clause);
+            let (key, value) = parse_clause(&clause)?;
+            if negated && !NEGATABLE.contains(&key) {
+                return Err(ParseError::NotNegatable(key));
+            }
+            let mut push = |predicate| {
+                predicates.push(match negated {
+                    true => WordPredicate::Not(Box::new(predicate)),
+                    false => predicate,
+                })
+            };
+            match key {
+                "p" => set_once(&mut prefix, key, parse_pattern(key, value)?)?,
+                "len" => set_once(&mut max_length, key, parse_value(key, value, "a number")?)?,
+                "a" => push(WordPredicate::AnagramOf(anagram(key, value)?)),
+                "a+" => push(WordPredicate::SuperanagramOf(anagram(key, value)?)),
+                "a-" => push(WordPredicate::SubanagramOf(anagram(key, value)?)),
+                "contains" => push(WordPredicate::Contains(letters(key, value)?)),
+                "sym" => push(WordPredicate::OnlyLetters(parse_symmetry(value)?)),
+                "lang" => push(parse_languages(value)?),
+                "category" => push(parse_categories(value)),
+                "common" => set_once(
+                    &mut frequency,
+                    key,
+                    Frequency::Common(percentile(key, value)?),
+                )?,
+                "rare" => set_once(
+                    &mut frequency,
+                    key,
+                    Frequency::Rare(percentile(key, value)?),
+                )?,
+                "sort" => set_once(&mut sort, key, parse_sort(value)?)?,
+                _ => unreachable!("clause keys are checked by parse_clause"),
+            }
+        }
+    }
+
+    Ok(Command::Find {
+        prefix: prefix.unwrap_or_default(),
+        predicate: WordPredicate::All(predicates),
+        sort,
+        max_length,
+        frequency,
+    })
+}
+
+fn parse_explain(tokens: &[Token]) -> Result<Command, ParseError> {
+    let Some(Token::Word(name)) = tokens.first() else {
+        return Err(ParseError::MissingValue("explain"));
+    };
+    match parse_tokens(tokens)? {
+        search @ (Command::Find { .. }
+        | Command::Anagram(_)
+        | Command::Subanagram(_)
+        | Command::Superanagram(_)) => Ok(Command::Explain(Box::new(search))),
+        _ => Err(ParseError::InvalidValue {
+            clause: "explain",
+            value: name.clone(),
+            expected: "a search with f, a, a- or a+",
+        }),
+    }
+}
+
+fn parse_export(tokens: &[Token]) -> Result<Command, ParseError> {
+    match tokens {
+        [Token::Word(format), Token::Word(path)] => Ok(Command::Export {
+            format: parse_value("export", format, "one of list, dict, json")?,
+            path: path.clone(),
+        }),
+        [] | [_] => Err(ParseError::MissingValue("export")),
+        [Token::Word(_), Token::Word(_), extra, ..] | [Token::Word(_), extra, ..] | [extra, ..] => {
+            Err(unexpected("export", extra))
+        }
+    }
+}
+
+// Letters to anagram, possibly in several words, with words to include
+// marked by + and words to leave out by -.
+fn parse_phrase_anagram(tokens: &[Token]) -> Result<Command, ParseError> {
+    let mut letters = String::new();
+    let mut required = vec![];
+    let mut forbidden = vec![];
+    for token in tokens {
+        let Token::Word(word) = token else {
+            return Err(unexpected("ap", token));
+        };
+        if let Some(word) = word.strip_prefix('+') {
+            required.push(word.to_string());
+        } else if let Some(word) = word.strip_prefix('-') {
+            forbidden.push(word.to_string());
+        } else {
+            letters += word;
+        }
+    }
+    if letters.is_empty() {
+        return Err(ParseError::MissingValue("ap"));
+    }
+    Ok(Command::PhraseAnagram {
+        letters,
+        required,
+        forbidden,
+    })
+}
+
+fn parse_letters(command: &'static str, tokens: &[Token]) -> Result<String, ParseError> {
+    let value = single_value(command, tokens)?;
+    anagram(command, value)?;
+    Ok(value.to_string())
+}
+
+// Whether a clause is negated by a leading 'not' or '!', and the clause
+// without it.
+fn strip_negation(tokens: &[Token]) -> (bool, Vec<Token>) {
+    match tokens {
+        [Token::Word(not), rest @ ..] if not == "not" && !rest.is_empty() => (true, rest.to_vec()),
+        [Token::Word(word), rest @ ..] if word.len() > 1 && word.starts_with('!') => {
+            let mut clause = vec![Token::Word(word[1..].to_string())];
+            clause.extend_from_slice(rest);
+            (true, clause)
+        }
+        _ => (false, tokens.to_vec()),
+    }
+}
+
+fn parse_clause(tokens: &[Token]) -> Result<(&'static str, &str), ParseError> {
+    let (name, rest) = match tokens.split_first() {
+        Some((Token::Word(name), rest)) => (name, rest),
+        _ => return Err(ParseError::EmptyClause),
+    };
+    let key = CLAUSES
+        .iter()
+        .map(|c| c.name)
+        .find(|c| c == name)
+        .ok_or_else(|| ParseError::UnknownClause {
+            name: name.to_string(),
+            suggestion: suggest(name, CLAUSES.iter().map(|c| c.name)),
+        })?;
+    Ok((key, single_value(key, rest)?))
+}
+
+fn unexpected(key: &'static str, token: &Token) -> ParseError {
+    ParseError::UnexpectedValue {
+        clause: key,
+        value: match token {
+            Token::Word(w) => w.clone(),
+            Token::Comma => ",".into(),
+        },
+    }
+}
+
+fn no_value(key: &'static str, tokens: &[Token]) -> Result<(), ParseError> {
+    match tokens.first() {
+        None => Ok(()),
+        Some(token) => Err(unexpected(key, token)),
+    }
+}
+
+fn single_value<'a>(key: &'static str, tokens: &'a [Token]) -> Result<&'a str, ParseError> {
+    match tokens {
+        [] => Err(ParseError::MissingValue(key)),
+        [Token::Word(value)] => Ok(value),
+        [_, extra, ..] | [extra] => Err(unexpected(key, extra)),
+    }
+}
+
+fn set_once<T>(slot: &mut Option<T>, key: &'static str, value: T) -> Result<(), ParseError> {
+    if slot.is_some() {
+        return Err(ParseError::DuplicateClause(key));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn parse_value<T: std::str::FromStr>(
+    clause: &'static str,
+    value: &str,
+    expected: &'static str,
+) -> Result<T, ParseError> {
+    value.parse().map_err(|_| ParseError::InvalidValue {
+        clause,
+        value: value.to_string(),
+        expected,
+    })
+}
+
+fn parse_pattern(clause: &'static str, value: &str) -> Result<String, ParseError> {
+    match TriePrefix::from_pattern(value) {
+        Ok(_) => Ok(value.to_string()),
+        Err(error) => Err(ParseError::InvalidPattern {
+            clause,
+            value: value.to_string(),
+            error,
+        }),
+    }
+}
+
+fn anagram(clause: &'static str, value: &str) -> Result<AnagramNumber, ParseError> {
+    let nw = NormalizedWord::from_str_safe(value);
+    AnagramNumber::try_from(&nw).map_err(|_| ParseError::InvalidValue {
+        clause,
+        value: value.to_string(),
+        expected: "fewer letters",
+    })
+}
+
+fn letters(clause: &'static str, value: &str) -> Result<NormalizedWord, ParseError> {
+    let nw = NormalizedWord::from_str_safe(value);
+    if nw.is_empty() {
+        return Err(ParseError::InvalidValue {
+            clause,
+            value: value.to_string(),
+            expected: "letters",
+        });
+    }
+    Ok(nw)
+}
+
+fn parse_symmetry(value: &str) -> Result<CharSet, ParseError> {
+    match value {
+        "v" => Ok(CharSet::vertically_symmetric()),
+        "h" => Ok(CharSet::horizontally_symmetric()),
+        "rot" => Ok(CharSet::rotatable()),
+        _ => Err(ParseError::InvalidValue {
+            clause: "sym",
+            value: value.to_string(),
+            expected: "one of v, h, rot",
+        }),
+    }
+}
+
+fn parse_languages(value: &str) -> Result<WordPredicate, ParseError> {
+    let languages = value
+        .split('/')
+        .map(|code| code.parse().map(WordPredicate::Language))
+        .collect::<Result<_, _>>()
+        .map_err(|_| ParseError::InvalidValue {
+            clause: "lang",
+            value: value.to_string(),
+            expected: "language codes separated by '/', e.g. en/fr",
+        })?;
+    Ok(WordPredicate::Any(languages))
+}
+
+fn parse_categories(value: &str) -> WordPredicate {
+    let categories = value
+        .split('/')
+        .map(|name| WordPredicate::Category(name.to_lowercase()));
+    WordPredicate::Any(categories.collect())
+}
+
+fn percentile(clause: &'static str, value: &str) -> Result<u8, ParseError> {
+    let digits = value.strip_prefix('p').unwrap_or(value);
+    match digits.parse() {
+        Ok(n) if n <= 100 => Ok(n),
+        _ => Err(ParseError::InvalidValue {
+            clause,
+            value: value.to_string(),
+            expected: "a percentile from p0 to p100",
+        }),
+    }
+}
+
+fn parse_sort(value: &str) -> Result<Sort, ParseError> {
+    let sort = match value {
+        "len" => Sort(SortAspect::Length, SortDirection::Ascending),
+        "len-" => Sort(SortAspect::Length, SortDirection::Descending),
+        "alph" => Sort(SortAspect::Alphabetical, SortDirection::Ascending),
+        "alph-" => Sort(SortAspect::Alphabetical, SortDirection::Descending),
+        "freq" => Sort(SortAspect::Score, SortDirection::Ascending),
+        "freq-" => Sort(SortAspect::Score, SortDirection::Descending),
+        _ => {
+            return Err(ParseError::InvalidValue {
+                clause: "sort",
+                value: value.to_string(),
+                expected: "one of len, len-, alph, alph-, freq, freq-",
+            })
+        }
+    };
+    Ok(sort)
+}
+
+fn suggest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|c| (edit_distance(word, c), c))
+        .filter(|&(d, _)| d <= 2)
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let next = (diag + usize::from(ca != cb))
+                .min(row[j] + 1)
+                .min(row[j + 1] + 1);
+            diag = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anag(str: &str) -> AnagramNumber {
+        AnagramNumber::try_from(&NormalizedWord::from_str_safe(str)).unwrap()
+    }
+
+    #[test]
+    fn parses_find_clauses() {
+        let res = parse_line("f p ca?, len 5, a+ st, sort len-");
+
+        assert_eq!(
+            res,
+            Ok(Command::Find {
+                prefix: "ca?".into(),
+                predicate: WordPredicate::All(vec![WordPredicate::SuperanagramOf(anag("st"))]),
+                sort: Some(Sort(SortAspect::Length, SortDirection::Descending)),
+                max_length: Some(5),
+                frequency: None,
+            })
+        )
+    }
+
+    #[test]
+    fn parses_symmetry_clause() {
+        let res = parse_line("f sym rot, len 4");
+
+        assert_eq!(
+            res,
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![WordPredicate::OnlyLetters(
+                    CharSet::rotatable()
+                )]),
+                sort: None,
+                max_length: Some(4),
+                frequency: None,
+            })
+        );
+        assert!(matches!(
+            parse_line("f sym x"),
+            Err(ParseError::InvalidValue { clause: "sym", .. })
+        ));
+    }
+
+    #[test]
+    fn parses_language_clause() {
+        let tag = |code: &str| WordPredicate::Language(code.parse().unwrap());
+
+        assert_eq!(
+            parse_line("f lang en/FR"),
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![WordPredicate::Any(vec![tag("en"), tag("fr")])]),
+                sort: None,
+                max_length: None,
+                frequency: None,
+            })
+        );
+        assert!(matches!(
+            parse_line("f lang en/french"),
+            Err(ParseError::InvalidValue { clause: "lang", .. })
+        ));
+    }
+
+    #[test]
+    fn parses_category_clause() {
+        let category = |name: &str| WordPredicate::Category(name.into());
+
+        assert_eq!(
+            parse_line("f category Element/country"),
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![WordPredicate::Any(vec![
+                    category("element"),
+                    category("country")
+                ])]),
+                sort: None,
+                max_length: None,
+                frequency: None,
+            })
+        );
+        assert_eq!(
+            parse_line("f !category capital"),
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![WordPredicate::Not(Box::new(
+                    WordPredicate::Any(vec![category("capital")])
+                ))]),
+                sort: None,
+                max_length: None,
+                frequency: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_frequency_clauses() {
+        let res = parse_line("f common p90, sort freq-");
+
+        assert_eq!(
+            res,
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![]),
+                sort: Some(Sort(SortAspect::Score, SortDirection::Descending)),
+                max_length: None,
+                frequency: Some(Frequency::Common(90)),
+            })
+        );
+        assert_eq!(
+            parse_line("f rare p10, common p90"),
+            Err(ParseError::DuplicateClause("common"))
+        );
+        assert!(matches!(
+            parse_line("f rare p101"),
+            Err(ParseError::InvalidValue { clause: "rare", .. })
+        ));
+    }
+
+    #[test]
+    fn parses_negated_clauses() {
+        let not = |predicate| WordPredicate::Not(Box::new(predicate));
+
+        assert_eq!(
+            parse_line("f !a listen, not contains qu, contains e"),
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![
+                    not(WordPredicate::AnagramOf(anag("listen"))),
+                    not(WordPredicate::Contains("qu".into())),
+                    WordPredicate::Contains("e".into()),
+                ]),
+                sort: None,
+                max_length: None,
+                frequency: None,
+            })
+        );
+        assert_eq!(
+            parse_line("f not len 5"),
+            Err(ParseError::NotNegatable("len"))
+        );
+        assert!(matches!(
+            parse_line("f !b x"),
+            Err(ParseError::UnknownClause { .. })
+        ));
+        assert!(matches!(
+            parse_line("f contains 123"),
+            Err(ParseError::InvalidValue {
+                clause: "contains",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_quoted_values() {
+        let res = parse_line("f a \"dormitory room\"");
+
+        assert_eq!(
+            res,
+            Ok(Command::Find {
+                prefix: "".into(),
+                predicate: WordPredicate::All(vec![WordPredicate::AnagramOf(anag(
+                    "dormitoryroom"
+                ))]),
+                sort: None,
+                max_length: None,
+                frequency: None,
+            })
+        )
+    }
+
+    #[test]
+    fn reports_unknown_clause_with_suggestion() {
+        let res = parse_line("f p ca, srot len");
+
+        assert_eq!(
+            res,
+            Err(ParseError::UnknownClause {
+                name: "srot".into(),
+                suggestion: Some("sort"),
+            })
+        );
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "unknown clause 'srot' (did you mean 'sort'?)"
+        )
+    }
+
+    #[test]
+    fn reports_unknown_command() {
+        let res = parse_line("quti");
+
+        assert_eq!(
+            res,
+            Err(ParseError::UnknownCommand {
+                name: "quti".into(),
+                suggestion: Some("quit"),
+            })
+        )
+    }
+
+    #[test]
+    fn reports_bad_values() {
+        assert_eq!(parse_line("f len"), Err(ParseError::MissingValue("len")));
+        assert_eq!(
+            parse_line("f len x"),
+            Err(ParseError::InvalidValue {
+                clause: "len",
+                value: "x".into(),
+                expected: "a number",
+            })
+        );
+        assert_eq!(
+            parse_line("f p ca ts"),
+            Err(ParseError::UnexpectedValue {
+                clause: "p",
+                value: "ts".into(),
+            })
+        );
+        assert_eq!(
+            parse_line("f p ca1"),
+            Err(ParseError::InvalidPattern {
+                clause: "p",
+                value: "ca1".into(),
+                error: TriePrefix::from_pattern("ca1").unwrap_err(),
+            })
+        );
+        assert_eq!(
+            parse_line("f p c*t").unwrap_err().to_string(),
+            "invalid pattern 'c*t' for 'p': unexpected '*' at character 2; use ? for any one letter"
+        );
+        assert_eq!(parse_line("f p a, , len 3"), Err(ParseError::EmptyClause));
+        assert_eq!(parse_line("f p 'ca"), Err(ParseError::UnterminatedQuote));
+        assert_eq!(
+            parse_line("f p a, p b"),
+            Err(ParseError::DuplicateClause("p"))
+        );
+    }
+
+    #[test]
+    fn parses_anagram_commands() {
+        assert_eq!(
+            parse_line("a listen"),
+            Ok(Command::Anagram("listen".into()))
+        );
+        assert_eq!(
+            parse_line("a- 'retains'"),
+            Ok(Command::Subanagram("retains".into()))
+        );
+        assert_eq!(parse_line("a+ qz"), Ok(Command::Superanagram("qz".into())));
+        assert_eq!(
+            parse_line("ap dirty room +room -dirty -dim"),
+            Ok(Command::PhraseAnagram {
+                letters: "dirtyroom".into(),
+                required: vec!["room".into()],
+                forbidden: vec!["dirty".into(), "dim".into()],
+            })
+        );
+        assert_eq!(parse_line("ap +room"), Err(ParseError::MissingValue("ap")));
+        assert_eq!(parse_line("a"), Err(ParseError::MissingValue("a")));
+        assert_eq!(
+            parse_line("a- abc, d"),
+            Err(ParseError::UnexpectedValue {
+                clause: "a-",
+                value: ",".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_result_actions() {
+        assert_eq!(parse_line("all"), Ok(Command::All));
+        assert_eq!(
+            parse_line("group len"),
+            Ok(Command::Group(Grouping::Length))
+        );
+        assert_eq!(parse_line("group off"), Ok(Command::Group(Grouping::None)));
+        assert!(matches!(
+            parse_line("group size"),
+            Err(ParseError::InvalidValue {
+                clause: "group",
+                ..
+            })
+        ));
+        assert_eq!(parse_line("info 3"), Ok(Command::Info(3)));
+        assert_eq!(
+            parse_line("save \"my results.txt\""),
+            Ok(Command::Save("my results.txt".into()))
+        );
+        assert_eq!(
+            parse_line("export dict words.dict"),
+            Ok(Command::Export {
+                format: ExportFormat::Dict,
+                path: "words.dict".into()
+            })
+        );
+        assert_eq!(
+            parse_line("export xml words.xml"),
+            Err(ParseError::InvalidValue {
+                clause: "export",
+                value: "xml".into(),
+                expected: "one of list, dict, json"
+            })
+        );
+        assert_eq!(
+            parse_line("export json"),
+            Err(ParseError::MissingValue("export"))
+        );
+        assert_eq!(
+            parse_line("all 3"),
+            Err(ParseError::UnexpectedValue {
+                clause: "all",
+                value: "3".into(),
+            })
+        );
+        assert!(matches!(
+            parse_line("info x"),
+            Err(ParseError::InvalidValue { clause: "info", .. })
+        ));
+    }
+
+    #[test]
+    fn parses_explain() {
+        assert_eq!(
+            parse_line("explain a- retains"),
+            Ok(Command::Explain(Box::new(Command::Subanagram(
+                "retains".into()
+            ))))
+        );
+        assert!(matches!(
+            parse_line("explain f p c?t, len 5"),
+            Ok(Command::Explain(search)) if matches!(*search, Command::Find { .. })
+        ));
+        assert_eq!(
+            parse_line("explain"),
+            Err(ParseError::MissingValue("explain"))
+        );
+        assert_eq!(
+            parse_line("explain save out.txt"),
+            Err(ParseError::InvalidValue {
+                clause: "explain",
+                value: "save".into(),
+                expected: "a search with f, a, a- or a+",
+            })
+        );
+    }
+
+    #[test]
+    fn reports_anagram_overflow() {
+        let res = parse_line("f a zzzzzzzzzzzzzzzzzzzzzzzz");
+
+        assert!(matches!(
+            res,
+            Err(ParseError::InvalidValue { clause: "a", .. })
+        ))
+    }
+}
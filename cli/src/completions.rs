@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use clap::{builder::PossibleValuesParser, Command};
+use clap_complete::{generate, Shell};
+
+/// A completion script for `shell`, generated from the command tree so it
+/// stays in step with the subcommands and flags. The word lists compared
+/// by `diff` complete as the configured `dictionaries`.
+pub fn completions(cmd: Command, shell: Shell, dictionaries: &[PathBuf]) -> String {
+    let names: Vec<String> = dictionaries
+        .iter()
+        .map(|x| x.display().to_string())
+        .collect();
+    let dictionary = |arg: clap::Arg| arg.value_parser(PossibleValuesParser::new(names.clone()));
+    let mut cmd = cmd.mut_subcommands(|sub| match sub.get_name() {
+        "diff" => sub.mut_arg("old", dictionary).mut_arg("new", dictionary),
+        _ => sub,
+    });
+    let name = cmd.get_name().to_string();
+    let mut out = Vec::new();
+    generate(shell, &mut cmd, name, &mut out);
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn completes_subcommands_flags_and_dictionaries() {
+        let dictionaries = [
+            PathBuf::from("data/enable.txt"),
+            PathBuf::from("extra.dict"),
+        ];
+        let script = |shell| completions(crate::Cli::command(), shell, &dictionaries);
+
+        let bash = script(Shell::Bash);
+        assert!(bash.contains("batch check completions diff index jumble man stats"));
+        assert!(bash.contains("--include-blocked"));
+        assert!(bash.contains("data/enable.txt extra.dict"));
+
+        let zsh = script(Shell::Zsh);
+        assert!(zsh.contains("'--columns[Show results in columns filling the terminal width]'"));
+        assert!(zsh.contains("_files"));
+        assert!(zsh.contains("(data/enable.txt extra.dict)"));
+
+        let fish = script(Shell::Fish);
+        assert!(fish.contains("-a \"build\""));
+        assert!(fish.contains("__fish_wordplay_using_subcommand diff\" -l json"));
+    }
+}
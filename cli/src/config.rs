@@ -0,0 +1,150 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Numbered,
+    Plain,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub dictionaries: Vec<PathBuf>,
+    pub limit: usize,
+    pub format: OutputFormat,
+    pub aliases: BTreeMap<String, String>,
+    /// Words to leave out of results, one per line.
+    pub blocklist: Option<PathBuf>,
+    /// Word lists by category name, such as `element`, for the `category`
+    /// clause.
+    pub categories: BTreeMap<String, PathBuf>,
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            dictionaries: vec![PathBuf::from("data/enable.txt")],
+            limit: 5,
+            format: Default::default(),
+            aliases: Default::default(),
+            blocklist: None,
+            categories: Default::default(),
+            source: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        config_dir.map(|dir| dir.join("wordplay").join("config.toml"))
+    }
+
+    // Loads the config file if there is one, falling back to defaults.
+    pub fn load() -> Result<Config, String> {
+        match Config::default_path() {
+            Some(path) if path.exists() => Config::load_from(&path),
+            _ => Ok(Default::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Config, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut config = Config::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        toml::from_str(text).map_err(|e| e.message().to_string())
+    }
+
+    // Replaces a leading alias in the line with its expansion.
+    pub fn expand_alias(&self, line: &str) -> String {
+        let line = line.trim_start();
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match self.aliases.get(name) {
+            Some(expansion) if rest.is_empty() => expansion.clone(),
+            Some(expansion) => format!("{} {}", expansion, rest),
+            None => line.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(path) => writeln!(f, "config file:  {}", path.display())?,
+            None => writeln!(f, "config file:  (none, using defaults)")?,
+        }
+        let dictionaries: Vec<_> = self
+            .dictionaries
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        writeln!(f, "dictionaries: {}", dictionaries.join(", "))?;
+        writeln!(f, "limit:        {}", self.limit)?;
+        write!(f, "format:       {:?}", self.format)?;
+        if let Some(path) = &self.blocklist {
+            write!(f, "\nblocklist:    {}", path.display())?;
+        }
+        for (name, path) in self.categories.iter() {
+            write!(f, "\ncategory:     {} = {}", name, path.display())?;
+        }
+        for (name, expansion) in self.aliases.iter() {
+            write!(f, "\nalias:        {} = {}", name, expansion)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_partial_config() {
+        let config = Config::parse("limit = 20\n[aliases]\nan = \"a\"\n").unwrap();
+
+        assert_eq!(config.limit, 20);
+        assert_eq!(config.format, OutputFormat::Numbered);
+        assert_eq!(config.dictionaries, vec![PathBuf::from("data/enable.txt")]);
+        assert_eq!(config.aliases.get("an"), Some(&"a".to_string()));
+        assert_eq!(config.blocklist, None);
+
+        let config = Config::parse("blocklist = \"blocked.txt\"").unwrap();
+        assert_eq!(config.blocklist, Some(PathBuf::from("blocked.txt")));
+
+        let config = Config::parse("[categories]\nelement = \"elements.txt\"\n").unwrap();
+        assert_eq!(
+            config.categories.get("element"),
+            Some(&PathBuf::from("elements.txt"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(Config::parse("limt = 20").is_err());
+    }
+
+    #[test]
+    fn expands_aliases() {
+        let config = Config::parse("[aliases]\nfive = \"f len 5, p\"\nx = \"all\"").unwrap();
+
+        assert_eq!(config.expand_alias("five ca"), "f len 5, p ca");
+        assert_eq!(config.expand_alias("x"), "all");
+        assert_eq!(config.expand_alias("f p ca"), "f p ca");
+    }
+}
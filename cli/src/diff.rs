@@ -0,0 +1,134 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use wordplay_core::{anagram_number::AnagramNumber, dictionary::Dictionary, progress::NoProgress};
+
+/// What changed between two word lists, by original spelling.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ListDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Anagram classes of words in both lists that gained or lost members.
+    pub changed: Vec<ClassChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ClassChange {
+    pub old: Vec<String>,
+    pub new: Vec<String>,
+}
+
+pub fn read_list(path: &Path) -> io::Result<Dictionary> {
+    let mut dict: Dictionary = Default::default();
+    dict.read_from(BufReader::new(File::open(path)?), None, NoProgress)?;
+    Ok(dict)
+}
+
+pub fn diff(old: &Dictionary, new: &Dictionary) -> ListDiff {
+    let old_words = words(old);
+    let new_words = words(new);
+    let old_classes = classes(old);
+    let new_classes = classes(new);
+
+    let mut changed: Vec<ClassChange> = old_classes
+        .iter()
+        .filter_map(|(anag, before)| {
+            let after = new_classes.get(anag)?;
+            let kept = before.intersection(after).next().is_some();
+            (kept && before != after).then(|| ClassChange {
+                old: before.iter().map(|x| x.to_string()).collect(),
+                new: after.iter().map(|x| x.to_string()).collect(),
+            })
+        })
+        .collect();
+    changed.sort_unstable_by(|a, b| a.old.cmp(&b.old));
+
+    ListDiff {
+        added: only_in(new, &old_words),
+        removed: only_in(old, &new_words),
+        changed,
+    }
+}
+
+fn words(dict: &Dictionary) -> HashSet<&str> {
+    dict.iter().map(|x| &x.original[..]).collect()
+}
+
+// Words of `dict` missing from `other`, in dictionary order.
+fn only_in(dict: &Dictionary, other: &HashSet<&str>) -> Vec<String> {
+    dict.iter()
+        .filter(|x| !other.contains(&x.original[..]))
+        .map(|x| x.original.clone())
+        .collect()
+}
+
+fn classes(dict: &Dictionary) -> HashMap<AnagramNumber, BTreeSet<&str>> {
+    let mut classes: HashMap<_, BTreeSet<_>> = HashMap::new();
+    for item in dict.iter() {
+        if let Some(anag) = item.anag_num {
+            classes.entry(anag).or_default().insert(&item.original[..]);
+        }
+    }
+    classes
+}
+
+/// Writes additions as `+ word`, removals as `- word` and changed anagram
+/// classes as `~ old words -> new words`, followed by a summary.
+pub fn write_diff<W: Write>(diff: &ListDiff, mut out: W) -> io::Result<()> {
+    for word in diff.added.iter() {
+        writeln!(out, "+ {}", word)?;
+    }
+    for word in diff.removed.iter() {
+        writeln!(out, "- {}", word)?;
+    }
+    for change in diff.changed.iter() {
+        writeln!(
+            out,
+            "~ {} -> {}",
+            change.old.join(" "),
+            change.new.join(" ")
+        )?;
+    }
+    writeln!(
+        out,
+        "{} added, {} removed, {} anagram classes changed",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_additions_removals_and_anagram_classes() {
+        let old = Dictionary::from_iter(vec!["listen", "silent", "cat", "dog", "god"]);
+        let new = Dictionary::from_iter(vec!["listen", "silent", "tinsel", "act", "cat", "dog"]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.added, ["act", "tinsel"]);
+        assert_eq!(diff.removed, ["god"]);
+
+        let mut out = Vec::new();
+        write_diff(&diff, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "+ act\n\
+             + tinsel\n\
+             - god\n\
+             ~ cat -> act cat\n\
+             ~ dog god -> dog\n\
+             ~ listen silent -> listen silent tinsel\n\
+             2 added, 1 removed, 3 anagram classes changed\n"
+        );
+        assert_eq!(
+            serde_json::to_string(&diff.changed[0]).unwrap(),
+            r#"{"old":["cat"],"new":["act","cat"]}"#
+        );
+    }
+}
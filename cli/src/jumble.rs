@@ -0,0 +1,130 @@
+use std::io::{self, Write};
+
+use wordplay_core::{
+    dictionary::Dictionary,
+    enumeration::{Enumeration, InvalidEnumeration},
+    jumble::Jumble,
+    normalized_word::NormalizedWord,
+};
+
+/// A scrambled word with the positions of its circled letters, counting
+/// from zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scramble {
+    pub letters: String,
+    pub circles: Vec<usize>,
+}
+
+/// Parses a scramble as typed, with circled positions counting from one
+/// after a colon: `nalge:1,3`.
+pub fn parse_scramble(arg: &str) -> Result<Scramble, String> {
+    let (letters, circles) = arg.split_once(':').unwrap_or((arg, ""));
+    let len = NormalizedWord::from_str_safe(letters).len();
+    if len == 0 {
+        return Err(format!("no letters to unscramble in '{}'", arg));
+    }
+    let circles = circles
+        .split(',')
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| match x.trim().parse::<usize>() {
+            Ok(n) if (1..=len).contains(&n) => Ok(n - 1),
+            _ => Err(format!(
+                "invalid circle '{}' in '{}': expected a position from 1 to {}",
+                x, arg, len
+            )),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Scramble {
+        letters: letters.to_string(),
+        circles,
+    })
+}
+
+pub fn parse_answer(arg: &str) -> Result<Enumeration, String> {
+    arg.parse()
+        .map_err(|err: InvalidEnumeration| err.to_string())
+}
+
+/// Writes what each scramble unscrambles to, then each way of unscrambling
+/// them all with up to `limit` final answers from its circled letters.
+pub fn write_jumble<W: Write>(
+    dict: &Dictionary,
+    scrambles: &[Scramble],
+    answer: Option<Enumeration>,
+    limit: usize,
+    mut out: W,
+) -> io::Result<()> {
+    let mut jumble = scrambles.iter().fold(Jumble::new(), |jumble, x| {
+        jumble.with_scramble(&x.letters, &x.circles)
+    });
+    if let Some(answer) = answer {
+        jumble = jumble.with_answer(answer);
+    }
+
+    for (scramble, words) in scrambles.iter().zip(jumble.unscramble(dict)) {
+        let words: Vec<String> = words.iter().map(|x| x.to_string()).collect();
+        if words.is_empty() {
+            writeln!(out, "{}: no words", scramble.letters)?;
+        } else {
+            writeln!(out, "{}: {}", scramble.letters, words.join(", "))?;
+        }
+    }
+    let solutions = jumble.solve(dict, limit);
+    if solutions.is_empty() {
+        return writeln!(out, "No final answer found");
+    }
+    for solution in solutions {
+        let words: Vec<String> = solution.words.iter().map(|x| x.to_string()).collect();
+        writeln!(out, "{} -> {}", words.join(" "), solution.circled)?;
+        for answer in solution.answers {
+            let answer: Vec<String> = answer.iter().map(|x| x.to_string()).collect();
+            writeln!(out, "  {}", answer.join(" "))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_circles_counting_from_one() {
+        assert_eq!(
+            parse_scramble("nalge:1, 3"),
+            Ok(Scramble {
+                letters: "nalge".into(),
+                circles: vec![0, 2]
+            })
+        );
+        assert!(parse_scramble("kawl").unwrap().circles.is_empty());
+        assert!(parse_scramble("kawl:5").is_err());
+        assert!(parse_scramble("kawl:0").is_err());
+        assert!(parse_scramble(":1").is_err());
+    }
+
+    #[test]
+    fn writes_unscramblings_and_answers() {
+        let dict = Dictionary::from_iter(vec!["angle", "glean", "walk", "blame", "lab"]);
+        let scrambles: Vec<Scramble> = ["nalge:1", "kawl", "lbame:1,2", "xyz"]
+            .iter()
+            .map(|x| parse_scramble(x).unwrap())
+            .collect();
+        let mut out = Vec::new();
+
+        write_jumble(&dict, &scrambles[..3], None, 5, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "nalge: ANGLE, GLEAN\n\
+             kawl: WALK\n\
+             lbame: BLAME\n\
+             ANGLE WALK BLAME -> ABL\n  LAB\n"
+        );
+
+        let mut out = Vec::new();
+        write_jumble(&dict, &scrambles, None, 5, &mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("xyz: no words\nNo final answer found\n"));
+    }
+}
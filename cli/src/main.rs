@@ -1,170 +1,687 @@
-use itertools::Itertools;
+mod batch;
+mod check;
+mod command;
+mod completions;
+mod config;
+mod diff;
+mod jumble;
+mod present;
+mod repl;
+#[cfg(feature = "serve")]
+mod serve;
+mod stats;
+
 use std::{
-    cmp::Ordering,
-    io::{self, stdin},
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc},
 };
 
+use clap::{CommandFactory, Parser, Subcommand, ValueHint};
+use command::{help_text, parse_line, Command, Frequency, Grouping, ParseError};
+use config::Config;
+use present::{present_info, Presenter};
+use repl::ReplHelper;
+use rustyline::{error::ReadlineError, history::DefaultHistory, Editor};
+use signal_hook::consts::SIGINT;
 use wordplay_core::{
-    anagram_number::AnagramNumber,
-    dict_enable,
-    dictionary::{DictIterItem, DictSearch, Dictionary, WordPredicate},
-    normalized_word::NormalizedWord,
-    trie::TrieSearch,
+    cancel::CancellationToken,
+    dictionary::{
+        ArcDictionary, DictIterItem, Dictionary, Sort, SortAspect, SortDirection, WordPredicate,
+    },
+    enumeration::Enumeration,
+    export::export,
+    hunspell::import_hunspell,
+    import::{import_frequencies, import_scored, import_scowl},
+    language::LanguageTag,
+    phrase_anagram::PhraseAnagrams,
+    query::DictQuery,
+    reload::ReloadableDictionary,
 };
 
-fn read_line() -> io::Result<String> {
-    let mut buffer = String::new();
-    stdin().read_line(&mut buffer)?;
-    Ok(buffer.trim().into())
+const PROMPT: &str = "> ";
+#[cfg(feature = "serve")]
+const RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Word puzzle search tools. Starts an interactive session when no command is given.
+#[derive(Parser)]
+#[command(name = "wordplay", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+    /// Show results in columns filling the terminal width
+    #[arg(long, global = true)]
+    columns: bool,
+    /// Highlight the letters of results fixed by the search pattern
+    #[arg(long, global = true)]
+    color: bool,
+    /// Summarize the lines of each word list skipped or flagged on loading
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    /// Show words on the configured blocklist
+    #[arg(long, global = true)]
+    include_blocked: bool,
 }
 
-fn parse_line(str: &str) -> Option<Command> {
-    if str == "q" || str == "quit" {
-        return Some(Command::Quit);
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Run each line of a file as a search, writing every result
+    Batch {
+        /// File of queries, one per line, as typed at the prompt
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+        /// Write each query's results to a numbered file in this directory
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        out_dir: Option<PathBuf>,
+    },
+    /// Report whether each line of a file is a word, an anagram of one, or neither
+    Check {
+        /// File of candidate words or phrases, one per line
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+    },
+    /// Print a shell completion script
+    Completions {
+        /// Shell to complete for
+        shell: clap_complete::Shell,
+    },
+    /// Compare two word lists, reporting added and removed words and changed anagram classes
+    Diff {
+        /// The earlier word list
+        #[arg(value_hint = ValueHint::FilePath)]
+        old: PathBuf,
+        /// The later word list
+        #[arg(value_hint = ValueHint::FilePath)]
+        new: PathBuf,
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Build paged on-disk indexes of the dictionary
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+    /// Unscramble the words of a jumble and anagram their circled letters into the final answer
+    Jumble {
+        /// Scrambled words, each with the positions of its circled letters counting from one, as nalge:1,3
+        #[arg(required = true, value_parser = jumble::parse_scramble, value_hint = ValueHint::Other)]
+        scrambles: Vec<jumble::Scramble>,
+        /// The lengths of the final answer's words, as (3,5)
+        #[arg(long, value_parser = jumble::parse_answer, value_hint = ValueHint::Other)]
+        answer: Option<Enumeration>,
+    },
+    /// Print a manual page in roff format
+    Man,
+    /// Summarize the dictionary: words by length, letter frequencies, common prefixes and suffixes, anagram classes and the longest isogram
+    Stats {
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Serve searches over HTTP as JSON
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080", value_hint = ValueHint::Other)]
+        addr: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexCommand {
+    /// Write the configured dictionaries, less blocked words, to an index file searchable one first letter at a time
+    Build {
+        /// Index file to write
+        #[arg(value_hint = ValueHint::FilePath)]
+        output: PathBuf,
+    },
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".wordplay_history"))
+}
+
+// Runs a search command, returning at most `limit` results in presentation order.
+// A cancelled search returns the results found before cancellation.
+fn results<'a>(
+    dict: &'a Dictionary,
+    command: &Command,
+    limit: usize,
+    cancel: Option<&CancellationToken>,
+) -> Vec<DictIterItem<'a>> {
+    if let Command::Anagram(word) = command {
+        return dict
+            .anagrams(word)
+            .filter(|x| !dict.is_blocked(&x.normalized))
+            .take(limit)
+            .collect();
     }
+    let Some(query) = query(dict, command) else {
+        return vec![];
+    };
+    let query = match cancel {
+        Some(token) => query.cancellable(token.clone()),
+        None => query,
+    };
+    dict.query(&query.limit(limit))
+}
 
-    if let Some(stripped) = str.strip_prefix("f ") {
-        let mut prefix: String = "".into();
-        let mut max_length: Option<usize> = None;
-        let mut predicates: Vec<WordPredicate> = vec![];
-        let mut sort: Option<Sort> = None;
-        for cmd in stripped.split(',') {
-            let cmd_parts: Vec<_> = cmd.trim().split(' ').collect();
-            match cmd_parts.as_slice() {
-                ["len", max] => match max.parse() {
-                    Ok(m) => max_length = Some(m),
-                    Err(_) => continue,
-                },
-                ["p", p] => prefix = String::from(*p),
-                ["a", a] => {
-                    let nw = NormalizedWord::from_str_safe(a);
-                    let anag = AnagramNumber::try_from(&nw).unwrap();
-                    predicates.push(WordPredicate::AnagramOf(anag))
-                }
-                ["a+", a] => {
-                    let nw = NormalizedWord::from_str_safe(a);
-                    let anag = AnagramNumber::try_from(&nw).unwrap();
-                    predicates.push(WordPredicate::SuperanagramOf(anag))
-                }
-                ["a-", a] => {
-                    let nw = NormalizedWord::from_str_safe(a);
-                    let anag = AnagramNumber::try_from(&nw).unwrap();
-                    predicates.push(WordPredicate::SubanagramOf(anag))
-                }
-                ["sort", s] => {
-                    sort = Some(match *s {
-                        "len" => Sort(SortAspect::Length, SortDirection::Ascending),
-                        "len-" => Sort(SortAspect::Length, SortDirection::Descending),
-                        "alph" => Sort(SortAspect::Alphabetical, SortDirection::Ascending),
-                        "alph-" => Sort(SortAspect::Alphabetical, SortDirection::Descending),
-                        _ => continue,
-                    })
-                }
-                _ => (),
-            }
-        }
-        return Some(Command::Find {
+// The query a search command runs, other than exact anagrams, which are
+// looked up directly.
+fn query(dict: &Dictionary, command: &Command) -> Option<DictQuery> {
+    use Command::*;
+    let query = match command {
+        Find {
             prefix,
-            predicate: WordPredicate::All(predicates),
+            predicate,
             sort,
             max_length,
-        });
-    }
-
-    None
+            frequency,
+        } => {
+            let mut query = DictQuery::new().prefix(prefix).matching(predicate.clone());
+            if let Some(max) = max_length {
+                query = query.length(..=*max);
+            }
+            if let Some(frequency) = frequency {
+                let (percentile, filter): (u8, fn(u32) -> WordPredicate) = match *frequency {
+                    Frequency::Common(p) => (p, WordPredicate::ScoreAtLeast),
+                    Frequency::Rare(p) => (p, WordPredicate::ScoreBelow),
+                };
+                // without scores no word is common or rare
+                query = query.matching(match dict.score_percentile(percentile) {
+                    Some(threshold) => filter(threshold),
+                    None => WordPredicate::Any(vec![]),
+                });
+            }
+            match sort {
+                Some(sort) => query.sort(*sort),
+                None => query,
+            }
+        }
+        Subanagram(letters) => DictQuery::new()
+            .formable_from(letters)
+            .sort(Sort(SortAspect::Length, SortDirection::Descending)),
+        Superanagram(letters) => DictQuery::new()
+            .containing_letters(letters)
+            .sort(Sort(SortAspect::Length, SortDirection::Ascending)),
+        _ => return None,
+    };
+    // a no-op unless a blocklist was loaded
+    Some(query.matching(WordPredicate::NotBlocked))
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum SortAspect {
-    Length,
-    Alphabetical,
+// Up to `limit` phrases using all of `letters`, with every required word
+// and none of the forbidden ones.
+fn phrase_anagrams(
+    dict: &Dictionary,
+    letters: &str,
+    required: &[String],
+    forbidden: &[String],
+    limit: usize,
+) -> Vec<String> {
+    let search = required
+        .iter()
+        .fold(PhraseAnagrams::new(dict), |search, x| {
+            search.with_required(x)
+        });
+    let search = forbidden
+        .iter()
+        .fold(search, |search, x| search.with_forbidden(x));
+    search
+        .search(letters, limit)
+        .iter()
+        .map(|phrase| {
+            let words: Vec<String> = phrase.iter().map(|x| x.to_string()).collect();
+            words.join(" ")
+        })
+        .collect()
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum SortDirection {
-    Ascending,
-    Descending,
+fn save(path: &str, results: &[DictIterItem]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for x in results {
+        writeln!(writer, "{}", x.original)?;
+    }
+    writer.flush()
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Sort(SortAspect, SortDirection);
+fn run_diff(old: &Path, new: &Path, json: bool) {
+    let lists = diff::read_list(old).and_then(|old| Ok((old, diff::read_list(new)?)));
+    let (old, new) = match lists {
+        Ok(lists) => lists,
+        Err(err) => {
+            println!("Error reading word lists: {}", err);
+            return;
+        }
+    };
+    let diff = diff::diff(&old, &new);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+    } else if let Err(err) = diff::write_diff(&diff, io::stdout().lock()) {
+        println!("Error writing differences: {}", err)
+    }
+}
 
-impl Sort {
-    pub fn compare(&self, a: &DictIterItem, b: &DictIterItem) -> Ordering {
-        let ordering = match self.0 {
-            SortAspect::Length => a.normalized.len().cmp(&b.normalized.len()),
-            SortAspect::Alphabetical => a.normalized.cmp(&b.normalized),
+fn load_dictionary(
+    paths: &[PathBuf],
+    blocklist: Option<&Path>,
+    categories: &BTreeMap<String, PathBuf>,
+    verbose: bool,
+) -> io::Result<Dictionary> {
+    let show_progress = io::stdout().is_terminal();
+    let mut dict: Dictionary = Default::default();
+    for path in load_order(paths) {
+        let file = File::open(path)?;
+        // scored word lists, as written by `export dict`
+        if path.extension().is_some_and(|x| x == "dict") {
+            import_scored(&mut dict, BufReader::new(file))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            continue;
+        }
+        // Hunspell dictionaries, with their affix rules beside them
+        if path.extension().is_some_and(|x| x == "dic") {
+            let aff = File::open(path.with_extension("aff"))?;
+            let forms = import_hunspell(&mut dict, BufReader::new(file), BufReader::new(aff))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            if verbose {
+                println!("  {}: {} forms", path.display(), forms);
+            }
+            continue;
+        }
+        // wordfreq and FrequencyWords lists of words and their frequencies
+        if path.extension().is_some_and(|x| x == "freq") {
+            import_frequencies(&mut dict, BufReader::new(file))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            continue;
+        }
+        if let Some(size) = scowl_size(path) {
+            import_scowl(&mut dict, BufReader::new(file), size)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            continue;
+        }
+        let size = file.metadata()?.len() as usize;
+        let progress = |done, total| {
+            if show_progress {
+                print!("\r  {} {}", path.display(), percentage(done, total));
+                let _ = io::stdout().flush();
+            }
+        };
+        let reader = BufReader::new(file);
+        let report = match language_of(path) {
+            Some(lang) => dict.read_tagged_from(reader, Some(size), progress, lang)?,
+            None => dict.read_from(reader, Some(size), progress)?,
         };
-        match self.1 {
-            SortDirection::Ascending => ordering,
-            SortDirection::Descending => ordering.reverse(),
+        if show_progress {
+            println!();
+        }
+        if verbose {
+            println!("  {}: {}", path.display(), report);
+        }
+    }
+    // themed lists, such as chemical elements, whose words are also inserted
+    for (category, path) in categories {
+        let reader = BufReader::new(File::open(path)?);
+        let report = dict.read_category_from(reader, None, |_, _| {}, category)?;
+        if verbose {
+            println!("  {} ({}): {}", path.display(), category, report);
         }
     }
+    if let Some(path) = blocklist {
+        let blocked = dict.read_blocklist(BufReader::new(File::open(path)?))?;
+        if verbose {
+            println!("  {}: {} blocked words", path.display(), blocked);
+        }
+    }
+    Ok(dict)
 }
 
-#[derive(Debug, PartialEq, Clone)]
-enum Command {
-    Find {
-        prefix: String,
-        predicate: WordPredicate,
-        sort: Option<Sort>,
-        max_length: Option<usize>,
-    },
-    Quit,
+// SCOWL lists skip words already present, so they load ahead of the other
+// lists, commonest size first, for their words to keep the higher scores.
+// The other lists keep their configured order.
+fn load_order(paths: &[PathBuf]) -> Vec<&PathBuf> {
+    let (mut scowl, rest): (Vec<&PathBuf>, Vec<&PathBuf>) =
+        paths.iter().partition(|x| scowl_size(x).is_some());
+    scowl.sort_by_key(|x| scowl_size(x));
+    scowl.into_iter().chain(rest).collect()
+}
+
+// The size of a SCOWL list named like `english-words.35`.
+fn scowl_size(path: &Path) -> Option<u8> {
+    let size: u8 = path.extension()?.to_str()?.parse().ok()?;
+    (size <= 100).then_some(size)
 }
 
-fn read_command() -> Option<Command> {
-    println!("Enter command");
-    let line = read_line().unwrap();
-    parse_line(&line)
+// The language of a word list named like `words.fr.txt`.
+fn language_of(path: &Path) -> Option<LanguageTag> {
+    let stem = Path::new(path.file_stem()?);
+    stem.extension()?.to_str()?.parse().ok()
 }
 
-fn present<'a, It: Iterator<Item = DictIterItem<'a>>>(iter: It) {
-    let res = iter.take(5);
-    for x in res {
-        println!("{}", x.original);
+fn percentage(done: usize, total: Option<usize>) -> String {
+    match total {
+        Some(total) if total > 0 => format!("{:>3}%", (done * 100 / total).min(100)),
+        _ => format!("{} bytes", done),
     }
 }
 
-fn command_loop(dict: Dictionary) {
-    use Command::*;
-    loop {
-        let command = read_command();
+struct Session<'a> {
+    source: &'a ReloadableDictionary,
+    dict: ArcDictionary,
+    config: &'a Config,
+    last_search: Option<Command>,
+    presenter: Presenter,
+    cancel: CancellationToken,
+}
+
+impl<'a> Session<'a> {
+    fn new(
+        source: &'a ReloadableDictionary,
+        config: &'a Config,
+        presenter: Presenter,
+        cancel: CancellationToken,
+    ) -> Self {
+        Session {
+            source,
+            dict: source.current(),
+            config,
+            last_search: None,
+            presenter,
+            cancel,
+        }
+    }
+
+    // Picks up edits to the word lists made since the last command.
+    fn refresh(&mut self) {
+        match self.source.reload_if_changed() {
+            Ok(true) => {
+                self.dict = self.source.current();
+                println!("Reloaded dictionary");
+            }
+            Ok(false) => (),
+            Err(err) => println!("Error reloading dictionary, keeping the old one: {}", err),
+        }
+    }
+
+    fn results(&self, command: &Command, limit: usize) -> Vec<DictIterItem<'_>> {
+        self.cancel.reset();
+        let results = results(&self.dict, command, limit, Some(&self.cancel));
+        if self.cancel.is_cancelled() {
+            println!("Cancelled, showing results found so far");
+        }
+        results
+    }
+
+    fn present(&self, search: &Command, results: &[DictIterItem]) {
+        let pattern = match search {
+            Command::Find { prefix, .. } => prefix,
+            _ => "",
+        };
+        print!("{}", self.presenter.render(results, pattern));
+    }
+
+    fn last_search(&self) -> Option<&Command> {
+        if self.last_search.is_none() {
+            println!("No previous search");
+        }
+        self.last_search.as_ref()
+    }
+
+    fn run(&mut self, command: Command) {
+        use Command::*;
         match command {
-            Some(Quit) => {
-                println!("Bye!");
-                break;
+            All => {
+                if let Some(last) = self.last_search() {
+                    self.present(last, &self.results(last, usize::MAX))
+                }
             }
-            Some(Find {
-                prefix,
-                predicate,
-                sort,
-                max_length,
-            }) => {
-                println!("Finding...");
-                let mut trie_search = TrieSearch::from_prefix(&prefix);
-                if let Some(max) = max_length {
-                    trie_search = trie_search.with_max(max);
+            Info(n) => {
+                if let Some(last) = self.last_search() {
+                    match self.results(last, n).get(n.wrapping_sub(1)) {
+                        Some(item) => present_info(item),
+                        None => println!("No result {}", n),
+                    }
+                }
+            }
+            Save(path) => {
+                if let Some(last) = self.last_search() {
+                    let all = self.results(last, usize::MAX);
+                    match save(&path, &all) {
+                        Ok(()) => println!("Saved {} results to {}", all.len(), path),
+                        Err(err) => println!("Error saving to {}: {}", path, err),
+                    }
                 }
-                let search = DictSearch::new(Some(trie_search), predicate);
-                let results = dict.iter_search(search);
-                match sort {
-                    Some(sort) => {
-                        let sorted = results.sorted_by(|a, b| sort.compare(a, b));
-                        present(sorted)
+            }
+            Export { format, path } => {
+                if let Some(last) = self.last_search() {
+                    let all = self.results(last, usize::MAX);
+                    let written = File::create(&path)
+                        .and_then(|file| export(format, BufWriter::new(file), &all));
+                    match written {
+                        Ok(()) => println!("Exported {} results to {}", all.len(), path),
+                        Err(err) => println!("Error exporting to {}: {}", path, err),
                     }
-                    None => present(results),
                 }
             }
-            None => {
-                println!("Unrecognised command")
+            Group(grouping) => {
+                self.presenter.grouping = grouping;
+                match grouping {
+                    Grouping::None => println!("Results ungrouped"),
+                    Grouping::Length => println!("Results grouped by length"),
+                }
+            }
+            PhraseAnagram {
+                letters,
+                required,
+                forbidden,
+            } => {
+                let phrases = phrase_anagrams(
+                    &self.dict,
+                    &letters,
+                    &required,
+                    &forbidden,
+                    self.config.limit,
+                );
+                if phrases.is_empty() {
+                    println!("No phrases found");
+                }
+                for phrase in phrases {
+                    println!("{}", phrase);
+                }
+            }
+            Explain(search) => match query(&self.dict, &search) {
+                Some(query) => println!("{}", query.to_search().explain()),
+                None => println!("Exact anagrams are looked up in the anagram index"),
+            },
+            Config => println!("{}", self.config),
+            Help => println!("{}", help_text()),
+            Quit => (),
+            search => {
+                println!("Finding...");
+                let limit = self.config.limit;
+                self.present(&search, &self.results(&search, limit));
+                self.last_search = Some(search);
+            }
+        }
+    }
+}
+
+fn command_loop(dict: ReloadableDictionary, config: Config, presenter: Presenter) {
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new().unwrap();
+    editor.set_helper(Some(ReplHelper::new(&dict)));
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    // Ctrl-C while reading a line is handled by the editor; while a search
+    // runs it cancels the search instead of killing the process.
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    if let Err(err) = signal_hook::flag::register(SIGINT, Arc::clone(&cancel_flag)) {
+        println!("Ctrl-C will not cancel searches: {}", err);
+    }
+
+    let mut session = Session::new(&dict, &config, presenter, cancel_flag.into());
+    println!("Enter command ('help' for usage, Tab to complete, Ctrl-D to quit)");
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                println!("Error reading input: {}", err);
+                break;
+            }
+        };
+        let _ = editor.add_history_entry(line.as_str());
+
+        match parse_line(&config.expand_alias(&line)) {
+            Ok(Command::Quit) => break,
+            Ok(command) => {
+                session.refresh();
+                session.run(command)
             }
+            Err(ParseError::Empty) => continue,
+            Err(err) => println!("Error: {}", err),
         }
     }
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+    println!("Bye!");
+}
+
+fn terminal_width() -> usize {
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(width), _)) => width as usize,
+        None => 80,
+    }
 }
 
 fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(CliCommand::Completions { shell }) => {
+            let config = Config::load().unwrap_or_default();
+            let script = completions::completions(Cli::command(), shell, &config.dictionaries);
+            print!("{}", script);
+            return;
+        }
+        Some(CliCommand::Man) => {
+            if let Err(err) = clap_mangen::Man::new(Cli::command()).render(&mut io::stdout()) {
+                println!("Error writing manual page: {}", err);
+            }
+            return;
+        }
+        Some(CliCommand::Diff { old, new, json }) => {
+            run_diff(&old, &new, json);
+            return;
+        }
+        _ => (),
+    }
+    let config = Config::load().unwrap_or_else(|err| {
+        println!("Ignoring config file: {}", err);
+        Default::default()
+    });
+
     println!("Loading...");
-    let enable = dict_enable();
-    command_loop(enable);
+    let verbose = cli.verbose;
+    let blocklist = config.blocklist.clone().filter(|_| !cli.include_blocked);
+    let categories = config.categories.clone();
+    let dict = match ReloadableDictionary::load_with(config.dictionaries.clone(), move |paths| {
+        load_dictionary(paths, blocklist.as_deref(), &categories, verbose)
+    }) {
+        Ok(dict) => dict,
+        Err(err) => {
+            println!("Error loading dictionary: {}", err);
+            return;
+        }
+    };
+
+    match cli.command {
+        None => {
+            let presenter = Presenter {
+                format: config.format,
+                columns: cli.columns.then(terminal_width),
+                color: cli.color,
+                ..Default::default()
+            };
+            command_loop(dict, config, presenter)
+        }
+        Some(CliCommand::Batch { file, out_dir }) => {
+            let ran = File::open(&file).and_then(|f| {
+                batch::run_batch(
+                    &dict.current(),
+                    &config,
+                    BufReader::new(f),
+                    out_dir.as_deref(),
+                    io::stdout().lock(),
+                )
+            });
+            if let Err(err) = ran {
+                println!("Error running {}: {}", file.display(), err)
+            }
+        }
+        Some(CliCommand::Check { file }) => {
+            let checked = File::open(&file).and_then(|f| {
+                check::check_lines(&dict.current(), BufReader::new(f), io::stdout().lock())
+            });
+            if let Err(err) = checked {
+                println!("Error checking {}: {}", file.display(), err)
+            }
+        }
+        Some(CliCommand::Jumble { scrambles, answer }) => {
+            let written = jumble::write_jumble(
+                &dict.current(),
+                &scrambles,
+                answer,
+                config.limit,
+                io::stdout().lock(),
+            );
+            if let Err(err) = written {
+                println!("Error writing jumble answers: {}", err)
+            }
+        }
+        Some(CliCommand::Index {
+            command: IndexCommand::Build { output },
+        }) => {
+            let written = File::create(&output)
+                .and_then(|file| dict.current().write_index(BufWriter::new(file)));
+            match written {
+                Ok(words) => println!("Indexed {} words in {}", words, output.display()),
+                Err(err) => println!("Error writing index to {}: {}", output.display(), err),
+            }
+        }
+        Some(CliCommand::Stats { json }) => {
+            let stats = stats::stats(&dict.current());
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+            } else if let Err(err) = stats::write_stats(&stats, io::stdout().lock()) {
+                println!("Error writing statistics: {}", err)
+            }
+        }
+        Some(CliCommand::Completions { .. } | CliCommand::Diff { .. } | CliCommand::Man) => {
+            unreachable!()
+        }
+        #[cfg(feature = "serve")]
+        Some(CliCommand::Serve { addr }) => {
+            let dict = Arc::new(dict);
+            dict.watch(
+                RELOAD_INTERVAL,
+                Default::default(),
+                |reloaded| match reloaded {
+                    Ok(()) => println!("Reloaded dictionary"),
+                    Err(err) => {
+                        println!("Error reloading dictionary, keeping the old one: {}", err)
+                    }
+                },
+            );
+            if let Err(err) = serve::serve(&dict, &addr) {
+                println!("Error serving on {}: {}", addr, err)
+            }
+        }
+    }
 }
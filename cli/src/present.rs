@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use wordplay_core::{
+    char_match::CharMatch,
+    dictionary::DictIterItem,
+    language::LanguageProfile,
+    normalized_word::{NormalizedWord, SourceMap},
+    trie::TriePrefix,
+};
+
+use crate::command::Grouping;
+use crate::config::OutputFormat;
+
+const HIGHLIGHT: &str = "\x1b[1;32m";
+const RESET: &str = "\x1b[0m";
+const COLUMN_GAP: usize = 2;
+
+/// Lays out search results for the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Presenter {
+    pub format: OutputFormat,
+    pub grouping: Grouping,
+    /// The width to fill with columns of results, or `None` for one result
+    /// per line.
+    pub columns: Option<usize>,
+    /// Whether to highlight letters fixed by the search pattern.
+    pub color: bool,
+}
+
+impl Presenter {
+    /// Renders `results` of a search for `pattern`, which is empty for
+    /// searches without one. Results are numbered in search order, as
+    /// `info` expects, whatever the layout.
+    pub fn render(&self, results: &[DictIterItem], pattern: &str) -> String {
+        let fixed = fixed_positions(pattern);
+        let cells = |items: &[(usize, &DictIterItem)]| -> Vec<Cell> {
+            items
+                .iter()
+                .map(|&(i, x)| self.cell(i, x, &fixed))
+                .collect()
+        };
+        let numbered: Vec<(usize, &DictIterItem)> = results.iter().enumerate().collect();
+
+        match self.grouping {
+            Grouping::None => self.lay_out(&cells(&numbered)),
+            Grouping::Length => {
+                let mut buckets: BTreeMap<usize, Vec<(usize, &DictIterItem)>> = BTreeMap::new();
+                for (i, x) in numbered {
+                    buckets.entry(x.normalized.len()).or_default().push((i, x));
+                }
+                buckets
+                    .into_iter()
+                    .map(|(len, bucket)| {
+                        format!(
+                            "{} letters ({})\n{}",
+                            len,
+                            bucket.len(),
+                            self.lay_out(&cells(&bucket))
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn cell(&self, index: usize, item: &DictIterItem, fixed: &[bool]) -> Cell {
+        let number = match self.format {
+            OutputFormat::Numbered => format!("{:>3}. ", index + 1),
+            OutputFormat::Plain => String::new(),
+        };
+        let word = match self.color {
+            true => highlight(item.original, &source_map(item), fixed),
+            false => item.original.clone(),
+        };
+        Cell {
+            width: number.chars().count() + item.original.chars().count(),
+            text: number + &word,
+        }
+    }
+
+    // Fills columns top to bottom, as `ls` does, when a width is set.
+    fn lay_out(&self, cells: &[Cell]) -> String {
+        let Some(width) = self.columns else {
+            return cells.iter().map(|x| format!("{}\n", x.text)).collect();
+        };
+        let cell_width = cells.iter().map(|x| x.width).max().unwrap_or(0) + COLUMN_GAP;
+        let columns = (width / cell_width).max(1);
+        let rows = cells.len().div_ceil(columns);
+
+        let mut out = String::new();
+        for row in 0..rows {
+            let line: Vec<&Cell> = cells.iter().skip(row).step_by(rows).collect();
+            for (i, cell) in line.iter().enumerate() {
+                out += &cell.text;
+                if i + 1 < line.len() {
+                    out += &" ".repeat(cell_width - cell.width);
+                }
+            }
+            out += "\n";
+        }
+        out
+    }
+}
+
+struct Cell {
+    // the width on screen, which escape codes take no part in
+    width: usize,
+    text: String,
+}
+
+// Which letters of a search pattern are fixed rather than wildcards.
+fn fixed_positions(pattern: &str) -> Vec<bool> {
+    let Ok(pattern) = TriePrefix::from_pattern(pattern) else {
+        return vec![];
+    };
+    pattern
+        .chars()
+        .iter()
+        .map(|ch| matches!(ch, CharMatch::Only(_)))
+        .collect()
+}
+
+// Where each normalized letter of `item` came from, folded as its
+// language's profile folds it when it is tagged with one.
+fn source_map(item: &DictIterItem) -> SourceMap {
+    match item.lang.and_then(LanguageProfile::get) {
+        Some(profile) => profile.source_map(item.original),
+        None => NormalizedWord::source_map(item.original),
+    }
+}
+
+fn highlight(original: &str, map: &SourceMap, fixed: &[bool]) -> String {
+    let mut out = String::new();
+    let mut done = 0;
+    for (i, _) in fixed.iter().enumerate().filter(|(_, &x)| x) {
+        let Some(range) = map.get(i) else {
+            break;
+        };
+        // a folded character already highlighted for an earlier letter
+        if range.start < done {
+            continue;
+        }
+        out += &original[done..range.start];
+        out += HIGHLIGHT;
+        out += &original[range.clone()];
+        out += RESET;
+        done = range.end;
+    }
+    out + &original[done..]
+}
+
+pub fn present_info(item: &DictIterItem) {
+    println!("original:   {}", item.original);
+    println!("normalized: {}", item.normalized);
+    println!("length:     {}", item.normalized.len());
+    match item.anag_num {
+        Some(anag) => println!("anagram:    {}", anag.value()),
+        None => println!("anagram:    (too long)"),
+    }
+    println!("letters:    {}", item.char_freq);
+    if let Some(score) = item.score {
+        println!("score:      {}", score);
+    }
+    if let Some(lang) = item.lang {
+        println!("language:   {}", lang);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordplay_core::dictionary::Dictionary;
+
+    #[test]
+    fn lays_out_columns_and_groups() {
+        let dict = Dictionary::from_iter(vec!["cat", "cart", "coat", "cut", "cot"]);
+        let results: Vec<_> = dict.iter().collect();
+        let columns = Presenter {
+            format: OutputFormat::Plain,
+            columns: Some(12),
+            ..Default::default()
+        };
+
+        assert_eq!(columns.render(&results, ""), "cart  cot\ncat   cut\ncoat\n");
+        assert_eq!(
+            Presenter {
+                grouping: Grouping::Length,
+                ..columns
+            }
+            .render(&results, ""),
+            "3 letters (3)\ncat  cut\ncot\n4 letters (2)\ncart  coat\n"
+        );
+        assert_eq!(
+            Presenter::default().render(&results[..2], ""),
+            "  1. cart\n  2. cat\n"
+        );
+    }
+
+    #[test]
+    fn highlights_fixed_letters() {
+        let highlight = |original: &str, pattern: &str| {
+            let map = NormalizedWord::source_map(original);
+            super::highlight(original, &map, &fixed_positions(pattern))
+        };
+        assert_eq!(
+            highlight("Café-au-lait", "??f?a"),
+            "Ca\x1b[1;32mf\x1b[0mé-\x1b[1;32ma\x1b[0mu-lait"
+        );
+        assert_eq!(highlight("at", "a?t"), "\x1b[1;32ma\x1b[0mt");
+    }
+
+    #[test]
+    fn highlights_folded_letters_of_tagged_entries() {
+        let mut dict = Dictionary::default();
+        dict.insert_tagged("Müller", "de".parse().unwrap());
+        let item = dict.iter().next().unwrap();
+        let fixed = fixed_positions("mue????");
+
+        assert_eq!(
+            highlight(item.original, &source_map(&item), &fixed),
+            "\x1b[1;32mM\x1b[0m\x1b[1;32mü\x1b[0mller"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use wordplay_core::reload::ReloadableDictionary;
+
+use crate::command::{CLAUSES, COMMANDS, GROUPINGS, SORTS, SYMMETRIES};
+
+const WORD_COMPLETIONS: usize = 20;
+
+pub struct ReplHelper<'a> {
+    dict: &'a ReloadableDictionary,
+}
+
+impl<'a> ReplHelper<'a> {
+    pub fn new(dict: &'a ReloadableDictionary) -> Self {
+        ReplHelper { dict }
+    }
+
+    fn candidates(&self, before: &str, partial: &str) -> Vec<String> {
+        let clause = match before.strip_prefix("f ") {
+            Some(clauses) => clauses.rsplit(',').next().unwrap_or_default().trim(),
+            None if before.trim() == "group" => {
+                return matching(GROUPINGS.iter().copied(), partial)
+            }
+            None if before.trim().is_empty() => {
+                return matching(COMMANDS.iter().map(|c| c.name), partial)
+            }
+            None => return vec![],
+        };
+
+        match clause {
+            "" => matching(CLAUSES.iter().map(|c| c.name), partial),
+            "sort" => matching(SORTS.iter().copied(), partial),
+            "sym" => matching(SYMMETRIES.iter().copied(), partial),
+            "category" => {
+                let dict = self.dict.current();
+                matching(dict.categories().into_iter(), partial)
+            }
+            "len" | "common" | "rare" => vec![],
+            _ if partial.is_empty() => vec![],
+            _ => self
+                .dict
+                .current()
+                .complete(partial, WORD_COMPLETIONS)
+                .into_iter()
+                .map(|x| x.original.clone())
+                .collect(),
+        }
+    }
+}
+
+fn matching<'a>(keywords: impl Iterator<Item = &'a str>, partial: &str) -> Vec<String> {
+    keywords
+        .filter(|k| k.starts_with(partial))
+        .map(|k| k.to_string())
+        .collect()
+}
+
+impl<'a> Completer for ReplHelper<'a> {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind([' ', ',']).map_or(0, |i| i + 1);
+        Ok((start, self.candidates(&line[..start], &line[start..])))
+    }
+}
+
+impl<'a> Hinter for ReplHelper<'a> {
+    type Hint = String;
+}
+
+impl<'a> Highlighter for ReplHelper<'a> {}
+
+impl<'a> Validator for ReplHelper<'a> {}
+
+impl<'a> Helper for ReplHelper<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordplay_core::dictionary::Dictionary;
+
+    #[test]
+    fn completes_commands_and_clauses() {
+        let dict = Dictionary::default().into();
+        let helper = ReplHelper::new(&dict);
+
+        assert_eq!(helper.candidates("", "q"), vec!["q", "quit"]);
+        assert_eq!(helper.candidates("f ", "a"), vec!["a", "a+", "a-"]);
+        assert_eq!(helper.candidates("f p ca, ", "l"), vec!["len", "lang"]);
+        assert_eq!(
+            helper.candidates("f p ca, sort ", "al"),
+            vec!["alph", "alph-"]
+        );
+        assert_eq!(helper.candidates("group ", ""), vec!["len", "off"]);
+    }
+
+    #[test]
+    fn completes_dictionary_words() {
+        let dict = Dictionary::from_iter(vec!["cattle", "cat", "dog"]).into();
+        let helper = ReplHelper::new(&dict);
+
+        assert_eq!(
+            helper.candidates("f len 6, p ", "ca"),
+            vec!["cat", "cattle"]
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::{io, thread};
+
+use serde::Serialize;
+use tiny_http::{Header, Response, Server};
+use wordplay_core::{
+    dictionary::{DictIterItem, Dictionary, WordPredicate},
+    query::DictQuery,
+    reload::ReloadableDictionary,
+};
+
+use crate::{command::Command, results};
+
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct WordResult<'a> {
+    word: &'a str,
+    normalized: String,
+    length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u32>,
+}
+
+impl<'a> From<&DictIterItem<'a>> for WordResult<'a> {
+    fn from(item: &DictIterItem<'a>) -> Self {
+        WordResult {
+            word: item.original,
+            normalized: item.normalized.to_string(),
+            length: item.normalized.len(),
+            score: item.score,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SearchResponse<'a> {
+    results: Vec<WordResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse<'a> {
+    error: &'a str,
+}
+
+pub fn serve(dict: &ReloadableDictionary, addr: &str) -> io::Result<()> {
+    let server = Server::http(addr).map_err(io::Error::other)?;
+    let workers = thread::available_parallelism().map_or(4, |n| n.get());
+    println!("Listening on http://{} with {} workers", addr, workers);
+
+    // the dictionary is shared read-only between the workers, each request
+    // searching whichever version was current when it arrived
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| handle_requests(dict, &server));
+        }
+    });
+    Ok(())
+}
+
+fn handle_requests(dict: &ReloadableDictionary, server: &Server) {
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    for request in server.incoming_requests() {
+        let (status, body) = match route(&dict.current(), request.url()) {
+            Ok(body) => (200, body),
+            Err((status, error)) => (status, json(&ErrorResponse { error: &error })),
+        };
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        let _ = request.respond(response);
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+// Maps a request URL to a JSON body, or a status code and error message.
+fn route(dict: &Dictionary, url: &str) -> Result<String, (u16, String)> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params: Vec<(String, String)> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (decode(k), decode(v)))
+        .collect();
+    let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| &v[..]);
+
+    let limit = match param("limit") {
+        Some(limit) => limit
+            .parse()
+            .map_err(|_| (400, "limit must be a number".into()))?,
+        None => DEFAULT_LIMIT,
+    };
+    let offset = match param("offset") {
+        Some(offset) => offset
+            .parse()
+            .map_err(|_| (400, "offset must be a number".into()))?,
+        None => 0,
+    };
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let items = match segments[..] {
+        ["search"] => {
+            let query = match (param("pattern"), param("prefix")) {
+                (Some(pattern), _) => DictQuery::new().try_pattern(pattern),
+                (None, Some(prefix)) => DictQuery::new().try_prefix(prefix),
+                (None, None) => return Err((400, "search needs a pattern or prefix".into())),
+            };
+            let query = query.map_err(|err| (400, err.to_string()))?;
+            // a no-op unless a blocklist was loaded
+            let query = query.matching(WordPredicate::NotBlocked);
+            dict.search_page(query.to_search(), offset, limit)
+        }
+        ["anagrams", letters] => results(dict, &Command::Anagram(decode(letters)), limit, None),
+        ["subanagrams", letters] => {
+            results(dict, &Command::Subanagram(decode(letters)), limit, None)
+        }
+        ["superanagrams", letters] => {
+            results(dict, &Command::Superanagram(decode(letters)), limit, None)
+        }
+        _ => return Err((404, "not found".into())),
+    };
+
+    let results = items.iter().map(WordResult::from).collect();
+    Ok(json(&SearchResponse { results }))
+}
+
+// Decodes `+` and `%XX` escapes in a URL component.
+fn decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec![
+            "banana", "canape", "listen", "silent", "tinsel", "lit",
+        ])
+    }
+
+    #[test]
+    fn searches_by_pattern() {
+        let body = route(&dict(), "/search?pattern=%3Fana%3F%3F").unwrap();
+
+        assert_eq!(
+            body,
+            r#"{"results":[{"word":"banana","normalized":"BANANA","length":6},{"word":"canape","normalized":"CANAPE","length":6}]}"#
+        );
+    }
+
+    #[test]
+    fn pages_search_results() {
+        let body = route(&dict(), "/search?prefix=&limit=2&offset=1").unwrap();
+
+        assert!(body.contains("\"canape\"") && body.contains("\"listen\""));
+        assert!(!body.contains("\"banana\"") && !body.contains("\"lit\""));
+        assert!(route(&dict(), "/search?prefix=a&offset=x").is_err());
+    }
+
+    #[test]
+    fn finds_anagrams_with_limit() {
+        let body = route(&dict(), "/anagrams/listen?limit=2").unwrap();
+
+        assert!(body.contains("\"listen\"") && body.contains("\"silent\""));
+        assert!(!body.contains("\"tinsel\""));
+    }
+
+    #[test]
+    fn leaves_out_blocked_words() {
+        let mut dict = dict();
+        dict.read_blocklist("# slurs\nsilent\n".as_bytes()).unwrap();
+
+        let body = route(&dict, "/search?pattern=%3F%3F%3F%3F%3F%3F").unwrap();
+        assert!(body.contains("\"listen\"") && !body.contains("\"silent\""));
+        let body = route(&dict, "/anagrams/listen").unwrap();
+        assert!(body.contains("\"tinsel\"") && !body.contains("\"silent\""));
+    }
+
+    #[test]
+    fn reports_bad_requests() {
+        assert_eq!(route(&dict(), "/nope"), Err((404, "not found".into())));
+        assert!(route(&dict(), "/search").is_err());
+        assert_eq!(
+            route(&dict(), "/search?pattern=b%2Ana"),
+            Err((
+                400,
+                "unexpected '*' at character 2; use ? for any one letter".into()
+            ))
+        );
+        assert!(route(&dict(), "/subanagrams/abc?limit=x").is_err());
+    }
+
+    #[test]
+    fn decodes_url_components() {
+        assert_eq!(decode("%3Fana%3f+x"), "?ana? x");
+        assert_eq!(decode("100%"), "100%");
+    }
+}
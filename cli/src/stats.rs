@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+
+use serde::Serialize;
+use wordplay_core::{
+    corpus::Corpus,
+    dictionary::Dictionary,
+    normalized_word::{NormalizedChar, NormalizedWord},
+};
+
+const AFFIX_LEN: usize = 3;
+const TOP_AFFIXES: usize = 10;
+
+/// A summary of a dictionary's words.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DictStats {
+    pub words: usize,
+    /// How many words have each number of letters.
+    pub lengths: BTreeMap<usize, usize>,
+    /// How often each letter appears across every word.
+    pub letters: BTreeMap<String, usize>,
+    /// The commonest three-letter starts of longer words, commonest first.
+    pub prefixes: Vec<AffixCount>,
+    /// The commonest three-letter ends of longer words, commonest first.
+    pub suffixes: Vec<AffixCount>,
+    /// Distinct sets of letters, each shared by a class of anagrams.
+    pub anagram_classes: usize,
+    /// The longest word with no letter repeated, first alphabetically.
+    pub longest_isogram: Option<String>,
+    /// How far the letter frequencies are from those of English text, as
+    /// a Kullback-Leibler divergence.
+    pub english_divergence: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AffixCount {
+    pub affix: String,
+    pub words: usize,
+}
+
+/// Gathers every statistic in one pass over the dictionary.
+pub fn stats(dict: &Dictionary) -> DictStats {
+    let mut stats: DictStats = Default::default();
+    let mut corpus = Corpus::new();
+    let mut prefixes: HashMap<NormalizedWord, usize> = HashMap::new();
+    let mut suffixes: HashMap<NormalizedWord, usize> = HashMap::new();
+    let mut classes: HashSet<NormalizedWord> = HashSet::new();
+    let mut isogram_len = 0;
+
+    for item in dict.iter() {
+        let word = &item.normalized;
+        stats.words += 1;
+        *stats.lengths.entry(word.len()).or_default() += 1;
+        corpus.add(word);
+        if word.len() > AFFIX_LEN {
+            let chars: Vec<NormalizedChar> = word.iter_chars().copied().collect();
+            let prefix = NormalizedWord::new(chars[..AFFIX_LEN].to_vec());
+            let suffix = NormalizedWord::new(chars[chars.len() - AFFIX_LEN..].to_vec());
+            *prefixes.entry(prefix).or_default() += 1;
+            *suffixes.entry(suffix).or_default() += 1;
+        }
+        let mut sorted: Vec<NormalizedChar> = word.iter_chars().copied().collect();
+        sorted.sort_unstable();
+        classes.insert(NormalizedWord::new(sorted));
+        if word.len() > isogram_len && item.letter_mask().len() == word.len() {
+            isogram_len = word.len();
+            stats.longest_isogram = Some(item.original.clone());
+        }
+    }
+
+    stats.letters = corpus
+        .counts()
+        .iter()
+        .filter(|(_, &count)| count > 0)
+        .map(|(ch, &count)| (ch.to_string(), count as usize))
+        .collect();
+    stats.english_divergence = corpus.compare_to_english().kl_divergence;
+    stats.prefixes = commonest(prefixes);
+    stats.suffixes = commonest(suffixes);
+    stats.anagram_classes = classes.len();
+    stats
+}
+
+// The most frequent affixes, ties broken alphabetically.
+fn commonest(counts: HashMap<NormalizedWord, usize>) -> Vec<AffixCount> {
+    let mut counts: Vec<(NormalizedWord, usize)> = counts.into_iter().collect();
+    counts.sort_unstable_by(|(a, m), (b, n)| n.cmp(m).then_with(|| a.cmp(b)));
+    counts
+        .into_iter()
+        .take(TOP_AFFIXES)
+        .map(|(affix, words)| AffixCount {
+            affix: affix.to_string(),
+            words,
+        })
+        .collect()
+}
+
+pub fn write_stats<W: Write>(stats: &DictStats, mut out: W) -> io::Result<()> {
+    writeln!(out, "{} words", stats.words)?;
+    writeln!(out, "\nBy length:")?;
+    for (len, words) in stats.lengths.iter() {
+        writeln!(out, "  {:>2}: {}", len, words)?;
+    }
+    writeln!(out, "\nLetters:")?;
+    let total: usize = stats.letters.values().sum();
+    for (letter, count) in stats.letters.iter() {
+        let percent = 100.0 * *count as f64 / total as f64;
+        writeln!(out, "  {}: {} ({:.1}%)", letter, count, percent)?;
+    }
+    for (name, affixes) in [("Prefixes", &stats.prefixes), ("Suffixes", &stats.suffixes)] {
+        let affixes: Vec<String> = affixes
+            .iter()
+            .map(|x| format!("{} {}", x.affix, x.words))
+            .collect();
+        writeln!(out, "\n{}: {}", name, affixes.join(", "))?;
+    }
+    writeln!(out, "\n{} anagram classes", stats.anagram_classes)?;
+    match &stats.longest_isogram {
+        Some(word) => writeln!(out, "Longest isogram: {}", word)?,
+        None => writeln!(out, "No isograms")?,
+    }
+    writeln!(
+        out,
+        "Divergence from English letter frequencies: {:.3}",
+        stats.english_divergence
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_a_word_list() {
+        let dict = Dictionary::from_iter(vec![
+            "listen", "silent", "tinsel", "lister", "cat", "act", "tinker",
+        ]);
+        let stats = stats(&dict);
+
+        assert_eq!(stats.words, 7);
+        assert_eq!(stats.lengths, BTreeMap::from([(3, 2), (6, 5)]));
+        assert_eq!(stats.letters["T"], 7);
+        assert_eq!(stats.letters["L"], 4);
+        assert_eq!(
+            stats.prefixes[0],
+            AffixCount {
+                affix: "LIS".into(),
+                words: 2
+            }
+        );
+        assert_eq!(stats.prefixes.len(), 3);
+        assert_eq!(stats.suffixes[0].affix, "ENT");
+        assert_eq!(stats.anagram_classes, 4);
+        assert_eq!(stats.longest_isogram.as_deref(), Some("listen"));
+
+        let mut out = Vec::new();
+        write_stats(&stats, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("7 words\n\nBy length:\n   3: 2\n   6: 5\n"));
+        assert!(out.contains("  T: 7 (19.4%)\n"));
+        assert!(out.contains("\nPrefixes: LIS 2, TIN 2, SIL 1\n"));
+        assert!(out.contains("\n4 anagram classes\nLongest isogram: listen\n"));
+        assert!(stats.english_divergence > 0.0);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.starts_with(r#"{"words":7,"lengths":{"3":2,"6":5},"letters":{"A":2,"#));
+    }
+}
@@ -0,0 +1,151 @@
+//! Lookup, prefix and pattern searches over ENABLE, in our trie and in an
+//! `fst::Set` of the same normalized words, for sizing up a frozen
+//! dictionary.
+//!
+//! Run from the `core` directory:
+//! `cargo bench --features bench-fst --bench trie_vs_fst`
+
+use std::fs::read_to_string;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fst::automaton::{Automaton, Str};
+use fst::{IntoStreamer, Set, Streamer};
+use wordplay_core::{
+    normalized_word::NormalizedWord,
+    trie::{Trie, TriePrefix, TrieSearch},
+};
+
+const PATTERNS: [&str; 3] = ["bana??", "?an", "??e??n?"];
+
+fn enable_words() -> Vec<NormalizedWord> {
+    read_to_string("../data/enable.txt")
+        .unwrap()
+        .lines()
+        .map(NormalizedWord::from_str_safe)
+        .collect()
+}
+
+fn build_trie(words: &[NormalizedWord]) -> Trie<()> {
+    let mut trie = Trie::empty();
+    for word in words {
+        trie.add(word, ());
+    }
+    trie
+}
+
+fn build_fst(words: &[NormalizedWord]) -> Set<Vec<u8>> {
+    let mut keys: Vec<String> = words.iter().map(|x| x.to_string()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    Set::from_iter(keys).unwrap()
+}
+
+/// Matches keys of the pattern's length, `?` matching any letter.
+struct Pattern(Vec<Option<u8>>);
+
+impl Pattern {
+    fn new(pattern: &str) -> Self {
+        Pattern(
+            pattern
+                .bytes()
+                .map(|b| (b != b'?').then(|| b.to_ascii_uppercase()))
+                .collect(),
+        )
+    }
+}
+
+impl Automaton for Pattern {
+    // letters matched so far, or `None` once the key has diverged
+    type State = Option<usize>;
+
+    fn start(&self) -> Self::State {
+        Some(0)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        *state == Some(self.0.len())
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let i = (*state)?;
+        let expected = *self.0.get(i)?;
+        expected.is_none_or(|x| x == byte).then_some(i + 1)
+    }
+}
+
+fn count<S: for<'a> Streamer<'a>>(mut stream: S) -> usize {
+    let mut count = 0;
+    while stream.next().is_some() {
+        count += 1;
+    }
+    count
+}
+
+fn trie_vs_fst(c: &mut Criterion) {
+    let words = enable_words();
+    let trie = build_trie(&words);
+    let set = build_fst(&words);
+    println!(
+        "{} words in an fst of {} bytes",
+        set.len(),
+        set.as_fst().size()
+    );
+
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+    group.bench_function("trie", |b| b.iter(|| build_trie(black_box(&words))));
+    group.bench_function("fst", |b| b.iter(|| build_fst(black_box(&words))));
+    group.finish();
+
+    let mut group = c.benchmark_group("lookup banana");
+    let banana = NormalizedWord::from_str_safe("banana");
+    group.bench_function("trie", |b| b.iter(|| trie.contains_key(black_box(&banana))));
+    group.bench_function("fst", |b| b.iter(|| set.contains(black_box("BANANA"))));
+    group.finish();
+
+    let mut group = c.benchmark_group("prefix ban");
+    group.bench_function("trie", |b| {
+        b.iter(|| {
+            let search = TrieSearch::from_prefix(black_box("ban")).unwrap();
+            trie.iter_search(search).count()
+        })
+    });
+    group.bench_function("fst", |b| {
+        b.iter(|| {
+            let prefix = Str::new(black_box("BAN")).starts_with();
+            count(set.search(prefix).into_stream())
+        })
+    });
+    group.finish();
+
+    for pattern in PATTERNS {
+        let prefix = TriePrefix::from_pattern(pattern).unwrap();
+        let search = TrieSearch::new(prefix, Some(pattern.len()));
+        assert_eq!(
+            trie.iter_search(search).count(),
+            count(set.search(Pattern::new(pattern)).into_stream()),
+            "{}",
+            pattern
+        );
+
+        let mut group = c.benchmark_group(format!("pattern {}", pattern));
+        group.bench_function("trie", |b| {
+            b.iter(|| {
+                let prefix = TriePrefix::from_pattern(black_box(pattern)).unwrap();
+                let search = TrieSearch::new(prefix, Some(pattern.len()));
+                trie.iter_search(search).count()
+            })
+        });
+        group.bench_function("fst", |b| {
+            b.iter(|| count(set.search(Pattern::new(black_box(pattern))).into_stream()))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, trie_vs_fst);
+criterion_main!(benches);
@@ -1,15 +1,48 @@
-use std::fs::File;
 use std::env::current_dir;
+use std::fs::read_to_string;
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use wordplay_core::{
-    dictionary::{DictSearch, Dictionary},
-    normalized_word::NormalizedWord,
+    dictionary::{DictSearch, Dictionary, WordPredicate},
+    frozen::Encoding,
+    normalized_word::{NormalizedChar, NormalizedWord},
+    trie::TrieSearch,
 };
 
-fn enable_bench(c: &mut Criterion) {
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+const RACK: &str = "retains";
+
+fn enable_words() -> Vec<String> {
     println!("{:?}", current_dir().unwrap());
-    let enable = Dictionary::from_file(File::open("../data/enable.txt").unwrap());
+    read_to_string("../data/enable.txt")
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+// Evenly spaced samples of the word list, so every size spans the alphabet,
+// followed by the full list.
+fn sized_word_lists(words: &[String]) -> Vec<Vec<&str>> {
+    let mut lists: Vec<Vec<&str>> = SIZES
+        .iter()
+        .filter(|&&size| size < words.len())
+        .map(|&size| {
+            let step = words.len() / size;
+            words
+                .iter()
+                .step_by(step)
+                .take(size)
+                .map(|w| &w[..])
+                .collect()
+        })
+        .collect();
+    lists.push(words.iter().map(|w| &w[..]).collect());
+    lists
+}
+
+fn enable_bench(c: &mut Criterion) {
+    let enable = Dictionary::from_iter(enable_words().iter().map(|w| &w[..]));
 
     c.bench_function("enable find banana", |b| {
         let banana = NormalizedWord::from_str_safe("banana");
@@ -19,7 +52,7 @@ fn enable_bench(c: &mut Criterion) {
     c.bench_function("enable search bana??", |b| {
         b.iter(|| {
             enable
-                .iter_search(black_box(DictSearch::from_pattern("bana??")))
+                .iter_search(black_box(DictSearch::from_pattern("bana??").unwrap()))
                 .count()
         })
     });
@@ -27,7 +60,7 @@ fn enable_bench(c: &mut Criterion) {
     c.bench_function("enable search ban prefix", |b| {
         b.iter(|| {
             enable
-                .iter_search(black_box(DictSearch::from_pattern("ban")))
+                .iter_search(black_box(DictSearch::from_pattern("ban").unwrap()))
                 .count()
         })
     });
@@ -35,11 +68,120 @@ fn enable_bench(c: &mut Criterion) {
     c.bench_function("enable search ?an prefix", |b| {
         b.iter(|| {
             enable
-                .iter_search(black_box(DictSearch::from_pattern("?an")))
+                .iter_search(black_box(DictSearch::from_pattern("?an").unwrap()))
                 .count()
         })
     });
+
+    // a grid slot of seven letters crossing an A and an E
+    c.bench_function("enable search ?a??e?? pattern", |b| {
+        b.iter(|| {
+            enable
+                .iter_search(black_box(DictSearch::from_pattern("?a??e??").unwrap()))
+                .count()
+        })
+    });
+
+    c.bench_function("enable frozen search ?a??e?? pattern", |b| {
+        let frozen = enable.freeze(Encoding::Louds);
+        b.iter(|| {
+            frozen
+                .iter_search(black_box(TrieSearch::exactly("?a??e??").unwrap()))
+                .count()
+        })
+    });
+
+    c.bench_function("enable candidates ?a??e??", |b| {
+        let constraints = [(1, NormalizedChar::A), (4, NormalizedChar::E)];
+        b.iter(|| enable.candidates(7, black_box(&constraints)).count())
+    });
+}
+
+fn sized_bench(c: &mut Criterion) {
+    let words = enable_words();
+    let lists = sized_word_lists(&words);
+    let dicts: Vec<(usize, Dictionary)> = lists
+        .iter()
+        .map(|list| (list.len(), Dictionary::from_iter(list.iter().copied())))
+        .collect();
+
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    for list in lists.iter() {
+        group.throughput(Throughput::Elements(list.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(list.len()), list, |b, list| {
+            b.iter(|| Dictionary::from_iter(list.iter().copied()))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("iterate");
+    for (size, dict) in dicts.iter() {
+        group.throughput(Throughput::Elements(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), dict, |b, dict| {
+            b.iter(|| dict.iter().count())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("anagram of");
+    for (size, dict) in dicts.iter() {
+        group.bench_with_input(BenchmarkId::new("index", size), dict, |b, dict| {
+            b.iter(|| dict.anagrams(black_box("listen")).count())
+        });
+        group.bench_with_input(BenchmarkId::new("search", size), dict, |b, dict| {
+            b.iter(|| {
+                dict.iter_search(black_box(DictSearch::anagram_of("listen")))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("subanagram of rack");
+    for (size, dict) in dicts.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), dict, |b, dict| {
+            b.iter(|| {
+                dict.iter_search(black_box(DictSearch::formable_from(RACK).unwrap()))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("contains tion");
+    let contains = || DictSearch::new(None, WordPredicate::Contains("tion".into()));
+    for (size, dict) in dicts.iter() {
+        group.bench_with_input(BenchmarkId::new("scan", size), dict, |b, dict| {
+            b.iter(|| dict.iter_search(black_box(contains())).count())
+        });
+    }
+    for list in lists.iter() {
+        let mut dict = Dictionary::from_iter(list.iter().copied());
+        dict.build_substring_index();
+        group.bench_with_input(BenchmarkId::new("index", list.len()), &dict, |b, dict| {
+            b.iter(|| dict.iter_search(black_box(contains())).count())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("round trip");
+    group.sample_size(10);
+    for (size, dict) in dicts.iter() {
+        group.throughput(Throughput::Elements(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), dict, |b, dict| {
+            b.iter(|| {
+                let mut bytes = Vec::new();
+                for item in dict.iter() {
+                    bytes.extend_from_slice(item.original.as_bytes());
+                    bytes.push(b'\n');
+                }
+                Dictionary::from_bytes(&bytes)
+            })
+        });
+    }
+    group.finish();
 }
 
-criterion_group!(benches, enable_bench);
+criterion_group!(benches, enable_bench, sized_bench);
 criterion_main!(benches);
@@ -0,0 +1,52 @@
+//! Fills an empty 15x15 grid from ENABLE, restarting with a new word order
+//! whenever an attempt backtracks too often.
+//!
+//! Run from the `core` directory: `cargo run --release --example fill_grid`
+
+use std::fs::File;
+use std::time::Instant;
+
+use wordplay_core::crossword::fill::Filler;
+use wordplay_core::crossword::Grid;
+use wordplay_core::dictionary::Dictionary;
+
+const GRID: &str = "
+    ....#....#.....
+    ....#....#.....
+    ....#....#.....
+    ...#...#....#..
+    #.....#....#...
+    ...#....#......
+    ....#...#....##
+    ...#...#...#...
+    ##....#...#....
+    ......#....#...
+    ...#....#.....#
+    ..#....#...#...
+    .....#....#....
+    .....#....#....
+    .....#....#....
+";
+
+fn main() {
+    let dict = Dictionary::from_file(File::open("../data/enable.txt").unwrap());
+    let grid: Grid = GRID.parse().unwrap();
+    assert!(grid.is_rotationally_symmetric());
+
+    let start = Instant::now();
+    let mut filler = Filler::new(grid, &dict).with_seed(1).with_restarts(50, 500);
+    match filler.fill() {
+        Some(grid) => print!("{}", grid),
+        None => println!("no fill found"),
+    }
+    let stats = filler.stats();
+    println!(
+        "{} attempts in {:?}; cache: {} slot hits, {} pattern hits, {} misses, {} invalidations",
+        filler.attempts(),
+        start.elapsed(),
+        stats.slot_hits,
+        stats.pattern_hits,
+        stats.misses,
+        stats.invalidations
+    );
+}
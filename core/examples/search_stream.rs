@@ -0,0 +1,48 @@
+//! Polls a search once a "frame", as a GUI event loop would, showing
+//! results as they arrive without blocking the loop.
+//!
+//! Run from the `core` directory: `cargo run --example search_stream`
+
+use std::fs::File;
+use std::thread;
+use std::time::Duration;
+
+use wordplay_core::dictionary::{ArcDictionary, Dictionary};
+use wordplay_core::query::DictQuery;
+use wordplay_core::search_handle::{SearchHandle, SearchState};
+
+const FRAME: Duration = Duration::from_millis(16);
+const BATCH: usize = 50;
+
+fn main() {
+    let dict = ArcDictionary::new(Dictionary::from_file(
+        File::open("../data/enable.txt").unwrap(),
+    ));
+
+    let mut handle = SearchHandle::spawn(dict, DictQuery::new().contains("ana"));
+    let mut shown = vec![];
+    let mut frames = 0;
+    while !handle.is_finished() {
+        shown.extend(handle.poll(BATCH).into_iter().map(|x| x.original));
+        frames += 1;
+        // a frontend would redraw here; stop early to show cancellation
+        if shown.len() >= 500 {
+            handle.cancel();
+        }
+        thread::sleep(FRAME);
+    }
+
+    let state = match handle.state() {
+        SearchState::Complete => "complete",
+        SearchState::Cancelled => "cancelled",
+        SearchState::Running => unreachable!(),
+    };
+    println!(
+        "{} results over {} frames in {:?} ({})",
+        shown.len(),
+        frames,
+        handle.elapsed(),
+        state
+    );
+    println!("first: {}", shown[..10.min(shown.len())].join(", "));
+}
@@ -0,0 +1,45 @@
+//! Loads the word list once and searches it from several threads.
+//!
+//! Run from the `core` directory: `cargo run --example shared_dictionary`
+
+use std::fs::File;
+use std::thread;
+
+use wordplay_core::dictionary::{ArcDictionary, DictSearch, Dictionary};
+
+fn main() {
+    let dict = ArcDictionary::new(Dictionary::from_file(
+        File::open("../data/enable.txt").unwrap(),
+    ));
+
+    // Each thread owns a clone of the handle; the dictionary itself is shared.
+    let handles: Vec<_> = ["listen", "danger", "pattern"]
+        .into_iter()
+        .map(|word| {
+            let dict = dict.clone();
+            thread::spawn(move || {
+                let anagrams: Vec<_> = dict.anagrams(word).map(|x| x.original.clone()).collect();
+                (word, anagrams)
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let (word, anagrams) = handle.join().unwrap();
+        println!("{}: {}", word, anagrams.join(", "));
+    }
+
+    // Scoped threads can borrow the dictionary directly.
+    let patterns = ["?ana??", "c?t", "qu??k"];
+    thread::scope(|scope| {
+        for pattern in patterns {
+            let dict = &dict;
+            scope.spawn(move || {
+                let count = dict
+                    .iter_search(DictSearch::from_pattern(pattern).unwrap())
+                    .count();
+                println!("{}: {} matches", pattern, count);
+            });
+        }
+    });
+}
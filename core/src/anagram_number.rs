@@ -1,10 +1,15 @@
 use std::convert::TryFrom;
+use std::fmt;
 
-use crate::{char_map::CharMap, normalized_word::NormalizedWord};
+use crate::{
+    char_freq::CharFreq,
+    char_map::CharMap,
+    normalized_word::{NormalizedChar, NormalizedWord},
+};
 
 type UnsignedAnag = u128;
 
-#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Clone, Copy)]
 pub struct AnagramNumber(UnsignedAnag);
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -16,6 +21,10 @@ pub enum AnagramComparison {
 }
 
 impl AnagramNumber {
+    pub fn value(&self) -> UnsignedAnag {
+        self.0
+    }
+
     pub fn compare(&self, other: AnagramNumber) -> AnagramComparison {
         match (*self, other) {
             (a, b) if a == b => AnagramComparison::Exact,
@@ -24,6 +33,43 @@ impl AnagramNumber {
             _ => AnagramComparison::Unrelated,
         }
     }
+
+    /// The number for these letters plus `ch`, unless it would overflow.
+    pub fn with(&self, ch: NormalizedChar) -> Option<AnagramNumber> {
+        self.0.checked_mul(*PRIMES_MAP.get(ch)).map(AnagramNumber)
+    }
+
+    /// The number for these letters less one `ch`, if they include it.
+    pub fn without(&self, ch: NormalizedChar) -> Option<AnagramNumber> {
+        let prime = *PRIMES_MAP.get(ch);
+        self.0
+            .is_multiple_of(prime)
+            .then(|| AnagramNumber(self.0 / prime))
+    }
+
+    /// The letter counts this number is the product of, recovered by trial
+    /// division by each letter's prime.
+    pub fn to_char_freq(&self) -> CharFreq {
+        let mut freq = CharFreq::new_empty();
+        let mut rest = self.0;
+        for (ch, &prime) in PRIMES_MAP.iter() {
+            while rest.is_multiple_of(prime) {
+                rest /= prime;
+                freq.update(ch, |x| x + 1);
+            }
+        }
+        freq
+    }
+}
+
+/// The letters in alphabetical order, each as many times as it occurs.
+impl fmt::Display for AnagramNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = self.to_char_freq();
+        PRIMES_MAP
+            .iter()
+            .try_for_each(|(ch, _)| (0..freq.get(ch)).try_for_each(|_| write!(f, "{}", ch)))
+    }
 }
 
 // more common letters are lower, to increase the maximum supported english word length
@@ -123,6 +169,35 @@ mod tests {
         assert_eq!(res, Subset)
     }
 
+    #[test]
+    fn factorizes_back_to_letters() {
+        let n = get_anag_num("Banana");
+
+        assert_eq!(
+            n.to_char_freq(),
+            CharFreq::from(&NormalizedWord::from_str_safe("banana"))
+        );
+        assert_eq!(n.to_string(), "AAABNN");
+        assert_eq!(get_anag_num("").to_string(), "");
+        assert_eq!(
+            get_anag_num("zzzzzzzzzzzzzzzzzzz").to_string(),
+            "Z".repeat(19)
+        );
+    }
+
+    #[test]
+    fn adds_and_removes_letters() {
+        let n = get_anag_num("CAT");
+
+        assert_eq!(n.with(NormalizedChar::S), Some(get_anag_num("CATS")));
+        assert_eq!(n.without(NormalizedChar::C), Some(get_anag_num("AT")));
+        assert_eq!(n.without(NormalizedChar::S), None);
+        assert_eq!(
+            get_anag_num("zzzzzzzzzzzzzzzzzzz").with(NormalizedChar::Z),
+            None
+        );
+    }
+
     #[test]
     fn nineteen_letter_word_supported() {
         let n = get_anag_num("zzzzzzzzzzzzzzzzzzz");
@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::char_map::CharMap;
+use crate::dictionary::Dictionary;
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+
+/// Where vowels and consonants fall in a dictionary's words, for setters
+/// balancing a fill. See `Dictionary::analytics`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Analytics {
+    /// For each word length, how many words have each number of vowels.
+    pub vowels_by_length: BTreeMap<usize, BTreeMap<usize, usize>>,
+    /// How many words start with each letter.
+    pub first_letters: CharMap<usize>,
+    /// How many words end with each letter.
+    pub last_letters: CharMap<usize>,
+    // runs of two or more consonants, with how often each occurs
+    clusters: HashMap<NormalizedWord, usize>,
+}
+
+impl Analytics {
+    pub fn of(dict: &Dictionary) -> Self {
+        let mut analytics: Analytics = Default::default();
+        for item in dict.iter() {
+            analytics.add(&item.normalized);
+        }
+        analytics
+    }
+
+    pub fn add(&mut self, word: &NormalizedWord) {
+        let (Some(&first), Some(&last)) = (word.iter_chars().next(), word.iter_chars().last())
+        else {
+            return;
+        };
+        *self.first_letters.get_mut(first) += 1;
+        *self.last_letters.get_mut(last) += 1;
+
+        let vowels = word.iter_chars().filter(|x| x.is_vowel()).count();
+        *self
+            .vowels_by_length
+            .entry(word.len())
+            .or_default()
+            .entry(vowels)
+            .or_default() += 1;
+
+        // a vowel after the end closes the last run
+        let mut cluster = NormalizedWord::default();
+        for &ch in word.iter_chars().chain([&NormalizedChar::A]) {
+            if !ch.is_vowel() {
+                cluster.push(ch);
+                continue;
+            }
+            if cluster.len() > 1 {
+                *self.clusters.entry(cluster.clone()).or_default() += 1;
+            }
+            cluster = Default::default();
+        }
+    }
+
+    /// The share of letters that are vowels in words of `len` letters, or
+    /// `None` if there are none.
+    pub fn vowel_ratio(&self, len: usize) -> Option<f64> {
+        let counts = self.vowels_by_length.get(&len)?;
+        let words: usize = counts.values().sum();
+        let vowels: usize = counts.iter().map(|(vowels, words)| vowels * words).sum();
+        Some(vowels as f64 / (words * len) as f64)
+    }
+
+    /// The `n` commonest runs of consonants, commonest first, with how
+    /// often each occurs. Ties are broken alphabetically.
+    pub fn common_clusters(&self, n: usize) -> Vec<(NormalizedWord, usize)> {
+        let mut clusters: Vec<(NormalizedWord, usize)> = self
+            .clusters
+            .iter()
+            .map(|(x, &count)| (x.clone(), count))
+            .collect();
+        clusters.sort_unstable_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+        clusters.truncate(n);
+        clusters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NormalizedChar::*;
+
+    #[test]
+    fn counts_vowels_clusters_and_ends() {
+        let dict = Dictionary::from_iter(vec!["strength", "street", "tree", "area", "cat", "act"]);
+        let analytics = dict.analytics();
+
+        assert_eq!(
+            analytics.vowels_by_length[&4],
+            BTreeMap::from([(2, 1), (3, 1)])
+        );
+        assert_eq!(analytics.vowel_ratio(4), Some(5.0 / 8.0));
+        assert_eq!(analytics.vowel_ratio(3), Some(1.0 / 3.0));
+        assert_eq!(analytics.vowel_ratio(5), None);
+
+        assert_eq!(
+            analytics.common_clusters(3),
+            [("STR".into(), 2), ("CT".into(), 1), ("NGTH".into(), 1)]
+        );
+
+        assert_eq!(analytics.first_letters.get(S), &2);
+        assert_eq!(analytics.first_letters.get(T), &1);
+        assert_eq!(analytics.last_letters.get(T), &3);
+        assert_eq!(analytics.last_letters.get(A), &1);
+    }
+}
@@ -0,0 +1,269 @@
+use std::io::{self, BufRead};
+use std::ops::{Bound, RangeBounds};
+
+use crate::dictionary::Dictionary;
+use crate::digraph::Digraphs;
+use crate::language::{LanguageProfile, LanguageTag};
+use crate::normalized_word::NormalizedWord;
+
+/// What to do with words containing digits, such as "4x4".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Digits {
+    /// Drop the digits and keep the letters, as `Dictionary::insert` does.
+    #[default]
+    Strip,
+    Reject,
+}
+
+/// What to do with accented letters, such as the é of "café".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Accents {
+    /// Fold them into their base letters, as `Dictionary::insert` does.
+    #[default]
+    Fold,
+    /// Reject words with any letter outside A to Z.
+    Reject,
+}
+
+/// Which words to skip as duplicates of ones already inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dedupe {
+    /// Keep every word, as `Dictionary::insert` does.
+    #[default]
+    None,
+    /// Skip words spelled exactly as an earlier one.
+    Exact,
+    /// Skip words normalizing to the same letters as an earlier one, so
+    /// "can't" after "cant" is skipped.
+    Normalized,
+}
+
+/// Policies for which words a dictionary accepts and how it stores them,
+/// for word lists that `Dictionary::insert` would take too much of.
+///
+/// Words are trimmed, and empty words always rejected. Lengths count
+/// normalized letters, with each digraph of the profile, if any, as one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictionaryBuilder {
+    min_len: usize,
+    max_len: Option<usize>,
+    digits: Digits,
+    accents: Accents,
+    dedupe: Dedupe,
+    anagram_numbers: bool,
+    lang: Option<LanguageTag>,
+    profile: Option<&'static LanguageProfile>,
+    digraphs: Digraphs,
+}
+
+impl Default for DictionaryBuilder {
+    fn default() -> Self {
+        DictionaryBuilder {
+            min_len: 1,
+            max_len: None,
+            digits: Default::default(),
+            accents: Default::default(),
+            dedupe: Default::default(),
+            anagram_numbers: true,
+            lang: None,
+            profile: None,
+            digraphs: Digraphs::new(),
+        }
+    }
+}
+
+impl DictionaryBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn length<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        self.min_len = match range.start_bound() {
+            Bound::Included(&n) => n.max(1),
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 1,
+        };
+        self.max_len = match range.end_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n.saturating_sub(1)),
+            Bound::Unbounded => None,
+        };
+        self
+    }
+
+    pub fn digits(mut self, digits: Digits) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    pub fn accents(mut self, accents: Accents) -> Self {
+        self.accents = accents;
+        self
+    }
+
+    pub fn dedupe(mut self, dedupe: Dedupe) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Whether to compute anagram numbers. Without them anagram searches
+    /// find nothing, but loading is faster and entries smaller.
+    pub fn anagram_numbers(mut self, anagram_numbers: bool) -> Self {
+        self.anagram_numbers = anagram_numbers;
+        self
+    }
+
+    /// Tags every word inserted as being in `lang`.
+    pub fn language(mut self, lang: LanguageTag) -> Self {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Normalizes words by the rules of `profile` and tags them with its
+    /// language, so that German "Straße" is found as STRASSE. Words with
+    /// letters outside its alphabet are rejected, and lengths count its
+    /// digraphs as one letter, as Spanish "chorro" has four tiles.
+    pub fn profile(mut self, profile: &'static LanguageProfile) -> Self {
+        self.profile = Some(profile);
+        self.lang = Some(profile.tag());
+        self.digraphs = profile.digraphs();
+        self
+    }
+
+    /// Inserts `word` into `dict` if the policies accept it, returning
+    /// whether they did.
+    pub fn insert(&self, dict: &mut Dictionary, word: &str) -> bool {
+        let word = word.trim();
+        let normalized = match self.profile {
+            Some(profile) => match profile.try_normalize(word) {
+                Ok(normalized) => normalized,
+                Err(_) => return false,
+            },
+            None => NormalizedWord::from_str_safe(word),
+        };
+        if !self.accepts(dict, word, &normalized) {
+            return false;
+        }
+        dict.insert_entry(word, normalized, None, self.anagram_numbers, self.lang);
+        true
+    }
+
+    fn accepts(&self, dict: &Dictionary, word: &str, normalized: &NormalizedWord) -> bool {
+        if self.digits == Digits::Reject && word.chars().any(|ch| ch.is_ascii_digit()) {
+            return false;
+        }
+        if self.accents == Accents::Reject
+            && word.chars().any(|ch| ch.is_alphabetic() && !ch.is_ascii())
+        {
+            return false;
+        }
+        let len = self.digraphs.token_len(normalized);
+        if len < self.min_len || self.max_len.is_some_and(|max| len > max) {
+            return false;
+        }
+        let existing = dict.find(normalized);
+        match self.dedupe {
+            Dedupe::None => true,
+            Dedupe::Exact => {
+                existing.is_none_or(|entries| entries.iter().all(|x| x.original != word))
+            }
+            Dedupe::Normalized => existing.is_none(),
+        }
+    }
+
+    pub fn build<'a, I: IntoIterator<Item = &'a str>>(&self, words: I) -> Dictionary {
+        let mut dict: Dictionary = Default::default();
+        for word in words {
+            self.insert(&mut dict, word);
+        }
+        dict
+    }
+
+    /// Builds a dictionary from a newline-separated word list.
+    pub fn read<R: BufRead>(&self, reader: R) -> io::Result<Dictionary> {
+        let mut dict: Dictionary = Default::default();
+        for line in reader.lines() {
+            self.insert(&mut dict, &line?);
+        }
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::{GERMAN, ITALIAN, SPANISH};
+
+    fn words(dict: &Dictionary) -> Vec<&str> {
+        dict.iter().map(|x| &x.original[..]).collect()
+    }
+
+    #[test]
+    fn applies_policies() {
+        let input = [
+            "cat",
+            "a",
+            "4x4",
+            "café",
+            "Cat",
+            "cat",
+            "can't",
+            "cant",
+            "catalogue",
+        ];
+
+        assert_eq!(
+            words(&DictionaryBuilder::new().build(input)).len(),
+            input.len()
+        );
+
+        let builder = DictionaryBuilder::new()
+            .length(2..=6)
+            .digits(Digits::Reject)
+            .accents(Accents::Reject);
+        assert_eq!(
+            words(&builder.clone().dedupe(Dedupe::Exact).build(input)),
+            ["can't", "cant", "cat", "Cat"]
+        );
+        assert_eq!(
+            words(&builder.dedupe(Dedupe::Normalized).build(input)),
+            ["can't", "cat"]
+        );
+    }
+
+    #[test]
+    fn normalizes_by_profile() {
+        let dict = DictionaryBuilder::new()
+            .profile(&GERMAN)
+            .build(["Straße", "Strasse", "Bär"]);
+
+        let words: Vec<String> = dict.iter().map(|x| x.normalized.to_string()).collect();
+        assert_eq!(words, ["BAER", "STRASSE", "STRASSE"]);
+        assert_eq!(dict.iter().next().unwrap().lang, Some(GERMAN.tag()));
+    }
+
+    #[test]
+    fn keeps_to_the_profile_alphabet_and_tiles() {
+        let italian = DictionaryBuilder::new()
+            .profile(&ITALIAN)
+            .build(["città", "kiwi", "jazz", "pizza"]);
+        assert_eq!(words(&italian), ["città", "pizza"]);
+
+        let spanish = DictionaryBuilder::new()
+            .profile(&SPANISH)
+            .length(..=4)
+            .build(["chorro", "perros", "llama", "casa"]);
+        assert_eq!(words(&spanish), ["casa", "chorro", "llama"]);
+    }
+
+    #[test]
+    fn can_skip_anagram_numbers() {
+        let dict = DictionaryBuilder::new()
+            .anagram_numbers(false)
+            .read("listen\nsilent\n".as_bytes())
+            .unwrap();
+
+        assert_eq!(dict.iter().count(), 2);
+        assert_eq!(dict.anagrams("tinsel").count(), 0);
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A shared flag for stopping long searches from another thread or a signal
+/// handler. A cancelled search ends as though it had run out of results, so
+/// check `is_cancelled` to tell partial results from complete ones.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clears the flag so the token can be reused for the next search.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        CancellationToken(flag)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::{DictSearch, Dictionary, WordPredicate};
+    use crate::trie::{Trie, TrieSearch};
+
+    #[test]
+    fn clones_share_the_flag() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        clone.reset();
+        assert!(!token.is_cancelled());
+
+        assert_eq!(token, clone);
+        assert_ne!(token, CancellationToken::new());
+        let flag = Arc::new(AtomicBool::new(false));
+        let shared = CancellationToken::from(flag.clone());
+        flag.store(true, Ordering::Relaxed);
+        assert!(shared.is_cancelled());
+    }
+
+    #[test]
+    fn stops_iteration_and_counting_early() {
+        let trie = Trie::from_iter(vec![("A", 1), ("AB", 2), ("B", 3), ("C", 4)]);
+        let token = CancellationToken::new();
+        let search = TrieSearch::default().with_cancellation(token.clone());
+
+        let mut iter = trie.iter_search(search.clone());
+        assert!(iter.next().is_some());
+        token.clone().cancel();
+        assert_eq!(iter.count(), 0);
+        assert_eq!(trie.count_search(&search), 0);
+
+        token.reset();
+        assert_eq!(trie.iter_search(search.clone()).count(), 4);
+        assert_eq!(trie.count_search(&search), 4);
+    }
+
+    #[test]
+    fn stops_dictionary_searches() {
+        let dict = Dictionary::from_iter(vec!["cat", "cart", "coat", "dog"]);
+        let token = CancellationToken::new();
+        let search = || {
+            DictSearch::new(None, WordPredicate::Contains("c".into()))
+                .with_cancellation(token.clone())
+        };
+
+        let mut iter = dict.iter_search(search());
+        assert!(iter.next().is_some());
+        token.cancel();
+        assert_eq!(iter.count(), 0);
+        assert_eq!(dict.count(search()), 0);
+
+        token.reset();
+        assert_eq!(dict.count(search()), 3);
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use crate::char_map::CharMap;
+use crate::char_match::CharMatch;
+use crate::dictionary::{Canonical, DictIterItem, DictSearch, Dictionary, WordPredicate};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::{TriePrefix, TrieSearch};
+
+/// Options for `Dictionary::chains`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainOptions {
+    /// Whether a word may appear more than once, including the start word.
+    pub repeats: bool,
+    /// Try words ending in letters that begin few words first, which leaves
+    /// an opponent the fewest replies.
+    pub rare_endings_first: bool,
+    /// The most chains to return.
+    pub limit: usize,
+    /// The most words to try before giving up, bounding searches from
+    /// starts with many dead ends.
+    pub max_visits: usize,
+}
+
+impl Default for ChainOptions {
+    fn default() -> Self {
+        ChainOptions {
+            repeats: false,
+            rare_endings_first: false,
+            limit: 10,
+            max_visits: 100_000,
+        }
+    }
+}
+
+impl ChainOptions {
+    pub fn with_repeats(mut self) -> Self {
+        self.repeats = true;
+        self
+    }
+
+    pub fn with_rare_endings_first(mut self) -> Self {
+        self.rare_endings_first = true;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn with_max_visits(mut self, max_visits: usize) -> Self {
+        self.max_visits = max_visits;
+        self
+    }
+}
+
+/// Finds chains of `length` words, each beginning with the last letter of
+/// the word before, the first following on from `start`. Fewer than
+/// `limit` are returned if `max_visits` words are tried first.
+pub fn chains<'a>(
+    dict: &'a Dictionary,
+    start: &str,
+    length: usize,
+    options: &ChainOptions,
+) -> Vec<Vec<DictIterItem<'a>>> {
+    let start = NormalizedWord::from_str_safe(start);
+    let Some(&last) = start.iter_chars().last() else {
+        return vec![];
+    };
+
+    let mut search = ChainSearch {
+        dict,
+        options,
+        groups: Default::default(),
+        counts: Default::default(),
+        used: HashSet::new(),
+        chain: vec![],
+        found: vec![],
+        visits: 0,
+    };
+    if !options.repeats {
+        search.used.insert(start);
+    }
+    search.extend(last, length);
+    search.found
+}
+
+struct ChainSearch<'a, 'b> {
+    dict: &'a Dictionary,
+    options: &'b ChainOptions,
+    // one entry per distinct word, by first letter, looked up when first needed
+    groups: CharMap<Option<Vec<DictIterItem<'a>>>>,
+    // how many distinct words begin with each letter, counted when first needed
+    counts: CharMap<Option<usize>>,
+    used: HashSet<NormalizedWord>,
+    chain: Vec<DictIterItem<'a>>,
+    found: Vec<Vec<DictIterItem<'a>>>,
+    visits: usize,
+}
+
+impl<'a> ChainSearch<'a, '_> {
+    // Depth-first, adding `remaining` more words starting with `letter`.
+    fn extend(&mut self, letter: NormalizedChar, remaining: usize) {
+        if remaining == 0 {
+            self.found.push(self.chain.clone());
+            return;
+        }
+        for i in 0..self.group(letter).len() {
+            if self.found.len() >= self.options.limit || self.visits >= self.options.max_visits {
+                return;
+            }
+            let word = self.group(letter)[i].clone();
+            if self.used.contains(&word.normalized) {
+                continue;
+            }
+            self.visits += 1;
+            let next = last_letter(&word);
+            if !self.options.repeats {
+                self.used.insert(word.normalized.clone());
+            }
+            self.chain.push(word);
+            self.extend(next, remaining - 1);
+            if let Some(word) = self.chain.pop() {
+                self.used.remove(&word.normalized);
+            }
+        }
+    }
+
+    fn group(&mut self, letter: NormalizedChar) -> &[DictIterItem<'a>] {
+        if self.groups.get(letter).is_none() {
+            let mut words: Vec<DictIterItem<'a>> =
+                self.dict.iter_search(starting_with(letter)).collect();
+            if self.options.rare_endings_first {
+                words.sort_by_cached_key(|x| self.count(last_letter(x)));
+            }
+            self.groups.set(letter, Some(words));
+        }
+        self.groups.get(letter).as_deref().unwrap_or_default()
+    }
+
+    fn count(&mut self, letter: NormalizedChar) -> usize {
+        if let Some(count) = *self.counts.get(letter) {
+            return count;
+        }
+        let count = self.dict.count(starting_with(letter));
+        self.counts.set(letter, Some(count));
+        count
+    }
+}
+
+// Distinct words beginning with `letter`, in normalized order.
+fn starting_with(letter: NormalizedChar) -> DictSearch {
+    let prefix = TriePrefix::new(vec![CharMatch::Only(letter)]);
+    DictSearch::new(Some(TrieSearch::new(prefix, None)), WordPredicate::None)
+        .with_dedupe(Canonical::First)
+}
+
+fn last_letter(word: &DictIterItem) -> NormalizedChar {
+    word.normalized[word.normalized.len() - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chains_of(words: &[&str], start: &str, length: usize, options: ChainOptions) -> Vec<String> {
+        let dict = Dictionary::from_iter(words.iter().copied());
+        chains(&dict, start, length, &options)
+            .iter()
+            .map(|chain| {
+                let words: Vec<&str> = chain.iter().map(|x| &x.original[..]).collect();
+                words.join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn links_last_letters_to_first() {
+        let words = ["apple", "eel", "egg", "gnat", "lemon", "tea"];
+        let repeating = ChainOptions::default().with_repeats().with_limit(1);
+
+        assert_eq!(
+            chains_of(&words, "Bra", 3, Default::default()),
+            ["apple eel lemon", "apple egg gnat"]
+        );
+        assert!(chains_of(&words, "tea", 4, Default::default()).is_empty());
+        assert_eq!(
+            chains_of(&words, "tea", 5, repeating),
+            ["apple egg gnat tea apple"]
+        );
+    }
+
+    #[test]
+    fn gives_up_after_max_visits() {
+        let words = ["apple", "eel", "egg", "gnat", "lemon", "tea"];
+
+        assert_eq!(chains_of(&words, "bra", 3, Default::default()).len(), 2);
+        // APPLE, EEL and LEMON are tried before the visits run out
+        let capped = ChainOptions::default().with_max_visits(3);
+        assert_eq!(chains_of(&words, "bra", 3, capped), ["apple eel lemon"]);
+        let capped = ChainOptions::default().with_max_visits(2);
+        assert!(chains_of(&words, "bra", 3, capped).is_empty());
+    }
+
+    #[test]
+    fn tries_rare_endings_first() {
+        let words = ["apple", "eel", "egg", "gnat", "lemon", "lynx"];
+        let rare = ChainOptions::default().with_rare_endings_first();
+
+        assert_eq!(
+            chains_of(&words, "bra", 2, Default::default()),
+            ["apple eel", "apple egg"]
+        );
+        assert_eq!(
+            chains_of(&words, "bra", 2, rare),
+            ["apple egg", "apple eel"]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::char_map::CharMap;
 use crate::normalized_word::*;
+use std::fmt;
 use strum::IntoEnumIterator;
 
 type UFreq = u8;
@@ -38,6 +39,14 @@ impl CharFreq {
         res
     }
 
+    /// The fewest letters to add or remove, between the two, to make them
+    /// anagrams of each other.
+    pub fn anagram_distance(&self, other: &CharFreq) -> usize {
+        NormalizedChar::iter()
+            .map(|ch| self.get(ch).abs_diff(other.get(ch)) as usize)
+            .sum()
+    }
+
     pub fn compare(self, other: &CharFreq) -> CharFreqComparisonResult {
         use CharFreqComparison::*;
         let mut comp = Same;
@@ -83,6 +92,16 @@ impl CharFreq {
     }
 }
 
+impl fmt::Display for CharFreq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut counts = self.freqs.iter().filter(|(_, &n)| n > 0);
+        if let Some((ch, n)) = counts.next() {
+            write!(f, "{}:{}", ch, n)?;
+        }
+        counts.try_for_each(|(ch, n)| write!(f, " {}:{}", ch, n))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 enum CharFreqComparison {
     Same,
@@ -109,6 +128,23 @@ mod tests {
         CharFreq::from(&asc)
     }
 
+    #[test]
+    fn counts_letters_to_add_or_remove() {
+        assert_eq!(
+            to_charfreq("ocean").anagram_distance(&to_charfreq("canoe")),
+            0
+        );
+        assert_eq!(
+            to_charfreq("ocean").anagram_distance(&to_charfreq("cane")),
+            1
+        );
+        assert_eq!(
+            to_charfreq("ocean").anagram_distance(&to_charfreq("scone")),
+            2
+        );
+        assert_eq!(to_charfreq("").anagram_distance(&to_charfreq("aa")), 2);
+    }
+
     #[test]
     fn charfreq_counts_a_once() {
         let freqs = to_charfreq("A");
@@ -132,6 +168,13 @@ mod tests {
         assert_eq!(freqs, expected);
     }
 
+    #[test]
+    fn charfreq_displays_nonzero_counts() {
+        let freqs = to_charfreq("BANANA");
+
+        assert_eq!(freqs.to_string(), "A:3 B:1 N:2")
+    }
+
     #[test]
     fn charfreq_comparison_identifies_same() {
         let a = to_charfreq("CAT");
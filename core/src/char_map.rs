@@ -29,6 +29,13 @@ impl<T> CharMap<T> {
         })
     }
 
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (NormalizedChar, &mut T)> {
+        self.array.iter_mut().enumerate().map(|(char_int, value)| {
+            let char: NormalizedChar = num::FromPrimitive::from_usize(char_int).unwrap();
+            (char, value)
+        })
+    }
+
     pub fn iter_values(&self) -> impl Iterator<Item = &T> {
         self.array.iter()
     }
@@ -1,16 +1,28 @@
+use std::convert::TryFrom;
+
+use crate::char_set::CharSet;
 use crate::normalized_word::NormalizedChar;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum CharMatch {
     Only(NormalizedChar),
     Any,
+    /// Any letter except these, as for a Wordle letter known to be elsewhere.
+    Not(CharSet),
 }
 
-impl From<char> for CharMatch {
-    fn from(ch: char) -> Self {
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UnknownSearchChar(pub char);
+
+impl TryFrom<char> for CharMatch {
+    type Error = UnknownSearchChar;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
         match ch {
-            ' ' | '.' | '?' => CharMatch::Any,
-            _ => CharMatch::Only(NormalizedChar::from_char(ch).expect("Unknown search char")),
+            ' ' | '.' | '?' => Ok(CharMatch::Any),
+            _ => NormalizedChar::from_char(ch)
+                .map(CharMatch::Only)
+                .ok_or(UnknownSearchChar(ch)),
         }
     }
 }
@@ -20,6 +32,30 @@ impl CharMatch {
         match self {
             CharMatch::Only(exp) => exp == ch,
             CharMatch::Any => true,
+            CharMatch::Not(excluded) => !excluded.contains(*ch),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_search_chars() {
+        assert_eq!(CharMatch::try_from('?'), Ok(CharMatch::Any));
+        assert_eq!(
+            CharMatch::try_from('b'),
+            Ok(CharMatch::Only(NormalizedChar::B))
+        );
+        assert_eq!(CharMatch::try_from('1'), Err(UnknownSearchChar('1')));
+    }
+
+    #[test]
+    fn not_excludes_letters() {
+        let not_e_or_s = CharMatch::Not(CharSet::from("es"));
+
+        assert!(not_e_or_s.matches(&NormalizedChar::T));
+        assert!(!not_e_or_s.matches(&NormalizedChar::E));
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+use crate::normalized_word::{NormalizedChar, NormalizedWord, ALPHABET_SIZE};
+
+/// A set of letters, stored as one bit per letter.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct CharSet(u32);
+
+impl CharSet {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Every letter from A to Z.
+    pub fn full() -> Self {
+        CharSet::new().complement()
+    }
+
+    /// Capitals that look the same in a mirror held beside them.
+    pub fn vertically_symmetric() -> Self {
+        CharSet::from("AHIMOTUVWXY")
+    }
+
+    /// Capitals that look the same reflected upside down.
+    pub fn horizontally_symmetric() -> Self {
+        CharSet::from("BCDEHIKOX")
+    }
+
+    /// Capitals that are still letters turned half a turn, as for ambigrams:
+    /// H, I, N, O, S, X and Z are unchanged while M and W swap.
+    pub fn rotatable() -> Self {
+        CharSet::from("HIMNOSWXZ")
+    }
+
+    pub fn contains(&self, ch: NormalizedChar) -> bool {
+        self.0 & bit(ch) != 0
+    }
+
+    pub fn insert(&mut self, ch: NormalizedChar) {
+        self.0 |= bit(ch)
+    }
+
+    pub fn remove(&mut self, ch: NormalizedChar) {
+        self.0 &= !bit(ch)
+    }
+
+    /// This set with `ch`.
+    pub fn with(self, ch: NormalizedChar) -> Self {
+        CharSet(self.0 | bit(ch))
+    }
+
+    /// This set without `ch`.
+    pub fn without(self, ch: NormalizedChar) -> Self {
+        CharSet(self.0 & !bit(ch))
+    }
+
+    pub fn union(self, other: CharSet) -> Self {
+        CharSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: CharSet) -> Self {
+        CharSet(self.0 & other.0)
+    }
+
+    /// Every letter not in this set.
+    pub fn complement(self) -> Self {
+        CharSet(!self.0 & ((1 << ALPHABET_SIZE) - 1))
+    }
+
+    pub fn is_subset(&self, other: &CharSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The letters in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = NormalizedChar> + '_ {
+        NormalizedChar::all().filter(|&ch| self.contains(ch))
+    }
+}
+
+fn bit(ch: NormalizedChar) -> u32 {
+    1 << ch as u32
+}
+
+impl FromIterator<NormalizedChar> for CharSet {
+    fn from_iter<I: IntoIterator<Item = NormalizedChar>>(iter: I) -> Self {
+        let mut set = CharSet::new();
+        for ch in iter {
+            set.insert(ch);
+        }
+        set
+    }
+}
+
+/// The letters of a string, ignoring case, accents and anything else.
+impl From<&str> for CharSet {
+    fn from(str: &str) -> Self {
+        str.chars().filter_map(NormalizedChar::from_char).collect()
+    }
+}
+
+impl From<&NormalizedWord> for CharSet {
+    fn from(word: &NormalizedWord) -> Self {
+        word.iter_chars().copied().collect()
+    }
+}
+
+impl fmt::Display for CharSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|ch| write!(f, "{}", ch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NormalizedChar::*;
+
+    #[test]
+    fn holds_each_letter_once() {
+        let mut set = CharSet::from("Éclat, tea");
+        set.remove(C);
+
+        assert_eq!(set.to_string(), "AELT");
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(E) && !set.contains(C));
+        assert!(CharSet::from("tale").is_subset(&set));
+        assert!(!set.is_subset(&CharSet::from("tale").without(T)));
+        assert_eq!(set.union(CharSet::from("c")), CharSet::from("cleat"));
+        assert_eq!(set.intersection(CharSet::from("tic")), CharSet::from("t"));
+        assert_eq!(CharSet::full().len(), ALPHABET_SIZE);
+        assert_eq!(set.complement().len(), 22);
+        assert!(!set.complement().contains(A));
+    }
+}
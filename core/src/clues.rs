@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::normalized_word::NormalizedWord;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clue {
+    pub answer: String,
+    pub clue: String,
+    pub source: String,
+    pub date: String,
+}
+
+#[derive(Debug)]
+pub enum ClueError {
+    Io(io::Error),
+    /// A line without both an answer and a clue, numbered from 1.
+    MissingField(usize),
+}
+
+impl fmt::Display for ClueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClueError::Io(err) => write!(f, "{}", err),
+            ClueError::MissingField(line) => write!(f, "line {} needs an answer and a clue", line),
+        }
+    }
+}
+
+impl From<io::Error> for ClueError {
+    fn from(err: io::Error) -> Self {
+        ClueError::Io(err)
+    }
+}
+
+/// Previously published clues, indexed by normalized answer and by the
+/// words of each clue.
+#[derive(Debug, Default)]
+pub struct ClueDatabase {
+    clues: Vec<Clue>,
+    by_answer: HashMap<NormalizedWord, Vec<usize>>,
+    by_term: HashMap<String, Vec<usize>>,
+}
+
+impl ClueDatabase {
+    /// Loads tab-separated `answer, clue, source, date` lines. Source and
+    /// date may be left off.
+    pub fn from_tsv<R: BufRead>(reader: R) -> Result<Self, ClueError> {
+        let mut db: ClueDatabase = Default::default();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.split('\t').map(str::trim);
+            let (Some(answer), Some(clue)) = (fields.next(), fields.next()) else {
+                return Err(ClueError::MissingField(i + 1));
+            };
+            if answer.is_empty() || clue.is_empty() {
+                return Err(ClueError::MissingField(i + 1));
+            }
+            db.insert(Clue {
+                answer: answer.to_string(),
+                clue: clue.to_string(),
+                source: fields.next().unwrap_or_default().to_string(),
+                date: fields.next().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(db)
+    }
+
+    pub fn insert(&mut self, clue: Clue) {
+        let id = self.clues.len();
+        let answer = NormalizedWord::from_str_safe(&clue.answer);
+        self.by_answer.entry(answer).or_default().push(id);
+        let mut terms: Vec<String> = terms(&clue.clue).collect();
+        terms.sort();
+        terms.dedup();
+        for term in terms {
+            self.by_term.entry(term).or_default().push(id);
+        }
+        self.clues.push(clue);
+    }
+
+    pub fn len(&self) -> usize {
+        self.clues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clues.is_empty()
+    }
+
+    /// Clues for an answer, ignoring case, accents, spaces and punctuation,
+    /// in the order they were added.
+    pub fn clues_for(&self, answer: &str) -> Vec<&Clue> {
+        let answer = NormalizedWord::from_str_safe(answer);
+        self.by_answer
+            .get(&answer)
+            .into_iter()
+            .flatten()
+            .map(|&id| &self.clues[id])
+            .collect()
+    }
+
+    /// Clues containing every word of `query`, ignoring case and punctuation.
+    pub fn search(&self, query: &str) -> Vec<&Clue> {
+        let mut lists: Vec<&Vec<usize>> = vec![];
+        for term in terms(query) {
+            match self.by_term.get(&term) {
+                Some(ids) => lists.push(ids),
+                None => return vec![],
+            }
+        }
+        lists.sort_by_key(|ids| ids.len());
+        let Some((shortest, rest)) = lists.split_first() else {
+            return vec![];
+        };
+        shortest
+            .iter()
+            .filter(|id| rest.iter().all(|ids| ids.binary_search(id).is_ok()))
+            .map(|&id| &self.clues[id])
+            .collect()
+    }
+}
+
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TSV: &str = "\
+        ERIE\tGreat Lake\tNYT\t2021-03-04\n\
+        erie\tCanal city, or lake\tLAT\n\
+        \n\
+        ONO\tHawaiian fish\tNYT\t2020-01-01\n";
+
+    #[test]
+    fn finds_clues_by_answer_and_text() {
+        let db = ClueDatabase::from_tsv(TSV.as_bytes()).unwrap();
+
+        assert_eq!(db.len(), 3);
+        let erie: Vec<&str> = db.clues_for("Erie").iter().map(|c| &c.clue[..]).collect();
+        assert_eq!(erie, vec!["Great Lake", "Canal city, or lake"]);
+        assert_eq!(db.clues_for("ERIE")[1].date, "");
+
+        let lake: Vec<&str> = db.search("LAKE").iter().map(|c| &c.answer[..]).collect();
+        assert_eq!(lake, vec!["ERIE", "erie"]);
+        assert_eq!(db.search("great lake").len(), 1);
+        assert!(db.search("lake fish").is_empty());
+        assert!(db.search("").is_empty());
+    }
+
+    #[test]
+    fn rejects_lines_without_a_clue() {
+        let err = ClueDatabase::from_tsv("ERIE\tGreat Lake\nONO\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, ClueError::MissingField(2)));
+    }
+}
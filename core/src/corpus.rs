@@ -0,0 +1,118 @@
+use crate::char_map::CharMap;
+use crate::dictionary::Dictionary;
+use crate::language::ENGLISH;
+use crate::normalized_word::NormalizedWord;
+
+/// Letter counts of a body of text or a word list, for judging how much it
+/// looks like English.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Corpus {
+    counts: CharMap<u64>,
+    total: u64,
+}
+
+/// How far a corpus's letters are from the English distribution. Both
+/// measures are zero for a perfect match, or for an empty corpus.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistributionComparison {
+    /// Pearson's chi-squared statistic over the letter counts, which grows
+    /// with the size of the corpus.
+    pub chi_squared: f64,
+    /// The Kullback-Leibler divergence of the corpus from English in nats,
+    /// which does not.
+    pub kl_divergence: f64,
+}
+
+impl Corpus {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Counts the letters of `text`, ignoring anything else.
+    pub fn from_text(text: &str) -> Self {
+        let mut corpus = Corpus::new();
+        corpus.add(&NormalizedWord::from_str_safe(text));
+        corpus
+    }
+
+    /// Counts the letters of every entry in `dict`.
+    pub fn from_dictionary(dict: &Dictionary) -> Self {
+        let mut corpus = Corpus::new();
+        for item in dict.iter() {
+            corpus.add(&item.normalized);
+        }
+        corpus
+    }
+
+    pub fn add(&mut self, word: &NormalizedWord) {
+        for &ch in word.iter_chars() {
+            *self.counts.get_mut(ch) += 1;
+        }
+        self.total += word.len() as u64;
+    }
+
+    pub fn counts(&self) -> &CharMap<u64> {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Compares the letter distribution with `language::ENGLISH`. On a
+    /// candidate decryption, lower is more likely to be right.
+    pub fn compare_to_english(&self) -> DistributionComparison {
+        let mut comparison = DistributionComparison {
+            chi_squared: 0.0,
+            kl_divergence: 0.0,
+        };
+        if self.total == 0 {
+            return comparison;
+        }
+        let total = self.total as f64;
+        for (ch, &count) in self.counts.iter() {
+            let expected = ENGLISH.frequency(ch) as f64 / 100.0;
+            let observed = count as f64 / total;
+            comparison.chi_squared +=
+                (count as f64 - expected * total).powi(2) / (expected * total);
+            if count > 0 {
+                comparison.kl_divergence += observed * (observed / expected).ln();
+            }
+        }
+        comparison
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_word::NormalizedChar;
+
+    #[test]
+    fn english_is_closer_than_gibberish() {
+        let english = Corpus::from_text(
+            "It was the best of times, it was the worst of times, it was the age of wisdom, \
+             it was the age of foolishness",
+        )
+        .compare_to_english();
+        let gibberish = Corpus::from_text("Qzxj vkqw zzyx jqxk wvzq xjkz").compare_to_english();
+
+        assert!(english.chi_squared < gibberish.chi_squared);
+        assert!(english.kl_divergence < gibberish.kl_divergence);
+        assert!(english.kl_divergence < 0.5);
+
+        let empty = Corpus::from_text("1, 2, 3").compare_to_english();
+        assert_eq!(empty.chi_squared, 0.0);
+        assert_eq!(empty.kl_divergence, 0.0);
+    }
+
+    #[test]
+    fn counts_a_word_list() {
+        let dict = Dictionary::from_iter(vec!["tea", "eat", "ate"]);
+        let corpus = Corpus::from_dictionary(&dict);
+
+        assert_eq!(corpus.total(), 9);
+        assert_eq!(corpus.counts().get(NormalizedChar::E), &3);
+        assert!(corpus.compare_to_english().kl_divergence > 0.0);
+    }
+}
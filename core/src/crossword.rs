@@ -0,0 +1,295 @@
+pub mod fill;
+pub mod theme;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::scrabble::Direction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Block,
+    Empty,
+    Letter(NormalizedChar),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    UnknownChar(char),
+    /// A row whose length differs from the first row's.
+    RaggedRow(usize),
+}
+
+/// A crossword grid, parsed from lines of `#` for blocks, `.` for empty
+/// squares and letters for filled squares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    width: usize,
+    cells: Vec<Cell>,
+}
+
+/// A run of two or more white squares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+    pub cells: Vec<Cell>,
+}
+
+impl Entry {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The answer, once every square is filled.
+    pub fn answer(&self) -> Option<NormalizedWord> {
+        self.cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Letter(ch) => Some(*ch),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The entry as a search pattern, with `?` for empty squares.
+    pub fn pattern(&self) -> String {
+        self.cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Letter(ch) => ch.to_string(),
+                _ => "?".to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Quality measures for a grid and its fill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridReport {
+    pub width: usize,
+    pub height: usize,
+    /// Blocks mirror each other under a half turn about the centre.
+    pub rotationally_symmetric: bool,
+    pub across_words: usize,
+    pub down_words: usize,
+    pub blocks: usize,
+    pub block_ratio: f64,
+    pub average_word_length: f64,
+    /// White squares not crossed by an entry in both directions.
+    pub unchecked: usize,
+    /// The share of white squares belonging to both an across and a down entry.
+    pub checked_ratio: f64,
+    /// Complete answers appearing more than once, with their counts.
+    pub duplicates: Vec<(NormalizedWord, usize)>,
+}
+
+impl Grid {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> Cell {
+        self.cells[row * self.width + col]
+    }
+
+    pub fn set_cell(&mut self, row: usize, col: usize, cell: Cell) {
+        self.cells[row * self.width + col] = cell;
+    }
+
+    fn is_white(&self, row: usize, col: usize) -> bool {
+        row < self.height() && col < self.width && self.cell(row, col) != Cell::Block
+    }
+
+    /// Every entry, across entries first, each in reading order.
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries = vec![];
+        for direction in [Direction::Across, Direction::Down] {
+            let step = |row: usize, col: usize| match direction {
+                Direction::Across => (row, col + 1),
+                Direction::Down => (row + 1, col),
+            };
+            for row in 0..self.height() {
+                for col in 0..self.width {
+                    let before = match direction {
+                        Direction::Across => col.checked_sub(1).map(|c| (row, c)),
+                        Direction::Down => row.checked_sub(1).map(|r| (r, col)),
+                    };
+                    let starts =
+                        self.is_white(row, col) && before.is_none_or(|(r, c)| !self.is_white(r, c));
+                    if !starts {
+                        continue;
+                    }
+                    let mut cells = vec![];
+                    let (mut r, mut c) = (row, col);
+                    while self.is_white(r, c) {
+                        cells.push(self.cell(r, c));
+                        (r, c) = step(r, c);
+                    }
+                    if cells.len() > 1 {
+                        entries.push(Entry {
+                            row,
+                            col,
+                            direction,
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+        entries
+    }
+
+    pub fn is_rotationally_symmetric(&self) -> bool {
+        let last = self.cells.len().saturating_sub(1);
+        (0..self.cells.len())
+            .all(|i| (self.cells[i] == Cell::Block) == (self.cells[last - i] == Cell::Block))
+    }
+
+    pub fn report(&self) -> GridReport {
+        let entries = self.entries();
+        let (across, down): (Vec<&Entry>, Vec<&Entry>) = entries
+            .iter()
+            .partition(|e| e.direction == Direction::Across);
+
+        // how many entries cross each square, one per direction at most
+        let mut crossings = vec![0; self.cells.len()];
+        for entry in entries.iter() {
+            for i in 0..entry.len() {
+                let (row, col) = match entry.direction {
+                    Direction::Across => (entry.row, entry.col + i),
+                    Direction::Down => (entry.row + i, entry.col),
+                };
+                crossings[row * self.width + col] += 1;
+            }
+        }
+        let blocks = self.cells.iter().filter(|&&c| c == Cell::Block).count();
+        let white = self.cells.len() - blocks;
+        let checked = crossings.iter().filter(|&&n| n == 2).count();
+
+        let mut answers: BTreeMap<NormalizedWord, usize> = BTreeMap::new();
+        for answer in entries.iter().filter_map(Entry::answer) {
+            *answers.entry(answer).or_default() += 1;
+        }
+
+        GridReport {
+            width: self.width,
+            height: self.height(),
+            rotationally_symmetric: self.is_rotationally_symmetric(),
+            across_words: across.len(),
+            down_words: down.len(),
+            blocks,
+            block_ratio: ratio(blocks, self.cells.len()),
+            average_word_length: ratio(entries.iter().map(Entry::len).sum(), entries.len()),
+            unchecked: white - checked,
+            checked_ratio: ratio(checked, white),
+            duplicates: answers.into_iter().filter(|&(_, n)| n > 1).collect(),
+        }
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// Writes the grid as `FromStr` reads it, one line per row.
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                match cell {
+                    Cell::Block => write!(f, "#")?,
+                    Cell::Empty => write!(f, ".")?,
+                    Cell::Letter(ch) => write!(f, "{}", ch)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Grid {
+    type Err = GridError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut cells = vec![];
+        let rows = str.lines().map(str::trim).filter(|line| !line.is_empty());
+        for (i, row) in rows.enumerate() {
+            let start = cells.len();
+            for ch in row.chars() {
+                cells.push(match ch {
+                    '#' => Cell::Block,
+                    '.' | '?' => Cell::Empty,
+                    _ => Cell::Letter(
+                        NormalizedChar::from_char(ch).ok_or(GridError::UnknownChar(ch))?,
+                    ),
+                });
+            }
+            let len = cells.len() - start;
+            if *width.get_or_insert(len) != len {
+                return Err(GridError::RaggedRow(i));
+            }
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            cells,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_on_grid() {
+        let grid: Grid = "
+            CAT#
+            A..O
+            TOT#
+        "
+        .parse()
+        .unwrap();
+
+        let report = grid.report();
+        assert!(!report.rotationally_symmetric);
+        assert_eq!((report.across_words, report.down_words), (3, 3));
+        assert_eq!(report.blocks, 2);
+        assert_eq!(report.unchecked, 1);
+        assert_eq!(report.checked_ratio, 9.0 / 10.0);
+        assert_eq!(report.duplicates, vec![("CAT".into(), 2)]);
+
+        let entries = grid.entries();
+        assert_eq!(entries[1].pattern(), "A??O");
+        assert_eq!(entries[1].answer(), None);
+    }
+
+    #[test]
+    fn checks_symmetry() {
+        let symmetric: Grid = "#..\n...\n..#".parse().unwrap();
+        let asymmetric: Grid = "#..\n...\n#..".parse().unwrap();
+
+        assert!(symmetric.is_rotationally_symmetric());
+        assert!(!asymmetric.is_rotationally_symmetric());
+        assert_eq!(symmetric.to_string(), "#..\n...\n..#\n");
+        assert_eq!("ab\nc".parse::<Grid>(), Err(GridError::RaggedRow(1)));
+        assert_eq!("a*".parse::<Grid>(), Err(GridError::UnknownChar('*')));
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::{Cell, Grid};
+use crate::dictionary::Dictionary;
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::scrabble::Direction;
+
+/// Counts for tuning a `CandidateCache`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// Lookups answered by the slot's own list.
+    pub slot_hits: usize,
+    /// Lookups answered by an earlier walk for the same length and letters,
+    /// by this slot before backtracking or by another slot.
+    pub pattern_hits: usize,
+    /// Lookups that walked the dictionary.
+    pub misses: usize,
+    /// Slot lists dropped because a letter of the slot changed.
+    pub invalidations: usize,
+}
+
+// a length and the letters fixed at positions within it
+type Pattern = (usize, Vec<(usize, NormalizedChar)>);
+
+/// Candidate answers for the slots of a grid being filled, so that
+/// backtracking doesn't repeat identical dictionary walks.
+///
+/// Each slot keeps its list until `invalidate` is called for it, which a
+/// filler does whenever a letter of the slot is placed or removed. Lists
+/// are also kept by length and fixed letters, for any slot asking again.
+#[derive(Debug, Default)]
+pub struct CandidateCache {
+    by_pattern: HashMap<Pattern, Rc<[NormalizedWord]>>,
+    by_slot: Vec<Option<Rc<[NormalizedWord]>>>,
+    stats: CacheStats,
+}
+
+impl CandidateCache {
+    pub fn new(slots: usize) -> Self {
+        CandidateCache {
+            by_slot: vec![None; slots],
+            ..Default::default()
+        }
+    }
+
+    /// The words for `slot`, of `len` letters with the given letters at
+    /// the given positions, each normalized word once.
+    pub fn get(
+        &mut self,
+        dict: &Dictionary,
+        slot: usize,
+        len: usize,
+        constraints: &[(usize, NormalizedChar)],
+    ) -> Rc<[NormalizedWord]> {
+        if let Some(words) = &self.by_slot[slot] {
+            self.stats.slot_hits += 1;
+            return words.clone();
+        }
+        let pattern = (len, constraints.to_vec());
+        let words = match self.by_pattern.get(&pattern) {
+            Some(words) => {
+                self.stats.pattern_hits += 1;
+                words.clone()
+            }
+            None => {
+                self.stats.misses += 1;
+                let mut words: Vec<NormalizedWord> = dict
+                    .candidates(len, constraints)
+                    .map(|x| x.normalized)
+                    .collect();
+                words.dedup();
+                let words: Rc<[NormalizedWord]> = words.into();
+                self.by_pattern.insert(pattern, words.clone());
+                words
+            }
+        };
+        self.by_slot[slot] = Some(words.clone());
+        words
+    }
+
+    pub fn invalidate(&mut self, slot: usize) {
+        if self.by_slot[slot].take().is_some() {
+            self.stats.invalidations += 1;
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+// Letters placed by one answer, and the answers completed by them.
+struct Placement {
+    cells: Vec<usize>,
+    answers: Vec<NormalizedWord>,
+}
+
+/// Fills the empty squares of a grid with dictionary words by backtracking,
+/// never using an answer twice.
+///
+/// The slot with the fewest candidates is filled next, and a word is only
+/// placed if every slot it crosses still has a candidate. With restarts, a
+/// fill that backtracks too often starts over, trying words in a different
+/// order drawn from the seed.
+pub struct Filler<'a> {
+    dict: &'a Dictionary,
+    grid: Grid,
+    // the cells of each entry, as indexes into the grid's cells
+    slots: Vec<Vec<usize>>,
+    // the slots through each cell
+    cell_slots: Vec<Vec<usize>>,
+    used: HashSet<NormalizedWord>,
+    cache: CandidateCache,
+    rng: Option<StdRng>,
+    restarts: usize,
+    backtrack_limit: Option<usize>,
+    backtracks: usize,
+    attempts: usize,
+}
+
+impl<'a> Filler<'a> {
+    pub fn new(grid: Grid, dict: &'a Dictionary) -> Self {
+        let slots: Vec<Vec<usize>> = grid
+            .entries()
+            .iter()
+            .map(|entry| {
+                (0..entry.len())
+                    .map(|i| match entry.direction {
+                        Direction::Across => entry.row * grid.width + entry.col + i,
+                        Direction::Down => (entry.row + i) * grid.width + entry.col,
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut cell_slots = vec![vec![]; grid.cells.len()];
+        for (slot, cells) in slots.iter().enumerate() {
+            for &cell in cells {
+                cell_slots[cell].push(slot);
+            }
+        }
+        let mut filler = Filler {
+            dict,
+            grid,
+            cache: CandidateCache::new(slots.len()),
+            slots,
+            cell_slots,
+            used: Default::default(),
+            rng: None,
+            restarts: 0,
+            backtrack_limit: None,
+            backtracks: 0,
+            attempts: 0,
+        };
+        filler.used = (0..filler.slots.len())
+            .filter_map(|slot| filler.answer(slot))
+            .collect();
+        filler
+    }
+
+    /// Tries candidates for each slot starting from a point drawn from
+    /// `seed`, rather than in dictionary order.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Filler {
+            rng: Some(StdRng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+
+    /// Gives up on an attempt after `backtracks` words have been taken
+    /// back, then starts over up to `restarts` times. Restarts try words in
+    /// a new order only with a seed.
+    pub fn with_restarts(self, restarts: usize, backtracks: usize) -> Self {
+        Filler {
+            restarts,
+            backtrack_limit: Some(backtracks),
+            ..self
+        }
+    }
+
+    /// Fills every empty square, returning the filled grid, or `None` with
+    /// the grid unchanged if no fill exists or none was found within the
+    /// restarts allowed.
+    pub fn fill(&mut self) -> Option<&Grid> {
+        for _ in 0..=self.restarts {
+            self.attempts += 1;
+            self.backtracks = 0;
+            if self.solve() {
+                return Some(&self.grid);
+            }
+        }
+        None
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Attempts made by the last calls to `fill`, counting restarts.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    fn solve(&mut self) -> bool {
+        let Some((slot, candidates)) = self.most_constrained() else {
+            return true;
+        };
+        let start = match (&mut self.rng, candidates.len()) {
+            (Some(rng), len) if len > 0 => rng.gen_range(0..len),
+            _ => 0,
+        };
+        // from `start` to the end, then wrapping round to before it
+        let (before, after) = candidates.split_at(start);
+        for word in after.iter().chain(before) {
+            if self.used.contains(word) {
+                continue;
+            }
+            if let Some(placement) = self.place(slot, word) {
+                if self.solve() {
+                    return true;
+                }
+                self.unplace(placement);
+                self.backtracks += 1;
+                if self.backtrack_limit.is_some_and(|x| self.backtracks > x) {
+                    return false;
+                }
+            }
+        }
+        false
+    }
+
+    // The unfilled slot with the fewest candidates, and its candidates.
+    fn most_constrained(&mut self) -> Option<(usize, Rc<[NormalizedWord]>)> {
+        let mut best: Option<(usize, Rc<[NormalizedWord]>)> = None;
+        for slot in 0..self.slots.len() {
+            if self.is_full(slot) {
+                continue;
+            }
+            let candidates = self.candidates(slot);
+            if best
+                .as_ref()
+                .is_none_or(|(_, x)| candidates.len() < x.len())
+            {
+                let done = candidates.is_empty();
+                best = Some((slot, candidates));
+                if done {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn is_full(&self, slot: usize) -> bool {
+        self.slots[slot]
+            .iter()
+            .all(|&cell| self.grid.cells[cell] != Cell::Empty)
+    }
+
+    fn candidates(&mut self, slot: usize) -> Rc<[NormalizedWord]> {
+        let cells = &self.slots[slot];
+        let constraints: Vec<_> = cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &cell)| match self.grid.cells[cell] {
+                Cell::Letter(ch) => Some((i, ch)),
+                _ => None,
+            })
+            .collect();
+        self.cache.get(self.dict, slot, cells.len(), &constraints)
+    }
+
+    fn answer(&self, slot: usize) -> Option<NormalizedWord> {
+        let letters = self.slots[slot]
+            .iter()
+            .map(|&cell| match self.grid.cells[cell] {
+                Cell::Letter(ch) => Some(ch),
+                _ => None,
+            });
+        letters.collect()
+    }
+
+    // Writes `word` into `slot`, unless a crossing answer it completes isn't
+    // a word, any answer it completes is already used, or it leaves a
+    // crossing slot without candidates.
+    fn place(&mut self, slot: usize, word: &NormalizedWord) -> Option<Placement> {
+        let mut placement = Placement {
+            cells: vec![],
+            answers: vec![],
+        };
+        for (&cell, &ch) in self.slots[slot].iter().zip(word.iter_chars()) {
+            if self.grid.cells[cell] == Cell::Empty {
+                self.grid.cells[cell] = Cell::Letter(ch);
+                placement.cells.push(cell);
+            }
+        }
+        let mut completed = vec![slot];
+        for &cell in placement.cells.iter() {
+            completed.extend(self.cell_slots[cell].iter().filter(|&&x| x != slot));
+        }
+        for other in completed {
+            let Some(answer) = self.answer(other) else {
+                continue;
+            };
+            let valid = other == slot || self.dict.find(&answer).is_some();
+            if !valid || !self.used.insert(answer.clone()) {
+                self.unplace(placement);
+                return None;
+            }
+            placement.answers.push(answer);
+        }
+        self.invalidate(&placement.cells);
+        let crossing: Vec<usize> = placement
+            .cells
+            .iter()
+            .flat_map(|&cell| self.cell_slots[cell].iter().copied())
+            .filter(|&x| !self.is_full(x))
+            .collect();
+        for other in crossing {
+            let candidates = self.candidates(other);
+            if candidates.iter().all(|x| self.used.contains(x)) {
+                self.unplace(placement);
+                return None;
+            }
+        }
+        Some(placement)
+    }
+
+    fn unplace(&mut self, placement: Placement) {
+        for &cell in placement.cells.iter() {
+            self.grid.cells[cell] = Cell::Empty;
+        }
+        for answer in placement.answers.iter() {
+            self.used.remove(answer);
+        }
+        self.invalidate(&placement.cells);
+    }
+
+    fn invalidate(&mut self, cells: &[usize]) {
+        for &cell in cells {
+            for &slot in self.cell_slots[cell].iter() {
+                self.cache.invalidate(slot);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_grid() {
+        let dict = Dictionary::from_iter(vec!["cat", "dog", "ore", "co", "ar", "te", "ox"]);
+        let grid: Grid = "...\n...".parse().unwrap();
+
+        let mut filler = Filler::new(grid, &dict);
+        assert_eq!(filler.fill().unwrap().to_string(), "CAT\nORE\n");
+        assert!(filler.stats().misses > 0);
+        assert!(filler.stats().invalidations > 0);
+
+        let grid: Grid = "..\n..".parse().unwrap();
+        let mut filler = Filler::new(grid.clone(), &dict);
+        assert_eq!(filler.fill(), None);
+        assert_eq!(filler.grid(), &grid);
+    }
+
+    #[test]
+    fn seeds_word_order_and_limits_restarts() {
+        let dict = Dictionary::from_iter(vec!["cat", "bat", "ore", "co", "bo", "ar", "te"]);
+        let grid: Grid = "...\n...".parse().unwrap();
+        let fill = |seed| {
+            let mut filler = Filler::new(grid.clone(), &dict).with_seed(seed);
+            filler.fill().unwrap().to_string()
+        };
+
+        let fills: HashSet<String> = (0..20).map(fill).collect();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fill(3), fill(3));
+
+        let grid: Grid = "..\n..".parse().unwrap();
+        let mut filler = Filler::new(grid, &dict).with_restarts(2, 0);
+        assert_eq!(filler.fill(), None);
+        assert_eq!(filler.attempts(), 3);
+    }
+
+    #[test]
+    fn caches_by_slot_and_pattern() {
+        let dict = Dictionary::from_iter(vec!["cat", "cot", "Cot", "dog"]);
+        let mut cache = CandidateCache::new(2);
+        let c = [(0, NormalizedChar::C)];
+
+        assert_eq!(cache.get(&dict, 0, 3, &c).len(), 2);
+        cache.get(&dict, 0, 3, &c);
+        cache.get(&dict, 1, 3, &c);
+        cache.invalidate(0);
+        cache.get(&dict, 0, 3, &[]);
+
+        let stats = cache.stats();
+        assert_eq!(
+            (stats.slot_hits, stats.pattern_hits, stats.misses),
+            (1, 1, 2)
+        );
+        assert_eq!(stats.invalidations, 1);
+    }
+}
@@ -0,0 +1,350 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::{Cell, Grid};
+use crate::dictionary::Dictionary;
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::scrabble::Direction;
+
+/// The shortest entry a grid is usually allowed.
+const MIN_LEN: usize = 3;
+
+/// One theme answer placed in a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeEntry {
+    pub answer: NormalizedWord,
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+/// Theme answers placed symmetrically in a square grid, scored by how many
+/// words could cross them: the sum, over each column of theme letters,
+/// of the log of the number of words that could run through it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeLayout {
+    pub size: usize,
+    pub entries: Vec<ThemeEntry>,
+    pub score: f64,
+}
+
+impl ThemeLayout {
+    /// The grid with the theme answers filled in, blocks at either end of
+    /// each, and every other square empty, ready for a `Filler`.
+    pub fn grid(&self) -> Grid {
+        let mut grid = Grid {
+            width: self.size,
+            cells: vec![Cell::Empty; self.size * self.size],
+        };
+        for entry in self.entries.iter() {
+            let at = |i: usize| match entry.direction {
+                Direction::Across => (entry.row, entry.col + i),
+                Direction::Down => (entry.row + i, entry.col),
+            };
+            for (i, &ch) in entry.answer.iter_chars().enumerate() {
+                let (row, col) = at(i);
+                grid.set_cell(row, col, Cell::Letter(ch));
+            }
+            let before = match entry.direction {
+                Direction::Across => entry.col.checked_sub(1).map(|col| (entry.row, col)),
+                Direction::Down => entry.row.checked_sub(1).map(|row| (row, entry.col)),
+            };
+            let after =
+                Some(at(entry.answer.len())).filter(|&(r, c)| r < self.size && c < self.size);
+            for (row, col) in before.into_iter().chain(after) {
+                grid.set_cell(row, col, Cell::Block);
+            }
+        }
+        grid
+    }
+}
+
+/// Layouts of `answers` in a grid of `size` squares a side, all running in
+/// `direction`, symmetric under a half turn, best scoring first, at most
+/// `limit` of them.
+///
+/// Answers of equal length are paired in mirrored rows, and with an odd
+/// number of answers one goes in the middle row, centred. Layouts where a
+/// theme letter, or a column of letters in stacked answers, has no word of
+/// `MIN_LEN` letters or more to cross it are left out.
+pub fn theme_layouts(
+    dict: &Dictionary,
+    size: usize,
+    answers: &[&str],
+    direction: Direction,
+    limit: usize,
+) -> Vec<ThemeLayout> {
+    let answers: Vec<NormalizedWord> = answers
+        .iter()
+        .map(|x| NormalizedWord::from_str_safe(x))
+        .collect();
+    if answers.iter().any(|x| x.len() < MIN_LEN || x.len() > size) {
+        return vec![];
+    }
+    let mut search = LayoutSearch {
+        dict,
+        size,
+        counts: HashMap::new(),
+        layouts: vec![],
+    };
+    for (center, pairs) in pairings(&answers, size) {
+        let mut rows = vec![None; size];
+        if let Some(center) = center {
+            rows[size / 2] = Some((center, (size - center.len()) / 2));
+        }
+        search.place(&pairs, &mut rows, true);
+    }
+
+    let mut layouts = search.layouts;
+    layouts.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    layouts.truncate(limit);
+    for layout in layouts.iter_mut() {
+        layout.entries.sort_by_key(|x| x.row);
+        if direction == Direction::Down {
+            for entry in layout.entries.iter_mut() {
+                (entry.row, entry.col) = (entry.col, entry.row);
+                entry.direction = Direction::Down;
+            }
+        }
+    }
+    layouts
+}
+
+type Pairs<'a> = [(&'a NormalizedWord, &'a NormalizedWord)];
+type Pairing<'a> = (
+    Option<&'a NormalizedWord>,
+    Vec<(&'a NormalizedWord, &'a NormalizedWord)>,
+);
+
+// Every way to split the answers into pairs of equal length, with one left
+// for the middle row when their number is odd.
+fn pairings(answers: &[NormalizedWord], size: usize) -> Vec<Pairing<'_>> {
+    let mut found = vec![];
+    let indexes: Vec<usize> = (0..answers.len()).collect();
+    if answers.len().is_multiple_of(2) {
+        pair_up(answers, &indexes, &mut vec![], &mut |pairs| {
+            found.push((None, pairs))
+        });
+        return found;
+    }
+    for (i, center) in answers.iter().enumerate() {
+        // centring needs the same number of squares either side
+        if size.is_multiple_of(2) || !(size - center.len()).is_multiple_of(2) {
+            continue;
+        }
+        let rest: Vec<usize> = indexes.iter().copied().filter(|&x| x != i).collect();
+        pair_up(answers, &rest, &mut vec![], &mut |pairs| {
+            found.push((Some(center), pairs))
+        });
+    }
+    found
+}
+
+fn pair_up<'a>(
+    answers: &'a [NormalizedWord],
+    left: &[usize],
+    pairs: &mut Vec<(&'a NormalizedWord, &'a NormalizedWord)>,
+    found: &mut dyn FnMut(Vec<(&'a NormalizedWord, &'a NormalizedWord)>),
+) {
+    let Some((&first, rest)) = left.split_first() else {
+        found(pairs.clone());
+        return;
+    };
+    for (j, &other) in rest.iter().enumerate() {
+        if answers[other].len() != answers[first].len() {
+            continue;
+        }
+        let remaining: Vec<usize> = rest[..j].iter().chain(&rest[j + 1..]).copied().collect();
+        pairs.push((&answers[first], &answers[other]));
+        pair_up(answers, &remaining, pairs, found);
+        pairs.pop();
+    }
+}
+
+struct LayoutSearch<'a> {
+    dict: &'a Dictionary,
+    size: usize,
+    // words of a length with letters at positions, counted once
+    counts: HashMap<(usize, Vec<(usize, NormalizedChar)>), usize>,
+    layouts: Vec<ThemeLayout>,
+}
+
+// The answer in each row, if any, and the column it starts at.
+type Rows<'w> = [Option<(&'w NormalizedWord, usize)>];
+
+impl LayoutSearch<'_> {
+    fn place<'w>(&mut self, pairs: &Pairs<'w>, rows: &mut Rows<'w>, first: bool) {
+        let Some((&(a, b), rest)) = pairs.split_first() else {
+            self.score(rows);
+            return;
+        };
+        // turning the whole grid swaps every pair, so fix the first pair's
+        // order to skip those duplicates
+        let orders = if first { 1 } else { 2 };
+        let len = a.len();
+        for row in 0..self.size / 2 {
+            let mirror = self.size - 1 - row;
+            if rows[row].is_some() {
+                continue;
+            }
+            for col in 0..=self.size - len {
+                for (top, bottom) in [(a, b), (b, a)].into_iter().take(orders) {
+                    rows[row] = Some((top, col));
+                    rows[mirror] = Some((bottom, self.size - col - len));
+                    self.place(rest, rows, false);
+                }
+            }
+            rows[row] = None;
+            rows[mirror] = None;
+        }
+    }
+
+    fn score(&mut self, rows: &Rows) {
+        let size = self.size;
+        let mut cells = vec![Cell::Empty; size * size];
+        for (row, placed) in rows.iter().enumerate() {
+            let Some((word, col)) = placed else { continue };
+            for (i, &ch) in word.iter_chars().enumerate() {
+                cells[row * size + col + i] = Cell::Letter(ch);
+            }
+            if *col > 0 {
+                cells[row * size + col - 1] = Cell::Block;
+            }
+            if col + word.len() < size {
+                cells[row * size + col + word.len()] = Cell::Block;
+            }
+        }
+
+        let mut score = 0.0;
+        for col in 0..size {
+            let mut row = 0;
+            while row < size {
+                let top = row;
+                let mut chain = vec![];
+                while let Some(&Cell::Letter(ch)) = cells.get(row * size + col) {
+                    chain.push(ch);
+                    row += 1;
+                }
+                if chain.is_empty() {
+                    row += 1;
+                    continue;
+                }
+                let crossings = self.crossings(&cells, col, top, &chain);
+                if crossings == 0 {
+                    return;
+                }
+                score += (crossings as f64).ln_1p();
+            }
+        }
+
+        let entries = rows.iter().enumerate().filter_map(|(row, placed)| {
+            placed.map(|(word, col)| ThemeEntry {
+                answer: word.clone(),
+                row,
+                col,
+                direction: Direction::Across,
+            })
+        });
+        self.layouts.push(ThemeLayout {
+            size,
+            entries: entries.collect(),
+            score,
+        });
+    }
+
+    // The number of words that could run down through `chain`, the theme
+    // letters of `col` from row `top`, reaching up to two empty squares
+    // beyond them.
+    fn crossings(
+        &mut self,
+        cells: &[Cell],
+        col: usize,
+        top: usize,
+        chain: &[NormalizedChar],
+    ) -> usize {
+        let size = self.size;
+        let dict = self.dict;
+        let cell = |row: usize| cells[row * size + col];
+        // a word can stop at the edge or at a square that can be a block
+        let can_end = |row: Option<usize>| {
+            row.filter(|&x| x < size)
+                .is_none_or(|x| !matches!(cell(x), Cell::Letter(_)))
+        };
+        let bottom = top + chain.len() - 1;
+        let mut count = 0;
+        for len in chain.len().max(MIN_LEN)..=chain.len() + 2 {
+            for before in 0..=len - chain.len() {
+                let Some(start) = top.checked_sub(before) else {
+                    continue;
+                };
+                let end = bottom + len - chain.len() - before;
+                let fits = end < size
+                    && (start..top)
+                        .chain(bottom + 1..=end)
+                        .all(|x| cell(x) == Cell::Empty)
+                    && can_end(start.checked_sub(1))
+                    && can_end(Some(end + 1));
+                if !fits {
+                    continue;
+                }
+                let letters: Vec<_> = chain
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &ch)| (before + i, ch))
+                    .collect();
+                count += *self
+                    .counts
+                    .entry((len, letters))
+                    .or_insert_with_key(|(len, letters)| dict.candidates(*len, letters).count());
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec!["tea", "eat", "ate", "set", "sea", "ant", "tan", "net"])
+    }
+
+    #[test]
+    fn places_pairs_symmetrically() {
+        let layouts = theme_layouts(&dict(), 5, &["tea", "eat"], Direction::Across, 10);
+
+        assert!(!layouts.is_empty() && layouts.len() <= 6);
+        assert!(layouts.windows(2).all(|x| x[0].score >= x[1].score));
+        for layout in layouts.iter() {
+            let [top, bottom] = &layout.entries[..] else {
+                panic!("expected two entries");
+            };
+            assert_eq!(
+                (top.answer.to_string(), bottom.answer.to_string()),
+                ("TEA".into(), "EAT".into())
+            );
+            assert_eq!((bottom.row, bottom.col), (4 - top.row, 2 - top.col));
+            assert!(layout.grid().is_rotationally_symmetric());
+        }
+
+        let down = theme_layouts(&dict(), 5, &["tea", "eat"], Direction::Down, 10);
+        let (across, down) = (layouts[0].grid(), down[0].grid());
+        for (row, col) in (0..5).flat_map(|row| (0..5).map(move |col| (row, col))) {
+            assert_eq!(across.cell(row, col), down.cell(col, row));
+        }
+    }
+
+    #[test]
+    fn centres_odd_answer_and_rejects_misfits() {
+        let layouts = theme_layouts(&dict(), 5, &["tea", "ate", "eat"], Direction::Across, 100);
+        assert!(!layouts.is_empty());
+        assert!(layouts
+            .iter()
+            .flat_map(|x| x.entries.iter())
+            .any(|x| (x.row, x.col) == (2, 1)));
+
+        assert!(theme_layouts(&dict(), 5, &["tea", "eats"], Direction::Across, 10).is_empty());
+        assert!(theme_layouts(&dict(), 5, &["qua", "qua"], Direction::Across, 10).is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::dictionary::{DictIterItem, Dictionary};
+
+const OBSCURITY_WEIGHT: f64 = 0.4;
+const SHORTNESS_WEIGHT: f64 = 0.3;
+const VOWEL_WEIGHT: f64 = 0.3;
+// words this long or longer are not short at all
+const LONG_WORD: usize = 7;
+// obscurity of every word when the dictionary has no scores
+const UNKNOWN_OBSCURITY: f64 = 0.5;
+
+/// How much an entry looks like crosswordese, the short, vowel-heavy and
+/// obscure words such as ERNE and OLEO that constructors lean on to finish
+/// a grid, from 0 for none of that to 1.
+///
+/// Obscurity comes from how the entry's score ranks among the dictionary's,
+/// unscored entries being the most obscure, or is neutral without scores.
+pub fn crosswordese(dict: &Dictionary, entry: &DictIterItem) -> f64 {
+    let word = &entry.normalized;
+    if word.is_empty() {
+        return 0.0;
+    }
+    let obscurity = dict
+        .score_rank(entry.score)
+        .map_or(UNKNOWN_OBSCURITY, |rank| 1.0 - rank);
+    let shortness = (LONG_WORD.saturating_sub(word.len()) as f64 / (LONG_WORD - 3) as f64).min(1.0);
+    let vowels = word.iter_chars().filter(|x| x.is_vowel()).count() as f64 / word.len() as f64;
+    // a quarter vowels is ordinary, three quarters as vowel-heavy as it gets
+    let vowel_heaviness = ((vowels - 0.25) / 0.5).clamp(0.0, 1.0);
+
+    OBSCURITY_WEIGHT * obscurity + SHORTNESS_WEIGHT * shortness + VOWEL_WEIGHT * vowel_heaviness
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::{DictSearch, WordPredicate};
+    use crate::normalized_word::NormalizedWord;
+
+    fn score(dict: &Dictionary, word: &str) -> f64 {
+        let word = NormalizedWord::from(word);
+        let entry = dict.iter().find(|x| x.normalized == word).unwrap();
+        crosswordese(dict, &entry)
+    }
+
+    #[test]
+    fn flags_short_vowel_heavy_rare_words() {
+        let mut dict: Dictionary = Default::default();
+        for (i, word) in ["street", "answer", "strength", "area"].iter().enumerate() {
+            dict.insert_scored(word, 100 + i as u32);
+        }
+        dict.insert_scored("erne", 1);
+        dict.insert("oleo");
+
+        assert!(score(&dict, "oleo") > score(&dict, "erne"));
+        assert!(score(&dict, "erne") > score(&dict, "area"));
+        assert!(score(&dict, "area") > score(&dict, "strength"));
+        assert!(score(&dict, "oleo") > 0.9);
+        assert!(score(&dict, "strength") < 0.2);
+
+        let fill = DictSearch::new(None, WordPredicate::NotCrosswordese(0.5));
+        let words: Vec<String> = dict.iter_search(fill).map(|x| x.original.clone()).collect();
+        assert_eq!(words, ["answer", "street", "strength"]);
+
+        // without scores only the letters count
+        let dict = Dictionary::from_iter(vec!["oleo", "strength"]);
+        assert!((score(&dict, "oleo") - (0.2 + 0.3 * 0.75 + 0.3)).abs() < 1e-9);
+        assert!((score(&dict, "strength") - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// What an indicator in a cryptic clue tells the solver to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndicatorKind {
+    /// Rearrange the letters of the fodder.
+    Anagram,
+    /// Put one part inside another.
+    Container,
+    /// Read the fodder backwards.
+    Reversal,
+}
+
+impl IndicatorKind {
+    pub const ALL: [IndicatorKind; 3] = [
+        IndicatorKind::Anagram,
+        IndicatorKind::Container,
+        IndicatorKind::Reversal,
+    ];
+
+    pub const NAMES: [&'static str; 3] = ["anagram", "container", "reversal"];
+
+    /// The curated indicators of this kind, lowercase and in alphabetical
+    /// order. Some, such as "about", indicate more than one kind.
+    pub fn indicators(self) -> &'static [&'static str] {
+        match self {
+            IndicatorKind::Anagram => ANAGRAM,
+            IndicatorKind::Container => CONTAINER,
+            IndicatorKind::Reversal => REVERSAL,
+        }
+    }
+}
+
+impl fmt::Display for IndicatorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let i = IndicatorKind::ALL.iter().position(|x| x == self).unwrap();
+        write!(f, "{}", IndicatorKind::NAMES[i])
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownIndicatorKind(pub String);
+
+impl fmt::Display for UnknownIndicatorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown indicator kind '{}': expected one of {}",
+            self.0,
+            IndicatorKind::NAMES.join(", ")
+        )
+    }
+}
+
+impl FromStr for IndicatorKind {
+    type Err = UnknownIndicatorKind;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        IndicatorKind::NAMES
+            .iter()
+            .position(|&name| name == str)
+            .map(|i| IndicatorKind::ALL[i])
+            .ok_or_else(|| UnknownIndicatorKind(str.to_string()))
+    }
+}
+
+const ANAGRAM: &[&str] = &[
+    "abandoned",
+    "abnormal",
+    "about",
+    "abroad",
+    "adapted",
+    "adjusted",
+    "altered",
+    "amended",
+    "anew",
+    "arranged",
+    "awful",
+    "awkward",
+    "bad",
+    "badly",
+    "battered",
+    "broken",
+    "bust",
+    "changed",
+    "chaotic",
+    "confused",
+    "converted",
+    "corrupt",
+    "crazy",
+    "crooked",
+    "damaged",
+    "disorderly",
+    "disturbed",
+    "drunk",
+    "erratic",
+    "fancy",
+    "free",
+    "jumbled",
+    "mad",
+    "messy",
+    "mixed",
+    "mixed up",
+    "novel",
+    "odd",
+    "off",
+    "out",
+    "poor",
+    "rebuilt",
+    "reformed",
+    "repaired",
+    "revised",
+    "rough",
+    "ruined",
+    "scrambled",
+    "shaken",
+    "shattered",
+    "shuffled",
+    "strange",
+    "twisted",
+    "unusual",
+    "upset",
+    "wild",
+    "wrong",
+];
+
+const CONTAINER: &[&str] = &[
+    "about",
+    "admitting",
+    "around",
+    "boxing",
+    "capturing",
+    "clutching",
+    "containing",
+    "covering",
+    "embracing",
+    "enclosing",
+    "entering",
+    "grasping",
+    "gripping",
+    "held by",
+    "holding",
+    "housing",
+    "in",
+    "inside",
+    "keeping",
+    "nursing",
+    "outside",
+    "round",
+    "seizing",
+    "swallowing",
+    "taking in",
+    "within",
+    "without",
+    "wrapping",
+];
+
+const REVERSAL: &[&str] = &[
+    "about",
+    "around",
+    "back",
+    "backed",
+    "backwards",
+    "brought back",
+    "flipped",
+    "from the east",
+    "going back",
+    "in retreat",
+    "lifted",
+    "mounted",
+    "over",
+    "raised",
+    "recalled",
+    "rejected",
+    "retiring",
+    "returned",
+    "returning",
+    "reversed",
+    "rising",
+    "round",
+    "sent back",
+    "turned",
+    "turned up",
+    "up",
+    "upset",
+    "westward",
+];
+
+/// The kinds of indicator `phrase` is, ignoring case and spacing.
+pub fn lookup(phrase: &str) -> Vec<IndicatorKind> {
+    let phrase = words(phrase).collect::<Vec<_>>().join(" ");
+    IndicatorKind::ALL
+        .into_iter()
+        .filter(|kind| kind.indicators().binary_search(&&phrase[..]).is_ok())
+        .collect()
+}
+
+/// An indicator found in the surface of a clue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Indicator {
+    /// The position of its first word among the words of the clue.
+    pub word_index: usize,
+    /// The indicator as listed, lowercase.
+    pub phrase: &'static str,
+    pub kind: IndicatorKind,
+}
+
+/// Every indicator among the words of `clue`, in order of position and
+/// then kind. Phrases of more than one word are found as well as the
+/// single words within them.
+pub fn classify(clue: &str) -> Vec<Indicator> {
+    let clue: Vec<String> = words(clue).collect();
+    let mut found = vec![];
+    for word_index in 0..clue.len() {
+        for kind in IndicatorKind::ALL {
+            for &phrase in kind.indicators() {
+                let len = phrase.split(' ').count();
+                let matches = clue
+                    .get(word_index..word_index + len)
+                    .is_some_and(|words| phrase.split(' ').eq(words.iter().map(|x| &x[..])));
+                if matches {
+                    found.push(Indicator {
+                        word_index,
+                        phrase,
+                        kind,
+                    });
+                }
+            }
+        }
+    }
+    found
+}
+
+// The words of a phrase, lowercase, with punctuation other than
+// apostrophes and hyphens dropped.
+fn words(phrase: &str) -> impl Iterator<Item = String> + '_ {
+    phrase
+        .split(|ch: char| !(ch.is_alphabetic() || ch == '\'' || ch == '-'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_are_sorted() {
+        for kind in IndicatorKind::ALL {
+            assert!(
+                kind.indicators().windows(2).all(|w| w[0] < w[1]),
+                "{}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn looks_up_indicators() {
+        use IndicatorKind::*;
+
+        assert_eq!(lookup("Mixed  up"), [Anagram]);
+        assert_eq!(lookup("about"), [Anagram, Container, Reversal]);
+        assert!(lookup("cat").is_empty());
+        assert_eq!("reversal".parse(), Ok(Reversal));
+        assert!("hidden".parse::<IndicatorKind>().is_err());
+    }
+
+    #[test]
+    fn classifies_clue_words() {
+        let found: Vec<(usize, &str, IndicatorKind)> =
+            classify("Tea mixed up, brought back in cup (3)")
+                .into_iter()
+                .map(|x| (x.word_index, x.phrase, x.kind))
+                .collect();
+
+        assert_eq!(
+            found,
+            [
+                (1, "mixed", IndicatorKind::Anagram),
+                (1, "mixed up", IndicatorKind::Anagram),
+                (2, "up", IndicatorKind::Reversal),
+                (3, "brought back", IndicatorKind::Reversal),
+                (4, "back", IndicatorKind::Reversal),
+                (5, "in", IndicatorKind::Container),
+            ]
+        );
+    }
+}
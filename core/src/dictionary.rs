@@ -1,18 +1,55 @@
-use crate::anagram_number::{AnagramComparison, AnagramNumber};
+use crate::anagram_number::{AnagramComparison, AnagramNumber, AnagramNumberOverflow};
+use crate::analytics::Analytics;
+use crate::cancel::CancellationToken;
+use crate::chain::{self, ChainOptions};
 use crate::char_freq::CharFreq;
 use crate::char_match::CharMatch;
-use crate::normalized_word::NormalizedWord;
-use crate::trie::{Trie, TriePrefix, TrieSearch};
+use crate::char_set::CharSet;
+use crate::crosswordese::crosswordese;
+use crate::digraph::Digraphs;
+use crate::enumeration::Enumeration;
+use crate::frozen::{Encoding, FrozenDictionary};
+use crate::keyboard::{self, Layout};
+use crate::language::{LanguageProfile, LanguageTag};
+use crate::load_report::{LineIssue, LoadReport};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::paged_index::{self, IndexedDictionary};
+use crate::phonetic::PhoneticIndex;
+use crate::phoneword;
+use crate::plan::SearchPlan;
+use crate::progress::{NoProgress, ProgressSink, PROGRESS_INTERVAL};
+use crate::query::DictQuery;
+use crate::ranking::Scorer;
+use crate::spellcheck::{self, SuggestOptions};
+#[cfg(feature = "stemming")]
+use crate::stem::{self, StemIndex};
+use crate::substring_index::SubstringIndex;
+use crate::trie::{PatternError, TraversalOrder, Trie, TriePrefix, TrieSearch};
+use crate::validation::Validation;
+#[cfg(feature = "regex")]
+use crate::word_regex::WordRegex;
+use rand::Rng;
+use smallvec::{smallvec, SmallVec};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::iter::FromIterator;
+use std::io::{self, BufRead, BufReader, Write};
+use std::iter::{FromIterator, Peekable};
+use std::ops::{Deref, RangeInclusive};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DictEntry {
     pub char_freq: CharFreq,
     pub anag_num: Option<AnagramNumber>,
     pub original: String,
+    pub score: Option<u32>,
+    pub lang: Option<LanguageTag>,
+    /// Letters of the normalized word, for rejecting candidates before
+    /// comparing letter counts.
+    pub letters: CharSet,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -21,6 +58,16 @@ pub struct DictIterItem<'a> {
     pub char_freq: &'a CharFreq,
     pub anag_num: Option<AnagramNumber>,
     pub original: &'a String,
+    pub score: Option<u32>,
+    pub lang: Option<LanguageTag>,
+    letters: CharSet,
+}
+
+impl DictIterItem<'_> {
+    /// The letters present in the normalized word.
+    pub fn letter_mask(&self) -> CharSet {
+        self.letters
+    }
 }
 
 impl<'a> From<(NormalizedWord, &'a DictEntry)> for DictIterItem<'a> {
@@ -30,57 +77,941 @@ impl<'a> From<(NormalizedWord, &'a DictEntry)> for DictIterItem<'a> {
             char_freq: &entry.char_freq,
             anag_num: entry.anag_num,
             original: &entry.original,
+            score: entry.score,
+            lang: entry.lang,
+            letters: entry.letters,
         }
     }
 }
 
+fn normalize(word: &str, lang: Option<LanguageTag>) -> NormalizedWord {
+    match lang.and_then(LanguageProfile::get) {
+        Some(profile) => profile.normalize(word),
+        None => NormalizedWord::from_str_safe(word),
+    }
+}
+
+#[cfg(feature = "async")]
+const LOAD_YIELD_INTERVAL: usize = 1024;
+
 #[derive(Default)]
 pub struct Dictionary {
     trie: Trie<DictEntry>,
+    anagram_index: HashMap<AnagramNumber, Vec<NormalizedWord>>,
+    substring_index: Option<SubstringIndex>,
+    #[cfg(feature = "stemming")]
+    stem_index: Option<StemIndex>,
+    // every entry's score in order, gathered when first needed
+    sorted_scores: OnceLock<Vec<u32>>,
+    // words by phonetic code, gathered when first needed
+    phonetic_index: OnceLock<PhoneticIndex>,
+    blocklist: HashSet<NormalizedWord>,
+    // words by lowercased category name, such as "element"
+    categories: HashMap<String, HashSet<NormalizedWord>>,
 }
 
 impl Dictionary {
     pub fn from_file(file: File) -> Dictionary {
-        let reader = BufReader::new(file);
-        let lines = reader.lines().map(|l| l.unwrap());
+        Dictionary::from_file_with_progress(file, NoProgress)
+    }
+
+    /// Like `from_file`, reporting bytes read against the file size.
+    pub fn from_file_with_progress<P: ProgressSink>(file: File, sink: P) -> Dictionary {
+        let size = file.metadata().ok().map(|m| m.len() as usize);
+        let mut dict: Dictionary = Default::default();
+        dict.read_from(BufReader::new(file), size, sink).unwrap();
+        dict
+    }
+
+    /// Inserts every line of `reader` with a letter in it, reporting bytes
+    /// read as progress, and returns what happened to each line.
+    pub fn read_from<R: BufRead, P: ProgressSink>(
+        &mut self,
+        reader: R,
+        total_bytes: Option<usize>,
+        sink: P,
+    ) -> io::Result<LoadReport> {
+        self.read_lines(reader, total_bytes, sink, None, None)
+    }
+
+    /// Like `read_from`, tagging every entry as being in `lang` and
+    /// normalizing by its bundled `LanguageProfile`, if it has one.
+    pub fn read_tagged_from<R: BufRead, P: ProgressSink>(
+        &mut self,
+        reader: R,
+        total_bytes: Option<usize>,
+        sink: P,
+        lang: LanguageTag,
+    ) -> io::Result<LoadReport> {
+        self.read_lines(reader, total_bytes, sink, Some(lang), None)
+    }
+
+    /// Like `read_from`, putting every word read in `category`, such as
+    /// "element" for a list of chemical elements, for
+    /// `WordPredicate::Category`. Words already present with the same
+    /// spelling are categorized without being inserted again.
+    pub fn read_category_from<R: BufRead, P: ProgressSink>(
+        &mut self,
+        reader: R,
+        total_bytes: Option<usize>,
+        sink: P,
+        category: &str,
+    ) -> io::Result<LoadReport> {
+        self.read_lines(reader, total_bytes, sink, None, Some(category))
+    }
+
+    fn read_lines<R: BufRead, P: ProgressSink>(
+        &mut self,
+        reader: R,
+        total_bytes: Option<usize>,
+        mut sink: P,
+        lang: Option<LanguageTag>,
+        category: Option<&str>,
+    ) -> io::Result<LoadReport> {
+        let mut report: LoadReport = Default::default();
+        let mut done = 0;
+        let mut last_report = 0;
+        for line in reader.lines() {
+            let line = line?;
+            report.lines += 1;
+            let issue = match category {
+                // a word the dictionary already spells this way is only
+                // categorized, not given a second entry
+                Some(category) if self.has_spelling(&normalize(&line, lang), &line) => {
+                    self.categorize(&line, category);
+                    Some(LineIssue::Duplicate)
+                }
+                _ => {
+                    let issue = self.insert_line(&line, lang);
+                    if !issue.is_some_and(LineIssue::is_skipped) {
+                        report.inserted += 1;
+                        if let Some(category) = category {
+                            self.categorize(&line, category);
+                        }
+                    }
+                    issue
+                }
+            };
+            if let Some(issue) = issue {
+                report.issues.push((report.lines, issue));
+            }
+            done += line.len() + 1;
+            if done - last_report >= PROGRESS_INTERVAL {
+                last_report = done;
+                sink.progress(done, total_bytes);
+            }
+        }
+        sink.progress(done, total_bytes);
+        Ok(report)
+    }
+
+    // Inserts a line unless it has no letters, returning its issue if any.
+    fn insert_line(&mut self, line: &str, lang: Option<LanguageTag>) -> Option<LineIssue> {
+        if line.trim().is_empty() {
+            return Some(LineIssue::Empty);
+        }
+        let normalized = normalize(line, lang);
+        if normalized.is_empty() {
+            return Some(LineIssue::NoLetters);
+        }
+        let duplicate = self.has_spelling(&normalized, line);
+        self.insert_entry(line, normalized.clone(), None, true, lang);
+        if duplicate {
+            Some(LineIssue::Duplicate)
+        } else if AnagramNumber::try_from(&normalized).is_err() {
+            Some(LineIssue::TooLongForAnagram)
+        } else {
+            None
+        }
+    }
+
+    // Whether an entry under `normalized` is spelled `original`.
+    fn has_spelling(&self, normalized: &NormalizedWord, original: &str) -> bool {
+        self.find(normalized)
+            .is_some_and(|entries| entries.iter().any(|x| x.original == original))
+    }
+
+    /// Loads a newline-separated word list without blocking the runtime,
+    /// yielding to other tasks every `LOAD_YIELD_INTERVAL` words.
+    #[cfg(feature = "async")]
+    pub async fn from_async_reader<R>(reader: R) -> std::io::Result<Dictionary>
+    where
+        R: tokio::io::AsyncBufRead + Unpin,
+    {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut lines = reader.lines();
         let mut dict: Dictionary = Default::default();
-        for line in lines {
+        let mut since_yield = 0;
+        while let Some(line) = lines.next_line().await? {
             dict.insert(&line);
+            since_yield += 1;
+            if since_yield == LOAD_YIELD_INTERVAL {
+                since_yield = 0;
+                tokio::task::yield_now().await;
+            }
         }
-        dict
+        Ok(dict)
+    }
+
+    /// Builds a dictionary from a newline-separated word list.
+    pub fn from_bytes(bytes: &[u8]) -> Dictionary {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect()
     }
 
     pub fn insert(&mut self, original: &str) {
         let normalized = NormalizedWord::from_str_safe(original);
+        self.insert_entry(original, normalized, None, true, None)
+    }
+
+    pub fn insert_scored(&mut self, original: &str, score: u32) {
+        let normalized = NormalizedWord::from_str_safe(original);
+        self.insert_entry(original, normalized, Some(score), true, None)
+    }
+
+    /// Inserts a word in `lang`, normalized by its bundled
+    /// `LanguageProfile` if it has one.
+    pub fn insert_tagged(&mut self, original: &str, lang: LanguageTag) {
+        let normalized = normalize(original, Some(lang));
+        self.insert_entry(original, normalized, None, true, Some(lang))
+    }
+
+    /// Inserts `original` under `normalized`, computing its anagram number
+    /// only if `anagrams`. Words without one are missed by anagram searches.
+    pub(crate) fn insert_entry(
+        &mut self,
+        original: &str,
+        normalized: NormalizedWord,
+        score: Option<u32>,
+        anagrams: bool,
+        lang: Option<LanguageTag>,
+    ) {
         let char_freq = CharFreq::from(&normalized);
-        let anag_num = AnagramNumber::try_from(&normalized).ok();
+        let anag_num = match anagrams {
+            true => AnagramNumber::try_from(&normalized).ok(),
+            false => None,
+        };
         let entry = DictEntry {
             char_freq,
             anag_num,
             original: String::from(original),
+            score,
+            lang,
+            letters: CharSet::from(&normalized),
         };
-        self.trie.add(&normalized, entry);
+        if let Some(anag) = anag_num {
+            let class = self.anagram_index.entry(anag).or_default();
+            if let Err(pos) = class.binary_search(&normalized) {
+                class.insert(pos, normalized.clone());
+            }
+        }
+        self.trie.add_scored(&normalized, entry, score);
+        self.substring_index = None;
+        self.sorted_scores = OnceLock::new();
+        self.phonetic_index = OnceLock::new();
+        #[cfg(feature = "stemming")]
+        {
+            self.stem_index = None;
+        }
+    }
+
+    /// Builds a trigram index that `Contains` predicates of three or more
+    /// letters use to find candidates instead of scanning. Inserting another
+    /// word discards the index.
+    pub fn build_substring_index(&mut self) {
+        let mut words: Vec<NormalizedWord> = self.trie.iter().map(|(word, _)| word).collect();
+        words.dedup();
+        self.substring_index = Some(SubstringIndex::build(&words));
+    }
+
+    pub fn has_substring_index(&self) -> bool {
+        self.substring_index.is_some()
+    }
+
+    /// Builds an index of words by stem for `stem_family`. Inserting another
+    /// word discards the index.
+    #[cfg(feature = "stemming")]
+    pub fn build_stem_index(&mut self) {
+        let mut words: Vec<NormalizedWord> = self.trie.iter().map(|(word, _)| word).collect();
+        words.dedup();
+        self.stem_index = Some(StemIndex::build(&words));
+    }
+
+    /// Entries sharing a stem with `word`, including the word itself, so
+    /// that a grid need not use both RUN and RUNS. Stems every word unless
+    /// `build_stem_index` has been called.
+    #[cfg(feature = "stemming")]
+    pub fn stem_family(&self, word: &str) -> Vec<DictIterItem<'_>> {
+        match &self.stem_index {
+            Some(index) => index
+                .family(word)
+                .iter()
+                .flat_map(|word| {
+                    self.trie
+                        .get(word)
+                        .into_iter()
+                        .flatten()
+                        .map(move |entry| DictIterItem::from((word.clone(), entry)))
+                })
+                .collect(),
+            None => {
+                let target = stem::stem(word);
+                self.iter()
+                    .filter(|x| stem::stem(x.original) == target)
+                    .collect()
+            }
+        }
+    }
+
+    // Candidate entries from the substring index, when it exists and the
+    // search both needs a substring and yields in normalized order.
+    fn indexed_candidates<'a>(
+        &'a self,
+        trie_search: &TrieSearch,
+        predicate: &WordPredicate,
+    ) -> Option<impl Iterator<Item = DictIterItem<'a>> + 'a> {
+        let trie_search = trie_search.clone();
+        if trie_search.order() == TraversalOrder::BreadthFirst {
+            return None;
+        }
+        let substring = predicate.required_substring()?;
+        let words = self.substring_index.as_ref()?.candidates(substring)?;
+
+        let search = trie_search.clone();
+        let entries = words
+            .into_iter()
+            .filter(move |word| search.matches(word))
+            .take_while(move |_| !trie_search.is_cancelled())
+            .flat_map(move |word| {
+                self.trie
+                    .get(word)
+                    .into_iter()
+                    .flatten()
+                    .map(move |entry| DictIterItem::from((word.clone(), entry)))
+            });
+        Some(entries)
+    }
+
+    pub(crate) fn trie(&self) -> &Trie<DictEntry> {
+        &self.trie
     }
 
     pub fn find(&self, word: &NormalizedWord) -> Option<&Vec<DictEntry>> {
         self.trie.get(word)
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = DictIterItem> {
+    /// Iterates over every entry, ordered by normalized word.
+    pub fn iter(&self) -> impl Iterator<Item = DictIterItem<'_>> {
         self.trie.iter().map(|x| x.into())
     }
 
-    pub fn iter_search(&self, search: DictSearch) -> impl Iterator<Item = DictIterItem> {
-        let trie_search = search.trie_search.unwrap_or_default();
+    /// A read-only copy of the entries in a more compact `encoding`, for
+    /// searching lexicons too large to hold otherwise.
+    pub fn freeze(&self, encoding: Encoding) -> FrozenDictionary {
+        FrozenDictionary::new(&self.trie, encoding)
+    }
+
+    /// Writes the entries not on the blocklist as a paged index, segmented
+    /// by first letter, for `open_indexed`. Returns the number written.
+    pub fn write_index<W: Write>(&self, out: W) -> io::Result<usize> {
+        paged_index::write_index(self, out)
+    }
+
+    /// Opens an index written by `write_index`, reading only its header
+    /// until a search needs the words of some first letter.
+    pub fn open_indexed<P: AsRef<Path>>(path: P) -> io::Result<IndexedDictionary> {
+        IndexedDictionary::open(path.as_ref())
+    }
+
+    /// Iterates over matching entries, ordered by normalized word.
+    pub fn iter_search(&self, search: DictSearch) -> impl Iterator<Item = DictIterItem<'_>> {
+        let (candidates, _) = self.search_candidates(&search);
         let predicate = search.predicate;
+        let bounds = predicate.letter_bounds();
+        let matches = candidates.filter(move |x| bounds.admits(x) && predicate.matches(self, x));
+        Deduped {
+            inner: matches.peekable(),
+            canonical: search.dedupe.then_some(search.canonical),
+        }
+    }
+
+    // The entries a search checks against its predicate: those from the
+    // substring index when it can serve them, otherwise those of one trie
+    // search per alternative prefix, or of the single pruned search. Also
+    // whether they came from that single search, whose size the trie counts
+    // without a traversal of its own.
+    fn search_candidates<'a>(
+        &'a self,
+        search: &DictSearch,
+    ) -> (Box<dyn Iterator<Item = DictIterItem<'a>> + 'a>, bool) {
+        let trie_search = search.pruned_trie_search();
+        match (
+            self.indexed_candidates(&trie_search, &search.predicate),
+            search.split_trie_searches(),
+        ) {
+            (Some(candidates), _) => (Box::new(candidates), false),
+            (None, Some(searches)) => (
+                Box::new(self.trie.iter_union(searches).map(DictIterItem::from)),
+                false,
+            ),
+            (None, None) => (
+                Box::new(self.trie.iter_search(trie_search).map(DictIterItem::from)),
+                true,
+            ),
+        }
+    }
+
+    /// Up to `limit` matches after the first `offset`, ordered by normalized
+    /// word as `iter_search` is, so that consecutive pages of an unchanged
+    /// dictionary neither repeat nor skip a match.
+    pub fn search_page(
+        &self,
+        search: DictSearch,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<DictIterItem<'_>> {
+        self.iter_search(search).skip(offset).take(limit).collect()
+    }
+
+    /// Up to `n` matches chosen uniformly at random, in search order. Holds
+    /// no more than `n` entries however many match.
+    pub fn sample<R: Rng + ?Sized>(
+        &self,
+        search: DictSearch,
+        n: usize,
+        rng: &mut R,
+    ) -> Vec<DictIterItem<'_>> {
+        // reservoir sampling, keeping each pick's position to restore order;
+        // no more can match than there are entries, however large `n` is
+        let mut picks: Vec<(usize, DictIterItem<'_>)> = Vec::with_capacity(n.min(self.trie.len()));
+        for (i, item) in self.iter_search(search).enumerate() {
+            if i < n {
+                picks.push((i, item));
+                continue;
+            }
+            let j = rng.gen_range(0..=i);
+            if j < n {
+                picks[j] = (i, item);
+            }
+        }
+        picks.sort_unstable_by_key(|(i, _)| *i);
+        picks.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Like `iter_search`, pairing each match with its score by `scorer`.
+    /// Matches are scored as they are yielded, not collected first.
+    pub fn iter_scored<'a, S: Scorer + 'a>(
+        &'a self,
+        search: DictSearch,
+        scorer: S,
+    ) -> impl Iterator<Item = (f64, DictIterItem<'a>)> + 'a {
+        self.iter_search(search).map(move |x| (scorer.score(&x), x))
+    }
+
+    /// The share of scored entries scored below `score`, from 0 to 1, with
+    /// unscored words below them all. `None` if no scores are loaded.
+    pub fn score_rank(&self, score: Option<u32>) -> Option<f64> {
+        let scores = self.sorted_scores();
+        if scores.is_empty() {
+            return None;
+        }
+        let below = score.map_or(0, |score| scores.partition_point(|&x| x < score));
+        Some(below as f64 / scores.len() as f64)
+    }
+
+    fn sorted_scores(&self) -> &[u32] {
+        self.sorted_scores.get_or_init(|| {
+            let mut scores: Vec<u32> = self.iter().filter_map(|x| x.score).collect();
+            scores.sort_unstable();
+            scores
+        })
+    }
+
+    /// Distinct words whose `phonetic::phonetic_code` is `code`, in
+    /// normalized order, from an index built on first use.
+    pub fn sounds_like(&self, code: &str) -> &[NormalizedWord] {
+        self.phonetic_index
+            .get_or_init(|| PhoneticIndex::build(self))
+            .get(code)
+    }
+
+    /// Blocks `word`, so that `WordPredicate::NotBlocked` rejects it. It need
+    /// not be in the dictionary.
+    pub fn block(&mut self, word: &str) {
+        let word = NormalizedWord::from_str_safe(word);
+        if !word.is_empty() {
+            self.blocklist.insert(word);
+        }
+    }
+
+    /// Blocks every word of a list, one per line, skipping blank lines and
+    /// those starting with `#`. Returns the number of words read.
+    pub fn read_blocklist<R: BufRead>(&mut self, reader: R) -> io::Result<usize> {
+        let mut count = 0;
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            self.block(line);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn is_blocked(&self, word: &NormalizedWord) -> bool {
+        self.blocklist.contains(word)
+    }
+
+    /// Puts `word` in `category`, so that `WordPredicate::Category` accepts
+    /// it. It need not be in the dictionary. Category names ignore case.
+    pub fn categorize(&mut self, word: &str, category: &str) {
+        let word = NormalizedWord::from_str_safe(word);
+        if !word.is_empty() {
+            let words = self.categories.entry(category.to_lowercase()).or_default();
+            words.insert(word);
+        }
+    }
+
+    /// Whether `word` is in `category`, which must be in lower case as
+    /// `categories` lists them.
+    pub fn in_category(&self, word: &NormalizedWord, category: &str) -> bool {
+        self.categories
+            .get(category)
+            .is_some_and(|words| words.contains(word))
+    }
+
+    /// The names of the categories with words in them, in lower case and
+    /// alphabetical order.
+    pub fn categories(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.categories.keys().map(|x| x.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    /// Inserts `original` unless an entry with the same normalized form is
+    /// already present, returning whether it was inserted.
+    pub fn insert_unique(&mut self, original: &str) -> bool {
+        let normalized = NormalizedWord::from_str_safe(original);
+        if self.trie.get(&normalized).is_some() {
+            return false;
+        }
+        self.insert(original);
+        true
+    }
+
+    /// Like `iter_search`, reporting how many candidates have been checked
+    /// against the predicate out of the total to check. The total is known
+    /// only for searches of a single trie traversal, which the trie's cached
+    /// counts size up front; those using the substring index or several
+    /// prefixes report none.
+    pub fn iter_search_with_progress<'a, P: ProgressSink + 'a>(
+        &'a self,
+        search: DictSearch,
+        mut sink: P,
+    ) -> impl Iterator<Item = DictIterItem<'a>> + 'a {
+        let (mut candidates, single) = self.search_candidates(&search);
+        let total = single.then(|| self.trie.count_search(&search.pruned_trie_search()));
+        let predicate = search.predicate;
+        let bounds = predicate.letter_bounds();
+        let mut done = 0;
+        let mut finished = false;
+
+        let matches = std::iter::from_fn(move || {
+            while !finished {
+                let Some(item) = candidates.next() else {
+                    finished = true;
+                    sink.progress(done, total);
+                    break;
+                };
+                done += 1;
+                if done % PROGRESS_INTERVAL == 0 {
+                    sink.progress(done, total);
+                }
+                if bounds.admits(&item) && predicate.matches(self, &item) {
+                    return Some(item);
+                }
+            }
+            None
+        });
+        Deduped {
+            inner: matches.peekable(),
+            canonical: search.dedupe.then_some(search.canonical),
+        }
+    }
+
+    /// The score that `percentile` percent of scored entries fall below, or
+    /// `None` if no scores are loaded. Percentiles above 100 count as 100.
+    pub fn score_percentile(&self, percentile: u8) -> Option<u32> {
+        let scores = self.sorted_scores();
+        if scores.is_empty() {
+            return None;
+        }
+        let index = scores.len() * percentile.min(100) as usize / 100;
+        Some(scores[index.min(scores.len() - 1)])
+    }
+
+    /// Words that spell other words backwards, one entry per word, each
+    /// paired with the first entry of its reversal.
+    pub fn reversals(&self) -> impl Iterator<Item = (DictIterItem<'_>, DictIterItem<'_>)> {
+        let search = DictSearch::new(None, WordPredicate::None).with_dedupe(Canonical::First);
+        self.iter_search(search).filter_map(move |item| {
+            let partner = self.reversal_of(&item.normalized)?;
+            Some((item, partner))
+        })
+    }
+
+    fn reversal_of(&self, word: &NormalizedWord) -> Option<DictIterItem<'_>> {
+        let reversed = word.reversed();
+        if &reversed == word {
+            return None;
+        }
+        let entry = self.find(&reversed)?.first()?;
+        Some((reversed, entry).into())
+    }
+
+    /// Iterates over the exact anagrams of `word`, ordered by normalized word,
+    /// using the anagram index rather than a trie search.
+    pub fn anagrams(&self, word: &str) -> impl Iterator<Item = DictIterItem<'_>> {
+        let word = NormalizedWord::from_str_safe(word);
+        let anag = AnagramNumber::try_from(&word).ok();
+        anag.into_iter().flat_map(|anag| self.anagram_class(anag))
+    }
+
+    /// Iterates over words using every letter of `word` plus one more, with
+    /// the added letter, ordered by that letter and then normalized word.
+    pub fn anagrams_plus_one(
+        &self,
+        word: &str,
+    ) -> impl Iterator<Item = (NormalizedChar, DictIterItem<'_>)> {
+        self.anagrams_changing_one(word, |anag, ch| anag.with(ch))
+    }
+
+    /// Iterates over words using every letter of `word` but one, with the
+    /// dropped letter, ordered by that letter and then normalized word.
+    pub fn anagrams_minus_one(
+        &self,
+        word: &str,
+    ) -> impl Iterator<Item = (NormalizedChar, DictIterItem<'_>)> {
+        self.anagrams_changing_one(word, |anag, ch| anag.without(ch))
+    }
+
+    fn anagrams_changing_one<F>(
+        &self,
+        word: &str,
+        change: F,
+    ) -> impl Iterator<Item = (NormalizedChar, DictIterItem<'_>)>
+    where
+        F: Fn(AnagramNumber, NormalizedChar) -> Option<AnagramNumber>,
+    {
+        let word = NormalizedWord::from_str_safe(word);
+        let Ok(anag) = AnagramNumber::try_from(&word) else {
+            return vec![].into_iter();
+        };
+        NormalizedChar::all()
+            .filter_map(|ch| Some((ch, change(anag, ch)?)))
+            .flat_map(|(ch, changed)| self.anagram_class(changed).map(move |x| (ch, x)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Writes every anagram class from the anagram index as a line of its
+    /// sorted letters and then its words, tab separated, in order of their
+    /// first words. Classes are streamed in one pass over the trie, each
+    /// written when its first word is reached. Words too long for an
+    /// anagram number are left out. Returns the number of classes written.
+    pub fn export_anagram_clusters<W: Write>(&self, mut out: W) -> io::Result<usize> {
+        let mut count = 0;
+        let mut last: Option<NormalizedWord> = None;
+        for item in self.iter() {
+            // entries sharing a normalized form follow each other
+            if last.as_ref() == Some(&item.normalized) {
+                continue;
+            }
+            last = Some(item.normalized.clone());
+            let Some(anag) = item.anag_num else {
+                continue;
+            };
+            match self.anagram_index.get(&anag) {
+                Some(words) if words[0] == item.normalized => {
+                    write!(out, "{}", anag)?;
+                    for word in words.iter() {
+                        write!(out, "\t{}", word)?;
+                    }
+                    writeln!(out)?;
+                    count += 1;
+                }
+                _ => (),
+            }
+        }
+        out.flush()?;
+        Ok(count)
+    }
+
+    /// The anagram classes of the longest words with a distinct anagram,
+    /// each in normalized order, ordered by their first word.
+    pub fn longest_anagram_pairs(&self) -> Vec<&[NormalizedWord]> {
+        let classes = self.anagram_index.values().filter(|x| x.len() > 1);
+        let Some(longest) = classes.clone().map(|x| x[0].len()).max() else {
+            return vec![];
+        };
+        let mut classes: Vec<&[NormalizedWord]> = classes
+            .filter(|x| x[0].len() == longest)
+            .map(|x| &x[..])
+            .collect();
+        classes.sort_unstable();
+        classes
+    }
+
+    /// The `k` anagram classes with the most words, largest first, with ties
+    /// ordered by their first word. Words without an anagram are left out.
+    pub fn most_anagrammable(&self, k: usize) -> Vec<&[NormalizedWord]> {
+        let mut classes: Vec<&[NormalizedWord]> = self
+            .anagram_index
+            .values()
+            .filter(|x| x.len() > 1)
+            .map(|x| &x[..])
+            .collect();
+        classes.sort_unstable_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        classes.truncate(k);
+        classes
+    }
+
+    fn anagram_class(&self, anag: AnagramNumber) -> impl Iterator<Item = DictIterItem<'_>> {
+        let class = self.anagram_index.get(&anag);
+        class.into_iter().flatten().flat_map(move |normalized| {
+            self.trie
+                .get(normalized)
+                .into_iter()
+                .flatten()
+                .map(move |entry| DictIterItem::from((normalized.clone(), entry)))
+        })
+    }
+
+    /// Words of `len` letters with the given letters at the given positions,
+    /// counting from zero, as a crossword filler asks of each slot.
+    /// Conflicting or out of range constraints match nothing.
+    pub fn candidates(
+        &self,
+        len: usize,
+        constraints: &[(usize, NormalizedChar)],
+    ) -> impl Iterator<Item = DictIterItem<'_>> {
+        let mut pattern: SmallVec<[CharMatch; 24]> = smallvec![CharMatch::Any; len];
+        let mut possible = true;
+        for &(i, ch) in constraints {
+            match pattern.get(i) {
+                Some(CharMatch::Only(existing)) if *existing != ch => possible = false,
+                Some(_) => pattern[i] = CharMatch::Only(ch),
+                None => possible = false,
+            }
+        }
+        possible
+            .then(|| self.trie.iter_exact(&pattern))
+            .into_iter()
+            .flatten()
+            .map(DictIterItem::from)
+    }
 
+    /// Returns up to `limit` words starting with `prefix`, highest score first
+    /// when scores are loaded, and otherwise shortest first. Blocked words
+    /// are left out.
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<DictIterItem<'_>> {
+        let prefix = NormalizedWord::from_str_safe(prefix);
         self.trie
-            .iter_search(trie_search)
+            .iter_best_first(&prefix, |entry| entry.score)
+            .filter(|(word, _)| !self.is_blocked(word))
+            .take(limit)
             .map(DictIterItem::from)
-            .filter(move |x| predicate.matches(x))
+            .collect()
+    }
+
+    pub fn count(&self, search: DictSearch) -> usize {
+        if search.predicate.is_trivial() && !search.dedupe {
+            let trie_search = search.trie_search.unwrap_or_default();
+            return self.trie.count_search(&trie_search);
+        }
+        self.iter_search(search).count()
+    }
+
+    pub fn exists(&self, search: DictSearch) -> bool {
+        if search.predicate.is_trivial() {
+            let trie_search = search.trie_search.unwrap_or_default();
+            return self.trie.exists_search(&trie_search);
+        }
+        self.iter_search(search).next().is_some()
+    }
+
+    pub fn query(&self, query: &DictQuery) -> Vec<DictIterItem<'_>> {
+        query.run(self)
+    }
+
+    /// Explains whether `word` is accepted: exact and normalized hits, near
+    /// misses within one edit, and anagrams.
+    pub fn validate(&self, word: &str) -> Validation<'_> {
+        Validation::of(self, word)
+    }
+
+    /// Words and word sequences typed by a phone number's digits, such as
+    /// CAT, ACT and BAT for 228. See `phoneword::sequences`.
+    pub fn from_digits(&self, digits: &str) -> impl Iterator<Item = Vec<DictIterItem<'_>>> + '_ {
+        phoneword::sequences(self, digits)
+    }
+
+    /// Words a slip of the finger away from `word` on `layout`, each letter
+    /// kept or swapped for a neighboring key. See `keyboard::typo_neighbors`.
+    pub fn typo_neighbors(&self, word: &str, layout: Layout) -> Vec<DictIterItem<'_>> {
+        keyboard::typo_neighbors(self, word, layout)
+    }
+
+    /// Up to `k` spelling corrections for `word`, best first, with their
+    /// scores. See `spellcheck::suggest`.
+    pub fn suggest(&self, word: &str, k: usize) -> Vec<(DictIterItem<'_>, f64)> {
+        spellcheck::suggest(self, word, k, &Default::default())
+    }
+
+    /// Like `suggest`, weighing candidates by `options`.
+    pub fn suggest_with(
+        &self,
+        word: &str,
+        k: usize,
+        options: &SuggestOptions,
+    ) -> Vec<(DictIterItem<'_>, f64)> {
+        spellcheck::suggest(self, word, k, options)
+    }
+
+    /// Vowel counts by length, consonant clusters and first and last
+    /// letters across every entry. See `analytics::Analytics`.
+    pub fn analytics(&self) -> Analytics {
+        Analytics::of(self)
+    }
+
+    /// Finds word chains, as in shiritori, each word beginning with the
+    /// last letter of the one before. See `chain::chains`.
+    pub fn chains(
+        &self,
+        start: &str,
+        length: usize,
+        options: &ChainOptions,
+    ) -> Vec<Vec<DictIterItem<'_>>> {
+        chain::chains(self, start, length, options)
+    }
+
+    /// Returns the first `k` matching entries according to `sort`, without
+    /// holding more than `k` entries in memory. Ties keep search order.
+    pub fn top_k(&self, search: DictSearch, k: usize, sort: Sort) -> Vec<DictIterItem<'_>> {
+        if k == 0 {
+            return vec![];
+        }
+
+        let mut heap: BinaryHeap<Ranked> = BinaryHeap::new();
+        for (index, item) in self.iter_search(search).enumerate() {
+            let ranked = Ranked { item, index, sort };
+            if heap.len() < k {
+                heap.push(ranked);
+            } else if heap.peek().is_some_and(|max| ranked < *max) {
+                heap.pop();
+                heap.push(ranked);
+            }
+        }
+
+        heap.into_sorted_vec().into_iter().map(|x| x.item).collect()
+    }
+
+    /// The `k` other words closest to being anagrams of `word`, by the
+    /// letters to add or remove, as `CharFreq::anagram_distance`. Ties are
+    /// in normalized order, with one entry per word.
+    pub fn nearest_anagrams(&self, word: &str, k: usize) -> Vec<(DictIterItem<'_>, usize)> {
+        if k == 0 {
+            return vec![];
+        }
+        let word = NormalizedWord::from_str_safe(word);
+        let char_freq = CharFreq::from(&word);
+
+        let search = DictSearch::new(None, WordPredicate::None).with_dedupe(Canonical::First);
+        let candidates = self
+            .iter_search(search)
+            .filter(|x| x.normalized != word)
+            .enumerate();
+        let mut heap: BinaryHeap<Nearest> = BinaryHeap::new();
+        for (index, item) in candidates {
+            let nearest = Nearest {
+                distance: char_freq.anagram_distance(item.char_freq),
+                index,
+                item,
+            };
+            if heap.len() < k {
+                heap.push(nearest);
+            } else if heap.peek().is_some_and(|max| nearest < *max) {
+                heap.pop();
+                heap.push(nearest);
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|x| (x.item, x.distance))
+            .collect()
+    }
+}
+
+struct Nearest<'a> {
+    distance: usize,
+    index: usize,
+    item: DictIterItem<'a>,
+}
+
+impl<'a> Ord for Nearest<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.distance, self.index).cmp(&(other.distance, other.index))
+    }
+}
+
+impl<'a> PartialOrd for Nearest<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> PartialEq for Nearest<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a> Eq for Nearest<'a> {}
+
+struct Ranked<'a> {
+    item: DictIterItem<'a>,
+    index: usize,
+    sort: Sort,
+}
+
+impl<'a> Ord for Ranked<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort
+            .compare(&self.item, &other.item)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+impl<'a> PartialOrd for Ranked<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
+impl<'a> PartialEq for Ranked<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a> Eq for Ranked<'a> {}
+
 impl<'a> Extend<&'a str> for Dictionary {
     fn extend<T: IntoIterator<Item = &'a str>>(&mut self, iter: T) {
         for str in iter {
@@ -97,72 +1028,639 @@ impl<'a> FromIterator<&'a str> for Dictionary {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// A cheaply cloneable handle to a loaded dictionary, for sharing one copy
+/// across threads. `Dictionary` is `Send + Sync`, so plain references work
+/// too wherever scoped threads are an option.
+#[derive(Clone, Default)]
+pub struct ArcDictionary(Arc<Dictionary>);
+
+impl ArcDictionary {
+    pub fn new(dict: Dictionary) -> Self {
+        ArcDictionary(Arc::new(dict))
+    }
+}
+
+impl From<Dictionary> for ArcDictionary {
+    fn from(dict: Dictionary) -> Self {
+        ArcDictionary::new(dict)
+    }
+}
+
+impl Deref for ArcDictionary {
+    type Target = Dictionary;
+
+    fn deref(&self) -> &Dictionary {
+        &self.0
+    }
+}
+
+/// Which original spelling represents a group of entries sharing a
+/// normalized form when results are deduplicated.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Canonical {
+    /// The first inserted.
+    #[default]
+    First,
+    /// The shortest, e.g. "cant" over "can't".
+    Shortest,
+    /// The first without capitals, e.g. "polish" over "Polish".
+    LowercasePreferred,
+}
+
+impl Canonical {
+    fn prefers(&self, candidate: &str, current: &str) -> bool {
+        let is_lowercase = |s: &str| !s.chars().any(char::is_uppercase);
+        match self {
+            Canonical::First => false,
+            Canonical::Shortest => candidate.chars().count() < current.chars().count(),
+            Canonical::LowercasePreferred => is_lowercase(candidate) && !is_lowercase(current),
+        }
+    }
+}
+
+// Collapses runs of entries with the same normalized form, which the trie
+// always yields together.
+struct Deduped<'a, I: Iterator<Item = DictIterItem<'a>>> {
+    inner: Peekable<I>,
+    canonical: Option<Canonical>,
+}
+
+impl<'a, I: Iterator<Item = DictIterItem<'a>>> Iterator for Deduped<'a, I> {
+    type Item = DictIterItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut best = self.inner.next()?;
+        if let Some(canonical) = self.canonical {
+            while let Some(next) = self.inner.next_if(|x| x.normalized == best.normalized) {
+                if canonical.prefers(next.original, best.original) {
+                    best = next;
+                }
+            }
+        }
+        Some(best)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
 pub enum WordPredicate {
     AnagramOf(AnagramNumber),
     SubanagramOf(AnagramNumber),
     SuperanagramOf(AnagramNumber),
+    Contains(NormalizedWord),
+    /// Made only from these letters, in any numbers.
+    OnlyLetters(CharSet),
+    /// Spelled backwards, another word of the dictionary.
+    ReversalIsWord,
+    /// Scored at least this.
+    ScoreAtLeast(u32),
+    /// Scored below this. Unscored entries never match either.
+    ScoreBelow(u32),
+    /// Tagged with this language. Untagged entries never match.
+    Language(LanguageTag),
+    /// Spelled as words of these lengths, such as "ice cream" for (3,5).
+    Enumeration(Enumeration),
+    /// Spelled in a number of tokens in this range, each of these digraphs
+    /// counting as one, such as 4 for "chorro" with `Digraphs::spanish()`.
+    TokenLength(Digraphs, RangeInclusive<usize>),
+    /// Scoring below this as crosswordese, from 0 to 1. See
+    /// `crosswordese::crosswordese`.
+    NotCrosswordese(f64),
+    /// Not on the dictionary's blocklist.
+    NotBlocked,
+    /// In this category of the dictionary, such as "element" or "capital",
+    /// named in lower case. See `Dictionary::categorize`.
+    Category(String),
+    /// With a number of different letters in this range, such as 7..=7 for
+    /// Spelling Bee pangrams. Pruned during the trie traversal.
+    DistinctLetters(RangeInclusive<u8>),
+    /// Starting with letters matching this pattern.
+    Prefix(TriePrefix),
+    /// Matching this pattern letter for letter, with nothing after.
+    Pattern(TriePrefix),
+    /// With an original spelling matching this.
+    #[cfg(feature = "regex")]
+    OriginalRegex(WordRegex),
+    /// With a normalized form in lower case, such as `cant` for "can't",
+    /// matching this.
+    #[cfg(feature = "regex")]
+    NormalizedRegex(WordRegex),
+    /// Not matching this. Entries too long for anagram numbers, which match
+    /// the partial anagram predicates whatever their letters, match their
+    /// negations by comparing letter counts instead, however deeply those
+    /// predicates are nested in `All` and `Any`.
+    Not(Box<WordPredicate>),
     All(Vec<WordPredicate>),
     Any(Vec<WordPredicate>),
+    #[default]
     None,
 }
 
-impl WordPredicate {
-    pub fn matches(&self, entry: &DictIterItem) -> bool {
-        use AnagramComparison::*;
-        use WordPredicate::*;
-        match self {
-            AnagramOf(anag) => entry.anag_num.map_or(false, |x| anag.compare(x) == Exact),
-            SubanagramOf(anag) => entry.anag_num.map_or(true, |x| anag.compare(x) == Subset),
-            SuperanagramOf(anag) => entry.anag_num.map_or(true, |x| anag.compare(x) == Superset),
-            All(predicates) => predicates.iter().all(|x| x.matches(entry)),
-            Any(predicates) => predicates.iter().any(|x| x.matches(entry)),
-            None => true,
-        }
+// Whether `letters` has at least as many of each letter as `wanted`.
+fn has_letters(letters: &CharFreq, wanted: &CharFreq) -> bool {
+    NormalizedChar::all().all(|ch| letters.get(ch) >= wanted.get(ch))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LetterBounds {
+    required: CharSet,
+    allowed: CharSet,
+}
+
+impl LetterBounds {
+    fn admits(&self, entry: &DictIterItem) -> bool {
+        self.required.is_subset(&entry.letters) && entry.letters.is_subset(&self.allowed)
     }
 }
 
-impl Default for WordPredicate {
+impl Default for LetterBounds {
     fn default() -> Self {
-        WordPredicate::None
+        LetterBounds {
+            required: CharSet::new(),
+            allowed: CharSet::full(),
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Default)]
-pub struct DictSearch {
-    trie_search: Option<TrieSearch>,
-    predicate: WordPredicate,
-}
+impl WordPredicate {
+    pub fn matches(&self, dict: &Dictionary, entry: &DictIterItem) -> bool {
+        self.eval(dict, entry, false)
+    }
 
-impl DictSearch {
-    pub fn new(trie_search: Option<TrieSearch>, predicate: WordPredicate) -> Self {
-        Self {
-            trie_search,
-            predicate,
+    // Whether the entry matches, comparing letter counts for entries too long
+    // for anagram numbers when `exact`, as everything under a `Not` is.
+    fn eval(&self, dict: &Dictionary, entry: &DictIterItem, exact: bool) -> bool {
+        use AnagramComparison::*;
+        use WordPredicate::*;
+        match self {
+            AnagramOf(anag) => entry.anag_num.is_some_and(|x| anag.compare(x) == Exact),
+            SubanagramOf(anag) => match entry.anag_num {
+                Some(x) => anag.compare(x) == Subset,
+                Option::None => !exact || has_letters(&anag.to_char_freq(), entry.char_freq),
+            },
+            SuperanagramOf(anag) => match entry.anag_num {
+                Some(x) => anag.compare(x) == Superset,
+                Option::None => !exact || has_letters(entry.char_freq, &anag.to_char_freq()),
+            },
+            Contains(substring) => entry.normalized.contains(substring),
+            OnlyLetters(letters) => entry.letters.is_subset(letters),
+            ReversalIsWord => dict.reversal_of(&entry.normalized).is_some(),
+            ScoreAtLeast(min) => entry.score.is_some_and(|x| x >= *min),
+            ScoreBelow(max) => entry.score.is_some_and(|x| x < *max),
+            Language(lang) => entry.lang == Some(*lang),
+            Enumeration(enumeration) => enumeration.matches(entry.original),
+            TokenLength(digraphs, range) => range.contains(&digraphs.token_len(&entry.normalized)),
+            NotCrosswordese(threshold) => crosswordese(dict, entry) < *threshold,
+            NotBlocked => !dict.is_blocked(&entry.normalized),
+            Category(category) => dict.in_category(&entry.normalized, category),
+            DistinctLetters(range) => range.contains(&(entry.letters.len() as u8)),
+            Prefix(prefix) => prefix.is_prefix_of(&entry.normalized),
+            Pattern(pattern) => {
+                entry.normalized.len() == pattern.len() && pattern.is_prefix_of(&entry.normalized)
+            }
+            #[cfg(feature = "regex")]
+            OriginalRegex(regex) => regex.is_match(entry.original),
+            #[cfg(feature = "regex")]
+            NormalizedRegex(regex) => regex.is_match(&entry.normalized.to_string().to_lowercase()),
+            Not(predicate) => !predicate.eval(dict, entry, true),
+            All(predicates) => predicates.iter().all(|x| x.eval(dict, entry, exact)),
+            Any(predicates) => predicates.iter().any(|x| x.eval(dict, entry, exact)),
+            None => true,
         }
     }
 
-    pub fn from_pattern(pattern: &str) -> DictSearch {
-        let prefix = TriePrefix::from_pattern(pattern);
-        let max_length = prefix.len();
-        let trie_search = Some(TrieSearch::new(prefix, Some(max_length)));
-        DictSearch {
-            trie_search,
-            ..Default::default()
+    /// Letters every matching entry must have, and letters no matching
+    /// entry goes beyond, checked against `DictIterItem::letter_mask` before
+    /// anything slower.
+    fn letter_bounds(&self) -> LetterBounds {
+        use WordPredicate::*;
+        match self {
+            AnagramOf(anag) => {
+                let freq = anag.to_char_freq();
+                let letters = NormalizedChar::all()
+                    .filter(|&ch| freq.get(ch) > 0)
+                    .collect();
+                LetterBounds {
+                    required: letters,
+                    allowed: letters,
+                }
+            }
+            Contains(substring) => LetterBounds {
+                required: CharSet::from(substring),
+                allowed: CharSet::full(),
+            },
+            OnlyLetters(letters) => LetterBounds {
+                required: CharSet::new(),
+                allowed: *letters,
+            },
+            All(predicates) => predicates.iter().map(|x| x.letter_bounds()).fold(
+                LetterBounds::default(),
+                |acc, x| LetterBounds {
+                    required: acc.required.union(x.required),
+                    allowed: acc.allowed.intersection(x.allowed),
+                },
+            ),
+            Any(predicates) if !predicates.is_empty() => predicates
+                .iter()
+                .map(|x| x.letter_bounds())
+                .reduce(|acc, x| LetterBounds {
+                    required: acc.required.intersection(x.required),
+                    allowed: acc.allowed.union(x.allowed),
+                })
+                .unwrap(),
+            // entries too long for anagram numbers match the partial anagram
+            // predicates whatever their letters
+            _ => LetterBounds::default(),
         }
     }
 
-    pub fn anagram_of(str: &str) -> DictSearch {
-        let word = NormalizedWord::from_str_safe(str);
-        let anagram: AnagramNumber = (&word).try_into().unwrap();
-        let len = word.len();
-        let prefix = TriePrefix::new(vec![CharMatch::Any; len]);
-        let trie_search = Some(TrieSearch::new(prefix, Some(len)));
-        DictSearch {
+    // Each check made of an entry after the traversal, leaving out those the
+    // traversal makes itself.
+    fn filters(&self) -> Vec<String> {
+        match self {
+            WordPredicate::All(predicates) => predicates.iter().flat_map(|x| x.filters()).collect(),
+            WordPredicate::DistinctLetters(_) | WordPredicate::None => vec![],
+            predicate => vec![predicate.describe()],
+        }
+    }
+
+    fn describe(&self) -> String {
+        use WordPredicate::*;
+        let join = |predicates: &[WordPredicate], separator: &str| -> String {
+            let parts: Vec<String> = predicates.iter().map(|x| x.describe()).collect();
+            parts.join(separator)
+        };
+        match self {
+            AnagramOf(anag) => format!("anagram of {}", anag),
+            SubanagramOf(anag) => format!("made from the letters {}", anag),
+            SuperanagramOf(anag) => format!("containing the letters {}", anag),
+            Contains(substring) => format!("containing {}", substring),
+            OnlyLetters(letters) => format!("only the letters {}", letters),
+            ReversalIsWord => "a word when reversed".to_string(),
+            ScoreAtLeast(min) => format!("scored at least {}", min),
+            ScoreBelow(max) => format!("scored below {}", max),
+            Language(lang) => format!("in language {}", lang),
+            Enumeration(enumeration) => format!("spelled as {}", enumeration),
+            TokenLength(_, range) => {
+                format!("spelled in {} to {} tokens", range.start(), range.end())
+            }
+            NotCrosswordese(threshold) => format!("below {} as crosswordese", threshold),
+            NotBlocked => "not blocked".to_string(),
+            Category(category) => format!("in category {}", category),
+            DistinctLetters(range) => format!(
+                "with {} to {} different letters",
+                range.start(),
+                range.end()
+            ),
+            Prefix(prefix) => format!("starting {}", prefix),
+            Pattern(pattern) => format!("matching {}", pattern),
+            #[cfg(feature = "regex")]
+            OriginalRegex(regex) => format!("spelled to match {}", regex),
+            #[cfg(feature = "regex")]
+            NormalizedRegex(regex) => format!("matching {}", regex),
+            Not(predicate) => format!("not {}", predicate.describe()),
+            All(predicates) if predicates.is_empty() => "anything".to_string(),
+            All(predicates) => format!("all of: {}", join(predicates, "; ")),
+            Any(predicates) if predicates.is_empty() => "nothing".to_string(),
+            Any(predicates) => format!("any of: {}", join(predicates, "; ")),
+            None => "anything".to_string(),
+        }
+    }
+
+    // Whether every match must be an anagram of some letters.
+    fn wants_anagram(&self) -> bool {
+        match self {
+            WordPredicate::AnagramOf(_) => true,
+            WordPredicate::All(predicates) => predicates.iter().any(|x| x.wants_anagram()),
+            _ => false,
+        }
+    }
+
+    /// A substring every matching entry must contain, preferring the longest.
+    pub fn required_substring(&self) -> Option<&NormalizedWord> {
+        match self {
+            WordPredicate::Contains(substring) => Some(substring),
+            WordPredicate::All(predicates) => predicates
+                .iter()
+                .filter_map(|x| x.required_substring())
+                .max_by_key(|x| x.len()),
+            _ => None,
+        }
+    }
+
+    // The fewest and most letters a matching entry can have. The partial
+    // anagram predicates bound nothing, as entries too long for anagram
+    // numbers match them whatever their length. Enumerations count the
+    // letters as spelled, which language folds such as Ü to UE can only
+    // lengthen, so bound the minimum alone. A token is at least one letter
+    // and at most the longest digraph.
+    fn length_bounds(&self) -> (usize, Option<usize>) {
+        use WordPredicate::*;
+        match self {
+            AnagramOf(anag) => {
+                let freq = anag.to_char_freq();
+                let len = NormalizedChar::all().map(|ch| freq.get(ch) as usize).sum();
+                (len, Some(len))
+            }
+            Contains(substring) => (substring.len(), Option::None),
+            Enumeration(enumeration) => (enumeration.len(), Option::None),
+            TokenLength(digraphs, range) => {
+                (*range.start(), range.end().checked_mul(digraphs.max_len()))
+            }
+            DistinctLetters(range) => (*range.start() as usize, Option::None),
+            Prefix(prefix) => (prefix.len(), Option::None),
+            Pattern(pattern) => (pattern.len(), Some(pattern.len())),
+            All(predicates) => predicates.iter().map(|x| x.length_bounds()).fold(
+                (0, Option::None),
+                |(min, max), (x, y)| {
+                    let max = match (max, y) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                    (min.max(x), max)
+                },
+            ),
+            Any(predicates) if !predicates.is_empty() => predicates
+                .iter()
+                .map(|x| x.length_bounds())
+                .reduce(|(min, max), (x, y)| (min.min(x), max.zip(y).map(|(a, b)| a.max(b))))
+                .unwrap(),
+            _ => (0, Option::None),
+        }
+    }
+
+    // The prefixes, each with a maximum length, one of which every matching
+    // entry starts with, if this predicate requires one.
+    fn alternatives(&self) -> Option<Vec<(TriePrefix, Option<usize>)>> {
+        match self {
+            WordPredicate::Prefix(prefix) => Some(vec![(prefix.clone(), Option::None)]),
+            WordPredicate::Pattern(pattern) => Some(vec![(pattern.clone(), Some(pattern.len()))]),
+            WordPredicate::Any(predicates) if !predicates.is_empty() => predicates
+                .iter()
+                .map(|x| x.alternatives())
+                .collect::<Option<Vec<_>>>()
+                .map(|x| x.concat()),
+            WordPredicate::All(predicates) => predicates.iter().find_map(|x| x.alternatives()),
+            _ => Option::None,
+        }
+    }
+
+    /// The range of different letters every matching entry has, if this
+    /// predicate bounds it.
+    pub fn distinct_letters(&self) -> Option<RangeInclusive<u8>> {
+        match self {
+            WordPredicate::DistinctLetters(range) => Some(range.clone()),
+            WordPredicate::All(predicates) => predicates
+                .iter()
+                .filter_map(|x| x.distinct_letters())
+                .reduce(|a, b| *a.start().max(b.start())..=*a.end().min(b.end())),
+            _ => None,
+        }
+    }
+
+    /// Whether this predicate accepts every entry without inspecting it.
+    pub fn is_trivial(&self) -> bool {
+        match self {
+            WordPredicate::All(predicates) => predicates.iter().all(|x| x.is_trivial()),
+            WordPredicate::None => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SortAspect {
+    Length,
+    Alphabetical,
+    /// Score or frequency, with unscored entries lowest.
+    Score,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sort(pub SortAspect, pub SortDirection);
+
+impl Sort {
+    pub fn compare(&self, a: &DictIterItem, b: &DictIterItem) -> Ordering {
+        let ordering = match self.0 {
+            SortAspect::Length => a.normalized.len().cmp(&b.normalized.len()),
+            SortAspect::Alphabetical => a.normalized.cmp(&b.normalized),
+            SortAspect::Score => a.score.cmp(&b.score),
+        };
+        match self.1 {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DictSearch {
+    trie_search: Option<TrieSearch>,
+    predicate: WordPredicate,
+    dedupe: bool,
+    canonical: Canonical,
+}
+
+impl DictSearch {
+    pub fn new(trie_search: Option<TrieSearch>, predicate: WordPredicate) -> Self {
+        Self {
             trie_search,
-            predicate: WordPredicate::AnagramOf(anagram),
+            predicate,
+            ..Default::default()
+        }
+    }
+
+    /// Describes how the search runs: what is pushed into the trie
+    /// traversal, what is checked of each entry after, and which indexes
+    /// are used.
+    pub fn explain(&self) -> SearchPlan {
+        let trie_search = self.pruned_trie_search();
+        let bounds = self.predicate.letter_bounds();
+        let mut filters = vec![];
+        if bounds.allowed != CharSet::full() {
+            filters.push(format!("letter mask within {}", bounds.allowed));
+        }
+        filters.extend(self.predicate.filters());
+        if self.dedupe {
+            filters.push("one entry per normalized word".to_string());
+        }
+
+        let mut indexes = vec![];
+        let substring = self.predicate.required_substring();
+        if let Some(substring) =
+            substring.filter(|_| trie_search.order() != TraversalOrder::BreadthFirst)
+        {
+            indexes.push(format!(
+                "substring index, if built, for candidates containing {}",
+                substring
+            ));
+        }
+        indexes.push(match self.predicate.wants_anagram() {
+            true => "anagram index: not used; Dictionary::anagrams looks classes up in it directly"
+                .to_string(),
+            false => "anagram index: not used".to_string(),
+        });
+
+        let traversal = match self.split_trie_searches() {
+            Some(searches) if searches.len() > 1 => searches
+                .iter()
+                .enumerate()
+                .map(|(i, x)| format!("alternative {}: {}", i + 1, x.describe().join(", ")))
+                .collect(),
+            Some(searches) => searches[0].describe(),
+            None => trie_search.describe(),
+        };
+
+        SearchPlan {
+            traversal,
+            filters,
+            indexes,
+        }
+    }
+
+    // The trie search, narrowed by what of the predicate the traversal can
+    // prune on: the lengths of entries it can match, the letters they must
+    // have and how many different letters.
+    fn pruned_trie_search(&self) -> TrieSearch {
+        let (min, max) = self.predicate.length_bounds();
+        let required = self.predicate.letter_bounds().required;
+        let trie_search = self
+            .trie_search
+            .clone()
+            .unwrap_or_default()
+            .narrowed(min, max, required);
+        match self.predicate.distinct_letters() {
+            Some(range) => trie_search.with_distinct_letters(range),
+            None => trie_search,
+        }
+    }
+
+    // One trie search per prefix the predicate allows, when it requires one
+    // of them and the search has no prefix of its own, each narrowed as
+    // `pruned_trie_search` is. Together they yield in the same order.
+    fn split_trie_searches(&self) -> Option<Vec<TrieSearch>> {
+        let base = self.pruned_trie_search();
+        let unanchored =
+            (0..base.prefix_len()).all(|i| base.get_char_restriction(i) == CharMatch::Any);
+        if !unanchored || base.order() != TraversalOrder::DepthFirst {
+            return Option::None;
+        }
+        let alternatives = self.predicate.alternatives()?;
+        Some(
+            alternatives
+                .into_iter()
+                .map(|(prefix, max)| {
+                    base.with_prefix(prefix)
+                        .narrowed(base.prefix_len(), max, CharSet::new())
+                })
+                .collect(),
+        )
+    }
+
+    // The letters a matching entry can start with.
+    pub(crate) fn first_letters(&self) -> CharSet {
+        let restrictions = match self.split_trie_searches() {
+            Some(searches) => searches.iter().map(|x| x.get_char_restriction(0)).collect(),
+            None => vec![self.pruned_trie_search().get_char_restriction(0)],
+        };
+        let allowed = self.predicate.letter_bounds().allowed;
+        let mut letters = CharSet::new();
+        for ch in allowed.iter() {
+            if restrictions.iter().any(|x| x.matches(&ch)) {
+                letters.insert(ch);
+            }
+        }
+        letters
+    }
+
+    /// Yields one entry per normalized form, choosing its original by `canonical`.
+    pub fn with_dedupe(self, canonical: Canonical) -> Self {
+        DictSearch {
+            dedupe: true,
+            canonical,
+            ..self
+        }
+    }
+
+    /// Stops the search early once `token` is cancelled.
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        let trie_search = self.trie_search.unwrap_or_default();
+        DictSearch {
+            trie_search: Some(trie_search.with_cancellation(token)),
+            ..self
+        }
+    }
+
+    /// Keeps only phrases whose words have the lengths of `enumeration`,
+    /// joined by the same spaces and hyphens.
+    pub fn with_enumeration(self, enumeration: Enumeration) -> Self {
+        let len = enumeration.len();
+        let trie_search = self
+            .trie_search
+            .unwrap_or_else(|| TrieSearch::new(TriePrefix::any_with_length(len), None));
+        let predicate = match self.predicate {
+            WordPredicate::None => WordPredicate::Enumeration(enumeration),
+            predicate => {
+                WordPredicate::All(vec![predicate, WordPredicate::Enumeration(enumeration)])
+            }
+        };
+        DictSearch {
+            trie_search: Some(trie_search),
+            predicate,
+            ..self
         }
     }
+
+    pub fn from_pattern(pattern: &str) -> Result<DictSearch, PatternError> {
+        let prefix = TriePrefix::from_pattern(pattern)?;
+        let max_length = prefix.len();
+        let trie_search = Some(TrieSearch::new(prefix, Some(max_length)));
+        Ok(DictSearch {
+            trie_search,
+            ..Default::default()
+        })
+    }
+
+    pub fn anagram_of(str: &str) -> DictSearch {
+        let word = NormalizedWord::from_str_safe(str);
+        let anagram: AnagramNumber = (&word).try_into().unwrap();
+        DictSearch::new(None, WordPredicate::AnagramOf(anagram))
+    }
+
+    /// Words that can be spelled using only the given letters, or an error
+    /// if there are too many letters for an anagram number.
+    pub fn formable_from(letters: &str) -> Result<DictSearch, AnagramNumberOverflow> {
+        let word = NormalizedWord::from_str_safe(letters);
+        let anagram = AnagramNumber::try_from(&word)?;
+        let trie_search = Some(TrieSearch::new(Default::default(), Some(word.len())));
+        Ok(DictSearch::new(
+            trie_search,
+            WordPredicate::Any(vec![
+                WordPredicate::AnagramOf(anagram),
+                WordPredicate::SubanagramOf(anagram),
+            ]),
+        ))
+    }
+
+    /// Words that use every one of the given letters, and possibly others,
+    /// or an error if there are too many letters for an anagram number.
+    pub fn containing_letters(letters: &str) -> Result<DictSearch, AnagramNumberOverflow> {
+        let word = NormalizedWord::from_str_safe(letters);
+        let anagram = AnagramNumber::try_from(&word)?;
+        let prefix = TriePrefix::any_with_length(word.len());
+        Ok(DictSearch::new(
+            Some(TrieSearch::new(prefix, None)),
+            WordPredicate::Any(vec![
+                WordPredicate::AnagramOf(anagram),
+                WordPredicate::SuperanagramOf(anagram),
+            ]),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -215,4 +1713,868 @@ mod tests {
 
         assert_eq!(res, vec!["bat"])
     }
+
+    #[test]
+    fn anagrams_from_index() {
+        let dict = Dictionary::from_iter(vec!["tab", "cat", "bat", "Bat", "at"]);
+
+        let res: Vec<_> = dict.anagrams("tba").map(|x| x.original).collect();
+
+        assert_eq!(res, vec!["bat", "Bat", "tab"])
+    }
+
+    #[test]
+    fn anagrams_with_one_letter_more_or_less() {
+        let dict = Dictionary::from_iter(vec!["at", "cast", "cat", "coat", "scat", "taco", "to"]);
+        let changed = |results: Vec<(NormalizedChar, DictIterItem)>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|(ch, x)| format!("{}+{}", ch, x.original))
+                .collect()
+        };
+
+        assert_eq!(
+            changed(dict.anagrams_plus_one("act").collect()),
+            ["O+coat", "O+taco", "S+cast", "S+scat"]
+        );
+        assert_eq!(
+            changed(dict.anagrams_minus_one("tack").collect()),
+            ["K+cat"]
+        );
+        assert_eq!(dict.anagrams_minus_one("").count(), 0);
+    }
+
+    #[test]
+    fn filters_and_sorts_by_score() {
+        let mut dict: Dictionary = Default::default();
+        for (i, word) in ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"]
+            .iter()
+            .enumerate()
+        {
+            dict.insert_scored(word, i as u32 * 10);
+        }
+        dict.insert("unscored");
+
+        assert_eq!(dict.score_percentile(0), Some(0));
+        assert_eq!(dict.score_percentile(90), Some(90));
+        assert_eq!(dict.score_percentile(100), Some(90));
+        assert_eq!(Dictionary::from_iter(vec!["a"]).score_percentile(50), None);
+        assert_eq!(dict.score_rank(Some(50)), Some(0.5));
+        assert_eq!(dict.score_rank(None), Some(0.0));
+        assert_eq!(Dictionary::from_iter(vec!["a"]).score_rank(Some(1)), None);
+
+        let common = DictSearch::new(None, WordPredicate::ScoreAtLeast(80));
+        let rare = DictSearch::new(None, WordPredicate::ScoreBelow(20));
+        let sort = Sort(SortAspect::Score, SortDirection::Descending);
+        let originals = |items: Vec<DictIterItem>| -> Vec<String> {
+            items.into_iter().map(|x| x.original.clone()).collect()
+        };
+        assert_eq!(originals(dict.top_k(common, 5, sort)), ["j", "i"]);
+        assert_eq!(originals(dict.top_k(rare, 5, sort)), ["b", "a"]);
+
+        dict.insert_scored("k", 100);
+        assert_eq!(dict.score_percentile(100), Some(100));
+    }
+
+    #[test]
+    fn filters_blocked_words() {
+        let mut dict = Dictionary::from_iter(vec!["darn", "dart", "damn", "drat"]);
+        let list = "# mild ones\n\nDamn\ndrat\n";
+        assert_eq!(dict.read_blocklist(list.as_bytes()).unwrap(), 2);
+        dict.block("heck");
+
+        let search = DictSearch::new(None, WordPredicate::NotBlocked);
+        let words: Vec<&String> = dict.iter_search(search).map(|x| x.original).collect();
+        assert_eq!(words, ["darn", "dart"]);
+        assert!(dict.is_blocked(&NormalizedWord::from("heck")));
+    }
+
+    #[test]
+    fn filters_by_category() {
+        let mut dict = Dictionary::from_iter(vec!["iron", "irony", "neon", "lima"]);
+        let elements = "Iron\nNeon\n\nTin\n";
+        let report = dict
+            .read_category_from(elements.as_bytes(), None, NoProgress, "Element")
+            .unwrap();
+        assert_eq!(report.inserted, 3);
+        dict.categorize("Lima", "capital");
+        dict.categorize("Paris", "capital");
+
+        let search = |category: &str| -> Vec<String> {
+            let search = DictSearch::new(None, WordPredicate::Category(category.into()));
+            dict.iter_search(search)
+                .map(|x| x.original.clone())
+                .collect()
+        };
+        assert_eq!(search("element"), ["iron", "Iron", "neon", "Neon", "Tin"]);
+        assert_eq!(search("capital"), ["lima"]);
+        assert!(search("president").is_empty());
+        assert_eq!(dict.categories(), ["capital", "element"]);
+        assert!(dict.in_category(&NormalizedWord::from("paris"), "capital"));
+    }
+
+    #[test]
+    fn categorizes_words_already_present_without_duplicating_them() {
+        let mut dict = Dictionary::from_iter(vec!["iron", "irony", "tin"]);
+        let report = dict
+            .read_category_from("iron\nneon\n".as_bytes(), None, NoProgress, "element")
+            .unwrap();
+        assert_eq!(report.inserted, 1);
+        assert_eq!(report.issues, [(1, LineIssue::Duplicate)]);
+
+        let words: Vec<&str> = dict.iter().map(|x| &x.original[..]).collect();
+        assert_eq!(words, ["iron", "irony", "neon", "tin"]);
+        let search = DictSearch::new(None, WordPredicate::Category("element".into()));
+        let elements: Vec<&str> = dict.iter_search(search).map(|x| &x.original[..]).collect();
+        assert_eq!(elements, ["iron", "neon"]);
+    }
+
+    #[test]
+    fn finds_pangrams_and_isograms() {
+        let dict = Dictionary::from_iter(vec![
+            "warbling", "blowing", "glowing", "bowling", "brawl", "lowing",
+        ]);
+        let pangrams = DictSearch::new(
+            None,
+            WordPredicate::All(vec![
+                WordPredicate::OnlyLetters(CharSet::from(&NormalizedWord::from("bowling"))),
+                WordPredicate::DistinctLetters(7..=7),
+            ]),
+        );
+        let words: Vec<&String> = dict.iter_search(pangrams).map(|x| x.original).collect();
+        assert_eq!(words, ["blowing", "bowling"]);
+
+        let query = DictQuery::new()
+            .length(6..)
+            .matching(WordPredicate::DistinctLetters(6..=7));
+        let words: Vec<&String> = dict.query(&query).iter().map(|x| x.original).collect();
+        assert_eq!(words, ["blowing", "bowling", "glowing", "lowing"]);
+    }
+
+    #[test]
+    fn pages_and_samples_matches() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let dict = Dictionary::from_iter(vec!["ant", "bat", "cat", "dog", "eel", "fox", "gnu"]);
+        let all = || DictSearch::new(None, WordPredicate::None);
+        let words = |items: Vec<DictIterItem>| -> Vec<String> {
+            items.into_iter().map(|x| x.original.clone()).collect()
+        };
+
+        assert_eq!(words(dict.search_page(all(), 0, 3)), ["ant", "bat", "cat"]);
+        assert_eq!(words(dict.search_page(all(), 3, 3)), ["dog", "eel", "fox"]);
+        assert_eq!(words(dict.search_page(all(), 6, 3)), ["gnu"]);
+        assert!(dict.search_page(all(), 9, 3).is_empty());
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let sample = words(dict.sample(all(), 3, &mut rng));
+        assert_eq!(sample.len(), 3);
+        assert!(sample.windows(2).all(|x| x[0] < x[1]));
+        assert_eq!(dict.sample(all(), 10, &mut rng).len(), 7);
+        assert_eq!(dict.sample(all(), usize::MAX, &mut rng).len(), 7);
+
+        // every word turns up across many samples
+        let mut seen: Vec<String> = (0..50)
+            .flat_map(|_| words(dict.sample(all(), 1, &mut rng)))
+            .collect();
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 7);
+    }
+
+    #[test]
+    fn finds_reversals() {
+        let dict = Dictionary::from_iter(vec![
+            "stressed", "Desserts", "desserts", "noon", "live", "evil", "cat",
+        ]);
+        let pairs: Vec<(&str, &str)> = dict
+            .reversals()
+            .map(|(item, partner)| (&item.original[..], &partner.original[..]))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("Desserts", "stressed"),
+                ("evil", "live"),
+                ("live", "evil"),
+                ("stressed", "Desserts")
+            ]
+        );
+
+        let search = DictSearch::new(None, WordPredicate::ReversalIsWord);
+        assert_eq!(dict.count(search), 5);
+    }
+
+    #[test]
+    fn exports_anagram_clusters() {
+        let dict = Dictionary::from_iter(vec![
+            "tea", "eat", "Eat", "ate", "cat", "act", "dog", "zax", "bee",
+        ]);
+
+        // in order of first words, so AXZ for ZAX comes last
+        let mut out = Vec::new();
+        assert_eq!(dict.export_anagram_clusters(&mut out).unwrap(), 5);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "ACT\tACT\tCAT\nAET\tATE\tEAT\tTEA\nBEE\tBEE\nDGO\tDOG\nAXZ\tZAX\n"
+        );
+    }
+
+    #[test]
+    fn finds_anagram_records() {
+        let dict = Dictionary::from_iter(vec![
+            "tea", "eat", "Eat", "ate", "cat", "act", "listen", "silent", "tinsel", "enlist",
+            "master", "stream", "listened",
+        ]);
+        let words = |classes: Vec<&[NormalizedWord]>| -> Vec<String> {
+            classes
+                .iter()
+                .map(|x| {
+                    x.iter()
+                        .map(|w| w.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            words(dict.longest_anagram_pairs()),
+            ["ENLIST LISTEN SILENT TINSEL", "MASTER STREAM"]
+        );
+        assert_eq!(
+            words(dict.most_anagrammable(3)),
+            ["ENLIST LISTEN SILENT TINSEL", "ATE EAT TEA", "ACT CAT"]
+        );
+        assert!(Dictionary::from_iter(vec!["dog"])
+            .longest_anagram_pairs()
+            .is_empty());
+    }
+
+    #[test]
+    fn anagrams_of_unknown_letters_is_empty() {
+        let dict = Dictionary::from_iter(vec!["tab", "cat"]);
+
+        assert_eq!(dict.anagrams("dog").count(), 0)
+    }
+
+    #[test]
+    fn search_formable_from() {
+        let dict = Dictionary::from_iter(vec!["cat", "act", "at", "tact", "bat", "a"]);
+
+        let search = DictSearch::formable_from("tac").unwrap();
+        let res: Vec<_> = dict.iter_search(search).map(|x| x.original).collect();
+
+        assert_eq!(res, vec!["a", "act", "at", "cat"]);
+        assert_eq!(
+            DictSearch::formable_from(&"z".repeat(20)).err(),
+            Some(AnagramNumberOverflow)
+        );
+    }
+
+    #[test]
+    fn search_containing_letters() {
+        let dict = Dictionary::from_iter(vec!["cat", "act", "at", "tact", "bat", "a"]);
+
+        let search = DictSearch::containing_letters("ta").unwrap();
+        let res: Vec<_> = dict.iter_search(search).map(|x| x.original).collect();
+
+        assert_eq!(res, vec!["act", "at", "bat", "cat", "tact"])
+    }
+
+    #[test]
+    fn negates_predicates() {
+        let long = "catzzzzzzzzzzzzzzzzzzzz";
+        let dict = Dictionary::from_iter(vec!["cat", "tact", "bat", "zzzzzzzzzzzzzzzzzzzz", long]);
+        let search = |predicate| {
+            let res: Vec<_> = dict
+                .iter_search(DictSearch::new(None, predicate))
+                .map(|x| x.original.as_str())
+                .collect();
+            res
+        };
+        let not = |predicate| WordPredicate::Not(Box::new(predicate));
+        let anag = |x: &str| AnagramNumber::try_from(&NormalizedWord::from_str_safe(x)).unwrap();
+
+        assert_eq!(
+            search(not(WordPredicate::Contains("at".into()))),
+            ["tact", "zzzzzzzzzzzzzzzzzzzz"]
+        );
+        // too long for an anagram number, each long word has every
+        // superanagram but only one lacks the letters of CAT
+        assert_eq!(
+            search(WordPredicate::SuperanagramOf(anag("cat"))),
+            [long, "tact", "zzzzzzzzzzzzzzzzzzzz"]
+        );
+        assert_eq!(
+            search(not(WordPredicate::SuperanagramOf(anag("cat")))),
+            ["bat", "cat", "zzzzzzzzzzzzzzzzzzzz"]
+        );
+        assert_eq!(
+            search(not(WordPredicate::SubanagramOf(anag("tacts")))),
+            ["bat", long, "zzzzzzzzzzzzzzzzzzzz"]
+        );
+        // nested partial anagram predicates are compared by letter counts too
+        assert_eq!(
+            search(not(WordPredicate::All(vec![
+                WordPredicate::SubanagramOf(anag("tacts")),
+                WordPredicate::Contains("t".into()),
+            ]))),
+            ["bat", long, "zzzzzzzzzzzzzzzzzzzz"]
+        );
+        assert_eq!(
+            search(not(WordPredicate::Any(vec![
+                WordPredicate::SuperanagramOf(anag("cat")),
+                WordPredicate::Contains("b".into()),
+            ]))),
+            ["cat", "zzzzzzzzzzzzzzzzzzzz"]
+        );
+    }
+
+    #[test]
+    fn filters_by_letter_mask() {
+        let dict = Dictionary::from_iter(vec!["cat", "tact", "taco", "coat", "stoat"]);
+        assert_eq!(
+            dict.iter().next().unwrap().letter_mask(),
+            CharSet::from("act")
+        );
+
+        let predicate = WordPredicate::All(vec![
+            WordPredicate::Contains(NormalizedWord::from_str_safe("oa")),
+            WordPredicate::OnlyLetters(CharSet::from("acot")),
+        ]);
+        let search = DictSearch::new(None, predicate);
+        let res: Vec<_> = dict.iter_search(search).map(|x| x.original).collect();
+        assert_eq!(res, vec!["coat"]);
+
+        let either = WordPredicate::Any(vec![
+            WordPredicate::Contains(NormalizedWord::from_str_safe("s")),
+            WordPredicate::AnagramOf(
+                AnagramNumber::try_from(&NormalizedWord::from_str_safe("tca")).unwrap(),
+            ),
+        ]);
+        let search = DictSearch::new(None, either);
+        let res: Vec<_> = dict.iter_search(search).map(|x| x.original).collect();
+        assert_eq!(res, vec!["cat", "stoat"]);
+    }
+
+    #[test]
+    fn search_by_enumeration() {
+        let dict = Dictionary::from_iter(vec![
+            "ice cream",
+            "ice-cream",
+            "icecreams",
+            "ice creams",
+            "i scream",
+            "dice ream",
+        ]);
+        let search = |search: DictSearch, enumeration: &str| -> Vec<_> {
+            dict.iter_search(search.with_enumeration(enumeration.parse().unwrap()))
+                .map(|x| x.original)
+                .collect()
+        };
+
+        assert_eq!(search(DictSearch::default(), "3,5"), vec!["ice cream"]);
+        assert_eq!(search(DictSearch::default(), "3-5"), vec!["ice-cream"]);
+        assert_eq!(search(DictSearch::default(), "3,6"), vec!["ice creams"]);
+
+        let pattern = || DictSearch::from_pattern("i???????").unwrap();
+        assert_eq!(search(pattern(), "1,6"), Vec::<&String>::new());
+        assert_eq!(search(pattern(), "3,5"), vec!["ice cream"]);
+    }
+
+    #[test]
+    fn candidates_by_fixed_letters() {
+        use NormalizedChar::*;
+        let dict = Dictionary::from_iter(vec!["cat", "cot", "cut", "coat", "act", "Cot"]);
+        let words = |len, constraints: &[(usize, NormalizedChar)]| -> Vec<_> {
+            dict.candidates(len, constraints)
+                .map(|x| x.original)
+                .collect()
+        };
+
+        assert_eq!(words(3, &[(0, C), (2, T)]), ["cat", "cot", "Cot", "cut"]);
+        assert_eq!(words(3, &[(1, O), (1, O)]), ["cot", "Cot"]);
+        assert_eq!(words(4, &[]), ["coat"]);
+        assert!(words(3, &[(1, O), (1, A)]).is_empty());
+        assert!(words(3, &[(3, T)]).is_empty());
+    }
+
+    #[test]
+    fn complete_shortest_first() {
+        let mut dict = Dictionary::from_iter(vec!["cattle", "cat", "dog", "cats", "cab"]);
+        dict.block("cab");
+
+        let res: Vec<_> = dict
+            .complete("ca", 3)
+            .into_iter()
+            .map(|x| x.original)
+            .collect();
+
+        assert_eq!(res, vec!["cat", "cats", "cattle"])
+    }
+
+    #[test]
+    fn complete_by_score() {
+        let mut dict: Dictionary = Default::default();
+        dict.insert_scored("cattle", 40);
+        dict.insert_scored("cat", 20);
+        dict.insert_scored("cats", 60);
+        dict.insert("cab");
+
+        let res: Vec<_> = dict
+            .complete("ca", 10)
+            .into_iter()
+            .map(|x| x.original)
+            .collect();
+
+        assert_eq!(res, vec!["cats", "cattle", "cat", "cab"])
+    }
+
+    #[test]
+    fn count_matches_iteration() {
+        let dict = Dictionary::from_iter(vec!["cat", "bat", "bait", "at", "cat"]);
+
+        assert_eq!(dict.count(Default::default()), 5);
+        assert_eq!(dict.count(DictSearch::from_pattern("?at").unwrap()), 3);
+        assert_eq!(dict.count(DictSearch::anagram_of("tab")), 1);
+    }
+
+    #[test]
+    fn exists_finds_matches() {
+        let dict = Dictionary::from_iter(vec!["cat", "bat", "bait", "at"]);
+
+        assert!(dict.exists(DictSearch::from_pattern("b?it").unwrap()));
+        assert!(!dict.exists(DictSearch::from_pattern("b?t?s").unwrap()));
+        assert!(dict.exists(DictSearch::anagram_of("tac")));
+        assert!(!dict.exists(DictSearch::anagram_of("dog")));
+    }
+
+    #[test]
+    fn top_k_returns_first_k_sorted() {
+        let dict = Dictionary::from_iter(vec!["aaaa", "b", "ccc", "dd", "eeeee"]);
+
+        let sort = Sort(SortAspect::Length, SortDirection::Descending);
+        let res: Vec<_> = dict
+            .top_k(Default::default(), 3, sort)
+            .into_iter()
+            .map(|x| x.original)
+            .collect();
+
+        assert_eq!(res, vec!["eeeee", "aaaa", "ccc"])
+    }
+
+    #[test]
+    fn top_k_keeps_search_order_for_ties() {
+        let dict = Dictionary::from_iter(vec!["cat", "bat", "at", "ant", "a"]);
+
+        let sort = Sort(SortAspect::Length, SortDirection::Descending);
+        let res: Vec<_> = dict
+            .top_k(Default::default(), 2, sort)
+            .into_iter()
+            .map(|x| x.original)
+            .collect();
+
+        assert_eq!(res, vec!["ant", "bat"])
+    }
+
+    #[test]
+    fn nearest_anagrams_by_letters_changed() {
+        let dict = Dictionary::from_iter(vec![
+            "ocean", "canoe", "Canoe", "cane", "scone", "dog", "once",
+        ]);
+
+        let res: Vec<(&str, usize)> = dict
+            .nearest_anagrams("ocean", 3)
+            .into_iter()
+            .map(|(x, distance)| (&x.original[..], distance))
+            .collect();
+
+        assert_eq!(res, vec![("canoe", 0), ("cane", 1), ("once", 1)]);
+        assert!(dict.nearest_anagrams("ocean", 0).is_empty());
+    }
+
+    #[test]
+    fn iter_is_ordered_by_normalized_word() {
+        let dict = Dictionary::from_iter(vec!["tab", "Bat", "at", "a-b"]);
+
+        let res: Vec<_> = dict.iter().map(|x| x.original).collect();
+
+        assert_eq!(res, vec!["a-b", "at", "Bat", "tab"])
+    }
+
+    #[test]
+    fn shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Dictionary>();
+
+        let dict = ArcDictionary::from(Dictionary::from_iter(vec!["cat", "act", "dog"]));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let dict = dict.clone();
+                std::thread::spawn(move || dict.anagrams("tac").count())
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn reports_load_and_search_progress() {
+        let words = "cat\nact\ndog\n";
+        let mut updates = vec![];
+        let mut dict: Dictionary = Default::default();
+        dict.read_from(words.as_bytes(), Some(words.len()), |done, total| {
+            updates.push((done, total))
+        })
+        .unwrap();
+
+        assert_eq!(updates, vec![(12, Some(12))]);
+
+        let mut updates = vec![];
+        let found = dict
+            .iter_search_with_progress(DictSearch::anagram_of("tac"), |done, total| {
+                updates.push((done, total))
+            })
+            .count();
+
+        // dog is pruned from the traversal, lacking the anagram's letters
+        assert_eq!(found, 2);
+        assert_eq!(updates, vec![(2, Some(2))]);
+    }
+
+    #[test]
+    fn reports_skipped_and_suspect_lines() {
+        let long = "pneumonoultramicroscopicsilicovolcanoconiosis";
+        let words = format!("cat\n\n  \n42\ncat\nCat\n{}\n", long);
+        let mut dict: Dictionary = Default::default();
+        let report = dict.read_from(words.as_bytes(), None, NoProgress).unwrap();
+
+        assert_eq!(report.lines, 7);
+        assert_eq!(report.inserted, 4);
+        assert_eq!(
+            report.issues,
+            [
+                (2, LineIssue::Empty),
+                (3, LineIssue::Empty),
+                (4, LineIssue::NoLetters),
+                (5, LineIssue::Duplicate),
+                (7, LineIssue::TooLongForAnagram),
+            ]
+        );
+        assert_eq!(report.skipped(), 3);
+        assert_eq!(dict.iter().count(), 4);
+    }
+
+    #[test]
+    fn searches_by_language() {
+        let (en, fr) = ("en".parse().unwrap(), "fr".parse().unwrap());
+        let mut dict: Dictionary = Default::default();
+        dict.read_tagged_from("chat\ncat\n".as_bytes(), None, NoProgress, en)
+            .unwrap();
+        dict.read_tagged_from("chat\nchien\n".as_bytes(), None, NoProgress, fr)
+            .unwrap();
+        dict.insert("chit");
+
+        let find = |query: DictQuery| -> Vec<String> {
+            dict.query(&query)
+                .iter()
+                .map(|x| {
+                    format!(
+                        "{}:{}",
+                        x.original,
+                        x.lang.map_or("-".into(), |x| x.to_string())
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(
+            find(DictQuery::new().prefix("ch").languages(&[fr])),
+            ["chat:fr", "chien:fr"]
+        );
+        assert_eq!(
+            find(DictQuery::new().prefix("ch").languages(&[en, fr])),
+            ["chat:en", "chat:fr", "chien:fr"]
+        );
+        assert_eq!(find(DictQuery::new().prefix("chi")), ["chien:fr", "chit:-"]);
+    }
+
+    #[test]
+    fn normalizes_tagged_words_by_profile() {
+        let mut dict: Dictionary = Default::default();
+        dict.insert_tagged("Straße", "de".parse().unwrap());
+        dict.insert_tagged("Straße", "xx".parse().unwrap());
+
+        let words: Vec<String> = dict.iter().map(|x| x.normalized.to_string()).collect();
+        assert_eq!(words, ["STRAE", "STRASSE"]);
+    }
+
+    #[test]
+    fn dedupes_by_normalized_form() {
+        let mut dict = Dictionary::from_iter(vec!["Polish", "can't", "polish", "cant", "cat"]);
+        assert!(!dict.insert_unique("POLISH"));
+        assert!(dict.insert_unique("dog"));
+
+        let originals = |canonical| -> Vec<String> {
+            dict.iter_search(DictSearch::default().with_dedupe(canonical))
+                .map(|x| x.original.clone())
+                .collect()
+        };
+
+        assert_eq!(dict.count(Default::default()), 6);
+        assert_eq!(
+            dict.count(DictSearch::default().with_dedupe(Canonical::First)),
+            4
+        );
+        assert_eq!(
+            originals(Canonical::First),
+            vec!["can't", "cat", "dog", "Polish"]
+        );
+        assert_eq!(
+            originals(Canonical::Shortest),
+            vec!["cant", "cat", "dog", "Polish"]
+        );
+        assert_eq!(
+            originals(Canonical::LowercasePreferred),
+            vec!["can't", "cat", "dog", "polish"]
+        );
+    }
+
+    #[cfg(feature = "stemming")]
+    #[test]
+    fn finds_stem_family() {
+        let mut dict = Dictionary::from_iter(vec!["run", "Runs", "running", "ran", "rung"]);
+        let family = |dict: &Dictionary| -> Vec<String> {
+            dict.stem_family("runs")
+                .into_iter()
+                .map(|x| x.original.clone())
+                .collect()
+        };
+
+        assert_eq!(family(&dict), ["run", "running", "Runs"]);
+        dict.build_stem_index();
+        assert_eq!(family(&dict), ["run", "running", "Runs"]);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn matches_regular_expressions() {
+        let dict = Dictionary::from_iter(vec!["can't", "cant", "Cantor", "decant"]);
+        let search = |predicate| -> Vec<String> {
+            dict.iter_search(DictSearch::new(None, predicate))
+                .map(|x| x.original.clone())
+                .collect()
+        };
+        let regex = |x| WordRegex::new(x).unwrap();
+
+        assert_eq!(
+            search(WordPredicate::OriginalRegex(regex("^[a-z]+$"))),
+            ["cant", "decant"]
+        );
+        assert_eq!(
+            search(WordPredicate::NormalizedRegex(regex("^cant"))),
+            ["can't", "cant", "Cantor"]
+        );
+        assert_eq!(regex("^cant"), regex("^cant"));
+        assert!(WordRegex::new("(").is_err());
+    }
+
+    #[test]
+    fn substring_index_agrees_with_scan() {
+        let mut dict = Dictionary::from_iter(vec![
+            "acquire", "Quack", "quack", "queue", "squat", "suq", "aqua",
+        ]);
+        let search = || {
+            DictSearch::new(None, WordPredicate::Contains("qua".into()))
+                .with_dedupe(Canonical::First)
+        };
+        let scanned: Vec<String> = dict
+            .iter_search(search())
+            .map(|x| x.original.clone())
+            .collect();
+
+        dict.build_substring_index();
+        assert!(dict.has_substring_index());
+        let indexed: Vec<String> = dict
+            .iter_search(search())
+            .map(|x| x.original.clone())
+            .collect();
+        assert_eq!(indexed, scanned);
+        assert_eq!(indexed, vec!["aqua", "Quack", "squat"]);
+
+        let prefixed = DictSearch::new(
+            Some(TrieSearch::from_prefix("s?").unwrap()),
+            WordPredicate::Contains("qua".into()),
+        );
+        assert_eq!(dict.count(prefixed), 1);
+
+        dict.insert("equator");
+        assert!(!dict.has_substring_index());
+    }
+
+    #[test]
+    fn derives_trie_bounds_from_predicate() {
+        let dict = Dictionary::from_iter(vec![
+            "quite", "quiet", "queue", "equator", "qi", "aqua", "tea",
+        ]);
+        let predicate = WordPredicate::All(vec![
+            WordPredicate::Contains("qu".into()),
+            WordPredicate::Any(vec![
+                WordPredicate::AnagramOf(
+                    AnagramNumber::try_from(&NormalizedWord::from_str_safe("etiuq")).unwrap(),
+                ),
+                WordPredicate::Enumeration("(4)".parse().unwrap()),
+            ]),
+        ]);
+        let search = DictSearch::new(None, predicate.clone());
+
+        assert_eq!(
+            search.explain().traversal,
+            ["at least 4 letters", "containing each of QU"]
+        );
+        let found: Vec<String> = dict
+            .iter_search(search)
+            .map(|x| x.original.clone())
+            .collect();
+        let scanned: Vec<String> = dict
+            .iter()
+            .filter(|x| predicate.matches(&dict, x))
+            .map(|x| x.original.clone())
+            .collect();
+        assert_eq!(found, scanned);
+        assert_eq!(found, ["aqua", "quiet", "quite"]);
+
+        // bounds that cannot both hold leave nothing to traverse
+        let search = DictSearch::new(
+            Some(TrieSearch::from_prefix("q").unwrap().with_max(3)),
+            WordPredicate::Contains("quit".into()),
+        );
+        assert_eq!(dict.count(search), 0);
+    }
+
+    #[test]
+    fn enumerations_keep_words_lengthened_by_folding() {
+        let mut dict: Dictionary = Default::default();
+        dict.insert_tagged("Müller", "de".parse().unwrap());
+        dict.insert_tagged("Straße", "de".parse().unwrap());
+        dict.insert("miller");
+
+        let search = |x: &str| -> Vec<String> {
+            let search = DictSearch::new(None, WordPredicate::Enumeration(x.parse().unwrap()));
+            dict.iter_search(search)
+                .map(|x| x.normalized.to_string())
+                .collect()
+        };
+        assert_eq!(search("(6)"), ["MILLER", "MUELLER"]);
+        // ß is not a letter as spelled, but SS once folded
+        assert_eq!(search("(5)"), ["STRASSE"]);
+
+        let with_enumeration = |x: &str| -> Vec<String> {
+            dict.iter_search(DictSearch::default().with_enumeration(x.parse().unwrap()))
+                .map(|x| x.normalized.to_string())
+                .collect()
+        };
+        assert_eq!(with_enumeration("(6)"), ["MILLER", "MUELLER"]);
+        assert_eq!(with_enumeration("(5)"), ["STRASSE"]);
+    }
+
+    #[test]
+    fn splits_alternative_prefixes_into_several_searches() {
+        let dict = Dictionary::from_iter(vec![
+            "cat", "Cat", "cart", "cot", "coat", "dog", "bat", "scat",
+        ]);
+        let pattern = |x: &str| TriePrefix::from_pattern(x).unwrap();
+        let predicate = WordPredicate::All(vec![
+            WordPredicate::Any(vec![
+                WordPredicate::Pattern(pattern("c?t")),
+                WordPredicate::Prefix(pattern("ca")),
+                WordPredicate::Prefix(pattern("d")),
+            ]),
+            WordPredicate::Contains("t".into()),
+        ]);
+        let search = DictSearch::new(None, predicate.clone());
+
+        assert_eq!(
+            search.explain().traversal,
+            [
+                "alternative 1: starting C?T, at most 3 letters, containing each of T",
+                "alternative 2: starting CA, containing each of T",
+                "alternative 3: starting D, containing each of T"
+            ]
+        );
+        let found: Vec<String> = dict
+            .iter_search(search)
+            .map(|x| x.original.clone())
+            .collect();
+        let scanned: Vec<String> = dict
+            .iter()
+            .filter(|x| predicate.matches(&dict, x))
+            .map(|x| x.original.clone())
+            .collect();
+        assert_eq!(found, scanned);
+        assert_eq!(found, ["cart", "cat", "Cat", "cot"]);
+
+        let longest = dict.top_k(
+            DictSearch::new(None, predicate),
+            2,
+            Sort(SortAspect::Length, SortDirection::Descending),
+        );
+        let longest: Vec<&str> = longest.iter().map(|x| x.original.as_str()).collect();
+        assert_eq!(longest, ["cart", "cat"]);
+    }
+
+    #[test]
+    fn explains_a_search() {
+        let search = DictSearch::new(
+            Some(TrieSearch::from_prefix("c?t").unwrap().with_max(5)),
+            WordPredicate::All(vec![
+                WordPredicate::DistinctLetters(4..=4),
+                WordPredicate::Contains("at".into()),
+                WordPredicate::ScoreAtLeast(10),
+            ]),
+        )
+        .with_dedupe(Canonical::First);
+        let plan = search.explain();
+
+        assert_eq!(
+            plan.traversal,
+            [
+                "starting C?T",
+                "at least 4 letters",
+                "at most 5 letters",
+                "containing each of AT",
+                "with 4 different letters"
+            ]
+        );
+        assert_eq!(
+            plan.filters[plan.filters.len() - 3..],
+            [
+                "containing AT",
+                "scored at least 10",
+                "one entry per normalized word"
+            ]
+        );
+        assert_eq!(
+            plan.indexes[0],
+            "substring index, if built, for candidates containing AT"
+        );
+
+        let plan = DictSearch::new(
+            None,
+            WordPredicate::AnagramOf(
+                AnagramNumber::try_from(&NormalizedWord::from_str_safe("tea")).unwrap(),
+            ),
+        )
+        .explain();
+        assert_eq!(plan.traversal[0], "at least 3 letters");
+        assert!(plan.indexes[0].contains("Dictionary::anagrams"));
+
+        let plan = DictSearch::new(None, WordPredicate::ReversalIsWord).explain();
+        assert!(plan.traversal.is_empty());
+        assert!(plan
+            .to_string()
+            .starts_with("Trie traversal:\n  every word\nFilters:\n  a word when reversed\n"));
+    }
 }
@@ -0,0 +1,152 @@
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+
+/// One step in turning a word into another. `from` is a position in the
+/// first word and `to` a position in the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Keep {
+        from: usize,
+        to: usize,
+    },
+    Substitute {
+        from: usize,
+        to: usize,
+        ch: NormalizedChar,
+    },
+    Insert {
+        to: usize,
+        ch: NormalizedChar,
+    },
+    Delete {
+        from: usize,
+    },
+}
+
+/// The alignment of two words with the fewest changes, as returned by
+/// `NormalizedWord::diff`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WordDiff {
+    /// Every letter of both words in order, kept or changed.
+    pub edits: Vec<Edit>,
+}
+
+impl WordDiff {
+    pub fn between(a: &NormalizedWord, b: &NormalizedWord) -> Self {
+        let (n, m) = (a.len(), b.len());
+
+        // distances[i][j] is the distance between a[..i] and b[..j]
+        let mut distances = vec![vec![0; m + 1]; n + 1];
+        for i in 0..=n {
+            for j in 0..=m {
+                distances[i][j] = match (i, j) {
+                    (0, _) => j,
+                    (_, 0) => i,
+                    _ => (distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]))
+                        .min(distances[i - 1][j] + 1)
+                        .min(distances[i][j - 1] + 1),
+                };
+            }
+        }
+
+        let mut edits = vec![];
+        let (mut i, mut j) = (n, m);
+        while i > 0 || j > 0 {
+            let d = distances[i][j];
+            if i > 0 && j > 0 && d == distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]) {
+                i -= 1;
+                j -= 1;
+                edits.push(if a[i] == b[j] {
+                    Edit::Keep { from: i, to: j }
+                } else {
+                    Edit::Substitute {
+                        from: i,
+                        to: j,
+                        ch: b[j],
+                    }
+                });
+            } else if i > 0 && d == distances[i - 1][j] + 1 {
+                i -= 1;
+                edits.push(Edit::Delete { from: i });
+            } else {
+                j -= 1;
+                edits.push(Edit::Insert { to: j, ch: b[j] });
+            }
+        }
+        edits.reverse();
+        WordDiff { edits }
+    }
+
+    /// The edits other than letters kept.
+    pub fn changes(&self) -> impl Iterator<Item = &Edit> {
+        self.edits
+            .iter()
+            .filter(|x| !matches!(x, Edit::Keep { .. }))
+    }
+
+    /// The number of substitutions, insertions and deletions.
+    pub fn distance(&self) -> usize {
+        self.changes().count()
+    }
+
+    /// From 0 for words with nothing in common to 1 for the same word: the
+    /// share of the longer word's letters that need no change.
+    pub fn similarity(&self) -> f64 {
+        let longest = self
+            .edits
+            .iter()
+            .filter(|x| !matches!(x, Edit::Delete { .. }))
+            .count()
+            .max(
+                self.edits
+                    .iter()
+                    .filter(|x| !matches!(x, Edit::Insert { .. }))
+                    .count(),
+            );
+        if longest == 0 {
+            return 1.0;
+        }
+        1.0 - self.distance() as f64 / longest as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NormalizedChar::*;
+
+    #[test]
+    fn aligns_words_with_fewest_changes() {
+        let diff = NormalizedWord::from("kitten").diff(&"sitting".into());
+
+        assert_eq!(
+            diff.changes().copied().collect::<Vec<_>>(),
+            [
+                Edit::Substitute {
+                    from: 0,
+                    to: 0,
+                    ch: S
+                },
+                Edit::Substitute {
+                    from: 4,
+                    to: 4,
+                    ch: I
+                },
+                Edit::Insert { to: 6, ch: G },
+            ]
+        );
+        assert_eq!(diff.edits.len(), 7);
+        assert_eq!(diff.distance(), 3);
+        assert!((diff.similarity() - 4.0 / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scores_similarity() {
+        let diff = |a: &str, b: &str| NormalizedWord::from(a).diff(&b.into());
+
+        assert_eq!(diff("", "").similarity(), 1.0);
+        assert_eq!(diff("Café", "cafe").similarity(), 1.0);
+        assert_eq!(diff("abc", "").similarity(), 0.0);
+        assert_eq!(diff("cart", "cat").edits[2], Edit::Delete { from: 2 });
+        assert_eq!(diff("cart", "cat").similarity(), 0.75);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+
+/// A single token of a word: a letter, or a digraph counted as one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+    Letter(NormalizedChar),
+    /// The digraph at this index of the `Digraphs` that made the token.
+    Digraph(usize),
+}
+
+/// Runs of letters that count as a single token, as CH and LL in older
+/// Spanish alphabets, QU in some Scrabble variants or the trigraph SCH.
+/// Words are split into tokens from left to right, taking the longest
+/// digraph wherever one starts.
+///
+/// Only token counts are searchable, with `WordPredicate::TokenLength` or
+/// `DictQuery::token_length`. Tries and patterns still key words by single
+/// letters, so a digraph cannot stand in one square of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Digraphs {
+    runs: Vec<NormalizedWord>,
+}
+
+impl Digraphs {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the letters of `digraph` as a token. Fewer than two letters are
+    /// ignored.
+    pub fn with(mut self, digraph: &str) -> Self {
+        let run = NormalizedWord::from_str_safe(digraph);
+        if run.len() >= 2 {
+            self.runs.push(run);
+        }
+        self
+    }
+
+    /// CH, LL and RR, as tiles of Spanish Scrabble.
+    pub fn spanish() -> Self {
+        Digraphs::new().with("ch").with("ll").with("rr")
+    }
+
+    /// QU as one tile.
+    pub fn qu() -> Self {
+        Digraphs::new().with("qu")
+    }
+
+    /// The digraph a `Token::Digraph` stands for.
+    pub fn get(&self, index: usize) -> Option<&NormalizedWord> {
+        self.runs.get(index)
+    }
+
+    /// The most letters a token spells, which is 1 without digraphs.
+    pub fn max_len(&self) -> usize {
+        self.runs.iter().map(|x| x.len()).max().unwrap_or(1)
+    }
+
+    pub fn tokenize(&self, word: &NormalizedWord) -> Vec<Token> {
+        self.tokens(word).collect()
+    }
+
+    /// The number of tokens spelling `word`.
+    pub fn token_len(&self, word: &NormalizedWord) -> usize {
+        self.tokens(word).count()
+    }
+
+    // The tokens of `word` in order, without collecting them.
+    fn tokens<'a>(&'a self, word: &'a NormalizedWord) -> impl Iterator<Item = Token> + 'a {
+        let mut i = 0;
+        std::iter::from_fn(move || {
+            if i >= word.len() {
+                return None;
+            }
+            let digraph = self
+                .runs
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| word[i..].starts_with(&x[..]))
+                .max_by_key(|(index, x)| (x.len(), std::cmp::Reverse(*index)));
+            match digraph {
+                Some((index, run)) => {
+                    i += run.len();
+                    Some(Token::Digraph(index))
+                }
+                None => {
+                    i += 1;
+                    Some(Token::Letter(word[i - 1]))
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NormalizedChar::*;
+
+    #[test]
+    fn splits_words_into_tokens() {
+        let spanish = Digraphs::spanish();
+
+        assert_eq!(
+            spanish.tokenize(&"Llorar".into()),
+            [
+                Token::Digraph(1),
+                Token::Letter(O),
+                Token::Letter(R),
+                Token::Letter(A),
+                Token::Letter(R)
+            ]
+        );
+        assert_eq!(spanish.token_len(&"chorro".into()), 4);
+        assert_eq!(spanish.get(1), Some(&NormalizedWord::new(vec![L, L])));
+        assert_eq!(Digraphs::qu().token_len(&"quiche".into()), 5);
+        assert_eq!(Digraphs::new().with("q").token_len(&"quiche".into()), 6);
+    }
+
+    #[test]
+    fn prefers_the_longest_digraph() {
+        let german = Digraphs::new().with("sc").with("sch").with("ch");
+
+        assert_eq!(german.max_len(), 3);
+        assert_eq!(Digraphs::new().max_len(), 1);
+        assert_eq!(german.tokenize(&"schach".into())[0], Token::Digraph(1));
+        assert_eq!(german.token_len(&"schach".into()), 3);
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::crossword::{Cell, Grid};
+use crate::dictionary::{DictEntry, Dictionary};
+use crate::ngram::TrigramModel;
+use crate::normalized_word::{NormalizedChar, NormalizedWord, ALPHABET_SIZE};
+use crate::trie::Trie;
+
+const DEFAULT_BEAM: usize = 1000;
+// charged once for each word the dictionary lacks, on its first letter
+// that no dictionary word continues with
+const UNKNOWN_WORD: f64 = -6.0;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DropquoteError {
+    PoolCount {
+        columns: usize,
+        pools: usize,
+    },
+    /// A column whose pool is not as many letters as it has squares, or
+    /// lacks a letter given in the grid.
+    PoolMismatch(usize),
+}
+
+impl fmt::Display for DropquoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DropquoteError::PoolCount { columns, pools } => {
+                write!(f, "{} pools given for {} columns", pools, columns)
+            }
+            DropquoteError::PoolMismatch(col) => {
+                write!(f, "the pool of column {} does not fit its squares", col + 1)
+            }
+        }
+    }
+}
+
+/// A dropquote: a quotation whose letters have dropped to the foot of
+/// their columns, to be put back in the squares above.
+///
+/// The quotation reads across the grid then down, with blocks between
+/// words. A word reaching the end of a row goes on at the start of the
+/// next. Letters in the grid are given, and also counted in their pools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dropquote {
+    grid: Grid,
+    pools: Vec<[u8; ALPHABET_SIZE]>,
+}
+
+impl Dropquote {
+    pub fn new(grid: Grid, pools: &[&str]) -> Result<Self, DropquoteError> {
+        if pools.len() != grid.width() {
+            return Err(DropquoteError::PoolCount {
+                columns: grid.width(),
+                pools: pools.len(),
+            });
+        }
+        let mut counts = vec![];
+        for (col, pool) in pools.iter().enumerate() {
+            let mut count = [0; ALPHABET_SIZE];
+            let letters = NormalizedWord::from_str_safe(pool);
+            for &ch in letters.iter_chars() {
+                count[ch as usize] += 1;
+            }
+            let mut squares = 0;
+            let mut given = [0; ALPHABET_SIZE];
+            for row in 0..grid.height() {
+                match grid.cell(row, col) {
+                    Cell::Block => continue,
+                    Cell::Empty => {}
+                    Cell::Letter(ch) => given[ch as usize] += 1,
+                }
+                squares += 1;
+            }
+            if squares != letters.len() || given.iter().zip(count).any(|(&g, c)| g > c) {
+                return Err(DropquoteError::PoolMismatch(col));
+            }
+            // given letters are already placed, so not to be drawn again
+            for (left, g) in count.iter_mut().zip(given) {
+                *left -= g;
+            }
+            counts.push(count);
+        }
+        Ok(Dropquote {
+            grid,
+            pools: counts,
+        })
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    // The white squares in reading order, with whether each ends a word.
+    fn squares(&self) -> Vec<(usize, usize, bool)> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let cells: Vec<(usize, usize)> = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .collect();
+        cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &(row, col))| self.grid.cell(row, col) != Cell::Block)
+            .map(|(i, &(row, col))| {
+                let ends = cells
+                    .get(i + 1)
+                    .is_none_or(|&(row, col)| self.grid.cell(row, col) == Cell::Block);
+                (row, col, ends)
+            })
+            .collect()
+    }
+}
+
+/// A filling of a dropquote, with the words it reads as.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropquoteSolution {
+    pub grid: Grid,
+    pub words: Vec<NormalizedWord>,
+    /// The log of the chance of the letters by the trigram model, less a
+    /// penalty for each word not in the dictionary.
+    pub score: f64,
+}
+
+impl fmt::Display for DropquoteSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.words.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", word)?;
+        }
+        Ok(())
+    }
+}
+
+/// Solves dropquotes by beam search across the grid, scoring partial
+/// words by letter trigrams and finished ones by the dictionary, so that
+/// names and other words it lacks can still be found.
+pub struct DropquoteSolver<'a> {
+    dict: &'a Dictionary,
+    model: TrigramModel,
+    beam: usize,
+}
+
+#[derive(Clone)]
+struct State<'a> {
+    pools: Vec<[u8; ALPHABET_SIZE]>,
+    letters: Vec<NormalizedChar>,
+    word_start: usize,
+    // where the word so far is in the dictionary's trie, if anywhere
+    node: Option<&'a Trie<DictEntry>>,
+    score: f64,
+}
+
+impl<'a> DropquoteSolver<'a> {
+    pub fn new(dict: &'a Dictionary) -> Self {
+        DropquoteSolver {
+            dict,
+            model: TrigramModel::from_dictionary(dict),
+            beam: DEFAULT_BEAM,
+        }
+    }
+
+    /// Keeps the best `beam` partial fillings at each square. Wider beams
+    /// find more, more slowly.
+    pub fn with_beam(self, beam: usize) -> Self {
+        DropquoteSolver {
+            beam: beam.max(1),
+            ..self
+        }
+    }
+
+    /// Returns up to `limit` fillings, best first.
+    pub fn solve(&self, puzzle: &Dropquote, limit: usize) -> Vec<DropquoteSolution> {
+        let squares = puzzle.squares();
+        let mut states = vec![State {
+            pools: puzzle.pools.clone(),
+            letters: vec![],
+            word_start: 0,
+            node: Some(self.dict.trie()),
+            score: 0.0,
+        }];
+        for (i, &(row, col, ends)) in squares.iter().enumerate() {
+            let mut next: Vec<State> = vec![];
+            // states with the same letters left and the same word so far
+            // score the same from here on, so keep only the best, until
+            // the last square where each is a different filling
+            let mut seen: HashMap<(Vec<[u8; ALPHABET_SIZE]>, Vec<NormalizedChar>), usize> =
+                HashMap::new();
+            let given = match puzzle.grid.cell(row, col) {
+                Cell::Letter(ch) => Some(ch),
+                _ => None,
+            };
+            for state in states.iter() {
+                let choices: Vec<NormalizedChar> = match given {
+                    Some(ch) => vec![ch],
+                    None => NormalizedChar::all()
+                        .filter(|&ch| state.pools[col][ch as usize] > 0)
+                        .collect(),
+                };
+                for ch in choices {
+                    let state = self.extend(state, col, ch, ends, given.is_none());
+                    if i + 1 == squares.len() {
+                        next.push(state);
+                        continue;
+                    }
+                    let key = (
+                        state.pools.clone(),
+                        state.letters[state.word_start..].to_vec(),
+                    );
+                    match seen.get(&key) {
+                        Some(&j) if next[j].score >= state.score => {}
+                        Some(&j) => next[j] = state,
+                        None => {
+                            seen.insert(key, next.len());
+                            next.push(state);
+                        }
+                    }
+                }
+            }
+            next.sort_by(|a, b| b.score.total_cmp(&a.score));
+            next.truncate(self.beam);
+            states = next;
+        }
+        states.truncate(limit);
+        states
+            .into_iter()
+            .map(|state| self.solution(puzzle, &squares, state))
+            .collect()
+    }
+
+    fn extend(
+        &self,
+        state: &State<'a>,
+        col: usize,
+        ch: NormalizedChar,
+        ends: bool,
+        from_pool: bool,
+    ) -> State<'a> {
+        let mut next = state.clone();
+        if from_pool {
+            next.pools[col][ch as usize] -= 1;
+        }
+        next.score += self
+            .model
+            .next_log_prob(&state.letters[state.word_start..], Some(ch));
+        next.node = state.node.and_then(|node| node.child(ch));
+        if state.node.is_some() && next.node.is_none() {
+            next.score += UNKNOWN_WORD;
+        }
+        next.letters.push(ch);
+        if ends {
+            let word = &next.letters[next.word_start..];
+            next.score += self.model.next_log_prob(word, None);
+            if next.node.is_some_and(|node| !node.is_terminal()) {
+                next.score += UNKNOWN_WORD;
+            }
+            next.word_start = next.letters.len();
+            next.node = Some(self.dict.trie());
+        }
+        next
+    }
+
+    fn solution(
+        &self,
+        puzzle: &Dropquote,
+        squares: &[(usize, usize, bool)],
+        state: State,
+    ) -> DropquoteSolution {
+        let mut grid = puzzle.grid.clone();
+        let mut words = vec![];
+        let mut word = NormalizedWord::default();
+        for (&(row, col, ends), &ch) in squares.iter().zip(state.letters.iter()) {
+            grid.set_cell(row, col, Cell::Letter(ch));
+            word.push(ch);
+            if ends {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        DropquoteSolution {
+            grid,
+            words,
+            score: state.score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLVED: &str = "
+        THE#CAT
+        #SAT#ON
+        #A#MAT#";
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec![
+            "the", "cat", "sat", "on", "mat", "hat", "tan", "ant", "net", "ten", "at", "he", "as",
+            "has", "ash", "no", "am", "ma", "eh",
+        ])
+    }
+
+    // The letters of each column of a solved grid.
+    fn pools(solved: &Grid) -> Vec<String> {
+        (0..solved.width())
+            .map(|col| {
+                (0..solved.height())
+                    .filter_map(|row| match solved.cell(row, col) {
+                        Cell::Letter(ch) => Some(ch.to_string()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn puzzle(solved: &Grid) -> Dropquote {
+        let blank = solved
+            .to_string()
+            .replace(|ch: char| ch.is_alphabetic(), ".");
+        let pools = pools(solved);
+        let pools: Vec<&str> = pools.iter().map(|x| &x[..]).collect();
+        Dropquote::new(blank.parse().unwrap(), &pools).unwrap()
+    }
+
+    #[test]
+    fn solves_words_wrapping_across_rows() {
+        let solved: Grid = SOLVED.parse().unwrap();
+        let dict = dict();
+        let solutions = DropquoteSolver::new(&dict)
+            .with_beam(200)
+            .solve(&puzzle(&solved), 3);
+
+        assert_eq!(solutions[0].to_string(), "THE CAT SAT ON A MAT");
+        assert_eq!(solutions[0].grid, solved);
+        assert!(solutions.windows(2).all(|x| x[0].score >= x[1].score));
+    }
+
+    #[test]
+    fn keeps_given_letters_and_checks_pools() {
+        let solved: Grid = SOLVED.parse().unwrap();
+        let mut given = puzzle(&solved);
+        given.grid.set_cell(2, 3, Cell::Letter(NormalizedChar::M));
+        let dict = dict();
+        let solution = &DropquoteSolver::new(&dict).solve(&given, 1)[0];
+        assert_eq!(solution.grid, solved);
+
+        let grid: Grid = "..\n.#".parse().unwrap();
+        assert_eq!(
+            Dropquote::new(grid.clone(), &["ab"]),
+            Err(DropquoteError::PoolCount {
+                columns: 2,
+                pools: 1
+            })
+        );
+        assert_eq!(
+            Dropquote::new(grid.clone(), &["ab", "cd"]),
+            Err(DropquoteError::PoolMismatch(1))
+        );
+        let given: Grid = "a.\n.#".parse().unwrap();
+        assert_eq!(
+            Dropquote::new(given, &["bc", "d"]),
+            Err(DropquoteError::PoolMismatch(0))
+        );
+        assert!(Dropquote::new(grid, &["ab", "c"]).is_ok());
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::normalized_word::NormalizedChar;
+
+/// What joins two words of a phrase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Separator {
+    Space,
+    Hyphen,
+}
+
+/// The lengths of the words of an answer, as crossword clues give them: (3,5)
+/// for ICE CREAM and (3-5) for ICE-CREAM.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Enumeration {
+    lengths: Vec<usize>,
+    // one fewer than the lengths, joining each word to the next
+    separators: Vec<Separator>,
+}
+
+impl Enumeration {
+    /// The enumeration of a word or phrase as spelled. Letters count once
+    /// each, spaces and hyphens split words, and anything else, such as the
+    /// apostrophe of "can't", is ignored.
+    pub fn of(phrase: &str) -> Self {
+        let mut lengths = vec![];
+        let mut separators = vec![];
+        let mut len = 0;
+        let mut gap = None;
+        for ch in phrase.chars() {
+            let separator = match ch {
+                '-' => Separator::Hyphen,
+                ch if ch.is_whitespace() => Separator::Space,
+                ch => {
+                    if NormalizedChar::from_char(ch).is_some() {
+                        if len == 0 {
+                            separators.extend(gap.take());
+                        }
+                        len += 1;
+                    }
+                    continue;
+                }
+            };
+            if len > 0 {
+                lengths.push(len);
+                len = 0;
+                gap = Some(separator);
+            } else if gap.is_some() && separator == Separator::Hyphen {
+                // "ice - cream" is hyphenated
+                gap = Some(separator);
+            }
+        }
+        if len > 0 {
+            lengths.push(len);
+        }
+        Enumeration {
+            lengths,
+            separators,
+        }
+    }
+
+    pub fn lengths(&self) -> &[usize] {
+        &self.lengths
+    }
+
+    pub fn separators(&self) -> &[Separator] {
+        &self.separators
+    }
+
+    /// The number of letters in all.
+    pub fn len(&self) -> usize {
+        self.lengths.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// Whether `phrase` has words of these lengths, joined the same way.
+    pub fn matches(&self, phrase: &str) -> bool {
+        Enumeration::of(phrase) == *self
+    }
+}
+
+impl fmt::Display for Enumeration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, len) in self.lengths.iter().enumerate() {
+            if i > 0 {
+                match self.separators[i - 1] {
+                    Separator::Space => write!(f, ",")?,
+                    Separator::Hyphen => write!(f, "-")?,
+                }
+            }
+            write!(f, "{}", len)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidEnumeration(pub String);
+
+impl fmt::Display for InvalidEnumeration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid enumeration '{}': expected word lengths such as '3,4' or '3-4'",
+            self.0
+        )
+    }
+}
+
+impl FromStr for Enumeration {
+    type Err = InvalidEnumeration;
+
+    /// Parses lengths separated by commas for spaces and hyphens for hyphens,
+    /// optionally in parentheses.
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidEnumeration(str.to_string());
+        let trimmed = str.trim();
+        let inner = trimmed
+            .strip_prefix('(')
+            .and_then(|x| x.strip_suffix(')'))
+            .unwrap_or(trimmed);
+
+        let mut lengths = vec![];
+        let mut separators = vec![];
+        for (i, part) in inner.split(',').enumerate() {
+            if i > 0 {
+                separators.push(Separator::Space);
+            }
+            for (j, word) in part.split('-').enumerate() {
+                if j > 0 {
+                    separators.push(Separator::Hyphen);
+                }
+                match word.trim().parse() {
+                    Ok(len) if len > 0 => lengths.push(len),
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+        Ok(Enumeration {
+            lengths,
+            separators,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(str: &str) -> Enumeration {
+        str.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_lengths_and_separators() {
+        assert_eq!(parse("3,5"), Enumeration::of("ice cream"));
+        assert_eq!(parse("(3-5)"), Enumeration::of("ice-cream"));
+        assert_eq!(parse(" 2, 3-4 ").to_string(), "(2,3-4)");
+        assert_eq!(parse("2,3-4").len(), 9);
+        assert!("3,,4".parse::<Enumeration>().is_err());
+        assert!("0".parse::<Enumeration>().is_err());
+        assert!("three".parse::<Enumeration>().is_err());
+    }
+
+    #[test]
+    fn describes_phrases() {
+        assert_eq!(Enumeration::of("can't").lengths(), [4]);
+        assert_eq!(Enumeration::of("  ice  -  cream ").to_string(), "(3-5)");
+        assert_eq!(Enumeration::of("jack-in-the-box").to_string(), "(4-2-3-3)");
+        assert!(parse("3,1,3").matches("Man o' War"));
+        assert!(!parse("3,5").matches("ice-cream"));
+        assert!(Enumeration::of("").is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::dictionary::DictIterItem;
+
+/// The score written for words without one, the usual neutral score in
+/// constructor word lists.
+pub const DEFAULT_SCORE: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `word;score` lines, keeping each word as originally written.
+    ScoredList,
+    /// Crossfire and Ingrid `.dict` files: `WORD;score` lines of normalized
+    /// words, each written once with the score of its first item.
+    Dict,
+    /// A JSON array of `{"word", "normalized", "score"}` objects.
+    Json,
+}
+
+impl ExportFormat {
+    pub const NAMES: [&'static str; 3] = ["list", "dict", "json"];
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownExportFormat(pub String);
+
+impl fmt::Display for UnknownExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown format '{}': expected one of {}",
+            self.0,
+            ExportFormat::NAMES.join(", ")
+        )
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = UnknownExportFormat;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "list" => Ok(ExportFormat::ScoredList),
+            "dict" => Ok(ExportFormat::Dict),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(UnknownExportFormat(str.to_string())),
+        }
+    }
+}
+
+/// Writes `items` in `format`, in the order given.
+pub fn export<'a, W, I>(format: ExportFormat, mut out: W, items: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a DictIterItem<'a>>,
+{
+    let items = items.into_iter();
+    match format {
+        ExportFormat::ScoredList => {
+            for item in items {
+                writeln!(out, "{};{}", item.original, score(item))?;
+            }
+        }
+        ExportFormat::Dict => {
+            let mut written = HashSet::new();
+            for item in items {
+                if written.insert(&item.normalized) {
+                    writeln!(out, "{};{}", item.normalized, score(item))?;
+                }
+            }
+        }
+        ExportFormat::Json => {
+            write!(out, "[")?;
+            for (i, item) in items.enumerate() {
+                let separator = if i == 0 { "" } else { "," };
+                write!(
+                    out,
+                    "{}\n  {{\"word\": {}, \"normalized\": \"{}\"",
+                    separator,
+                    json_string(item.original),
+                    item.normalized
+                )?;
+                if let Some(score) = item.score {
+                    write!(out, ", \"score\": {}", score)?;
+                }
+                write!(out, "}}")?;
+            }
+            writeln!(out, "\n]")?;
+        }
+    }
+    out.flush()
+}
+
+fn score(item: &DictIterItem) -> u32 {
+    item.score.unwrap_or(DEFAULT_SCORE)
+}
+
+fn json_string(str: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in str.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+
+    fn exported(format: ExportFormat) -> String {
+        let mut dict: Dictionary = Default::default();
+        dict.insert_scored("Can't", 60);
+        dict.insert("cant");
+        dict.insert("say \"hi\"");
+        let items: Vec<_> = dict.iter().collect();
+
+        let mut out = Vec::new();
+        export(format, &mut out, &items).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_each_format() {
+        assert_eq!(
+            exported(ExportFormat::ScoredList),
+            "Can't;60\ncant;50\nsay \"hi\";50\n"
+        );
+        assert_eq!(exported(ExportFormat::Dict), "CANT;60\nSAYHI;50\n");
+        assert_eq!(
+            exported(ExportFormat::Json),
+            "[\n  {\"word\": \"Can't\", \"normalized\": \"CANT\", \"score\": 60},\
+             \n  {\"word\": \"cant\", \"normalized\": \"CANT\"},\
+             \n  {\"word\": \"say \\\"hi\\\"\", \"normalized\": \"SAYHI\"}\n]\n"
+        );
+
+        // sorted results can separate spellings of the same word
+        let dict = Dictionary::from_iter(vec!["cant", "act", "Can't"]);
+        let mut items: Vec<_> = dict.iter().collect();
+        items.sort_by_key(|x| x.original.to_string());
+        let mut out = Vec::new();
+        export(ExportFormat::Dict, &mut out, &items).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "CANT;50\nACT;50\n");
+
+        assert_eq!(
+            "xml".parse::<ExportFormat>(),
+            Err(UnknownExportFormat("xml".into()))
+        );
+    }
+}
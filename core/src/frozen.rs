@@ -0,0 +1,381 @@
+use std::collections::VecDeque;
+use std::mem::size_of;
+use std::ops::Range;
+
+use crate::dictionary::{DictEntry, DictIterItem};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::{Trie, TrieSearch};
+
+// bits per block of the rank directory
+const BLOCK: usize = 512;
+
+/// How `Dictionary::freeze` lays out its read-only trie.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Encoding {
+    /// The level-order unary degree sequence: each node's children as that
+    /// many 1 bits and a 0, breadth first, navigated by rank and select.
+    /// Takes about two bits and a letter per node where the `Trie` takes a
+    /// table of 26 pointers.
+    Louds,
+}
+
+// A fixed sequence of bits answering rank and select queries, with the
+// ones before each block of 512 bits kept to answer them quickly.
+#[derive(Debug, Default, Clone)]
+struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+    block_ranks: Vec<u32>,
+}
+
+impl BitVec {
+    fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        if bit {
+            self.words[self.len / 64] |= 1 << (self.len % 64);
+        }
+        self.len += 1;
+    }
+
+    fn finish(&mut self) {
+        let mut ones = 0;
+        self.block_ranks = self
+            .words
+            .chunks(BLOCK / 64)
+            .map(|block| {
+                let rank = ones;
+                ones += block.iter().map(|x| x.count_ones()).sum::<u32>();
+                rank
+            })
+            .collect();
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    // The number of ones before position `i`.
+    fn rank1(&self, i: usize) -> usize {
+        let word = i / 64;
+        let block_start = word - word % (BLOCK / 64);
+        let whole: u32 = self.words[block_start..word]
+            .iter()
+            .map(|x| x.count_ones())
+            .sum();
+        let partial = match i % 64 {
+            0 => 0,
+            bits => (self.words[word] & (u64::MAX >> (64 - bits))).count_ones(),
+        };
+        self.block_ranks[i / BLOCK] as usize + (whole + partial) as usize
+    }
+
+    // The position of the zero with `k` zeros before it.
+    fn select0(&self, k: usize) -> usize {
+        let zeros_before = |block: usize| block * BLOCK - self.block_ranks[block] as usize;
+        // the last block with no more than `k` zeros before it
+        let (mut block, mut end) = (0, self.block_ranks.len());
+        while end - block > 1 {
+            let mid = (block + end) / 2;
+            if zeros_before(mid) <= k {
+                block = mid;
+            } else {
+                end = mid;
+            }
+        }
+        let mut remaining = k - zeros_before(block);
+        for (w, &word) in self.words.iter().enumerate().skip(block * BLOCK / 64) {
+            let zeros = (!word).count_ones() as usize;
+            if remaining < zeros {
+                let mut word = !word;
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                return w * 64 + word.trailing_zeros() as usize;
+            }
+            remaining -= zeros;
+        }
+        self.len
+    }
+
+    fn heap_size(&self) -> usize {
+        self.words.len() * size_of::<u64>() + self.block_ranks.len() * size_of::<u32>()
+    }
+}
+
+/// A read-only trie in the `Encoding::Louds` layout, built from a `Trie`,
+/// for lexicons too large to hold as one. Nodes are numbered breadth
+/// first from the root at 0.
+#[derive(Debug, Clone)]
+pub struct FrozenTrie<T> {
+    louds: BitVec,
+    // the letter leading to each node but the root
+    labels: Vec<NormalizedChar>,
+    terminal: BitVec,
+    // where each terminal node's values end in `values`
+    value_ends: Vec<u32>,
+    values: Vec<T>,
+}
+
+impl<T: Clone> FrozenTrie<T> {
+    pub fn from_trie(trie: &Trie<T>) -> Self {
+        let mut frozen = FrozenTrie {
+            louds: Default::default(),
+            labels: vec![],
+            terminal: Default::default(),
+            value_ends: vec![],
+            values: vec![],
+        };
+        // a super-root whose one child is the root
+        frozen.louds.push(true);
+        frozen.louds.push(false);
+        let mut queue = VecDeque::from([trie]);
+        while let Some(node) = queue.pop_front() {
+            frozen.terminal.push(node.is_terminal());
+            if node.is_terminal() {
+                frozen.values.extend_from_slice(node.terminals());
+                frozen.value_ends.push(frozen.values.len() as u32);
+            }
+            for ch in NormalizedChar::all() {
+                if let Some(child) = node.child(ch) {
+                    frozen.louds.push(true);
+                    frozen.labels.push(ch);
+                    queue.push_back(child);
+                }
+            }
+            frozen.louds.push(false);
+        }
+        frozen.louds.finish();
+        frozen.terminal.finish();
+        frozen
+    }
+}
+
+impl<T> FrozenTrie<T> {
+    /// The number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The number of nodes, the root included.
+    pub fn node_count(&self) -> usize {
+        self.labels.len() + 1
+    }
+
+    /// Bytes held on the heap by the encoding itself, not counting the
+    /// values or anything they own.
+    pub fn heap_size(&self) -> usize {
+        self.louds.heap_size()
+            + self.terminal.heap_size()
+            + self.labels.len() * size_of::<NormalizedChar>()
+            + self.value_ends.len() * size_of::<u32>()
+    }
+
+    pub fn get(&self, key: &NormalizedWord) -> Option<&[T]> {
+        let mut node = 0;
+        for &ch in key.iter_chars() {
+            node = self.child(node, ch)?;
+        }
+        Some(self.values(node)).filter(|x| !x.is_empty())
+    }
+
+    /// Iterates over every key and value, in lexicographic key order.
+    pub fn iter(&self) -> FrozenIter<'_, T> {
+        self.iter_search(Default::default())
+    }
+
+    /// Iterates over keys and values matching `search`, in lexicographic
+    /// key order whatever its `TraversalOrder`. Only the prefix and maximum
+    /// length prune the traversal; other constraints filter the keys found.
+    pub fn iter_search(&self, search: TrieSearch) -> FrozenIter<'_, T> {
+        FrozenIter {
+            trie: self,
+            search,
+            stack: vec![(0, NormalizedWord::default())],
+            current: Default::default(),
+            terminals: [].iter(),
+        }
+    }
+
+    fn children(&self, node: usize) -> Range<usize> {
+        let start = self.louds.select0(node) + 1;
+        let end = self.louds.select0(node + 1);
+        let first = self.louds.rank1(start);
+        first..first + (end - start)
+    }
+
+    fn child(&self, node: usize, ch: NormalizedChar) -> Option<usize> {
+        let children = self.children(node);
+        let labels = &self.labels[children.start - 1..children.end - 1];
+        labels.binary_search(&ch).ok().map(|i| children.start + i)
+    }
+
+    fn values(&self, node: usize) -> &[T] {
+        if !self.terminal.get(node) {
+            return &[];
+        }
+        let rank = self.terminal.rank1(node);
+        let start = match rank {
+            0 => 0,
+            _ => self.value_ends[rank - 1] as usize,
+        };
+        &self.values[start..self.value_ends[rank] as usize]
+    }
+}
+
+/// Iterator returned by `FrozenTrie::iter_search`.
+pub struct FrozenIter<'a, T> {
+    trie: &'a FrozenTrie<T>,
+    search: TrieSearch,
+    // nodes still to visit, last first, with their keys
+    stack: Vec<(usize, NormalizedWord)>,
+    current: NormalizedWord,
+    terminals: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for FrozenIter<'a, T> {
+    type Item = (NormalizedWord, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.terminals.next() {
+                return Some((self.current.clone(), value));
+            }
+            if self.search.is_cancelled() {
+                return None;
+            }
+            let (node, word) = self.stack.pop()?;
+            let depth = word.len();
+            if self.search.below_max(depth) {
+                let restriction = self.search.get_char_restriction(depth);
+                for child in self.trie.children(node).rev() {
+                    let ch = self.trie.labels[child - 1];
+                    if restriction.matches(&ch) {
+                        let mut child_word = word.clone();
+                        child_word.push(ch);
+                        self.stack.push((child, child_word));
+                    }
+                }
+            }
+            if self.search.matches(&word) {
+                self.terminals = self.trie.values(node).iter();
+                self.current = word;
+            }
+        }
+    }
+}
+
+/// A read-only copy of a dictionary's entries, without its indexes or
+/// blocklist, returned by `Dictionary::freeze`.
+#[derive(Debug, Clone)]
+pub struct FrozenDictionary {
+    trie: FrozenTrie<DictEntry>,
+}
+
+impl FrozenDictionary {
+    pub(crate) fn new(trie: &Trie<DictEntry>, encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Louds => FrozenDictionary {
+                trie: FrozenTrie::from_trie(trie),
+            },
+        }
+    }
+
+    pub fn trie(&self) -> &FrozenTrie<DictEntry> {
+        &self.trie
+    }
+
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    pub fn find(&self, word: &NormalizedWord) -> Option<&[DictEntry]> {
+        self.trie.get(word)
+    }
+
+    /// Iterates over every entry, ordered by normalized word.
+    pub fn iter(&self) -> impl Iterator<Item = DictIterItem<'_>> {
+        self.trie.iter().map(DictIterItem::from)
+    }
+
+    /// Iterates over entries matching `search`, ordered by normalized word.
+    pub fn iter_search(&self, search: TrieSearch) -> impl Iterator<Item = DictIterItem<'_>> {
+        self.trie.iter_search(search).map(DictIterItem::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+
+    fn words() -> Vec<String> {
+        let letters = "abcdefgh";
+        let mut words = vec![];
+        for a in letters.chars() {
+            words.push(a.to_string());
+            for b in letters.chars() {
+                for c in letters.chars() {
+                    words.push([a, b, c].iter().collect());
+                }
+            }
+        }
+        words
+    }
+
+    #[test]
+    fn agrees_with_the_trie() {
+        let mut trie: Trie<usize> = Default::default();
+        for (i, word) in words().iter().enumerate() {
+            trie.add_string(word, i);
+        }
+        trie.add_string("bad", 1000);
+        let frozen = FrozenTrie::from_trie(&trie);
+
+        assert_eq!(frozen.len(), trie.len());
+        assert!(frozen.node_count() > 512);
+        for search in [
+            TrieSearch::default(),
+            TrieSearch::from_prefix("b?d").unwrap(),
+            TrieSearch::exactly("?a?").unwrap(),
+            TrieSearch::from_prefix("h").unwrap().with_max(1),
+            TrieSearch::default().containing("GH".into()),
+        ] {
+            let expected: Vec<_> = trie.iter_search(search.clone()).collect();
+            let found: Vec<_> = frozen.iter_search(search).collect();
+            assert_eq!(found, expected);
+        }
+        assert_eq!(frozen.get(&"BAD".into()), Some(&[69, 1000][..]));
+        assert_eq!(frozen.get(&"BA".into()), None);
+        assert_eq!(frozen.get(&"BAZ".into()), None);
+    }
+
+    #[test]
+    fn freezes_a_dictionary() {
+        let dict = Dictionary::from_iter(vec!["cat", "Cat", "act", "cart"]);
+        let frozen = dict.freeze(Encoding::Louds);
+
+        let words: Vec<&str> = frozen.iter().map(|x| x.original.as_str()).collect();
+        assert_eq!(words, ["act", "cart", "cat", "Cat"]);
+        assert_eq!(frozen.find(&"CAT".into()).map(|x| x.len()), Some(2));
+        assert_eq!(
+            frozen
+                .iter_search(TrieSearch::exactly("c??").unwrap())
+                .count(),
+            2
+        );
+
+        let empty = Dictionary::default().freeze(Encoding::Louds);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().count(), 0);
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::char_map::CharMap;
+use crate::dictionary::{DictIterItem, Dictionary};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::Trie;
+
+/// Finds words within a number of substitutions, insertions and deletions
+/// of a query. The query is compiled once into a Levenshtein automaton,
+/// which can then be run against any number of tries and dictionaries.
+///
+/// ```
+/// use wordplay_core::dictionary::Dictionary;
+/// use wordplay_core::fuzzy::FuzzyMatcher;
+///
+/// let matcher = FuzzyMatcher::new("cart", 1);
+/// let dict = Dictionary::from_iter(vec!["art", "car", "cast", "crate"]);
+/// let found: Vec<_> = matcher.search(&dict).iter().map(|(x, _)| x.original.clone()).collect();
+///
+/// assert_eq!(found, ["art", "car", "cast"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FuzzyMatcher {
+    // letters of the query are numbered from 1, and 0 stands for any other
+    symbols: CharMap<usize>,
+    states: Vec<State>,
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    // the distance from the query of the letters read so far, if in range
+    distance: Option<usize>,
+    // the next state for each symbol, or `None` once out of range for good
+    next: Vec<Option<usize>>,
+}
+
+impl FuzzyMatcher {
+    pub fn new(query: &str, max_distance: usize) -> Self {
+        let query = NormalizedWord::from_str_safe(query);
+        let query: Vec<NormalizedChar> = query.iter_chars().copied().collect();
+
+        let mut symbols: CharMap<usize> = Default::default();
+        let mut letters: Vec<Option<NormalizedChar>> = vec![None];
+        for &ch in query.iter() {
+            if *symbols.get(ch) == 0 {
+                symbols.set(ch, letters.len());
+                letters.push(Some(ch));
+            }
+        }
+
+        // each state is a row of the edit distance table, with distances
+        // past the maximum all capped to one more than it
+        let cap = max_distance + 1;
+        let first: Vec<usize> = (0..=query.len()).map(|i| i.min(cap)).collect();
+        let mut rows = vec![first.clone()];
+        let mut ids = HashMap::from([(first, 0)]);
+        let mut states: Vec<State> = vec![];
+        while let Some(row) = rows.get(states.len()).cloned() {
+            let next = letters
+                .iter()
+                .map(|&letter| {
+                    let mut next = vec![(row[0] + 1).min(cap)];
+                    for (i, &ch) in query.iter().enumerate() {
+                        let substitute = row[i] + usize::from(letter != Some(ch));
+                        let best = substitute.min(row[i + 1] + 1).min(next[i] + 1);
+                        next.push(best.min(cap));
+                    }
+                    if next.iter().all(|&x| x == cap) {
+                        return None;
+                    }
+                    let id = *ids.entry(next.clone()).or_insert_with(|| {
+                        rows.push(next);
+                        rows.len() - 1
+                    });
+                    Some(id)
+                })
+                .collect();
+            let distance = row[query.len()];
+            states.push(State {
+                distance: (distance <= max_distance).then_some(distance),
+                next,
+            });
+        }
+
+        FuzzyMatcher { symbols, states }
+    }
+
+    /// The distance of `word` from the query, if within the maximum.
+    pub fn distance(&self, word: &NormalizedWord) -> Option<usize> {
+        let mut state = 0;
+        for &ch in word.iter_chars() {
+            state = self.step(state, ch)?;
+        }
+        self.states[state].distance
+    }
+
+    fn step(&self, state: usize, ch: NormalizedChar) -> Option<usize> {
+        self.states[state].next[*self.symbols.get(ch)]
+    }
+
+    /// Keys of `trie` within range of the query, in lexicographic order,
+    /// with each value and the key's distance.
+    pub fn search_trie<'t, T>(&self, trie: &'t Trie<T>) -> Vec<(NormalizedWord, &'t T, usize)> {
+        let mut found = vec![];
+        self.walk(trie, 0, &mut Default::default(), &mut found);
+        found
+    }
+
+    fn walk<'t, T>(
+        &self,
+        node: &'t Trie<T>,
+        state: usize,
+        word: &mut NormalizedWord,
+        found: &mut Vec<(NormalizedWord, &'t T, usize)>,
+    ) {
+        if let Some(distance) = self.states[state].distance {
+            found.extend(node.terminals().iter().map(|x| (word.clone(), x, distance)));
+        }
+        for ch in NormalizedChar::all() {
+            let Some(child) = node.child(ch) else {
+                continue;
+            };
+            let Some(next) = self.step(state, ch) else {
+                continue;
+            };
+            word.push(ch);
+            self.walk(child, next, word, found);
+            word.pop();
+        }
+    }
+
+    /// Entries of `dict` within range of the query, in normalized order,
+    /// with their distances.
+    pub fn search<'a>(&self, dict: &'a Dictionary) -> Vec<(DictIterItem<'a>, usize)> {
+        self.search_trie(dict.trie())
+            .into_iter()
+            .map(|(word, entry, distance)| ((word, entry).into(), distance))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_distance_within_the_maximum() {
+        let matcher = FuzzyMatcher::new("kitten", 3);
+
+        assert_eq!(matcher.distance(&"kitten".into()), Some(0));
+        assert_eq!(matcher.distance(&"sitting".into()), Some(3));
+        assert_eq!(matcher.distance(&"kit".into()), Some(3));
+        assert_eq!(matcher.distance(&"mitten".into()), Some(1));
+        assert_eq!(matcher.distance(&"sit".into()), None);
+        assert_eq!(FuzzyMatcher::new("", 1).distance(&"a".into()), Some(1));
+    }
+
+    #[test]
+    fn searches_many_dictionaries() {
+        let matcher = FuzzyMatcher::new("Tea", 1);
+        let first = Dictionary::from_iter(vec!["tea", "TEA", "sea", "team", "eat"]);
+        let second = Dictionary::from_iter(vec!["ta", "tear", "tree"]);
+
+        let found = |dict| -> Vec<(String, usize)> {
+            matcher
+                .search(dict)
+                .into_iter()
+                .map(|(x, distance)| (x.original.clone(), distance))
+                .collect()
+        };
+
+        assert_eq!(
+            found(&first),
+            [
+                ("sea".to_string(), 1),
+                ("tea".to_string(), 0),
+                ("TEA".to_string(), 0),
+                ("team".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            found(&second),
+            [("ta".to_string(), 1), ("tear".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::dictionary::Dictionary;
+
+#[derive(Debug)]
+pub enum HunspellError {
+    Io(io::Error),
+    /// An affix line of the `.aff` file that cannot be read, numbered from 1.
+    InvalidAffix(usize),
+    /// A line of the `.dic` file whose flags cannot be read, numbered from 1.
+    InvalidFlags(usize),
+}
+
+impl fmt::Display for HunspellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HunspellError::Io(err) => write!(f, "{}", err),
+            HunspellError::InvalidAffix(line) => {
+                write!(f, "line {} of the affix file is not a valid rule", line)
+            }
+            HunspellError::InvalidFlags(line) => {
+                write!(f, "line {} of the dictionary has invalid flags", line)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for HunspellError {
+    fn from(err: io::Error) -> Self {
+        HunspellError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum FlagType {
+    // one character each, the default, or one UTF-8 character with `FLAG UTF-8`
+    #[default]
+    Char,
+    // two characters each
+    Long,
+    // decimal numbers separated by commas
+    Num,
+}
+
+impl FlagType {
+    fn parse(self, flags: &str) -> Option<Vec<String>> {
+        match self {
+            FlagType::Char => Some(flags.chars().map(String::from).collect()),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                if !chars.len().is_multiple_of(2) {
+                    return None;
+                }
+                Some(chars.chunks(2).map(|x| x.iter().collect()).collect())
+            }
+            FlagType::Num => flags
+                .split(',')
+                .map(|x| x.parse::<u16>().ok().map(|x| x.to_string()))
+                .collect(),
+        }
+    }
+}
+
+// One position of an affix condition.
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Any,
+    In(Vec<char>),
+    NotIn(Vec<char>),
+}
+
+impl Condition {
+    fn parse(pattern: &str) -> Option<Vec<Condition>> {
+        if pattern == "." {
+            return Some(vec![]);
+        }
+        let mut conditions = vec![];
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            conditions.push(match ch {
+                '.' => Condition::Any,
+                '[' => {
+                    let mut set: Vec<char> = vec![];
+                    loop {
+                        match chars.next()? {
+                            ']' => break,
+                            ch => set.push(ch),
+                        }
+                    }
+                    match set.first() {
+                        Some('^') => Condition::NotIn(set[1..].to_vec()),
+                        _ => Condition::In(set),
+                    }
+                }
+                ch => Condition::In(vec![ch]),
+            });
+        }
+        Some(conditions)
+    }
+
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            Condition::Any => true,
+            Condition::In(set) => set.contains(&ch),
+            Condition::NotIn(set) => !set.contains(&ch),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<Condition>,
+    // flags of further suffixes that may follow this one
+    continuation: Vec<String>,
+}
+
+impl AffixRule {
+    fn apply_suffix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let start = chars.len().checked_sub(self.condition.len())?;
+        let fits = self
+            .condition
+            .iter()
+            .zip(&chars[start..])
+            .all(|(x, &ch)| x.matches(ch));
+        let stem = word.strip_suffix(self.strip.as_str())?;
+        (fits && !stem.is_empty()).then(|| format!("{}{}", stem, self.add))
+    }
+
+    fn apply_prefix(&self, word: &str) -> Option<String> {
+        let fits = self.condition.len() <= word.chars().count()
+            && self
+                .condition
+                .iter()
+                .zip(word.chars())
+                .all(|(x, ch)| x.matches(ch));
+        let stem = word.strip_prefix(self.strip.as_str())?;
+        (fits && !stem.is_empty()).then(|| format!("{}{}", self.add, stem))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AffixClass {
+    // whether the class combines with affixes of the other kind
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+/// The affix rules of a Hunspell `.aff` file: which prefixes and suffixes
+/// each flag of a `.dic` entry allows. Only what expands words into their
+/// forms is read; suggestion and compounding settings are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct AffixRules {
+    flag_type: FlagType,
+    // whether words are in ISO 8859-1 rather than UTF-8
+    latin1: bool,
+    prefixes: HashMap<String, AffixClass>,
+    suffixes: HashMap<String, AffixClass>,
+    need_affix: Option<String>,
+    forbidden: Option<String>,
+}
+
+impl AffixRules {
+    pub fn parse<R: BufRead>(mut reader: R) -> Result<Self, HunspellError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        // the affixes are in the encoding the file declares
+        let latin1 = bytes
+            .split(|&x| x == b'\n')
+            .any(|x| x.trim_ascii().eq_ignore_ascii_case(b"SET ISO8859-1"));
+        let mut rules = AffixRules {
+            latin1,
+            ..Default::default()
+        };
+        // rules still to read of the class last declared
+        let mut pending = 0;
+        for (i, line) in read_lines(&bytes[..], latin1)?.iter().enumerate() {
+            let invalid = || HunspellError::InvalidAffix(i + 1);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                ["FLAG", "long"] => rules.flag_type = FlagType::Long,
+                ["FLAG", "num"] => rules.flag_type = FlagType::Num,
+                ["NEEDAFFIX", flag] => rules.need_affix = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag] => rules.forbidden = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, cross, count] if pending == 0 => {
+                    let class = AffixClass {
+                        cross_product: cross == "Y",
+                        rules: vec![],
+                    };
+                    pending = count.parse().map_err(|_| invalid())?;
+                    rules.classes(kind).insert(flag.to_string(), class);
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, ref rest @ ..] if pending > 0 => {
+                    let (add, continuation) = match add.split_once('/') {
+                        Some((add, flags)) => {
+                            (add, rules.flag_type.parse(flags).ok_or_else(invalid)?)
+                        }
+                        None => (add, vec![]),
+                    };
+                    let rule = AffixRule {
+                        strip: zero_as_empty(strip),
+                        add: zero_as_empty(add),
+                        condition: Condition::parse(rest.first().unwrap_or(&"."))
+                            .ok_or_else(invalid)?,
+                        continuation,
+                    };
+                    let class = rules.classes(kind).get_mut(flag).ok_or_else(invalid)?;
+                    class.rules.push(rule);
+                    pending -= 1;
+                }
+                ["PFX" | "SFX", ..] => return Err(invalid()),
+                _ => (),
+            }
+        }
+        Ok(rules)
+    }
+
+    fn classes(&mut self, kind: &str) -> &mut HashMap<String, AffixClass> {
+        match kind {
+            "PFX" => &mut self.prefixes,
+            _ => &mut self.suffixes,
+        }
+    }
+
+    /// Every form of a `.dic` entry: the word itself, unless it needs an
+    /// affix, with each suffix and prefix its flags allow, prefixes and
+    /// suffixes combined where both allow it, and suffixes after suffixes
+    /// as continuation flags allow. None at all for a forbidden word.
+    pub fn expand(&self, word: &str, flags: &[String]) -> Vec<String> {
+        let has = |flag: &Option<String>| flag.as_ref().is_some_and(|x| flags.contains(x));
+        if has(&self.forbidden) {
+            return vec![];
+        }
+        let mut forms = vec![];
+        if !has(&self.need_affix) {
+            forms.push(word.to_string());
+        }
+
+        // suffixed forms that a prefix may go on
+        let mut crossable = vec![];
+        for class in flags.iter().filter_map(|x| self.suffixes.get(x)) {
+            for rule in class.rules.iter() {
+                let Some(form) = rule.apply_suffix(word) else {
+                    continue;
+                };
+                forms.push(form.clone());
+                let then = rule
+                    .continuation
+                    .iter()
+                    .filter_map(|x| self.suffixes.get(x));
+                for next in then {
+                    forms.extend(next.rules.iter().filter_map(|x| x.apply_suffix(&form)));
+                }
+                if class.cross_product {
+                    crossable.push(form);
+                }
+            }
+        }
+        for class in flags.iter().filter_map(|x| self.prefixes.get(x)) {
+            for rule in class.rules.iter() {
+                forms.extend(rule.apply_prefix(word));
+                if class.cross_product {
+                    forms.extend(crossable.iter().filter_map(|x| rule.apply_prefix(x)));
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        forms.retain(|x| seen.insert(x.clone()));
+        forms
+    }
+}
+
+fn zero_as_empty(affix: &str) -> String {
+    match affix {
+        "0" => String::new(),
+        _ => affix.to_string(),
+    }
+}
+
+// Lines of a file in UTF-8, or ISO 8859-1 if `latin1`, with comment lines
+// left blank.
+fn read_lines<R: BufRead>(mut reader: R, latin1: bool) -> io::Result<Vec<String>> {
+    let mut lines = vec![];
+    let mut bytes = vec![];
+    while reader.read_until(b'\n', &mut bytes)? > 0 {
+        let line: String = match latin1 {
+            true => bytes.iter().map(|&x| x as char).collect(),
+            false => String::from_utf8_lossy(&bytes).into_owned(),
+        };
+        match line.trim_start().starts_with('#') {
+            true => lines.push(String::new()),
+            false => lines.push(line.trim_end().to_string()),
+        }
+        bytes.clear();
+    }
+    Ok(lines)
+}
+
+/// Inserts every form of every word of a Hunspell `.dic` file, expanded by
+/// the rules of its `.aff` file, each form once. Morphological fields after
+/// a word are ignored, as are words with spaces. Returns the number of forms
+/// inserted; on an invalid line, the forms before it are kept.
+pub fn import_hunspell<D: BufRead, A: BufRead>(
+    dict: &mut Dictionary,
+    dic: D,
+    aff: A,
+) -> Result<usize, HunspellError> {
+    let rules = AffixRules::parse(aff)?;
+    let mut seen = HashSet::new();
+    for (i, line) in read_lines(dic, rules.latin1)?.iter().enumerate() {
+        let Some(entry) = line.split_whitespace().next() else {
+            continue;
+        };
+        // the first line counts the words
+        if i == 0 && entry.parse::<usize>().is_ok() {
+            continue;
+        }
+        let (word, flags) = match entry.split_once('/') {
+            Some((word, flags)) => (word, flags),
+            None => (entry, ""),
+        };
+        let flags = rules
+            .flag_type
+            .parse(flags)
+            .ok_or(HunspellError::InvalidFlags(i + 1))?;
+        for form in rules.expand(word, &flags) {
+            if seen.insert(form.clone()) {
+                dict.insert(&form);
+            }
+        }
+    }
+    Ok(seen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+
+# un- goes before suffixed forms too
+PFX U Y 1
+PFX U   0     un         .
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed         [aeiou]y
+
+SFX S Y 2
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s/M        [^y]
+
+SFX M N 1
+SFX M   0     '          s
+
+NEEDAFFIX X
+FORBIDDENWORD !
+";
+
+    fn expand(dic: &str) -> Vec<String> {
+        let mut dict: Dictionary = Default::default();
+        import_hunspell(&mut dict, dic.as_bytes(), AFF.as_bytes()).unwrap();
+        dict.iter().map(|x| x.original.clone()).collect()
+    }
+
+    #[test]
+    fn expands_affixes() {
+        assert_eq!(
+            expand("4\ntry/DS\nlock/UD\ncreate/DX\nplay/D po:verb\n"),
+            [
+                "created", "lock", "locked", "play", "played", "tried", "tries", "try", "unlock",
+                "unlocked",
+            ]
+        );
+        assert_eq!(expand("cat/S"), ["cat", "cats", "cats'"]);
+        assert_eq!(expand("color/D!"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reads_other_flag_types_and_encodings() {
+        let aff = "FLAG num\nSFX 101 Y 1\nSFX 101 0 s .\nPFX 7 Y 1\nPFX 7 0 re .\n";
+        let rules = AffixRules::parse(aff.as_bytes()).unwrap();
+        assert_eq!(
+            rules.expand("read", &FlagType::Num.parse("101,7").unwrap()),
+            ["read", "reads", "reread", "rereads"]
+        );
+
+        let aff = b"SET ISO8859-1\nFLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let mut dict: Dictionary = Default::default();
+        let dic = b"1\ncaf\xe9/Aa\n";
+        assert_eq!(import_hunspell(&mut dict, &dic[..], &aff[..]).unwrap(), 2);
+        let words: Vec<&str> = dict.iter().map(|x| x.original.as_str()).collect();
+        assert_eq!(words, ["café", "cafés"]);
+
+        let invalid = import_hunspell(&mut dict, "word/A".as_bytes(), &aff[..]);
+        assert!(matches!(invalid, Err(HunspellError::InvalidFlags(1))));
+        let invalid = AffixRules::parse("SFX A Y 1\nSFX B 0 s .\n".as_bytes());
+        assert!(matches!(invalid, Err(HunspellError::InvalidAffix(2))));
+    }
+}
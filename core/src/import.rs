@@ -0,0 +1,239 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::dictionary::Dictionary;
+use crate::normalized_word::NormalizedWord;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    /// A line without a `;` or tab before its score, numbered from 1.
+    MissingScore(usize),
+    /// A line whose word is empty, numbered from 1.
+    MissingWord(usize),
+    /// A line whose score is not a whole number, numbered from 1.
+    InvalidScore(usize, String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(err) => write!(f, "{}", err),
+            ImportError::MissingScore(line) => write!(f, "line {} needs a score", line),
+            ImportError::MissingWord(line) => write!(f, "line {} needs a word", line),
+            ImportError::InvalidScore(line, score) => {
+                write!(f, "line {} has an invalid score '{}'", line, score)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> Self {
+        ImportError::Io(err)
+    }
+}
+
+/// Inserts the words of a scored list, with lines of `word;score` as in
+/// Crossfire and Ingrid `.dict` files, or tab-separated `word\tscore`.
+/// Blank lines are skipped. Returns the number of words inserted; on a
+/// malformed line, the words before it are kept.
+pub fn import_scored<R: BufRead>(dict: &mut Dictionary, reader: R) -> Result<usize, ImportError> {
+    let mut count = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (word, score) = line
+            .rsplit_once([';', '\t'])
+            .ok_or(ImportError::MissingScore(i + 1))?;
+        let (word, score) = (word.trim(), score.trim());
+        if word.is_empty() {
+            return Err(ImportError::MissingWord(i + 1));
+        }
+        let score = score
+            .parse()
+            .map_err(|_| ImportError::InvalidScore(i + 1, score.to_string()))?;
+        dict.insert_scored(word, score);
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// The score of the words of a SCOWL list of `size`, from 90 for the
+/// commonest words, in size 10, to 5 for the rarest, in size 95: 100 less
+/// the size, so that the size-50 lists of typical spellcheckers score the
+/// neutral 50.
+pub fn scowl_score(size: u8) -> u32 {
+    100u32.saturating_sub(size as u32)
+}
+
+/// Inserts the words of a SCOWL list of `size`, such as `english-words.35`,
+/// scored by `scowl_score`. SCOWL lists are in ISO 8859-1, though UTF-8
+/// lines are read as such. A word already present with the same spelling is
+/// skipped, so load the smaller, commoner sizes first. Returns the number
+/// of words inserted.
+pub fn import_scowl<R: BufRead>(
+    dict: &mut Dictionary,
+    mut reader: R,
+    size: u8,
+) -> Result<usize, ImportError> {
+    let score = scowl_score(size);
+    let mut count = 0;
+    let mut bytes = vec![];
+    while reader.read_until(b'\n', &mut bytes)? > 0 {
+        let line = match std::str::from_utf8(&bytes) {
+            Ok(line) => line.to_string(),
+            Err(_) => bytes.iter().map(|&x| x as char).collect(),
+        };
+        bytes.clear();
+        let word = line.trim();
+        let normalized = NormalizedWord::from_str_safe(word);
+        let present = dict
+            .find(&normalized)
+            .is_some_and(|entries| entries.iter().any(|x| x.original == word));
+        if normalized.is_empty() || present {
+            continue;
+        }
+        dict.insert_scored(word, score);
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// The score of a word of Zipf frequency `zipf`, the base-10 logarithm of
+/// its uses per billion words, as wordfreq reports: 12.5 per unit, so that
+/// 8, about the commonest, scores 100 and 4 the neutral 50.
+pub fn zipf_score(zipf: f64) -> u32 {
+    (zipf * 12.5).round().clamp(0.0, 100.0) as u32
+}
+
+/// Inserts the words of a frequency list, with lines of a word and its
+/// frequency separated by a comma, tab or space, as exported from wordfreq
+/// or in the FrequencyWords lists, scored by `zipf_score`. If every
+/// frequency is a whole number they are taken as counts, and converted to
+/// Zipf frequencies by their share of the total; otherwise they are taken
+/// as Zipf frequencies. A first line without a frequency is skipped as a
+/// header. Returns the number of words inserted; on a malformed line,
+/// nothing is inserted.
+pub fn import_frequencies<R: BufRead>(
+    dict: &mut Dictionary,
+    reader: R,
+) -> Result<usize, ImportError> {
+    let mut words: Vec<(String, f64)> = vec![];
+    let mut counts = true;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (word, frequency) = line
+            .trim()
+            .rsplit_once([',', '\t', ' '])
+            .ok_or(ImportError::MissingScore(i + 1))?;
+        let (word, frequency) = (word.trim(), frequency.trim());
+        let parsed = frequency.parse::<f64>().ok().filter(|x| *x >= 0.0);
+        let Some(frequency) = parsed else {
+            if i == 0 {
+                continue;
+            }
+            return Err(ImportError::InvalidScore(i + 1, frequency.to_string()));
+        };
+        if word.is_empty() {
+            return Err(ImportError::MissingWord(i + 1));
+        }
+        counts &= frequency.fract() == 0.0;
+        words.push((word.to_string(), frequency));
+    }
+
+    let total: f64 = words.iter().map(|(_, x)| x).sum();
+    for (word, frequency) in words.iter() {
+        let zipf = match counts {
+            true => (frequency / total * 1e9).log10(),
+            false => *frequency,
+        };
+        dict.insert_scored(word, zipf_score(zipf));
+    }
+    Ok(words.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_both_separators() {
+        let mut dict: Dictionary = Default::default();
+        let list = "CAT;60\n\ndog\t45\nice cream ; 50\n";
+
+        assert_eq!(import_scored(&mut dict, list.as_bytes()).unwrap(), 3);
+        let score = |word: &str| dict.find(&NormalizedWord::from_str_safe(word)).unwrap()[0].score;
+        assert_eq!(score("cat"), Some(60));
+        assert_eq!(score("DOG"), Some(45));
+        assert_eq!(score("icecream"), Some(50));
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let import = |list: &str| import_scored(&mut Default::default(), list.as_bytes());
+
+        assert!(matches!(
+            import("CAT;60\nDOG"),
+            Err(ImportError::MissingScore(2))
+        ));
+        assert!(matches!(import(";60"), Err(ImportError::MissingWord(1))));
+        assert!(matches!(
+            import("CAT;60\nDOG;high"),
+            Err(ImportError::InvalidScore(2, score)) if score == "high"
+        ));
+    }
+
+    #[test]
+    fn scores_scowl_sizes() {
+        let mut dict: Dictionary = Default::default();
+        assert_eq!(
+            import_scowl(&mut dict, "cat\ndog\n".as_bytes(), 10).unwrap(),
+            2
+        );
+        assert_eq!(
+            import_scowl(&mut dict, b"cat\ncaf\xe9\n\n".as_slice(), 70).unwrap(),
+            1
+        );
+
+        let entries: Vec<(&str, Option<u32>)> = dict
+            .iter()
+            .map(|x| (x.original.as_str(), x.score))
+            .collect();
+        assert_eq!(
+            entries,
+            [("café", Some(30)), ("cat", Some(90)), ("dog", Some(90))]
+        );
+    }
+
+    #[test]
+    fn reads_counts_and_zipf_frequencies() {
+        let score = |dict: &Dictionary, word: &str| {
+            dict.find(&NormalizedWord::from_str_safe(word)).unwrap()[0].score
+        };
+
+        let mut dict: Dictionary = Default::default();
+        let counts = "word,count\nthe 999000\ncat\t1000\n";
+        assert_eq!(import_frequencies(&mut dict, counts.as_bytes()).unwrap(), 2);
+        assert_eq!(score(&dict, "the"), Some(100));
+        // a thousandth of the words, a Zipf frequency of 6
+        assert_eq!(score(&dict, "cat"), Some(75));
+
+        let mut dict: Dictionary = Default::default();
+        let zipf = "the,7.73\ncat,4.56\nerne,1.5\n";
+        assert_eq!(import_frequencies(&mut dict, zipf.as_bytes()).unwrap(), 3);
+        assert_eq!(score(&dict, "the"), Some(97));
+        assert_eq!(score(&dict, "cat"), Some(57));
+        assert_eq!(score(&dict, "erne"), Some(19));
+
+        assert!(matches!(
+            import_frequencies(&mut dict, "the,7.73\ncat,often\n".as_bytes()),
+            Err(ImportError::InvalidScore(2, frequency)) if frequency == "often"
+        ));
+    }
+}
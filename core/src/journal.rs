@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::iter::Peekable;
+use std::path::Path;
+
+use crate::dictionary::{DictIterItem, DictSearch, Dictionary};
+use crate::frozen::FrozenDictionary;
+use crate::normalized_word::NormalizedWord;
+use crate::trie::{TraversalOrder, TrieSearch};
+
+const HEADER: &str = "wordplay journal 1\n";
+
+/// A frozen dictionary with words added and removed since it was built,
+/// each edit appended to a journal file as it is made and replayed when
+/// the journal is next opened, so edits survive restarts without
+/// rebuilding the base.
+///
+/// The journal is text, after a header line: `+word` or `+word<TAB>score`
+/// for an insert and `-word` for a removal. Removing a word hides entries
+/// spelled exactly that way. A last line left unfinished by a crash is
+/// dropped on opening.
+pub struct JournaledDictionary {
+    base: FrozenDictionary,
+    // words added by the journal, in the order added, with their scores
+    custom: Vec<(String, Option<u32>)>,
+    added: Dictionary,
+    // spellings hidden from `base`
+    removed: HashSet<String>,
+    journal: File,
+}
+
+impl JournaledDictionary {
+    /// Opens the journal at `path` on top of `base`, creating it if it does
+    /// not exist, and replays its edits.
+    pub fn open<P: AsRef<Path>>(base: FrozenDictionary, path: P) -> io::Result<Self> {
+        let mut journal = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let mut text = String::new();
+        journal.read_to_string(&mut text)?;
+        if text.is_empty() {
+            journal.write_all(HEADER.as_bytes())?;
+        } else if !text.starts_with(HEADER) {
+            return Err(invalid("not a wordplay journal".to_string()));
+        }
+
+        let mut dict = JournaledDictionary {
+            base,
+            custom: vec![],
+            added: Default::default(),
+            removed: HashSet::new(),
+            journal,
+        };
+        let complete = text.rfind('\n').map_or(0, |x| x + 1);
+        let mut stale = false;
+        for (i, line) in text[..complete].lines().enumerate().skip(1) {
+            stale |= dict
+                .apply(line)
+                .ok_or_else(|| invalid(format!("journal line {} is malformed", i + 1)))?;
+        }
+        if complete < text.len() {
+            dict.journal.set_len(complete as u64)?;
+        }
+        if stale {
+            dict.rebuild();
+        }
+        Ok(dict)
+    }
+
+    pub fn base(&self) -> &FrozenDictionary {
+        &self.base
+    }
+
+    /// Adds `word` unless it is already present, returning whether it was
+    /// added.
+    pub fn insert(&mut self, word: &str) -> io::Result<bool> {
+        self.edit('+', word, None)
+    }
+
+    pub fn insert_scored(&mut self, word: &str, score: u32) -> io::Result<bool> {
+        self.edit('+', word, Some(score))
+    }
+
+    /// Removes the entries spelled `word`, returning whether there were any.
+    pub fn remove(&mut self, word: &str) -> io::Result<bool> {
+        self.edit('-', word, None)
+    }
+
+    /// Whether an entry is spelled `word`.
+    pub fn contains(&self, word: &str) -> bool {
+        let normalized = NormalizedWord::from_str_safe(word);
+        let in_base = self.in_base(&normalized, word) && !self.removed.contains(word);
+        in_base
+            || self
+                .added
+                .find(&normalized)
+                .is_some_and(|entries| entries.iter().any(|x| x.original == word))
+    }
+
+    /// Iterates over every entry, ordered by normalized word.
+    pub fn iter(&self) -> impl Iterator<Item = DictIterItem<'_>> {
+        self.iter_search(Default::default())
+    }
+
+    /// Iterates over entries matching `search`, ordered by normalized word
+    /// as `FrozenDictionary::iter_search` orders them.
+    pub fn iter_search(&self, search: TrieSearch) -> impl Iterator<Item = DictIterItem<'_>> {
+        let added = DictSearch::new(
+            Some(search.with_order(TraversalOrder::DepthFirst)),
+            Default::default(),
+        );
+        let base = self
+            .base
+            .iter_search(search)
+            .filter(|x| !self.removed.contains(x.original.as_str()));
+        Merged {
+            base: base.peekable(),
+            added: self.added.iter_search(added).peekable(),
+        }
+    }
+
+    // Appends an edit to the journal and applies it, unless it would change
+    // nothing.
+    fn edit(&mut self, op: char, word: &str, score: Option<u32>) -> io::Result<bool> {
+        if word.contains(['\t', '\n', '\r']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "journaled words cannot contain tabs or line breaks",
+            ));
+        }
+        let present = self.contains(word);
+        let letterless = NormalizedWord::from_str_safe(word).is_empty();
+        if letterless || present == (op == '+') {
+            return Ok(false);
+        }
+        let line = match score {
+            Some(score) => format!("{}{}\t{}", op, word, score),
+            None => format!("{}{}", op, word),
+        };
+        self.journal.write_all(format!("{}\n", line).as_bytes())?;
+        if self.apply(&line) == Some(true) {
+            self.rebuild();
+        }
+        Ok(true)
+    }
+
+    // Applies a line of the journal, returning whether `added` must be
+    // rebuilt because a custom word left it, or None if the line is
+    // malformed. New words go straight into `added`.
+    fn apply(&mut self, line: &str) -> Option<bool> {
+        let (op, rest) = line.split_at_checked(1)?;
+        let (word, score) = match rest.split_once('\t') {
+            Some((word, score)) => (word, Some(score.parse().ok()?)),
+            None => (rest, None),
+        };
+        let normalized = NormalizedWord::from_str_safe(word);
+        match op {
+            "+" => {
+                if self.removed.remove(word) || self.in_base(&normalized, word) {
+                    return Some(false);
+                }
+                let stale = self.forget(word);
+                self.custom.push((word.to_string(), score));
+                match score {
+                    Some(score) => self.added.insert_scored(word, score),
+                    None => self.added.insert(word),
+                }
+                Some(stale)
+            }
+            "-" if score.is_none() => {
+                if self.in_base(&normalized, word) {
+                    self.removed.insert(word.to_string());
+                }
+                Some(self.forget(word))
+            }
+            _ => None,
+        }
+    }
+
+    // Drops `word` from `custom`, returning whether it was there.
+    fn forget(&mut self, word: &str) -> bool {
+        let before = self.custom.len();
+        self.custom.retain(|(x, _)| x != word);
+        self.custom.len() < before
+    }
+
+    // Whether `base` has an entry spelled `word`, removed or not.
+    fn in_base(&self, normalized: &NormalizedWord, word: &str) -> bool {
+        self.base
+            .find(normalized)
+            .is_some_and(|entries| entries.iter().any(|x| x.original == word))
+    }
+
+    fn rebuild(&mut self) {
+        self.added = Default::default();
+        for (word, score) in self.custom.iter() {
+            match score {
+                Some(score) => self.added.insert_scored(word, *score),
+                None => self.added.insert(word),
+            }
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Merges two iterators ordered by normalized word, taking from `base` first
+// on ties.
+struct Merged<A: Iterator, B: Iterator> {
+    base: Peekable<A>,
+    added: Peekable<B>,
+}
+
+impl<'a, A, B> Iterator for Merged<A, B>
+where
+    A: Iterator<Item = DictIterItem<'a>>,
+    B: Iterator<Item = DictIterItem<'a>>,
+{
+    type Item = DictIterItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.base.peek(), self.added.peek()) {
+            (Some(x), Some(y)) if y.normalized < x.normalized => self.added.next(),
+            (Some(_), _) => self.base.next(),
+            (None, _) => self.added.next(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frozen::Encoding;
+
+    fn base() -> FrozenDictionary {
+        Dictionary::from_iter(vec!["cat", "Cat", "cart", "dog"]).freeze(Encoding::Louds)
+    }
+
+    fn words(dict: &JournaledDictionary) -> Vec<String> {
+        dict.iter().map(|x| x.original.clone()).collect()
+    }
+
+    #[test]
+    fn replays_edits_on_opening() {
+        let path =
+            std::env::temp_dir().join(format!("wordplay-journal-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut dict = JournaledDictionary::open(base(), &path).unwrap();
+        assert!(dict.insert("act").unwrap());
+        assert!(dict.insert_scored("catnip", 40).unwrap());
+        assert!(!dict.insert("cat").unwrap());
+        assert!(dict.remove("Cat").unwrap());
+        assert!(dict.remove("dog").unwrap());
+        assert!(!dict.remove("emu").unwrap());
+        assert!(dict.insert("dog").unwrap());
+        assert!(dict.remove("act").unwrap());
+        assert!(dict.insert("cot").unwrap());
+        assert_eq!(words(&dict), ["cart", "cat", "catnip", "cot", "dog"]);
+        drop(dict);
+
+        let journal = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            journal,
+            "wordplay journal 1\n+act\n+catnip\t40\n-Cat\n-dog\n+dog\n-act\n+cot\n"
+        );
+
+        // an edit cut off by a crash is dropped
+        std::fs::write(&path, journal + "+emu").unwrap();
+        let mut dict = JournaledDictionary::open(base(), &path).unwrap();
+        assert_eq!(words(&dict), ["cart", "cat", "catnip", "cot", "dog"]);
+        let found: Vec<_> = dict
+            .iter_search(TrieSearch::from_prefix("cat").unwrap())
+            .map(|x| (x.original.clone(), x.score))
+            .collect();
+        assert_eq!(
+            found,
+            [("cat".to_string(), None), ("catnip".to_string(), Some(40))]
+        );
+
+        assert!(dict.insert("emu").unwrap());
+        drop(dict);
+        let dict = JournaledDictionary::open(base(), &path).unwrap();
+        assert!(dict.contains("emu"));
+        assert!(!dict.contains("Cat"));
+
+        std::fs::write(&path, "wordplay journal 1\n*emu\n").unwrap();
+        assert!(JournaledDictionary::open(base(), &path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
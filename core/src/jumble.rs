@@ -0,0 +1,142 @@
+use crate::dictionary::Dictionary;
+use crate::enumeration::Enumeration;
+use crate::normalized_word::NormalizedWord;
+use crate::phrase_anagram::PhraseAnagrams;
+
+/// A jumble: scrambled words to unscramble, with letters of each circled,
+/// and a final answer to anagram from the circled letters.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Jumble {
+    scrambles: Vec<(NormalizedWord, Vec<usize>)>,
+    answer: Option<Enumeration>,
+}
+
+/// One way of unscrambling every word, with the circled letters it gives
+/// and the phrases they make.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JumbleSolution {
+    pub words: Vec<NormalizedWord>,
+    pub circled: NormalizedWord,
+    pub answers: Vec<Vec<NormalizedWord>>,
+}
+
+impl Jumble {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a scrambled word, circling the letters of its answer at
+    /// `circles`, counting from zero. Positions past its end are ignored.
+    pub fn with_scramble(mut self, letters: &str, circles: &[usize]) -> Self {
+        let letters = NormalizedWord::from_str_safe(letters);
+        self.scrambles.push((letters, circles.to_vec()));
+        self
+    }
+
+    /// Finds only final answers with words of these lengths.
+    pub fn with_answer(self, enumeration: Enumeration) -> Self {
+        Jumble {
+            answer: Some(enumeration),
+            ..self
+        }
+    }
+
+    /// The words each scramble could be, other than itself, leaving out
+    /// blocked words.
+    pub fn unscramble(&self, dict: &Dictionary) -> Vec<Vec<NormalizedWord>> {
+        self.scrambles
+            .iter()
+            .map(|(letters, _)| {
+                let mut words: Vec<NormalizedWord> = dict
+                    .anagrams(&letters.to_string())
+                    .map(|x| x.normalized)
+                    .filter(|x| x != letters && !dict.is_blocked(x))
+                    .collect();
+                words.dedup();
+                words
+            })
+            .collect()
+    }
+
+    /// Tries every way of unscrambling the words, finding up to `limit`
+    /// final answers for each. Ways without an answer are left out.
+    pub fn solve(&self, dict: &Dictionary, limit: usize) -> Vec<JumbleSolution> {
+        let mut search = PhraseAnagrams::new(dict);
+        if let Some(answer) = &self.answer {
+            search = search.with_enumeration(answer);
+        }
+        let mut ways: Vec<Vec<NormalizedWord>> = vec![vec![]];
+        for words in self.unscramble(dict) {
+            ways = ways
+                .into_iter()
+                .flat_map(|way| {
+                    words.iter().map(move |word| {
+                        let mut way = way.clone();
+                        way.push(word.clone());
+                        way
+                    })
+                })
+                .collect();
+        }
+        ways.into_iter()
+            .filter_map(|words| {
+                let mut circled = NormalizedWord::default();
+                for (word, (_, circles)) in words.iter().zip(&self.scrambles) {
+                    for &i in circles.iter().filter(|&&i| i < word.len()) {
+                        circled.push(word[i]);
+                    }
+                }
+                let answers = search.search(&circled.to_string(), limit);
+                (!answers.is_empty()).then_some(JumbleSolution {
+                    words,
+                    circled,
+                    answers,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec![
+            "angle", "glean", "walk", "blame", "ramble", "knot", "bake", "make", "go", "be", "lab",
+            "gab", "bag",
+        ])
+    }
+
+    #[test]
+    fn unscrambles_and_anagrams_the_circles() {
+        let jumble = Jumble::new()
+            .with_scramble("nalge", &[0])
+            .with_scramble("kawl", &[9])
+            .with_scramble("lbame", &[0, 1]);
+
+        let unscrambled = jumble.unscramble(&dict());
+        assert_eq!(unscrambled[0].len(), 2);
+        assert_eq!(unscrambled[1], [NormalizedWord::from("walk")]);
+
+        // GLEAN would circle G, B and L, which make no word
+        let solutions = jumble.solve(&dict(), 5);
+        assert_eq!(
+            solutions,
+            [JumbleSolution {
+                words: vec!["angle".into(), "walk".into(), "blame".into()],
+                circled: "abl".into(),
+                answers: vec![vec!["lab".into()]],
+            }]
+        );
+
+        let mut blocked = dict();
+        blocked.block("lab");
+        assert!(jumble.solve(&blocked, 5).is_empty());
+        blocked.block("walk");
+        assert!(jumble.unscramble(&blocked)[1].is_empty());
+
+        let jumble = jumble.with_answer("(1,2)".parse().unwrap());
+        assert!(jumble.solve(&dict(), 5).is_empty());
+    }
+}
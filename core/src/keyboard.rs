@@ -0,0 +1,111 @@
+use crate::char_match::CharMatch;
+use crate::char_set::CharSet;
+use crate::dictionary::{Canonical, DictIterItem, DictSearch, Dictionary, WordPredicate};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::{TriePrefix, TrieSearch};
+
+/// A keyboard layout, for finding which keys a finger may slip onto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Dvorak,
+}
+
+impl Layout {
+    // The three letter rows, top first, with the punctuation keys that share
+    // them so that letters keep their columns.
+    fn rows(self) -> [&'static str; 3] {
+        match self {
+            Layout::Qwerty => ["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            Layout::Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn"],
+            Layout::Dvorak => ["',.pyfgcrl", "aoeuidhtns", ";qjkxbmwvz"],
+        }
+    }
+
+    /// The letters on keys touching `ch`'s key. Each row is taken to sit
+    /// half a key to the right of the row above.
+    pub fn neighbors(self, ch: NormalizedChar) -> CharSet {
+        let rows: Vec<Vec<Option<NormalizedChar>>> = self
+            .rows()
+            .iter()
+            .map(|row| row.chars().map(NormalizedChar::from_char).collect())
+            .collect();
+        let mut neighbors = CharSet::new();
+        for (r, row) in rows.iter().enumerate() {
+            let Some(i) = row.iter().position(|&x| x == Some(ch)) else {
+                continue;
+            };
+            let mut add = |r: usize, cols: &[Option<usize>]| {
+                for &c in cols.iter().flatten() {
+                    if let Some(&Some(x)) = rows[r].get(c) {
+                        neighbors.insert(x);
+                    }
+                }
+            };
+            add(r, &[i.checked_sub(1), Some(i + 1)]);
+            if r > 0 {
+                add(r - 1, &[Some(i), Some(i + 1)]);
+            }
+            if r + 1 < rows.len() {
+                add(r + 1, &[i.checked_sub(1), Some(i)]);
+            }
+        }
+        neighbors
+    }
+}
+
+/// Other words typed by hitting a neighboring key in place of any of the
+/// letters of `word`, one entry per word.
+pub fn typo_neighbors<'a>(
+    dict: &'a Dictionary,
+    word: &str,
+    layout: Layout,
+) -> Vec<DictIterItem<'a>> {
+    let word = NormalizedWord::from_str_safe(word);
+    let chars = word
+        .iter_chars()
+        .map(|&ch| {
+            let mut allowed = layout.neighbors(ch);
+            allowed.insert(ch);
+            CharMatch::Not(allowed.complement())
+        })
+        .collect();
+    let search = DictSearch::new(
+        Some(TrieSearch::new(TriePrefix::new(chars), Some(word.len()))),
+        WordPredicate::None,
+    )
+    .with_dedupe(Canonical::First);
+    dict.iter_search(search)
+        .filter(|x| x.normalized != word)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NormalizedChar::*;
+
+    #[test]
+    fn finds_neighboring_keys() {
+        assert_eq!(Layout::Qwerty.neighbors(S).to_string(), "ADEWXZ");
+        assert_eq!(Layout::Qwerty.neighbors(P).to_string(), "LO");
+        assert_eq!(Layout::Azerty.neighbors(A).to_string(), "QZ");
+        assert_eq!(Layout::Dvorak.neighbors(P).to_string(), "EUY");
+    }
+
+    #[test]
+    fn finds_words_a_typo_away() {
+        let dict = Dictionary::from_iter(vec!["cat", "Cat", "vat", "car", "fat", "cot", "vast"]);
+        let typos = |layout| -> Vec<String> {
+            typo_neighbors(&dict, "cat", layout)
+                .iter()
+                .map(|x| x.original.clone())
+                .collect()
+        };
+
+        assert_eq!(typos(Layout::Qwerty), ["car", "fat", "vat"]);
+        assert_eq!(typos(Layout::Dvorak), ["car", "cot"]);
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::char_set::CharSet;
+use crate::digraph::Digraphs;
+use crate::normalized_word::{NormalizedChar, NormalizedWord, SourceMap, ALPHABET_SIZE};
+
+/// An ISO 639 language code, such as "en" or "fra", for telling apart the
+/// entries of a dictionary merged from several languages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LanguageTag {
+    // lowercase ASCII letters, padded with zeros after a two-letter code
+    code: [u8; 3],
+}
+
+impl LanguageTag {
+    pub fn as_str(&self) -> &str {
+        let len = if self.code[2] == 0 { 2 } else { 3 };
+        std::str::from_utf8(&self.code[..len]).unwrap()
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidLanguageTag(pub String);
+
+impl fmt::Display for InvalidLanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid language tag '{}': expected a two or three letter code such as 'en'",
+            self.0
+        )
+    }
+}
+
+impl FromStr for LanguageTag {
+    type Err = InvalidLanguageTag;
+
+    /// Parses a code of two or three letters, ignoring case.
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let valid = matches!(str.len(), 2 | 3) && str.bytes().all(|b| b.is_ascii_alphabetic());
+        if !valid {
+            return Err(InvalidLanguageTag(str.to_string()));
+        }
+        let mut code = [0; 3];
+        for (slot, b) in code.iter_mut().zip(str.bytes()) {
+            *slot = b.to_ascii_lowercase();
+        }
+        Ok(LanguageTag { code })
+    }
+}
+
+/// Letters of a word outside the alphabet of the language normalizing it,
+/// such as the K of Italian "kiwi".
+#[derive(Debug, PartialEq, Eq)]
+pub struct ForeignLetters {
+    pub language: &'static str,
+    pub letters: CharSet,
+}
+
+impl fmt::Display for ForeignLetters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "letters {} are not in the {} alphabet",
+            self.letters, self.language
+        )
+    }
+}
+
+/// How a language's words are spelled out in the letters A to Z, with
+/// facts about its alphabet for scoring and tile games.
+///
+/// Accents are folded as `NormalizedWord` does unless the profile says
+/// otherwise, as German does for umlauts, which crosswords write as AE,
+/// OE and UE.
+#[derive(Debug, PartialEq)]
+pub struct LanguageProfile {
+    pub name: &'static str,
+    code: &'static str,
+    /// Letters of the native alphabet, before folding.
+    letters: &'static str,
+    /// Characters spelled with other letters, as lowercase pairs.
+    folds: &'static [(char, &'static str)],
+    /// Percentage of letters in running text, from A to Z after folding.
+    frequencies: [f32; ALPHABET_SIZE],
+    /// Pairs of letters played as one tile.
+    digraphs: &'static [&'static str],
+}
+
+impl LanguageProfile {
+    pub const ALL: [&'static LanguageProfile; 5] = [&ENGLISH, &FRENCH, &GERMAN, &SPANISH, &ITALIAN];
+
+    /// The bundled profile for `tag`, if any.
+    pub fn get(tag: LanguageTag) -> Option<&'static LanguageProfile> {
+        LanguageProfile::ALL.into_iter().find(|x| x.tag() == tag)
+    }
+
+    pub fn tag(&self) -> LanguageTag {
+        self.code.parse().unwrap()
+    }
+
+    /// Letters of the native alphabet, such as the 21 of Italian.
+    pub fn letters(&self) -> CharSet {
+        CharSet::from(self.letters)
+    }
+
+    pub fn frequency(&self, ch: NormalizedChar) -> f32 {
+        self.frequencies[ch as usize]
+    }
+
+    pub fn digraphs(&self) -> Digraphs {
+        self.digraphs
+            .iter()
+            .fold(Digraphs::new(), |digraphs, x| digraphs.with(x))
+    }
+
+    /// Spells out the characters this language folds into several letters.
+    pub fn fold(&self, word: &str) -> String {
+        let mut folded = String::with_capacity(word.len());
+        for ch in word.chars() {
+            match self.folded(ch) {
+                Some(to) => folded += to,
+                None => folded.push(ch),
+            }
+        }
+        folded
+    }
+
+    /// Folds and normalizes `word`, whatever letters it spells. See
+    /// `try_normalize` to keep to this language's alphabet.
+    pub fn normalize(&self, word: &str) -> NormalizedWord {
+        NormalizedWord::from_str_safe(&self.fold(word))
+    }
+
+    /// Like `normalize`, failing if the word spells any letter outside
+    /// `letters`.
+    pub fn try_normalize(&self, word: &str) -> Result<NormalizedWord, ForeignLetters> {
+        let normalized = self.normalize(word);
+        let foreign = CharSet::from(&normalized).intersection(self.letters().complement());
+        match foreign.is_empty() {
+            true => Ok(normalized),
+            false => Err(ForeignLetters {
+                language: self.name,
+                letters: foreign,
+            }),
+        }
+    }
+
+    /// Like `NormalizedWord::source_map`, for `normalize`d words: each
+    /// letter a folded character spells out maps back to that character, so
+    /// both letters of UE come from the ü of "Müller".
+    pub fn source_map(&self, word: &str) -> SourceMap {
+        let mut ranges = vec![];
+        for (i, ch) in word.char_indices() {
+            let letters = match self.folded(ch) {
+                Some(to) => to.chars().filter_map(NormalizedChar::from_char).count(),
+                None => NormalizedChar::from_char(ch).into_iter().count(),
+            };
+            ranges.extend(std::iter::repeat_n(i..i + ch.len_utf8(), letters));
+        }
+        SourceMap::new(ranges)
+    }
+
+    // The letters `ch` is spelled with, if this language folds it.
+    fn folded(&self, ch: char) -> Option<&'static str> {
+        let lower = ch.to_lowercase().next().unwrap_or(ch);
+        self.folds
+            .iter()
+            .find(|(from, _)| *from == lower)
+            .map(|(_, to)| *to)
+    }
+}
+
+pub static ENGLISH: LanguageProfile = LanguageProfile {
+    name: "English",
+    code: "en",
+    letters: "abcdefghijklmnopqrstuvwxyz",
+    folds: &[('æ', "ae"), ('œ', "oe")],
+    frequencies: [
+        8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+        6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+    ],
+    digraphs: &[],
+};
+
+pub static FRENCH: LanguageProfile = LanguageProfile {
+    name: "French",
+    code: "fr",
+    letters: "abcdefghijklmnopqrstuvwxyz",
+    folds: &[('æ', "ae"), ('œ', "oe"), ('ÿ', "y")],
+    frequencies: [
+        8.173, 0.901, 3.345, 3.669, 16.716, 1.066, 0.866, 0.737, 7.579, 0.613, 0.074, 5.456, 2.968,
+        7.095, 5.837, 2.521, 1.362, 6.693, 7.948, 7.244, 6.429, 1.838, 0.049, 0.427, 0.128, 0.326,
+    ],
+    digraphs: &[],
+};
+
+pub static GERMAN: LanguageProfile = LanguageProfile {
+    name: "German",
+    code: "de",
+    letters: "abcdefghijklmnopqrstuvwxyz",
+    folds: &[('ä', "ae"), ('ö', "oe"), ('ü', "ue"), ('ß', "ss")],
+    frequencies: [
+        6.933, 1.843, 2.670, 4.961, 17.994, 1.618, 2.941, 4.473, 6.401, 0.262, 1.385, 3.359, 2.476,
+        9.554, 2.968, 0.655, 0.018, 6.844, 7.705, 6.014, 5.044, 0.827, 1.877, 0.033, 0.038, 1.108,
+    ],
+    digraphs: &[],
+};
+
+pub static SPANISH: LanguageProfile = LanguageProfile {
+    name: "Spanish",
+    code: "es",
+    letters: "abcdefghijklmnopqrstuvwxyz",
+    folds: &[],
+    frequencies: [
+        12.027, 2.215, 4.019, 5.010, 12.614, 0.692, 1.768, 0.703, 6.972, 0.493, 0.011, 4.967,
+        3.157, 7.023, 9.510, 2.510, 0.877, 6.871, 7.977, 4.632, 3.107, 1.138, 0.017, 0.215, 1.008,
+        0.467,
+    ],
+    digraphs: &["ch", "ll", "rr"],
+};
+
+pub static ITALIAN: LanguageProfile = LanguageProfile {
+    name: "Italian",
+    code: "it",
+    letters: "abcdefghilmnopqrstuvz",
+    folds: &[],
+    frequencies: [
+        12.375, 0.927, 4.499, 3.735, 12.051, 1.153, 1.643, 0.636, 10.199, 0.011, 0.009, 6.508,
+        2.511, 6.880, 9.830, 3.055, 0.505, 6.365, 4.979, 5.621, 3.176, 2.096, 0.033, 0.003, 0.020,
+        1.181,
+    ],
+    digraphs: &[],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_two_and_three_letter_codes() {
+        let en: LanguageTag = "EN".parse().unwrap();
+        assert_eq!(en.to_string(), "en");
+        assert_eq!("fra".parse::<LanguageTag>().unwrap().as_str(), "fra");
+        assert_ne!(en, "eng".parse().unwrap());
+        assert!("e".parse::<LanguageTag>().is_err());
+        assert!("en-GB".parse::<LanguageTag>().is_err());
+    }
+
+    #[test]
+    fn rejects_letters_outside_the_alphabet() {
+        assert_eq!(
+            ITALIAN.try_normalize("Kiwi"),
+            Err(ForeignLetters {
+                language: "Italian",
+                letters: CharSet::from("kw"),
+            })
+        );
+        assert_eq!(ITALIAN.try_normalize("città").unwrap().to_string(), "CITTA");
+        assert_eq!(
+            GERMAN.try_normalize("Straße").unwrap().to_string(),
+            "STRASSE"
+        );
+        assert_eq!(
+            ITALIAN.try_normalize("jazz").unwrap_err().to_string(),
+            "letters J are not in the Italian alphabet"
+        );
+    }
+
+    #[test]
+    fn maps_folded_letters_back_to_the_original() {
+        let map = GERMAN.source_map("Müller");
+        assert_eq!(map.len(), GERMAN.normalize("Müller").len());
+        assert_eq!(map.get(1), Some(1..3));
+        assert_eq!(map.get(2), Some(1..3));
+        assert_eq!(map.get(3), Some(3..4));
+        assert_eq!(
+            &"Straße"[GERMAN.source_map("Straße").span(4..6).unwrap()],
+            "ß"
+        );
+
+        let original = "Crème-brûlée";
+        assert_eq!(
+            FRENCH.source_map(original),
+            NormalizedWord::source_map(original)
+        );
+    }
+
+    #[test]
+    fn profiles_fold_and_describe_alphabets() {
+        assert_eq!(GERMAN.normalize("Straße").to_string(), "STRASSE");
+        assert_eq!(GERMAN.normalize("Übermäßig").to_string(), "UEBERMAESSIG");
+        assert_eq!(FRENCH.normalize("Cœur").to_string(), "COEUR");
+        assert_eq!(SPANISH.normalize("año").to_string(), "ANO");
+
+        assert_eq!(ITALIAN.letters().len(), 21);
+        assert!(!ITALIAN.letters().contains(NormalizedChar::K));
+        assert_eq!(SPANISH.digraphs(), Digraphs::spanish());
+        assert_eq!(LanguageProfile::get("de".parse().unwrap()), Some(&GERMAN));
+
+        for profile in LanguageProfile::ALL {
+            let total: f32 = profile.frequencies.iter().sum();
+            assert!((total - 100.0).abs() < 0.1, "{}: {}", profile.name, total);
+        }
+    }
+}
@@ -7,12 +7,60 @@ extern crate num;
 extern crate num_derive;
 
 pub mod anagram_number;
+pub mod analytics;
+pub mod builder;
+pub mod cancel;
+pub mod chain;
 pub mod char_freq;
 pub mod char_map;
 pub mod char_match;
+pub mod char_set;
+pub mod clues;
+pub mod corpus;
+pub mod crossword;
+pub mod crosswordese;
+pub mod cryptic;
 pub mod dictionary;
+pub mod diff;
+pub mod digraph;
+pub mod dropquote;
+pub mod enumeration;
+pub mod export;
+pub mod frozen;
+pub mod fuzzy;
+pub mod hunspell;
+pub mod import;
+pub mod journal;
+pub mod jumble;
+pub mod keyboard;
+pub mod language;
+pub mod load_report;
+pub mod morse;
+pub mod ngram;
 pub mod normalized_word;
+pub mod paged_index;
+pub mod phonetic;
+pub mod phoneword;
+pub mod phrase_anagram;
+pub mod plan;
+pub mod progress;
+pub mod query;
+pub mod ranking;
+pub mod reload;
+pub mod scoring;
+pub mod scrabble;
+pub mod search_handle;
+pub mod spellcheck;
+#[cfg(feature = "stemming")]
+pub mod stem;
+pub mod substring_index;
+pub mod transforms;
 pub mod trie;
+pub mod validation;
+#[cfg(feature = "regex")]
+pub mod word_regex;
+pub mod wordle;
+pub mod wordoku;
 
 pub fn dict_enable() -> Dictionary {
     Dictionary::from_file(File::open("data/enable.txt").unwrap())
@@ -0,0 +1,56 @@
+use std::fmt;
+
+/// Something wrong with a line of a word list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineIssue {
+    /// Blank or whitespace only. Skipped.
+    Empty,
+    /// Not blank, but without a letter to normalize, such as "42". Skipped.
+    NoLetters,
+    /// Spelled exactly as an earlier line. Inserted again.
+    Duplicate,
+    /// Too long for an anagram number, so anagram searches miss it.
+    /// Inserted.
+    TooLongForAnagram,
+}
+
+impl LineIssue {
+    pub fn is_skipped(self) -> bool {
+        matches!(self, LineIssue::Empty | LineIssue::NoLetters)
+    }
+}
+
+/// What happened to each line of a word list as it was loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoadReport {
+    pub lines: usize,
+    pub inserted: usize,
+    /// Lines with an issue, by line number counting from one.
+    pub issues: Vec<(usize, LineIssue)>,
+}
+
+impl LoadReport {
+    pub fn count(&self, issue: LineIssue) -> usize {
+        self.issues.iter().filter(|(_, x)| *x == issue).count()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.issues.iter().filter(|(_, x)| x.is_skipped()).count()
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lines, {} inserted: {} empty, {} without letters skipped; \
+             {} duplicates, {} too long for anagrams",
+            self.lines,
+            self.inserted,
+            self.count(LineIssue::Empty),
+            self.count(LineIssue::NoLetters),
+            self.count(LineIssue::Duplicate),
+            self.count(LineIssue::TooLongForAnagram)
+        )
+    }
+}
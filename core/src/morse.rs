@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+
+use crate::char_map::CharMap;
+use crate::dictionary::{DictIterItem, Dictionary};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+
+const CODES: CharMap<&str> = CharMap::new([
+    ".-",   // A
+    "-...", // B
+    "-.-.", // C
+    "-..",  // D
+    ".",    // E
+    "..-.", // F
+    "--.",  // G
+    "....", // H
+    "..",   // I
+    ".---", // J
+    "-.-",  // K
+    ".-..", // L
+    "--",   // M
+    "-.",   // N
+    "---",  // O
+    ".--.", // P
+    "--.-", // Q
+    ".-.",  // R
+    "...",  // S
+    "-",    // T
+    "..-",  // U
+    "...-", // V
+    ".--",  // W
+    "-..-", // X
+    "-.--", // Y
+    "--..", // Z
+]);
+
+pub fn letter_code(ch: NormalizedChar) -> &'static str {
+    CODES.get(ch)
+}
+
+/// The word's letters in Morse code, run together without the gaps that
+/// would separate them, so that different words may share a code.
+pub fn encode(word: &NormalizedWord) -> String {
+    word.iter_chars().map(|&ch| letter_code(ch)).collect()
+}
+
+pub fn is_palindrome(code: &str) -> bool {
+    code.bytes().eq(code.bytes().rev())
+}
+
+/// Words whose code reads the same backwards, one entry per word.
+pub fn palindromes(dict: &Dictionary) -> Vec<DictIterItem<'_>> {
+    distinct_words(dict)
+        .filter(|x| is_palindrome(&encode(&x.normalized)))
+        .collect()
+}
+
+fn distinct_words(dict: &Dictionary) -> impl Iterator<Item = DictIterItem<'_>> {
+    let mut last = None;
+    dict.iter().filter(move |x| {
+        let new = last.as_ref() != Some(&x.normalized) && !x.normalized.is_empty();
+        last = Some(x.normalized.clone());
+        new
+    })
+}
+
+/// Every word of a dictionary indexed by its code, for finding words that
+/// sound alike when keyed.
+#[derive(Debug, Default)]
+pub struct MorseIndex<'a> {
+    by_code: BTreeMap<String, Vec<DictIterItem<'a>>>,
+}
+
+impl<'a> MorseIndex<'a> {
+    pub fn build(dict: &'a Dictionary) -> Self {
+        let mut by_code: BTreeMap<String, Vec<DictIterItem>> = BTreeMap::new();
+        for item in distinct_words(dict) {
+            by_code
+                .entry(encode(&item.normalized))
+                .or_default()
+                .push(item);
+        }
+        MorseIndex { by_code }
+    }
+
+    /// Words with this code, in normalized order.
+    pub fn words_for(&self, code: &str) -> &[DictIterItem<'a>] {
+        self.by_code.get(code).map_or(&[], |words| &words[..])
+    }
+
+    /// Other words sharing the code of `word`.
+    pub fn same_code(&self, word: &str) -> Vec<&DictIterItem<'a>> {
+        let normalized = NormalizedWord::from_str_safe(word);
+        self.words_for(&encode(&normalized))
+            .iter()
+            .filter(|x| x.normalized != normalized)
+            .collect()
+    }
+
+    /// Codes shared by two or more words, in code order.
+    pub fn collisions(&self) -> impl Iterator<Item = (&str, &[DictIterItem<'a>])> {
+        self.by_code
+            .iter()
+            .filter(|(_, words)| words.len() > 1)
+            .map(|(code, words)| (&code[..], &words[..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn originals<'a>(items: impl IntoIterator<Item = &'a DictIterItem<'a>>) -> Vec<&'a str> {
+        items.into_iter().map(|x| &x.original[..]).collect()
+    }
+
+    #[test]
+    fn encodes_and_finds_palindromes() {
+        assert_eq!(encode(&"sos".into()), "...---...");
+        assert_eq!(encode(&"Eat".into()), "..--");
+
+        let dict = Dictionary::from_iter(vec!["sos", "SOS", "eat", "nu", "ten"]);
+        assert_eq!(originals(&palindromes(&dict)), ["nu", "sos"]);
+    }
+
+    #[test]
+    fn groups_words_sharing_a_code() {
+        let dict = Dictionary::from_iter(vec!["eat", "ut", "tee", "ti", "ten"]);
+        let index = MorseIndex::build(&dict);
+
+        assert_eq!(originals(index.same_code("eat")), ["ut"]);
+        assert_eq!(originals(index.words_for("-..")), ["tee", "ti"]);
+        let collisions: Vec<&str> = index.collisions().map(|(code, _)| code).collect();
+        assert_eq!(collisions, ["-..", "..--"]);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::dictionary::Dictionary;
+use crate::normalized_word::{NormalizedChar, NormalizedWord, ALPHABET_SIZE};
+
+// the start or end of a word
+const BOUNDARY: usize = ALPHABET_SIZE;
+const SYMBOLS: usize = ALPHABET_SIZE + 1;
+// add-k smoothing, so unseen trigrams are unlikely rather than impossible
+const SMOOTHING: f64 = 0.1;
+
+/// Counts of letter trigrams in a word list, for scoring how word-like a
+/// string of letters is, including words not in the list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrigramModel {
+    trigrams: Vec<u32>,
+    bigrams: Vec<u32>,
+}
+
+impl Default for TrigramModel {
+    fn default() -> Self {
+        TrigramModel {
+            trigrams: vec![0; SYMBOLS * SYMBOLS * SYMBOLS],
+            bigrams: vec![0; SYMBOLS * SYMBOLS],
+        }
+    }
+}
+
+impl TrigramModel {
+    /// Counts the trigrams of each distinct word in `dict`.
+    pub fn from_dictionary(dict: &Dictionary) -> Self {
+        let mut model: TrigramModel = Default::default();
+        for word in dict.trie().keys() {
+            model.add(&word);
+        }
+        model
+    }
+
+    pub fn add(&mut self, word: &NormalizedWord) {
+        let mut context = [BOUNDARY, BOUNDARY];
+        let symbols = word.iter_chars().map(|&x| x as usize).chain([BOUNDARY]);
+        for symbol in symbols {
+            self.bigrams[context[0] * SYMBOLS + context[1]] += 1;
+            self.trigrams[(context[0] * SYMBOLS + context[1]) * SYMBOLS + symbol] += 1;
+            context = [context[1], symbol];
+        }
+    }
+
+    /// The natural log of the chance that a word starting `prefix` goes on
+    /// with `next`, or ends there when `next` is `None`.
+    pub fn next_log_prob(&self, prefix: &[NormalizedChar], next: Option<NormalizedChar>) -> f64 {
+        let symbol = |i: Option<usize>| {
+            i.and_then(|i| prefix.get(i))
+                .map_or(BOUNDARY, |&x| x as usize)
+        };
+        let len = prefix.len();
+        let context = symbol(len.checked_sub(2)) * SYMBOLS + symbol(len.checked_sub(1));
+        let next = next.map_or(BOUNDARY, |x| x as usize);
+        let seen = self.trigrams[context * SYMBOLS + next] as f64;
+        let total = self.bigrams[context] as f64;
+        ((seen + SMOOTHING) / (total + SMOOTHING * SYMBOLS as f64)).ln()
+    }
+
+    /// The natural log of the chance of `word`, letter by letter.
+    pub fn log_prob(&self, word: &NormalizedWord) -> f64 {
+        let end = self.next_log_prob(&word[..], None);
+        (0..word.len())
+            .map(|i| self.next_log_prob(&word[..i], Some(word[i])))
+            .sum::<f64>()
+            + end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_word_like_strings() {
+        let dict = Dictionary::from_iter(vec!["there", "these", "three", "other", "thesis"]);
+        let model = TrigramModel::from_dictionary(&dict);
+
+        let word = |x: &str| model.log_prob(&NormalizedWord::from(x));
+        assert!(word("the") > word("hte"));
+        assert!(word("thither") > word("xqzvvkj"));
+
+        let th: Vec<NormalizedChar> = NormalizedWord::from("th").iter_chars().copied().collect();
+        assert!(
+            model.next_log_prob(&th, Some(NormalizedChar::E))
+                > model.next_log_prob(&th, Some(NormalizedChar::Q))
+        );
+        assert!(model.next_log_prob(&[], Some(NormalizedChar::T)) > (0.5f64).ln());
+    }
+}
@@ -1,13 +1,17 @@
 use std::{
-    ops::Index,
+    fmt,
+    ops::{Index, Range},
     slice::{Iter, SliceIndex},
 };
 
+use smallvec::SmallVec;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+use crate::diff::WordDiff;
+
 #[repr(u8)]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive, EnumIter, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, FromPrimitive, EnumIter, PartialOrd, Ord)]
 pub enum NormalizedChar {
     A,
     B,
@@ -46,7 +50,7 @@ impl NormalizedChar {
 
     pub fn from_char(ch: char) -> Option<NormalizedChar> {
         let ascii_ch = ch.to_ascii_uppercase();
-        if ('A'..='Z').contains(&ascii_ch) {
+        if ascii_ch.is_ascii_uppercase() {
             let u8_ch = (ascii_ch as u8) - b'A';
             return num::FromPrimitive::from_u8(u8_ch);
         }
@@ -67,21 +71,49 @@ impl NormalizedChar {
 
         Some(nc)
     }
+
+    /// Whether this is A, E, I, O or U. Y counts as a consonant.
+    pub fn is_vowel(self) -> bool {
+        use NormalizedChar::*;
+        matches!(self, A | E | I | O | U)
+    }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, PartialOrd, Ord)]
+impl fmt::Display for NormalizedChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", (b'A' + *self as u8) as char)
+    }
+}
+
+/// Letters a word holds without allocating, enough for all but a handful
+/// of ENABLE words.
+const INLINE_LEN: usize = 24;
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, PartialOrd, Ord)]
 pub struct NormalizedWord {
-    chars: Vec<NormalizedChar>,
+    chars: SmallVec<[NormalizedChar; INLINE_LEN]>,
 }
 
 impl NormalizedWord {
     pub fn new(chars: Vec<NormalizedChar>) -> NormalizedWord {
-        NormalizedWord { chars }
+        NormalizedWord {
+            chars: SmallVec::from_vec(chars),
+        }
     }
 
     pub fn from_str_safe(str: &str) -> NormalizedWord {
-        NormalizedWord {
-            chars: str.chars().filter_map(NormalizedChar::from_char).collect(),
+        str.chars().filter_map(NormalizedChar::from_char).collect()
+    }
+
+    /// Where each letter of `original`'s normalized word came from, for
+    /// marking matched letters in the original spelling.
+    pub fn source_map(original: &str) -> SourceMap {
+        SourceMap {
+            ranges: original
+                .char_indices()
+                .filter(|&(_, ch)| NormalizedChar::from_char(ch).is_some())
+                .map(|(i, ch)| i..i + ch.len_utf8())
+                .collect(),
         }
     }
 
@@ -97,10 +129,34 @@ impl NormalizedWord {
         self.chars.push(ch)
     }
 
-    pub fn iter_chars(&self) -> Iter<NormalizedChar> {
+    pub fn pop(&mut self) -> Option<NormalizedChar> {
+        self.chars.pop()
+    }
+
+    pub fn iter_chars(&self) -> Iter<'_, NormalizedChar> {
         self.chars.iter()
     }
 
+    pub fn contains(&self, other: &NormalizedWord) -> bool {
+        other.is_empty()
+            || self
+                .chars
+                .windows(other.len())
+                .any(|w| w == &other.chars[..])
+    }
+
+    /// How to turn this word into `other` with the fewest changes, for
+    /// showing how a near match differs.
+    pub fn diff(&self, other: &NormalizedWord) -> WordDiff {
+        WordDiff::between(self, other)
+    }
+
+    pub fn reversed(&self) -> NormalizedWord {
+        NormalizedWord {
+            chars: self.chars.iter().rev().copied().collect(),
+        }
+    }
+
     pub fn is_palindrome(self) -> bool {
         if self.is_empty() {
             return true;
@@ -120,15 +176,65 @@ impl NormalizedWord {
     }
 }
 
+impl fmt::Display for NormalizedWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars.iter().try_for_each(|ch| write!(f, "{}", ch))
+    }
+}
+
 impl From<&str> for NormalizedWord {
     fn from(str: &str) -> Self {
         NormalizedWord::from_str_safe(str)
     }
 }
 
+impl FromIterator<NormalizedChar> for NormalizedWord {
+    fn from_iter<I: IntoIterator<Item = NormalizedChar>>(iter: I) -> Self {
+        NormalizedWord {
+            chars: iter.into_iter().collect(),
+        }
+    }
+}
+
 impl Default for NormalizedWord {
     fn default() -> NormalizedWord {
-        NormalizedWord::new(Default::default())
+        NormalizedWord {
+            chars: SmallVec::new(),
+        }
+    }
+}
+
+/// The byte range in an original string of each letter of its normalized
+/// word, as returned by `NormalizedWord::source_map`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SourceMap {
+    ranges: Vec<Range<usize>>,
+}
+
+impl SourceMap {
+    pub(crate) fn new(ranges: Vec<Range<usize>>) -> Self {
+        SourceMap { ranges }
+    }
+
+    /// The bytes of the letter at `index` of the normalized word.
+    pub fn get(&self, index: usize) -> Option<Range<usize>> {
+        self.ranges.get(index).cloned()
+    }
+
+    /// The bytes from the first to the last of `letters`, including any
+    /// punctuation between them, or `None` if out of range or empty.
+    pub fn span(&self, letters: Range<usize>) -> Option<Range<usize>> {
+        let first = self.get(letters.start)?;
+        let last = self.get(letters.end.checked_sub(1)?)?;
+        (letters.start < letters.end).then_some(first.start..last.end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
     }
 }
 
@@ -196,6 +302,18 @@ mod tests {
         })
     }
 
+    #[test]
+    fn maps_letters_back_to_the_original() {
+        let original = "Crème-brûlée";
+        let map = NormalizedWord::source_map(original);
+
+        assert_eq!(map.len(), mk(original).len());
+        assert_eq!(map.get(2), Some(2..4));
+        assert_eq!(&original[map.span(3..7).unwrap()], "me-br");
+        assert_eq!(map.span(2..2), None);
+        assert_eq!(map.get(11), None);
+    }
+
     fn mk(str: &str) -> NormalizedWord {
         NormalizedWord::from_str_safe(str)
     }
@@ -256,6 +374,23 @@ mod tests {
         assert!(!nw.is_palindrome())
     }
 
+    #[test]
+    fn contains_substring() {
+        let nw = mk("BANANA");
+
+        assert!(nw.contains(&mk("NAN")));
+        assert!(nw.contains(&mk("")));
+        assert!(!nw.contains(&mk("NAB")));
+        assert!(!mk("AN").contains(&nw));
+    }
+
+    #[test]
+    fn displays_as_uppercase() {
+        let nw = mk("Café-au-lait");
+
+        assert_eq!(nw.to_string(), "CAFEAULAIT")
+    }
+
     #[test]
     fn chars_can_be_iterated() {
         let len = NormalizedChar::all().count();
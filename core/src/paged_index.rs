@@ -0,0 +1,369 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::dictionary::{DictEntry, DictIterItem, DictSearch, Dictionary};
+use crate::language::LanguageTag;
+use crate::normalized_word::{NormalizedChar, NormalizedWord, ALPHABET_SIZE};
+
+const MAGIC: &[u8; 5] = b"WPIDX";
+const VERSION: u8 = 1;
+// letter, entries, offset and length
+const SEGMENT_HEADER_LEN: usize = 1 + 4 + 8 + 8;
+const HEADER_LEN: usize = MAGIC.len() + 2 + ALPHABET_SIZE * SEGMENT_HEADER_LEN;
+
+const HAS_SCORE: u8 = 1;
+const HAS_LANG: u8 = 2;
+const HAS_ANAGRAM: u8 = 4;
+
+/// Writes the entries of `dict` not on its blocklist as a paged index, one
+/// segment per first letter after a header locating each segment, for
+/// `Dictionary::open_indexed`. Returns the number of entries written.
+pub(crate) fn write_index<W: Write>(dict: &Dictionary, mut out: W) -> io::Result<usize> {
+    let mut segments: Vec<(u32, Vec<u8>)> = vec![(0, vec![]); ALPHABET_SIZE];
+    for item in dict.iter().filter(|x| !dict.is_blocked(&x.normalized)) {
+        let Some(&first) = item.normalized.iter_chars().next() else {
+            continue;
+        };
+        let (entries, bytes) = &mut segments[first as usize];
+        *entries += 1;
+        write_entry(bytes, &item)?;
+    }
+
+    out.write_all(MAGIC)?;
+    out.write_all(&[VERSION, ALPHABET_SIZE as u8])?;
+    let mut offset = HEADER_LEN as u64;
+    for (ch, (entries, bytes)) in NormalizedChar::all().zip(segments.iter()) {
+        out.write_all(&[ch as u8])?;
+        out.write_all(&entries.to_le_bytes())?;
+        out.write_all(&offset.to_le_bytes())?;
+        out.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        offset += bytes.len() as u64;
+    }
+    for (_, bytes) in segments.iter() {
+        out.write_all(bytes)?;
+    }
+    out.flush()?;
+    Ok(segments.iter().map(|(entries, _)| *entries as usize).sum())
+}
+
+// The normalized word, the original, then the score and language if any.
+fn write_entry(out: &mut Vec<u8>, item: &DictIterItem) -> io::Result<()> {
+    let letters: Vec<u8> = item.normalized.iter_chars().map(|&x| x as u8).collect();
+    write_bytes(out, &letters)?;
+    write_bytes(out, item.original.as_bytes())?;
+    let mut flags = 0;
+    if item.score.is_some() {
+        flags |= HAS_SCORE;
+    }
+    if item.lang.is_some() {
+        flags |= HAS_LANG;
+    }
+    if item.anag_num.is_some() {
+        flags |= HAS_ANAGRAM;
+    }
+    out.push(flags);
+    if let Some(score) = item.score {
+        out.extend_from_slice(&score.to_le_bytes());
+    }
+    if let Some(lang) = item.lang {
+        let mut code = [0; 3];
+        code[..lang.as_str().len()].copy_from_slice(lang.as_str().as_bytes());
+        out.extend_from_slice(&code);
+    }
+    Ok(())
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) -> io::Result<()> {
+    let len = u16::try_from(bytes.len()).map_err(|_| invalid("word too long to index"))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Segment {
+    entries: u32,
+    offset: u64,
+    len: u64,
+    dict: OnceLock<Dictionary>,
+}
+
+/// A dictionary searched from a paged index file, as written by
+/// `Dictionary::write_index`, reading only the segments of words starting
+/// with letters a search can match. Returned by `Dictionary::open_indexed`.
+///
+/// Each segment is searched as a dictionary of its own, so predicates
+/// weighing an entry against the rest, such as `NotCrosswordese`, see only
+/// the words sharing its first letter.
+pub struct IndexedDictionary {
+    file: Mutex<File>,
+    // by first letter
+    segments: Vec<Segment>,
+}
+
+impl IndexedDictionary {
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut preamble = [0; MAGIC.len() + 2];
+        file.read_exact(&mut preamble)?;
+        if &preamble[..MAGIC.len()] != MAGIC {
+            return Err(invalid("not a wordplay index"));
+        }
+        if preamble[MAGIC.len()] != VERSION {
+            return Err(invalid("unsupported wordplay index version"));
+        }
+        if preamble[MAGIC.len() + 1] as usize != ALPHABET_SIZE {
+            return Err(invalid("corrupt wordplay index header"));
+        }
+        let mut header = vec![0; ALPHABET_SIZE * SEGMENT_HEADER_LEN];
+        file.read_exact(&mut header)?;
+        let segments: Vec<Segment> = header
+            .chunks(SEGMENT_HEADER_LEN)
+            .enumerate()
+            .map(|(i, x)| {
+                if x[0] as usize != i {
+                    return Err(invalid("corrupt wordplay index header"));
+                }
+                Ok(Segment {
+                    entries: u32::from_le_bytes(x[1..5].try_into().unwrap()),
+                    offset: u64::from_le_bytes(x[5..13].try_into().unwrap()),
+                    len: u64::from_le_bytes(x[13..21].try_into().unwrap()),
+                    dict: OnceLock::new(),
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        check_segments(&segments, file.metadata()?.len())?;
+        Ok(IndexedDictionary {
+            file: Mutex::new(file),
+            segments,
+        })
+    }
+
+    /// The number of entries, read from the header.
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|x| x.entries as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of segments read into memory so far.
+    pub fn loaded_segments(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|x| x.dict.get().is_some())
+            .count()
+    }
+
+    pub fn find(&self, word: &NormalizedWord) -> io::Result<Option<&Vec<DictEntry>>> {
+        match word.iter_chars().next() {
+            Some(&first) if self.segments[first as usize].entries > 0 => {
+                Ok(self.segment(first)?.find(word))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Reads the segments of every letter `search` allows a match to start
+    /// with, then iterates over their matches, ordered by first letter and
+    /// within each segment as `Dictionary::iter_search` orders them.
+    pub fn iter_search(
+        &self,
+        search: DictSearch,
+    ) -> io::Result<impl Iterator<Item = DictIterItem<'_>>> {
+        let letters = search.first_letters();
+        let segments = letters
+            .iter()
+            .filter(|&ch| self.segments[ch as usize].entries > 0)
+            .map(|ch| self.segment(ch))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(segments
+            .into_iter()
+            .flat_map(move |x| x.iter_search(search.clone())))
+    }
+
+    // The dictionary of words starting with `first`, read on first use.
+    fn segment(&self, first: NormalizedChar) -> io::Result<&Dictionary> {
+        let segment = &self.segments[first as usize];
+        if let Some(dict) = segment.dict.get() {
+            return Ok(dict);
+        }
+        let mut bytes = vec![0; segment.len as usize];
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(segment.offset))?;
+            file.read_exact(&mut bytes)?;
+        }
+        let dict = read_segment(&bytes, segment.entries)?;
+        Ok(segment.dict.get_or_init(|| dict))
+    }
+}
+
+// Whether the segments lie after the header, within the file and apart,
+// so that reading one never allocates more than the file holds.
+fn check_segments(segments: &[Segment], file_len: u64) -> io::Result<()> {
+    let mut ranges = segments
+        .iter()
+        .map(|x| Some((x.offset, x.offset.checked_add(x.len)?)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| invalid("corrupt wordplay index header"))?;
+    ranges.sort();
+    let in_file = ranges
+        .iter()
+        .all(|&(start, end)| start >= HEADER_LEN as u64 && end <= file_len);
+    let apart = ranges.windows(2).all(|x| x[0].1 <= x[1].0);
+    match in_file && apart {
+        true => Ok(()),
+        false => Err(invalid("corrupt wordplay index header")),
+    }
+}
+
+fn read_segment(mut bytes: &[u8], entries: u32) -> io::Result<Dictionary> {
+    let mut dict: Dictionary = Default::default();
+    for _ in 0..entries {
+        let normalized = read_bytes(&mut bytes)?
+            .iter()
+            .map(|&x| num::FromPrimitive::from_u8(x).ok_or_else(|| invalid("corrupt word")))
+            .collect::<io::Result<NormalizedWord>>()?;
+        let original =
+            std::str::from_utf8(read_bytes(&mut bytes)?).map_err(|_| invalid("corrupt word"))?;
+        let flags = take(&mut bytes, 1)?[0];
+        let score = match flags & HAS_SCORE {
+            0 => None,
+            _ => Some(u32::from_le_bytes(take(&mut bytes, 4)?.try_into().unwrap())),
+        };
+        let lang = match flags & HAS_LANG {
+            0 => None,
+            _ => {
+                let code = take(&mut bytes, 3)?;
+                let len = code.iter().position(|&x| x == 0).unwrap_or(3);
+                let tag = std::str::from_utf8(&code[..len]).ok();
+                Some(
+                    tag.and_then(|x| x.parse::<LanguageTag>().ok())
+                        .ok_or_else(|| invalid("corrupt language tag"))?,
+                )
+            }
+        };
+        dict.insert_entry(original, normalized, score, flags & HAS_ANAGRAM != 0, lang);
+    }
+    Ok(dict)
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8]) -> io::Result<&'a [u8]> {
+    let len = u16::from_le_bytes(take(bytes, 2)?.try_into().unwrap());
+    take(bytes, len as usize)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(invalid("truncated wordplay index segment"));
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::TrieSearch;
+
+    fn write_to_temp(dict: &Dictionary, name: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("wordplay-{}-{}.idx", name, std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        write_index(dict, &mut file).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_only_the_segments_searched() {
+        let mut dict = Dictionary::from_iter(vec!["cat", "Cat", "act", "cart", "tac", "dog"]);
+        dict.insert_scored("crate", 60);
+        dict.insert_tagged("café", "fr".parse().unwrap());
+        dict.block("dog");
+        let path = write_to_temp(&dict, "paged");
+        let indexed = Dictionary::open_indexed(&path).unwrap();
+
+        assert_eq!(indexed.len(), 7);
+        assert_eq!(indexed.loaded_segments(), 0);
+
+        let search = DictSearch::new(TrieSearch::from_prefix("c").ok(), Default::default());
+        let words: Vec<&str> = indexed
+            .iter_search(search)
+            .unwrap()
+            .map(|x| x.original.as_str())
+            .collect();
+        assert_eq!(words, ["café", "cart", "cat", "Cat", "crate"]);
+        assert_eq!(indexed.loaded_segments(), 1);
+
+        let crate_entries = indexed.find(&"CRATE".into()).unwrap().unwrap();
+        assert_eq!(crate_entries[0].score, Some(60));
+        let cafe = indexed.find(&"CAFE".into()).unwrap().unwrap();
+        assert_eq!(cafe[0].lang.map(|x| x.to_string()).as_deref(), Some("fr"));
+        assert_eq!(indexed.find(&"DOG".into()).unwrap(), None);
+
+        let anagrams: Vec<&str> = indexed
+            .iter_search(DictSearch::anagram_of("cat"))
+            .unwrap()
+            .map(|x| x.original.as_str())
+            .collect();
+        assert_eq!(anagrams, ["act", "cat", "Cat", "tac"]);
+        assert_eq!(indexed.loaded_segments(), 3);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let path =
+            std::env::temp_dir().join(format!("wordplay-not-index-{}.txt", std::process::id()));
+        std::fs::write(&path, "cat\ndog\n").unwrap();
+        let opened = Dictionary::open_indexed(&path);
+        assert!(opened.is_err_and(|x| x.kind() == io::ErrorKind::InvalidData));
+
+        let dict = Dictionary::from_iter(vec!["cat"]);
+        let mut bytes = vec![];
+        write_index(&dict, &mut bytes).unwrap();
+        let rejects = |bytes: &[u8]| {
+            std::fs::write(&path, bytes).unwrap();
+            let opened = Dictionary::open_indexed(&path);
+            assert!(opened.is_err_and(|x| x.kind() == io::ErrorKind::InvalidData));
+        };
+        // the entries count, offset and length of a letter's segment
+        let field = |ch: NormalizedChar, start: usize, end: usize| {
+            let at = MAGIC.len() + 2 + ch as usize * SEGMENT_HEADER_LEN;
+            at + start..at + end
+        };
+
+        rejects(&bytes[..bytes.len() - 1]);
+
+        let mut huge = bytes.clone();
+        huge[field(NormalizedChar::C, 13, 21)].copy_from_slice(&u64::MAX.to_le_bytes());
+        rejects(&huge);
+
+        // B's empty segment starts where C's does, so lengthening it overlaps
+        let mut overlapping = bytes.clone();
+        let c_len = bytes[field(NormalizedChar::C, 13, 21)].to_vec();
+        overlapping[field(NormalizedChar::B, 13, 21)].copy_from_slice(&c_len);
+        rejects(&overlapping);
+
+        let mut in_header = bytes.clone();
+        in_header[field(NormalizedChar::C, 5, 13)].copy_from_slice(&0u64.to_le_bytes());
+        rejects(&in_header);
+
+        let mut miscounted = bytes.clone();
+        miscounted[field(NormalizedChar::C, 1, 5)].copy_from_slice(&2u32.to_le_bytes());
+        std::fs::write(&path, &miscounted).unwrap();
+        let indexed = Dictionary::open_indexed(&path).unwrap();
+        assert!(indexed.find(&"CAT".into()).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::dictionary::Dictionary;
+use crate::normalized_word::NormalizedWord;
+
+/// A key for how a word sounds in English, by a simplified Metaphone:
+/// consonants coded by sound, as PH as F and soft C as S, silent letters
+/// such as the K of KNIGHT and the GH of NIGHT dropped, and vowels kept
+/// only at the start, as A. Words that sound alike usually share a key,
+/// like PHONETIC and FONETIK, or KNIGHT and NITE.
+pub fn phonetic_code(word: &NormalizedWord) -> String {
+    let s = word.to_string().into_bytes();
+    let at = |i: usize| s.get(i).copied();
+    let vowel = |i: usize| matches!(at(i), Some(b'A' | b'E' | b'I' | b'O' | b'U'));
+    let front_vowel = |i: usize| matches!(at(i), Some(b'E' | b'I' | b'Y'));
+
+    let start = match s.get(..2) {
+        Some(b"KN" | b"GN" | b"PN" | b"PS" | b"WR") => 1,
+        _ => 0,
+    };
+    let mut code = String::new();
+    // the last sound, forgotten after a vowel so that only doubled letters merge
+    let mut last = None;
+    let mut i = start;
+    while i < s.len() {
+        let next = at(i + 1);
+        let (sound, len) = match s[i] {
+            b'A' | b'E' | b'I' | b'O' | b'U' if i == start => ("A", 1),
+            b'A' | b'E' | b'I' | b'O' | b'U' => ("", 1),
+            b'P' if next == Some(b'H') => ("F", 2),
+            b'C' if next == Some(b'K') => ("K", 2),
+            b'C' | b'S' if next == Some(b'H') => ("X", 2),
+            b'T' if next == Some(b'H') => ("0", 2),
+            b'G' if next == Some(b'H') => (if vowel(i + 2) { "K" } else { "" }, 2),
+            b'D' if next == Some(b'G') && front_vowel(i + 2) => ("J", 2),
+            b'C' if front_vowel(i + 1) => ("S", 1),
+            b'G' if front_vowel(i + 1) => ("J", 1),
+            b'B' if i > 0 && s[i - 1] == b'M' && next.is_none() => ("", 1),
+            b'H' if vowel(i + 1) => ("H", 1),
+            b'W' if vowel(i + 1) => ("W", 1),
+            b'Y' if vowel(i + 1) => ("Y", 1),
+            b'H' | b'W' | b'Y' => ("", 1),
+            b'C' | b'G' | b'K' | b'Q' => ("K", 1),
+            b'X' => ("KS", 1),
+            b'Z' => ("S", 1),
+            b'V' => ("F", 1),
+            b'B' => ("B", 1),
+            b'D' => ("D", 1),
+            b'F' => ("F", 1),
+            b'J' => ("J", 1),
+            b'L' => ("L", 1),
+            b'M' => ("M", 1),
+            b'N' => ("N", 1),
+            b'P' => ("P", 1),
+            b'R' => ("R", 1),
+            b'S' => ("S", 1),
+            b'T' => ("T", 1),
+            _ => ("", 1),
+        };
+        if vowel(i) {
+            last = None;
+        }
+        for ch in sound.chars() {
+            if last != Some(ch) {
+                code.push(ch);
+            }
+            last = Some(ch);
+        }
+        i += len;
+    }
+    code
+}
+
+/// Words of a dictionary by phonetic code, for finding those that sound
+/// like a misspelling. See `Dictionary::sounds_like`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PhoneticIndex(HashMap<String, Vec<NormalizedWord>>);
+
+impl PhoneticIndex {
+    pub(crate) fn build(dict: &Dictionary) -> Self {
+        let mut index: HashMap<String, Vec<NormalizedWord>> = HashMap::new();
+        for item in dict.iter() {
+            let words = index.entry(phonetic_code(&item.normalized)).or_default();
+            if words.last() != Some(&item.normalized) {
+                words.push(item.normalized);
+            }
+        }
+        PhoneticIndex(index)
+    }
+
+    /// Words with `code`, in normalized order.
+    pub(crate) fn get(&self, code: &str) -> &[NormalizedWord] {
+        self.0.get(code).map_or(&[], |x| x.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(word: &str) -> String {
+        phonetic_code(&NormalizedWord::from_str_safe(word))
+    }
+
+    #[test]
+    fn codes_words_by_sound() {
+        assert_eq!(code("phonetic"), "FNTK");
+        assert_eq!(code("fonetik"), "FNTK");
+        assert_eq!(code("knight"), code("nite"));
+        assert_eq!(code("write"), code("right"));
+        assert_eq!(code("ceiling"), code("sealing"));
+        assert_eq!(code("lamb"), "LM");
+        assert_eq!(code("apple"), "APL");
+        assert_eq!(code("tattoo"), "TT");
+        assert_eq!(code("thistle"), "0STL");
+        assert_eq!(code("cat"), code("kat"));
+        assert_eq!(code(""), "");
+    }
+}
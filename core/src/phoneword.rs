@@ -0,0 +1,199 @@
+use crate::char_match::CharMatch;
+use crate::char_set::CharSet;
+use crate::dictionary::{Canonical, DictIterItem, DictSearch, Dictionary, WordPredicate};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::{TriePrefix, TrieSearch};
+
+/// The letters on each phone key, from 2 to 9.
+const KEYPAD: [&str; 8] = ["ABC", "DEF", "GHI", "JKL", "MNO", "PQRS", "TUV", "WXYZ"];
+
+/// The letters on a phone key, or `None` for keys without letters.
+pub fn letters(digit: char) -> Option<CharSet> {
+    let key = digit.to_digit(10)?.checked_sub(2)?;
+    KEYPAD
+        .get(key as usize)
+        .map(|&letters| CharSet::from(letters))
+}
+
+/// The key each letter of `word` is on.
+pub fn to_digits(word: &NormalizedWord) -> String {
+    word.iter_chars().map(|&ch| digit(ch)).collect()
+}
+
+fn digit(ch: NormalizedChar) -> char {
+    let key = KEYPAD
+        .iter()
+        .position(|letters| CharSet::from(*letters).contains(ch))
+        .unwrap();
+    char::from(b'2' + key as u8)
+}
+
+/// A trie pattern matching the words typed by `digits`, or `None` if a
+/// digit has no letters. Anything other than a digit is skipped, so
+/// numbers may be written with spaces and dashes.
+pub fn pattern(digits: &str) -> Option<TriePrefix> {
+    let chars = digits
+        .chars()
+        .filter(|ch| ch.is_ascii_digit())
+        .map(|digit| Some(CharMatch::Not(letters(digit)?.complement())))
+        .collect::<Option<_>>()?;
+    Some(TriePrefix::new(chars))
+}
+
+/// Single words and then sequences of more words spelling out `digits`,
+/// fewest words first, spelled lazily as the iterator is advanced. Each
+/// word appears under its first spelling.
+pub fn sequences<'a>(
+    dict: &'a Dictionary,
+    digits: &str,
+) -> impl Iterator<Item = Vec<DictIterItem<'a>>> + 'a {
+    let digits: Vec<char> = digits.chars().filter(|ch| ch.is_ascii_digit()).collect();
+    let len = digits.len();
+
+    // words[i][j] spell out digits[i..j]
+    let mut words: Vec<Vec<Vec<DictIterItem>>> = vec![vec![vec![]; len + 1]; len];
+    for (i, row) in words.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate().skip(i + 1) {
+            let Some(prefix) = pattern(&digits[i..j].iter().collect::<String>()) else {
+                continue;
+            };
+            let search = DictSearch::new(
+                Some(TrieSearch::new(prefix, Some(j - i))),
+                WordPredicate::None,
+            )
+            .with_dedupe(Canonical::First);
+            *cell = dict.iter_search(search).collect();
+        }
+    }
+
+    // can_finish[i][k]: digits[i..] can be spelled in exactly k words
+    let mut can_finish = vec![vec![false; len + 1]; len + 1];
+    can_finish[len][0] = true;
+    for i in (0..len).rev() {
+        for k in 1..=len - i {
+            can_finish[i][k] =
+                (i + 1..=len).any(|j| !words[i][j].is_empty() && can_finish[j][k - 1]);
+        }
+    }
+
+    let counts: Vec<usize> = (1..=len).filter(|&k| can_finish[0][k]).collect();
+    Sequences {
+        words,
+        can_finish,
+        counts: counts.into_iter(),
+        k: 0,
+        stack: vec![],
+    }
+}
+
+// Spells out the digits in each number of words in turn, depth-first, one
+// sequence at a time.
+struct Sequences<'a> {
+    words: Vec<Vec<Vec<DictIterItem<'a>>>>,
+    can_finish: Vec<Vec<bool>>,
+    counts: std::vec::IntoIter<usize>,
+    // the number of words in the sequences being spelled
+    k: usize,
+    // each word of the current sequence as (i, j, index into words[i][j])
+    stack: Vec<(usize, usize, usize)>,
+}
+
+impl Sequences<'_> {
+    // The first word at or after the `w`th of words[i][j], trying longer
+    // words after, that leaves the rest spellable in `remaining - 1` words.
+    fn choose(
+        &self,
+        i: usize,
+        mut j: usize,
+        mut w: usize,
+        remaining: usize,
+    ) -> Option<(usize, usize)> {
+        while j < self.can_finish.len() {
+            if self.can_finish[j][remaining - 1] && w < self.words[i][j].len() {
+                return Some((j, w));
+            }
+            j += 1;
+            w = 0;
+        }
+        None
+    }
+
+    // Completes the current sequence with the first words that fit.
+    fn fill(&mut self) -> bool {
+        while self.stack.len() < self.k {
+            let i = self.stack.last().map_or(0, |&(_, j, _)| j);
+            let Some((j, w)) = self.choose(i, i + 1, 0, self.k - self.stack.len()) else {
+                return false;
+            };
+            self.stack.push((i, j, w));
+        }
+        true
+    }
+
+    // Moves on to the next sequence of the same number of words.
+    fn advance(&mut self) -> bool {
+        while let Some((i, j, w)) = self.stack.pop() {
+            if let Some((j, w)) = self.choose(i, j, w + 1, self.k - self.stack.len()) {
+                self.stack.push((i, j, w));
+                return self.fill();
+            }
+        }
+        false
+    }
+}
+
+impl<'a> Iterator for Sequences<'a> {
+    type Item = Vec<DictIterItem<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let found = if self.stack.is_empty() {
+            self.k = self.counts.next()?;
+            self.fill()
+        } else {
+            self.advance() || {
+                self.k = self.counts.next()?;
+                self.fill()
+            }
+        };
+        if !found {
+            return None;
+        }
+        let words = self
+            .stack
+            .iter()
+            .map(|&(i, j, w)| self.words[i][j][w].clone());
+        Some(words.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_keys_and_letters() {
+        assert_eq!(letters('7'), Some(CharSet::from("pqrs")));
+        assert_eq!(letters('1'), None);
+        assert_eq!(to_digits(&"Cat".into()), "228");
+        assert!(pattern("2-0").is_none());
+    }
+
+    #[test]
+    fn finds_words_then_sequences() {
+        let dict = Dictionary::from_iter(vec!["act", "bat", "Cat", "cat", "dog", "a", "at"]);
+
+        let spelled: Vec<String> = sequences(&dict, "228 364")
+            .map(|words| {
+                let words: Vec<&str> = words.iter().map(|x| &x.original[..]).collect();
+                words.join(" ")
+            })
+            .collect();
+
+        assert_eq!(spelled, ["act dog", "bat dog", "Cat dog", "a at dog"]);
+        assert_eq!(sequences(&dict, "").count(), 0);
+
+        // lazily, so the first of 4^20 sequences comes at once
+        let first = sequences(&dict, &"228".repeat(20)).next().unwrap();
+        assert_eq!(first.len(), 20);
+    }
+}
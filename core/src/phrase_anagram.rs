@@ -0,0 +1,344 @@
+use crate::char_freq::{CharFreq, CharFreqComparisonResult};
+use crate::dictionary::{DictEntry, Dictionary};
+use crate::enumeration::Enumeration;
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::Trie;
+
+const DEFAULT_MAX_WORDS: usize = 4;
+
+/// Finds phrases of dictionary words using exactly the letters given, such
+/// as DORMITORY to DIRTY ROOM.
+///
+/// Without an enumeration each phrase is found once, with required words
+/// first and the rest longest first. With one, words come in the order it
+/// gives their lengths.
+pub struct PhraseAnagrams<'a> {
+    dict: &'a Dictionary,
+    max_words: usize,
+    min_word_len: usize,
+    lengths: Option<Vec<usize>>,
+    required: Vec<NormalizedWord>,
+    forbidden: Vec<NormalizedWord>,
+}
+
+impl<'a> PhraseAnagrams<'a> {
+    pub fn new(dict: &'a Dictionary) -> Self {
+        PhraseAnagrams {
+            dict,
+            max_words: DEFAULT_MAX_WORDS,
+            min_word_len: 1,
+            lengths: None,
+            required: vec![],
+            forbidden: vec![],
+        }
+    }
+
+    /// The most words in a phrase, counting required ones.
+    pub fn with_max_words(self, max_words: usize) -> Self {
+        PhraseAnagrams { max_words, ..self }
+    }
+
+    pub fn with_min_word_len(self, min_word_len: usize) -> Self {
+        PhraseAnagrams {
+            min_word_len,
+            ..self
+        }
+    }
+
+    /// Finds only phrases with words of these lengths, in this order.
+    pub fn with_enumeration(self, enumeration: &Enumeration) -> Self {
+        PhraseAnagrams {
+            lengths: Some(enumeration.lengths().to_vec()),
+            ..self
+        }
+    }
+
+    /// Finds only phrases including `word`, which need not be in the
+    /// dictionary. Its letters are taken before the search for the rest.
+    pub fn with_required(mut self, word: &str) -> Self {
+        self.required.push(NormalizedWord::from_str_safe(word));
+        self
+    }
+
+    /// Finds only phrases without `word`.
+    pub fn with_forbidden(mut self, word: &str) -> Self {
+        self.forbidden.push(NormalizedWord::from_str_safe(word));
+        self
+    }
+
+    /// Returns up to `limit` phrases using every letter of `letters`.
+    pub fn search(&self, letters: &str, limit: usize) -> Vec<Vec<NormalizedWord>> {
+        let mut letters = CharFreq::from(&NormalizedWord::from_str_safe(letters));
+        // each required word fills the first free square of its length
+        let mut slots: Vec<Option<&NormalizedWord>> =
+            vec![None; self.lengths.as_ref().map_or(0, |x| x.len())];
+        for word in self.required.iter() {
+            letters = match letters.compare(&CharFreq::from(word)) {
+                CharFreqComparisonResult::Same => CharFreq::new_empty(),
+                CharFreqComparisonResult::Superset { diff } => diff,
+                _ => return vec![],
+            };
+            if let Some(lengths) = &self.lengths {
+                let free =
+                    (0..lengths.len()).find(|&i| slots[i].is_none() && lengths[i] == word.len());
+                let Some(i) = free else {
+                    return vec![];
+                };
+                slots[i] = Some(word);
+            }
+        }
+        let lengths: Option<Vec<usize>> = self.lengths.as_ref().map(|lengths| {
+            (0..lengths.len())
+                .filter(|&i| slots[i].is_none())
+                .map(|i| lengths[i])
+                .collect()
+        });
+        let Some(max_words) = self.max_words.checked_sub(self.required.len()) else {
+            return vec![];
+        };
+
+        let mut words = vec![];
+        formable(
+            self.dict.trie(),
+            &mut letters.clone(),
+            &mut NormalizedWord::default(),
+            &mut words,
+        );
+        let mut words: Vec<(NormalizedWord, CharFreq)> = words
+            .into_iter()
+            .filter(|x| x.len() >= self.min_word_len && !self.forbidden.contains(x))
+            .filter(|x| !self.dict.is_blocked(x))
+            .filter(|x| {
+                lengths
+                    .as_ref()
+                    .is_none_or(|lengths| lengths.contains(&x.len()))
+            })
+            .map(|x| {
+                let freq = CharFreq::from(&x);
+                (x, freq)
+            })
+            .collect();
+        words.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let mut search = Search {
+            words: &words,
+            max_words: lengths.as_ref().map_or(max_words, |x| x.len()),
+            lengths,
+            found: vec![],
+            limit,
+        };
+        if limit > 0 {
+            search.extend(0, letters, &mut vec![]);
+        }
+        search
+            .found
+            .into_iter()
+            .map(|found| match self.lengths {
+                Some(_) => {
+                    let mut found = found.into_iter();
+                    slots
+                        .iter()
+                        .map(|slot| slot.cloned().or_else(|| found.next()).unwrap())
+                        .collect()
+                }
+                None => self.required.iter().cloned().chain(found).collect(),
+            })
+            .collect()
+    }
+}
+
+struct Search<'w> {
+    words: &'w [(NormalizedWord, CharFreq)],
+    lengths: Option<Vec<usize>>,
+    max_words: usize,
+    found: Vec<Vec<NormalizedWord>>,
+    limit: usize,
+}
+
+impl Search<'_> {
+    // Adds words from `start` onwards, or of the next length of the
+    // enumeration, to `phrase` until no letters are left. Returns whether
+    // the limit is reached.
+    fn extend(&mut self, start: usize, left: CharFreq, phrase: &mut Vec<NormalizedWord>) -> bool {
+        if left == CharFreq::new_empty() {
+            if self
+                .lengths
+                .as_ref()
+                .is_none_or(|x| x.len() == phrase.len())
+            {
+                self.found.push(phrase.clone());
+            }
+            return self.found.len() >= self.limit;
+        }
+        if phrase.len() == self.max_words {
+            return false;
+        }
+        let next_len = self.lengths.as_ref().map(|x| x[phrase.len()]);
+        let from = if next_len.is_some() { 0 } else { start };
+        for (i, (word, freq)) in self.words.iter().enumerate().skip(from) {
+            if next_len.is_some_and(|len| len != word.len()) {
+                continue;
+            }
+            let rest = match left.clone().compare(freq) {
+                CharFreqComparisonResult::Same => CharFreq::new_empty(),
+                CharFreqComparisonResult::Superset { diff } => diff,
+                _ => continue,
+            };
+            phrase.push(word.clone());
+            let done = self.extend(i, rest, phrase);
+            phrase.pop();
+            if done {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// Collects the words below `node` spelled from `left`.
+fn formable(
+    node: &Trie<DictEntry>,
+    left: &mut CharFreq,
+    word: &mut NormalizedWord,
+    found: &mut Vec<NormalizedWord>,
+) {
+    if node.is_terminal() && !word.is_empty() {
+        found.push(word.clone());
+    }
+    for ch in NormalizedChar::all() {
+        let count = left.get(ch);
+        let Some(child) = node.child(ch).filter(|_| count > 0) else {
+            continue;
+        };
+        left.set(ch, count - 1);
+        word.push(ch);
+        formable(child, left, word, found);
+        word.pop();
+        left.set(ch, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrases(found: Vec<Vec<NormalizedWord>>) -> Vec<String> {
+        found
+            .iter()
+            .map(|x| {
+                x.iter()
+                    .map(|w| w.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_each_phrase_once() {
+        let dict = Dictionary::from_iter(vec![
+            "dirty",
+            "room",
+            "dormitory",
+            "or",
+            "dirt",
+            "moor",
+            "y",
+            "my",
+            "rot",
+            "dim",
+        ]);
+        let search = PhraseAnagrams::new(&dict);
+
+        assert_eq!(
+            phrases(search.search("dormitory", 10)),
+            [
+                "DORMITORY",
+                "DIRTY MOOR",
+                "DIRTY ROOM",
+                "DIRT MOOR Y",
+                "DIRT ROOM Y",
+                "DIM ROT OR Y",
+            ]
+        );
+        assert_eq!(search.search("dormitory", 2).len(), 2);
+        assert_eq!(
+            phrases(
+                search
+                    .with_max_words(2)
+                    .with_min_word_len(4)
+                    .search("dormitory", 10)
+            ),
+            ["DORMITORY", "DIRTY MOOR", "DIRTY ROOM"]
+        );
+    }
+
+    #[test]
+    fn pins_and_forbids_words() {
+        let mut dict = Dictionary::from_iter(vec![
+            "dirty",
+            "room",
+            "dormitory",
+            "or",
+            "dirt",
+            "moor",
+            "y",
+            "my",
+            "rot",
+            "dim",
+        ]);
+        let search = |x: PhraseAnagrams| phrases(x.search("dormitory", 10));
+
+        assert_eq!(
+            search(PhraseAnagrams::new(&dict).with_required("room")),
+            ["ROOM DIRTY", "ROOM DIRT Y"]
+        );
+        assert_eq!(
+            search(
+                PhraseAnagrams::new(&dict)
+                    .with_forbidden("y")
+                    .with_forbidden("dormitory")
+            ),
+            ["DIRTY MOOR", "DIRTY ROOM"]
+        );
+        assert_eq!(
+            search(
+                PhraseAnagrams::new(&dict)
+                    .with_required("dirty")
+                    .with_required("moor")
+            ),
+            ["DIRTY MOOR"]
+        );
+        assert!(search(PhraseAnagrams::new(&dict).with_required("doom")).is_empty());
+        assert!(search(PhraseAnagrams::new(&dict).with_required("zoom")).is_empty());
+        assert!(search(
+            PhraseAnagrams::new(&dict)
+                .with_max_words(1)
+                .with_required("room")
+        )
+        .is_empty());
+
+        let enumerated = PhraseAnagrams::new(&dict).with_enumeration(&"(4,5)".parse().unwrap());
+        assert_eq!(
+            search(enumerated.with_required("dirty")),
+            ["MOOR DIRTY", "ROOM DIRTY"]
+        );
+
+        dict.block("moor");
+        assert_eq!(
+            search(PhraseAnagrams::new(&dict).with_required("dirty")),
+            ["DIRTY ROOM"]
+        );
+    }
+
+    #[test]
+    fn follows_an_enumeration() {
+        let dict = Dictionary::from_iter(vec!["dirty", "room", "moor", "dormitory"]);
+        let search = PhraseAnagrams::new(&dict).with_enumeration(&"(4,5)".parse().unwrap());
+
+        assert_eq!(
+            phrases(search.search("dormitory", 10)),
+            ["MOOR DIRTY", "ROOM DIRTY"]
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+
+/// How a `DictSearch` runs, as returned by `DictSearch::explain`: what the
+/// trie traversal prunes on, what is checked of each entry it reaches, and
+/// which indexes stand in for the traversal.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchPlan {
+    pub traversal: Vec<String>,
+    pub filters: Vec<String>,
+    pub indexes: Vec<String>,
+}
+
+impl fmt::Display for SearchPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            ("Trie traversal", &self.traversal, "every word"),
+            ("Filters", &self.filters, "none"),
+            ("Indexes", &self.indexes, "none"),
+        ];
+        for (i, (title, lines, empty)) in sections.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}:", title)?;
+            if lines.is_empty() {
+                write!(f, "\n  {}", empty)?;
+            }
+            for line in lines.iter() {
+                write!(f, "\n  {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+/// Receives progress updates from long-running loads and searches.
+///
+/// `done` counts units of work (bytes for loads, candidates for searches);
+/// `total_hint` is the expected total when it is known up front.
+pub trait ProgressSink {
+    fn progress(&mut self, done: usize, total_hint: Option<usize>);
+}
+
+/// Discards progress updates.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn progress(&mut self, _done: usize, _total_hint: Option<usize>) {}
+}
+
+impl<F: FnMut(usize, Option<usize>)> ProgressSink for F {
+    fn progress(&mut self, done: usize, total_hint: Option<usize>) {
+        self(done, total_hint)
+    }
+}
+
+/// Number of units between progress updates.
+pub const PROGRESS_INTERVAL: usize = 4096;
+
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::{Canonical, DictSearch, Dictionary, WordPredicate};
+    use crate::trie::TriePrefix;
+
+    // Distinct letter-only words, three letters each.
+    fn words() -> String {
+        let letter = |n: usize| (b'a' + (n % 26) as u8) as char;
+        (0..26 * 26 * 26)
+            .map(|i| format!("{}{}{}\n", letter(i / 676), letter(i / 26), letter(i)))
+            .collect()
+    }
+
+    fn assert_monotonic(updates: &[(usize, Option<usize>)], total: usize) {
+        assert!(updates.len() > 1, "{:?}", updates);
+        assert!(updates.windows(2).all(|x| x[0].0 <= x[1].0));
+        assert!(updates.iter().all(|&(_, hint)| hint == Some(total)));
+        assert_eq!(updates.last().unwrap().0, total);
+    }
+
+    #[test]
+    fn reports_monotonic_progress_while_loading() {
+        let text = words();
+        let mut updates = vec![];
+        let mut dict: Dictionary = Default::default();
+        dict.read_from(text.as_bytes(), Some(text.len()), |done, total| {
+            updates.push((done, total))
+        })
+        .unwrap();
+
+        assert_monotonic(&updates, text.len());
+    }
+
+    #[test]
+    fn reports_monotonic_progress_while_searching() {
+        let dict = Dictionary::from_bytes(words().as_bytes());
+        let mut updates = vec![];
+        // a negated predicate prunes nothing, so every word is a candidate
+        let without_q = WordPredicate::Not(Box::new(WordPredicate::Contains("q".into())));
+        let search = DictSearch::new(None, without_q);
+        let found = dict
+            .iter_search_with_progress(search, |done, total| updates.push((done, total)))
+            .count();
+
+        assert_eq!(found, 25 * 25 * 25);
+        assert_monotonic(&updates, 26 * 26 * 26);
+    }
+
+    #[test]
+    fn dedupes_while_reporting_progress() {
+        let dict = Dictionary::from_iter(vec!["cat", "Cat", "CAT", "dog"]);
+        let search = || DictSearch::default().with_dedupe(Canonical::First);
+        let mut updates = vec![];
+        let found: Vec<_> = dict
+            .iter_search_with_progress(search(), |done, total| updates.push((done, total)))
+            .map(|x| x.original)
+            .collect();
+        let expected: Vec<_> = dict.iter_search(search()).map(|x| x.original).collect();
+
+        assert_eq!(found, vec!["cat", "dog"]);
+        assert_eq!(found, expected);
+        // every duplicate is still a candidate checked
+        assert_eq!(updates, vec![(4, Some(4))]);
+    }
+
+    #[test]
+    fn reports_no_total_for_several_prefixes() {
+        let dict = Dictionary::from_bytes(words().as_bytes());
+        let prefix = |x: &str| WordPredicate::Prefix(TriePrefix::from_pattern(x).unwrap());
+        let search = DictSearch::new(None, WordPredicate::Any(vec![prefix("ab"), prefix("ba")]));
+        let mut updates = vec![];
+        let found = dict
+            .iter_search_with_progress(search, |done, total| updates.push((done, total)))
+            .count();
+
+        assert_eq!(found, 2 * 26);
+        assert_eq!(updates, vec![(2 * 26, None)]);
+    }
+}
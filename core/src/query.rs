@@ -0,0 +1,457 @@
+use crate::anagram_number::AnagramNumber;
+use crate::cancel::CancellationToken;
+use crate::char_match::CharMatch;
+use crate::char_set::CharSet;
+use crate::dictionary::{
+    Canonical, DictIterItem, DictSearch, Dictionary, Sort, SortAspect, SortDirection, WordPredicate,
+};
+use crate::digraph::Digraphs;
+use crate::language::LanguageTag;
+use crate::normalized_word::NormalizedWord;
+use crate::trie::{PatternError, TriePrefix, TrieSearch};
+use std::convert::TryFrom;
+use std::ops::{Bound, RangeBounds};
+
+/// A fluent description of a dictionary search, compiled into the tightest
+/// `TrieSearch` its constraints allow plus whatever predicates remain.
+///
+/// Letter arguments too long for an `AnagramNumber`, and patterns with
+/// characters other than letters and wildcards, make the query match nothing.
+/// `try_prefix` and `try_pattern` return the pattern's error instead.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DictQuery {
+    prefix: Vec<CharMatch>,
+    min_len: usize,
+    max_len: Option<usize>,
+    predicates: Vec<WordPredicate>,
+    sort: Option<Sort>,
+    limit: Option<usize>,
+    dedupe: Option<Canonical>,
+    cancel: Option<CancellationToken>,
+    unsatisfiable: bool,
+}
+
+impl DictQuery {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn prefix(self, pattern: &str) -> Self {
+        match TriePrefix::from_pattern(pattern) {
+            Ok(pattern) => self.with_prefix(&pattern),
+            Err(_) => self.match_nothing(),
+        }
+    }
+
+    /// Like `prefix`, returning the error of a pattern that does not parse.
+    pub fn try_prefix(self, pattern: &str) -> Result<Self, PatternError> {
+        Ok(self.with_prefix(&TriePrefix::from_pattern(pattern)?))
+    }
+
+    pub fn pattern(self, pattern: &str) -> Self {
+        match TriePrefix::from_pattern(pattern) {
+            Ok(pattern) => self.with_pattern(&pattern),
+            Err(_) => self.match_nothing(),
+        }
+    }
+
+    /// Like `pattern`, returning the error of a pattern that does not parse.
+    pub fn try_pattern(self, pattern: &str) -> Result<Self, PatternError> {
+        Ok(self.with_pattern(&TriePrefix::from_pattern(pattern)?))
+    }
+
+    fn match_nothing(mut self) -> Self {
+        self.unsatisfiable = true;
+        self
+    }
+
+    fn with_pattern(self, pattern: &TriePrefix) -> Self {
+        let len = pattern.len();
+        self.with_prefix(pattern).length(len..=len)
+    }
+
+    fn with_prefix(mut self, pattern: &TriePrefix) -> Self {
+        for (i, &ch) in pattern.chars().iter().enumerate() {
+            match self.prefix.get(i) {
+                None => self.prefix.push(ch),
+                Some(CharMatch::Any) => self.prefix[i] = ch,
+                Some(&existing) if ch == CharMatch::Any || ch == existing => (),
+                Some(_) => self.unsatisfiable = true,
+            }
+        }
+        self
+    }
+
+    pub fn length<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        let Some((min, max)) = inclusive_bounds(&range) else {
+            return self.match_nothing();
+        };
+        self.min_len = self.min_len.max(min);
+        self.max_len = match (self.max_len, max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self
+    }
+
+    /// Words spelled in a number of tokens in `range`, each of `digraphs`
+    /// counting as one. See `WordPredicate::TokenLength`.
+    pub fn token_length<R: RangeBounds<usize>>(self, digraphs: &Digraphs, range: R) -> Self {
+        let Some((min, max)) = inclusive_bounds(&range) else {
+            return self.match_nothing();
+        };
+        let tokens = min..=max.unwrap_or(usize::MAX);
+        self.matching(WordPredicate::TokenLength(digraphs.clone(), tokens))
+            .length(min..)
+    }
+
+    pub fn anagram_of(self, letters: &str) -> Self {
+        let word = NormalizedWord::from_str_safe(letters);
+        let len = word.len();
+        self.with_anagram(&word, WordPredicate::AnagramOf)
+            .length(len..=len)
+    }
+
+    pub fn formable_from(self, letters: &str) -> Self {
+        let word = NormalizedWord::from_str_safe(letters);
+        let len = word.len();
+        self.with_anagram(&word, |anag| {
+            WordPredicate::Any(vec![
+                WordPredicate::AnagramOf(anag),
+                WordPredicate::SubanagramOf(anag),
+            ])
+        })
+        .length(..=len)
+    }
+
+    pub fn containing_letters(self, letters: &str) -> Self {
+        let word = NormalizedWord::from_str_safe(letters);
+        let len = word.len();
+        self.with_anagram(&word, |anag| {
+            WordPredicate::Any(vec![
+                WordPredicate::AnagramOf(anag),
+                WordPredicate::SuperanagramOf(anag),
+            ])
+        })
+        .length(len..)
+    }
+
+    pub fn contains(self, substring: &str) -> Self {
+        let word = NormalizedWord::from_str_safe(substring);
+        let len = word.len();
+        self.matching(WordPredicate::Contains(word)).length(len..)
+    }
+
+    /// Words made only from `letters`, such as `CharSet::rotatable()`.
+    pub fn only_letters(self, letters: CharSet) -> Self {
+        self.matching(WordPredicate::OnlyLetters(letters))
+    }
+
+    /// Only entries tagged with one of `langs`.
+    pub fn languages(self, langs: &[LanguageTag]) -> Self {
+        let predicates = langs.iter().map(|&x| WordPredicate::Language(x));
+        self.matching(WordPredicate::Any(predicates.collect()))
+    }
+
+    /// Only words in `category`, such as "element", in any case. See
+    /// `Dictionary::categorize`.
+    pub fn category(self, category: &str) -> Self {
+        self.matching(WordPredicate::Category(category.to_lowercase()))
+    }
+
+    pub fn matching(mut self, predicate: WordPredicate) -> Self {
+        if !predicate.is_trivial() {
+            self.predicates.push(predicate);
+        }
+        self
+    }
+
+    pub fn sort(mut self, sort: Sort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn sort_by(self, aspect: SortAspect) -> Self {
+        self.sort(Sort(aspect, SortDirection::Ascending))
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns one result per normalized form, choosing its original by `canonical`.
+    pub fn dedupe(mut self, canonical: Canonical) -> Self {
+        self.dedupe = Some(canonical);
+        self
+    }
+
+    /// Stops the query early, returning the results found so far, once
+    /// `token` is cancelled.
+    pub fn cancellable(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancel.as_ref()
+    }
+
+    fn with_anagram<F: Fn(AnagramNumber) -> WordPredicate>(
+        mut self,
+        word: &NormalizedWord,
+        predicate: F,
+    ) -> Self {
+        match AnagramNumber::try_from(word) {
+            Ok(anag) => self.predicates.push(predicate(anag)),
+            Err(_) => self.unsatisfiable = true,
+        }
+        self
+    }
+
+    fn is_unsatisfiable(&self) -> bool {
+        let min_len = self.min_len.max(self.prefix.len());
+        self.unsatisfiable || self.max_len.is_some_and(|max| max < min_len)
+    }
+
+    pub fn to_search(&self) -> DictSearch {
+        if self.is_unsatisfiable() {
+            let nothing = TrieSearch::new(TriePrefix::any_with_length(1), Some(0));
+            return DictSearch::new(Some(nothing), WordPredicate::Any(vec![]));
+        }
+
+        let mut chars = self.prefix.clone();
+        if chars.len() < self.min_len {
+            chars.resize(self.min_len, CharMatch::Any);
+        }
+        let trie_search = TrieSearch::new(TriePrefix::new(chars), self.max_len);
+
+        let predicate = match &self.predicates[..] {
+            [] => WordPredicate::None,
+            [single] => single.clone(),
+            many => WordPredicate::All(many.to_vec()),
+        };
+
+        let mut search = DictSearch::new(Some(trie_search), predicate);
+        if let Some(canonical) = self.dedupe {
+            search = search.with_dedupe(canonical);
+        }
+        match &self.cancel {
+            Some(token) => search.with_cancellation(token.clone()),
+            None => search,
+        }
+    }
+
+    pub fn run<'a>(&self, dict: &'a Dictionary) -> Vec<DictIterItem<'a>> {
+        self.iter(dict).collect()
+    }
+
+    /// Like `run`, yielding results as they are found. Sorted queries other
+    /// than alphabetical must see every match before yielding the first.
+    pub fn iter<'a>(
+        &self,
+        dict: &'a Dictionary,
+    ) -> Box<dyn Iterator<Item = DictIterItem<'a>> + 'a> {
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let search = self.to_search();
+        match self.sort {
+            // results are already yielded in alphabetical order
+            None | Some(Sort(SortAspect::Alphabetical, SortDirection::Ascending)) => {
+                Box::new(dict.iter_search(search).take(limit))
+            }
+            Some(sort) => Box::new(dict.top_k(search, limit, sort).into_iter()),
+        }
+    }
+}
+
+// The least and, if any, greatest values in `range`, or `None` if it holds
+// nothing.
+fn inclusive_bounds<R: RangeBounds<usize>>(range: &R) -> Option<(usize, Option<usize>)> {
+    let min = match range.start_bound() {
+        Bound::Included(&x) => x,
+        Bound::Excluded(&x) => x + 1,
+        Bound::Unbounded => 0,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(&x) => Some(x),
+        Bound::Excluded(&0) => return None,
+        Bound::Excluded(&x) => Some(x - 1),
+        Bound::Unbounded => None,
+    };
+    Some((min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dict: &Dictionary, query: DictQuery) -> Vec<&str> {
+        query
+            .run(dict)
+            .into_iter()
+            .map(|x| &x.original[..])
+            .collect()
+    }
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec![
+            "cat", "cater", "crate", "react", "trace", "caret", "quack", "quick", "cart",
+        ])
+    }
+
+    #[test]
+    fn compiles_prefix_and_length_into_trie_search() {
+        let query = DictQuery::new().prefix("ca").length(3..=5);
+
+        let expected = DictSearch::new(
+            Some(TrieSearch::new(
+                TriePrefix::from_pattern("ca?").unwrap(),
+                Some(5),
+            )),
+            WordPredicate::None,
+        );
+        assert_eq!(query.to_search(), expected)
+    }
+
+    #[test]
+    fn anagram_fixes_length() {
+        let query = DictQuery::new().anagram_of("trace");
+
+        let search = query.to_search();
+        assert_eq!(
+            search,
+            DictSearch::new(
+                Some(TrieSearch::new(TriePrefix::any_with_length(5), Some(5))),
+                WordPredicate::AnagramOf(
+                    AnagramNumber::try_from(&NormalizedWord::from("trace")).unwrap()
+                ),
+            )
+        )
+    }
+
+    #[test]
+    fn runs_combined_constraints() {
+        let dict = dict();
+        let res = run(
+            &dict,
+            DictQuery::new()
+                .prefix("c")
+                .anagram_of("trace")
+                .sort_by(SortAspect::Alphabetical),
+        );
+
+        assert_eq!(res, vec!["caret", "cater", "crate"])
+    }
+
+    #[test]
+    fn contains_sets_minimum_length() {
+        let dict = dict();
+        let res = run(&dict, DictQuery::new().contains("qu").length(..=5));
+
+        assert_eq!(res, vec!["quack", "quick"])
+    }
+
+    #[test]
+    fn only_letters_filters_by_letter_set() {
+        let dict = Dictionary::from_iter(vec!["mom", "wow", "swim", "noon", "moon", "cat"]);
+        let res = run(&dict, DictQuery::new().only_letters(CharSet::rotatable()));
+        assert_eq!(res, ["mom", "moon", "noon", "swim", "wow"]);
+
+        let res = run(
+            &dict,
+            DictQuery::new().only_letters(CharSet::vertically_symmetric()),
+        );
+        assert_eq!(res, ["mom", "wow"]);
+    }
+
+    #[test]
+    fn counts_digraphs_as_single_tokens() {
+        let dict = Dictionary::from_iter(vec!["chorro", "calle", "cosa", "llama", "oso", "tomate"]);
+        let spanish = Digraphs::spanish();
+
+        let res = run(&dict, DictQuery::new().token_length(&spanish, 4..=4));
+        assert_eq!(res, ["calle", "chorro", "cosa", "llama"]);
+
+        let res = run(&dict, DictQuery::new().token_length(&spanish, ..4));
+        assert_eq!(res, ["oso"]);
+
+        let res = run(&dict, DictQuery::new().token_length(&Digraphs::new(), 5..));
+        assert_eq!(res, ["calle", "chorro", "llama", "tomate"]);
+
+        // seven letters in three tokens, beyond two letters a token
+        let dict = Dictionary::from_iter(vec!["schschs", "schach", "schon", "tisch"]);
+        let sch = Digraphs::new().with("sch");
+        let res = run(&dict, DictQuery::new().token_length(&sch, 3..=3));
+        assert_eq!(res, ["schon", "schschs", "tisch"]);
+    }
+
+    #[test]
+    fn category_ignores_case() {
+        let mut dict = dict();
+        dict.categorize("cart", "Vehicle");
+        let res = run(&dict, DictQuery::new().category("VEHICLE"));
+        assert_eq!(res, ["cart"]);
+    }
+
+    #[test]
+    fn sorts_and_limits() {
+        let query = DictQuery::new()
+            .formable_from("cartes")
+            .sort(Sort(SortAspect::Length, SortDirection::Descending))
+            .limit(2);
+
+        let dict = dict();
+        assert_eq!(run(&dict, query), vec!["caret", "cater"])
+    }
+
+    #[test]
+    fn conflicting_constraints_match_nothing() {
+        let dict = dict();
+        assert!(run(&dict, DictQuery::new().prefix("ca").prefix("cr")).is_empty());
+        assert!(run(&dict, DictQuery::new().prefix("cat").length(..3)).is_empty());
+        assert!(run(&dict, DictQuery::new().anagram_of("cat").length(4..)).is_empty());
+    }
+
+    #[test]
+    fn fallible_patterns_report_errors() {
+        let dict = dict();
+        let query = DictQuery::new().try_pattern("c??t").unwrap();
+        assert_eq!(query, DictQuery::new().pattern("c??t"));
+        assert_eq!(run(&dict, query), vec!["cart"]);
+
+        let err = DictQuery::new().try_pattern("ca*").unwrap_err();
+        assert_eq!(err.position, 2);
+        assert!(DictQuery::new().try_prefix("c[at").is_err());
+        assert!(run(&dict, DictQuery::new().prefix("ca*")).is_empty());
+    }
+
+    #[test]
+    fn cancelled_query_stops_early() {
+        let dict = dict();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let query = DictQuery::new().prefix("c").cancellable(token.clone());
+        assert!(run(&dict, query.clone()).is_empty());
+
+        token.reset();
+        assert_eq!(run(&dict, query).len(), 5);
+    }
+
+    #[test]
+    fn merges_compatible_prefixes() {
+        let dict = dict();
+        let res = run(&dict, DictQuery::new().prefix("c??t").prefix("?a"));
+
+        assert_eq!(res, vec!["cart"])
+    }
+
+    #[test]
+    fn patterns_take_rebus_squares() {
+        let dict = dict();
+
+        assert_eq!(run(&dict, DictQuery::new().pattern("[cat]??")), ["cater"]);
+        assert_eq!(run(&dict, DictQuery::new().prefix("?[ua]")), ["quack"]);
+        assert!(run(&dict, DictQuery::new().prefix("[ca")).is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::dictionary::{DictIterItem, Dictionary};
+use crate::ngram::TrigramModel;
+use crate::trie::TriePrefix;
+
+/// A signal for ranking search results, higher for better.
+///
+/// Scorers combine with `Weighted`, and any closure over a result is one,
+/// so `Dictionary::iter_scored` can rank by several signals at once.
+pub trait Scorer {
+    fn score(&self, item: &DictIterItem) -> f64;
+}
+
+impl<F: Fn(&DictIterItem) -> f64> Scorer for F {
+    fn score(&self, item: &DictIterItem) -> f64 {
+        self(item)
+    }
+}
+
+/// Scores by the dictionary's score or frequency, on a log scale so that
+/// very common words do not drown out other signals. Unscored entries
+/// score zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Frequency;
+
+impl Scorer for Frequency {
+    fn score(&self, item: &DictIterItem) -> f64 {
+        item.score.map_or(0.0, |x| (x as f64).ln_1p())
+    }
+}
+
+/// Scores by the number of letters, so longer words rank higher. Weight it
+/// negatively to prefer shorter ones.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Length;
+
+impl Scorer for Length {
+    fn score(&self, item: &DictIterItem) -> f64 {
+        item.normalized.len() as f64
+    }
+}
+
+/// Scores by how much of a pattern a word fits, from 0 to 1: the share of
+/// letters matching the pattern at the same position, out of the longer
+/// of the two. Useful for ranking near misses, as from a fuzzy search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternFit(pub TriePrefix);
+
+impl Scorer for PatternFit {
+    fn score(&self, item: &DictIterItem) -> f64 {
+        let pattern = self.0.chars();
+        let len = pattern.len().max(item.normalized.len());
+        if len == 0 {
+            return 1.0;
+        }
+        let fits = pattern
+            .iter()
+            .zip(item.normalized.iter_chars())
+            .filter(|(restriction, ch)| restriction.matches(ch))
+            .count();
+        fits as f64 / len as f64
+    }
+}
+
+/// Scores by how word-like the letters are by a trigram model, as the
+/// average log chance of each letter and the end, so that length does not
+/// count against a word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pronounceability(pub TrigramModel);
+
+impl Pronounceability {
+    pub fn from_dictionary(dict: &Dictionary) -> Self {
+        Pronounceability(TrigramModel::from_dictionary(dict))
+    }
+}
+
+impl Scorer for Pronounceability {
+    fn score(&self, item: &DictIterItem) -> f64 {
+        self.0.log_prob(&item.normalized) / (item.normalized.len() + 1) as f64
+    }
+}
+
+/// The weighted sum of other scorers.
+#[derive(Default)]
+pub struct Weighted<'a> {
+    parts: Vec<(f64, Box<dyn Scorer + 'a>)>,
+}
+
+impl<'a> Weighted<'a> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with<S: Scorer + 'a>(mut self, weight: f64, scorer: S) -> Self {
+        self.parts.push((weight, Box::new(scorer)));
+        self
+    }
+}
+
+impl Scorer for Weighted<'_> {
+    fn score(&self, item: &DictIterItem) -> f64 {
+        self.parts
+            .iter()
+            .map(|(weight, scorer)| weight * scorer.score(item))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::DictQuery;
+    use std::cell::Cell;
+
+    fn dict() -> Dictionary {
+        let mut dict = Dictionary::from_iter(vec!["cart", "xqzt", "carts"]);
+        dict.insert_scored("care", 100);
+        dict.insert_scored("core", 10);
+        dict
+    }
+
+    fn scored(dict: &Dictionary, query: DictQuery, scorer: impl Scorer) -> Vec<(String, f64)> {
+        dict.iter_scored(query.to_search(), scorer)
+            .map(|(score, x)| (x.normalized.to_string(), score))
+            .collect()
+    }
+
+    #[test]
+    fn scores_single_signals() {
+        let dict = dict();
+        let all = || DictQuery::new();
+
+        let frequency = scored(&dict, all(), Frequency);
+        assert!(frequency.contains(&("CART".into(), 0.0)));
+        assert!(frequency.contains(&("CARE".into(), 101f64.ln())));
+
+        let length = scored(&dict, all().pattern("c???"), Length);
+        assert!(length.iter().all(|(_, x)| *x == 4.0));
+
+        let fit = PatternFit(TriePrefix::from_pattern("car?").unwrap());
+        let fit = scored(&dict, all(), fit);
+        assert!(fit.contains(&("CARE".into(), 1.0)));
+        assert!(fit.contains(&("CARTS".into(), 0.8)));
+        assert!(fit.contains(&("CORE".into(), 0.75)));
+
+        let pronounceable = scored(&dict, all(), Pronounceability::from_dictionary(&dict));
+        let of = |word: &str| pronounceable.iter().find(|x| x.0 == word).unwrap().1;
+        assert!(of("CART") > of("XQZT"));
+    }
+
+    #[test]
+    fn combines_signals_lazily() {
+        let dict = dict();
+        let scorer = Weighted::new()
+            .with(1.0, Frequency)
+            .with(-0.5, Length)
+            .with(2.0, |x: &DictIterItem| x.normalized.len() as f64);
+        let combined = scored(&dict, DictQuery::new().pattern("c?r?"), scorer);
+        assert_eq!(
+            combined,
+            [
+                ("CARE".into(), 101f64.ln() + 6.0),
+                ("CART".into(), 6.0),
+                ("CORE".into(), 11f64.ln() + 6.0),
+            ]
+        );
+
+        let calls = Cell::new(0);
+        let counting = |_: &DictIterItem| {
+            calls.set(calls.get() + 1);
+            0.0
+        };
+        let first = dict
+            .iter_scored(DictQuery::new().to_search(), counting)
+            .next();
+        assert!(first.is_some());
+        assert_eq!(calls.get(), 1);
+    }
+}
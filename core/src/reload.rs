@@ -0,0 +1,148 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::cancel::CancellationToken;
+use crate::dictionary::{ArcDictionary, Dictionary};
+use crate::progress::NoProgress;
+
+type Loader = Box<dyn Fn(&[PathBuf]) -> io::Result<Dictionary> + Send + Sync>;
+
+// A file's modification time and length, or None if it couldn't be read.
+type Stamp = Option<(SystemTime, u64)>;
+
+/// A dictionary loaded from word list files that can be reloaded when they
+/// change. Readers take a snapshot with `current`, which a reload swaps
+/// out whole, so a search never sees a half-loaded dictionary.
+pub struct ReloadableDictionary {
+    paths: Vec<PathBuf>,
+    loader: Loader,
+    current: RwLock<ArcDictionary>,
+    // held for the whole of a reload so concurrent checks load only once
+    stamps: Mutex<Vec<Stamp>>,
+}
+
+impl ReloadableDictionary {
+    /// Loads newline-separated word lists from `paths`.
+    pub fn load(paths: Vec<PathBuf>) -> io::Result<Self> {
+        Self::load_with(paths, |paths| {
+            let mut dict: Dictionary = Default::default();
+            for path in paths {
+                dict.read_from(BufReader::new(File::open(path)?), None, NoProgress)?;
+            }
+            Ok(dict)
+        })
+    }
+
+    /// Loads `paths` with `loader`, which is used again on every reload.
+    pub fn load_with<F>(paths: Vec<PathBuf>, loader: F) -> io::Result<Self>
+    where
+        F: Fn(&[PathBuf]) -> io::Result<Dictionary> + Send + Sync + 'static,
+    {
+        let stamps = stamps(&paths);
+        let dict = loader(&paths)?;
+        Ok(ReloadableDictionary {
+            paths,
+            loader: Box::new(loader),
+            current: RwLock::new(dict.into()),
+            stamps: Mutex::new(stamps),
+        })
+    }
+
+    pub fn current(&self) -> ArcDictionary {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Reloads the files if any has been modified since the last load,
+    /// returning whether it did. On error the previous dictionary is kept,
+    /// and the next call tries again.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        let mut stamps = self.stamps.lock().unwrap();
+        let latest = self::stamps(&self.paths);
+        if latest == *stamps {
+            return Ok(false);
+        }
+        let dict = (self.loader)(&self.paths)?;
+        *self.current.write().unwrap() = dict.into();
+        *stamps = latest;
+        Ok(true)
+    }
+
+    /// Checks for changes every `interval` on a background thread until
+    /// `cancel` is cancelled, calling `on_reload` after each reload or
+    /// failed attempt.
+    pub fn watch<F>(
+        self: &Arc<Self>,
+        interval: Duration,
+        cancel: CancellationToken,
+        mut on_reload: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut(io::Result<()>) + Send + 'static,
+    {
+        let dict = Arc::clone(self);
+        thread::spawn(move || {
+            while !cancel.is_cancelled() {
+                thread::sleep(interval);
+                match dict.reload_if_changed() {
+                    Ok(false) => (),
+                    Ok(true) => on_reload(Ok(())),
+                    Err(err) => on_reload(Err(err)),
+                }
+            }
+        })
+    }
+}
+
+/// A dictionary with no files behind it, which never reloads.
+impl From<Dictionary> for ReloadableDictionary {
+    fn from(dict: Dictionary) -> Self {
+        ReloadableDictionary {
+            paths: vec![],
+            loader: Box::new(|_| Ok(Default::default())),
+            current: RwLock::new(dict.into()),
+            stamps: Mutex::new(vec![]),
+        }
+    }
+}
+
+fn stamps(paths: &[PathBuf]) -> Vec<Stamp> {
+    paths
+        .iter()
+        .map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_word::NormalizedWord;
+
+    #[test]
+    fn reloads_changed_files() {
+        let path = std::env::temp_dir().join(format!("wordplay-reload-{}.txt", std::process::id()));
+        fs::write(&path, "cat\n").unwrap();
+        let dict = ReloadableDictionary::load(vec![path.clone()]).unwrap();
+        let has = |dict: &Dictionary, word: &str| {
+            dict.find(&NormalizedWord::from_str_safe(word)).is_some()
+        };
+
+        let before = dict.current();
+        assert!(!dict.reload_if_changed().unwrap());
+
+        fs::write(&path, "cat\ndogs\n").unwrap();
+        assert!(dict.reload_if_changed().unwrap());
+        assert!(has(&dict.current(), "dogs"));
+        assert!(!has(&before, "dogs"));
+
+        fs::remove_file(&path).unwrap();
+        assert!(dict.reload_if_changed().is_err());
+        assert!(has(&dict.current(), "dogs"));
+    }
+}
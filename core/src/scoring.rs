@@ -0,0 +1,213 @@
+use rand::Rng;
+
+use crate::char_map::CharMap;
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::scrabble::{Rack, Tile, RACK_SIZE};
+
+/// Standard English Scrabble tile values.
+pub const LETTER_VALUES: CharMap<u32> = CharMap::new([
+    1, 3, 3, 2, 1, 4, 2, 4, 1, 8, 5, 1, 3, 1, 1, 3, 10, 1, 1, 1, 1, 4, 4, 8, 4, 10,
+]);
+
+pub fn letter_value(ch: NormalizedChar) -> u32 {
+    *LETTER_VALUES.get(ch)
+}
+
+/// The face value of a word's tiles, ignoring premium squares.
+pub fn word_score(word: &NormalizedWord) -> u32 {
+    word.iter_chars().map(|&ch| letter_value(ch)).sum()
+}
+
+/// Standard English tile counts, plus two blanks.
+pub const STANDARD_DISTRIBUTION: CharMap<u8> = CharMap::new([
+    9, 2, 2, 4, 12, 2, 3, 2, 9, 1, 1, 4, 2, 6, 8, 2, 1, 6, 4, 6, 4, 2, 2, 1, 2, 1,
+]);
+pub const STANDARD_BLANKS: u8 = 2;
+
+/// The unseen tiles, from which racks are refilled.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TileBag {
+    letters: CharMap<u8>,
+    blanks: u8,
+}
+
+impl TileBag {
+    pub fn standard() -> Self {
+        TileBag::new(STANDARD_DISTRIBUTION, STANDARD_BLANKS)
+    }
+
+    pub fn new(letters: CharMap<u8>, blanks: u8) -> Self {
+        TileBag { letters, blanks }
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters
+            .iter_values()
+            .map(|&n| n as usize)
+            .sum::<usize>()
+            + self.blanks as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn count(&self, letter: NormalizedChar) -> u8 {
+        *self.letters.get(letter)
+    }
+
+    pub fn blanks(&self) -> u8 {
+        self.blanks
+    }
+
+    /// Takes out a tile known to be elsewhere, such as on the board or a
+    /// rack, returning whether it was in the bag.
+    pub fn remove(&mut self, tile: Tile) -> bool {
+        let count = if tile.blank {
+            &mut self.blanks
+        } else {
+            self.letters.get_mut(tile.letter)
+        };
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// Draws a tile uniformly at random. Blanks are drawn as `A`, marked blank.
+    pub fn draw<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Tile> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut n = rng.gen_range(0..self.len());
+        if n < self.blanks as usize {
+            self.blanks -= 1;
+            return Some(Tile {
+                letter: NormalizedChar::A,
+                blank: true,
+            });
+        }
+        n -= self.blanks as usize;
+        for letter in NormalizedChar::all() {
+            let count = self.letters.get_mut(letter);
+            if n < *count as usize {
+                *count -= 1;
+                return Some(Tile {
+                    letter,
+                    blank: false,
+                });
+            }
+            n -= *count as usize;
+        }
+        unreachable!("n is less than the number of tiles")
+    }
+
+    /// Draws until `rack` holds `RACK_SIZE` tiles or the bag is empty.
+    pub fn fill<R: Rng + ?Sized>(&mut self, rack: &mut Rack, rng: &mut R) {
+        while rack.len() < RACK_SIZE {
+            match self.draw(rng) {
+                Some(tile) => rack.add(tile),
+                None => break,
+            }
+        }
+    }
+
+    /// Simulates refilling `leave` from this bag `trials` times, for
+    /// estimating what a leave tends to draw into.
+    pub fn simulate_fills<R: Rng + ?Sized>(
+        &self,
+        leave: &Rack,
+        trials: usize,
+        rng: &mut R,
+    ) -> Vec<Rack> {
+        (0..trials)
+            .map(|_| {
+                let mut bag = self.clone();
+                let mut rack = leave.clone();
+                bag.fill(&mut rack, rng);
+                rack
+            })
+            .collect()
+    }
+}
+
+// Rough worth in points of keeping each tile, before balance and duplicates.
+const LEAVE_VALUES: CharMap<f64> = CharMap::new([
+    1.0, -2.0, 0.5, 0.5, 2.0, -2.0, -2.0, 0.5, -0.5, -2.5, -1.5, -0.5, 0.0, 0.5, -1.0, -0.5, -7.0,
+    1.5, 8.0, 0.0, -3.0, -5.0, -3.0, 3.5, -0.5, 3.0,
+]);
+const BLANK_LEAVE_VALUE: f64 = 25.0;
+const IDEAL_VOWEL_SHARE: f64 = 0.4;
+const IMBALANCE_PENALTY: f64 = 2.5;
+const DUPLICATE_PENALTY: f64 = 4.0;
+
+/// Estimates how many points the tiles kept after a play are worth on later
+/// turns: each tile's own worth, less penalties for straying from about two
+/// vowels to three consonants and for duplicated letters.
+pub fn leave_value(leave: &Rack) -> f64 {
+    let mut value = leave.blanks() as f64 * BLANK_LEAVE_VALUE;
+    let (mut vowels, mut letters) = (0, 0);
+    for letter in NormalizedChar::all() {
+        let count = leave.count(letter);
+        if count == 0 {
+            continue;
+        }
+        value += LEAVE_VALUES.get(letter) * count as f64;
+        value -= DUPLICATE_PENALTY * (count - 1) as f64;
+        letters += count;
+        if letter.is_vowel() {
+            vowels += count;
+        }
+    }
+    let imbalance = (vowels as f64 - letters as f64 * IDEAL_VOWEL_SHARE).abs();
+    value - IMBALANCE_PENALTY * imbalance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn scores_tiles() {
+        assert_eq!(word_score(&"quiz".into()), 22);
+        assert_eq!(word_score(&"".into()), 0);
+    }
+
+    #[test]
+    fn draws_every_tile_once() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut bag = TileBag::standard();
+        assert_eq!(bag.len(), 100);
+
+        let mut rack: Rack = Default::default();
+        bag.fill(&mut rack, &mut rng);
+        assert_eq!((rack.len(), bag.len()), (7, 93));
+
+        let mut drawn = 7;
+        while bag.draw(&mut rng).is_some() {
+            drawn += 1;
+        }
+        assert_eq!(drawn, 100);
+        assert!(!bag.remove(Tile {
+            letter: NormalizedChar::E,
+            blank: false
+        }));
+
+        let fills = TileBag::standard().simulate_fills(&"qu".into(), 20, &mut rng);
+        assert!(fills
+            .iter()
+            .all(|r| r.len() == 7 && r.count(NormalizedChar::Q) >= 1));
+    }
+
+    #[test]
+    fn prefers_balanced_leaves() {
+        let value = |rack: &str| leave_value(&rack.into());
+
+        assert!(value("ers") > value("eee"));
+        assert!(value("aei") < value("ain"));
+        assert!(value("?") > value("s"));
+        assert!(value("q") < value(""));
+    }
+}
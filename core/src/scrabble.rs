@@ -0,0 +1,748 @@
+use crate::char_map::CharMap;
+use crate::dictionary::{DictEntry, Dictionary};
+use crate::normalized_word::{NormalizedChar, NormalizedWord, ALPHABET_SIZE};
+use crate::scoring::{leave_value, letter_value};
+use crate::trie::Trie;
+
+pub const BOARD_SIZE: usize = 15;
+pub const RACK_SIZE: usize = 7;
+pub const BINGO_BONUS: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Premium {
+    #[default]
+    None,
+    DoubleLetter,
+    TripleLetter,
+    DoubleWord,
+    TripleWord,
+}
+
+impl Premium {
+    fn letter_multiplier(self) -> u32 {
+        match self {
+            Premium::DoubleLetter => 2,
+            Premium::TripleLetter => 3,
+            _ => 1,
+        }
+    }
+
+    fn word_multiplier(self) -> u32 {
+        match self {
+            Premium::DoubleWord => 2,
+            Premium::TripleWord => 3,
+            _ => 1,
+        }
+    }
+}
+
+// The top-left quadrant of the standard board, mirrored to fill the rest.
+const STANDARD_QUADRANT: [&str; 8] = [
+    "T..d...T", ".D...t..", "..D...d.", "d..D...d", "....D...", ".t...t..", "..d...d.", "T..d...D",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub letter: NormalizedChar,
+    /// Blank tiles stand for a letter but score nothing.
+    pub blank: bool,
+}
+
+impl Tile {
+    pub fn value(self) -> u32 {
+        if self.blank {
+            0
+        } else {
+            letter_value(self.letter)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Across,
+    Down,
+}
+
+/// Letter tiles plus blanks, which are written `?`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Rack {
+    letters: CharMap<u8>,
+    blanks: u8,
+}
+
+impl Rack {
+    pub fn len(&self) -> usize {
+        self.letters
+            .iter_values()
+            .map(|&n| n as usize)
+            .sum::<usize>()
+            + self.blanks as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn count(&self, letter: NormalizedChar) -> u8 {
+        *self.letters.get(letter)
+    }
+
+    pub fn blanks(&self) -> u8 {
+        self.blanks
+    }
+
+    // The tiles that could be played as `letter`.
+    fn tiles_for(&self, letter: NormalizedChar) -> impl Iterator<Item = Tile> {
+        let real = (*self.letters.get(letter) > 0).then_some(Tile {
+            letter,
+            blank: false,
+        });
+        let blank = (self.blanks > 0).then_some(Tile {
+            letter,
+            blank: true,
+        });
+        real.into_iter().chain(blank)
+    }
+
+    /// Takes `tile` off the rack, returning whether it was there. A tile
+    /// that is not is left alone.
+    pub fn remove(&mut self, tile: Tile) -> bool {
+        let count = if tile.blank {
+            &mut self.blanks
+        } else {
+            self.letters.get_mut(tile.letter)
+        };
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    pub fn add(&mut self, tile: Tile) {
+        if tile.blank {
+            self.blanks += 1;
+        } else {
+            *self.letters.get_mut(tile.letter) += 1;
+        }
+    }
+}
+
+impl From<&str> for Rack {
+    fn from(str: &str) -> Self {
+        let mut rack: Rack = Default::default();
+        for ch in str.chars() {
+            match ch {
+                '?' => rack.blanks += 1,
+                _ => {
+                    if let Some(letter) = NormalizedChar::from_char(ch) {
+                        *rack.letters.get_mut(letter) += 1;
+                    }
+                }
+            }
+        }
+        rack
+    }
+}
+
+/// A legal play, with the coordinates of its main word's first square.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+    /// The main word, including tiles already on the board.
+    pub word: NormalizedWord,
+    /// The tiles placed from the rack, as `(row, col, tile)`.
+    pub placed: Vec<(usize, usize, Tile)>,
+    pub score: u32,
+}
+
+impl Move {
+    /// The tiles left on `rack` after this play, or None if the play uses
+    /// tiles the rack does not have.
+    pub fn leave(&self, rack: &Rack) -> Option<Rack> {
+        let mut leave = rack.clone();
+        for &(_, _, tile) in self.placed.iter() {
+            if !leave.remove(tile) {
+                return None;
+            }
+        }
+        Some(leave)
+    }
+}
+
+/// A play ranked by its score plus the estimated value of its leave.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedMove {
+    pub play: Move,
+    pub leave: Rack,
+    pub equity: f64,
+}
+
+/// A square board of premium squares and placed tiles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    size: usize,
+    premiums: Vec<Premium>,
+    tiles: Vec<Option<Tile>>,
+}
+
+impl Board {
+    /// The standard 15x15 layout.
+    pub fn standard() -> Self {
+        let premiums = (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|i| {
+                let fold = |x: usize| x.min(BOARD_SIZE - 1 - x);
+                let (row, col) = (fold(i / BOARD_SIZE), fold(i % BOARD_SIZE));
+                match STANDARD_QUADRANT[row].as_bytes()[col] {
+                    b'd' => Premium::DoubleLetter,
+                    b't' => Premium::TripleLetter,
+                    b'D' => Premium::DoubleWord,
+                    b'T' => Premium::TripleWord,
+                    _ => Premium::None,
+                }
+            })
+            .collect();
+        Board::with_premiums(BOARD_SIZE, premiums)
+    }
+
+    /// An empty board with premiums given row by row.
+    pub fn with_premiums(size: usize, premiums: Vec<Premium>) -> Self {
+        assert_eq!(premiums.len(), size * size, "one premium per square");
+        Board {
+            size,
+            premiums,
+            tiles: vec![None; size * size],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn tile(&self, row: usize, col: usize) -> Option<Tile> {
+        self.tiles[row * self.size + col]
+    }
+
+    pub fn premium(&self, row: usize, col: usize) -> Premium {
+        self.premiums[row * self.size + col]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.iter().all(Option::is_none)
+    }
+
+    pub fn set_tile(&mut self, row: usize, col: usize, tile: Tile) {
+        self.tiles[row * self.size + col] = Some(tile);
+    }
+
+    /// Writes `word` onto the board without checking it, for setting up
+    /// positions. Letters outside the board are dropped.
+    pub fn place_word(&mut self, row: usize, col: usize, direction: Direction, word: &str) {
+        let word = NormalizedWord::from_str_safe(word);
+        for (i, &letter) in word.iter_chars().enumerate() {
+            let (r, c) = match direction {
+                Direction::Across => (row, col + i),
+                Direction::Down => (row + i, col),
+            };
+            if r < self.size && c < self.size {
+                self.set_tile(
+                    r,
+                    c,
+                    Tile {
+                        letter,
+                        blank: false,
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn play(&mut self, play: &Move) {
+        for &(row, col, tile) in play.placed.iter() {
+            self.set_tile(row, col, tile);
+        }
+    }
+
+    /// Every legal play of tiles from `rack`, highest score first.
+    ///
+    /// Uses Appel and Jacobson's algorithm: plays are grown from anchor
+    /// squares next to existing tiles, walking the dictionary's trie and
+    /// pruning letters that would form invalid words in the other direction.
+    pub fn moves(&self, dict: &Dictionary, rack: &Rack) -> Vec<Move> {
+        let mut moves = Generator::new(self, dict, rack, Direction::Across).generate();
+        moves.extend(Generator::new(&self.transposed(), dict, rack, Direction::Down).generate());
+
+        // a single tile forming words both ways is found in both directions
+        let mut singles: Vec<(usize, usize, Tile)> = vec![];
+        moves.retain(|m| match m.placed[..] {
+            [single] if singles.contains(&single) => false,
+            [single] => {
+                singles.push(single);
+                true
+            }
+            _ => true,
+        });
+
+        moves.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.word.cmp(&b.word))
+                .then_with(|| (a.row, a.col).cmp(&(b.row, b.col)))
+        });
+        moves
+    }
+
+    /// Every legal play, ranked by score plus `leave_value` of the tiles
+    /// kept, so that a slightly lower score can win by keeping a better rack.
+    pub fn ranked_moves(&self, dict: &Dictionary, rack: &Rack) -> Vec<RankedMove> {
+        let mut ranked: Vec<RankedMove> = self
+            .moves(dict, rack)
+            .into_iter()
+            .map(|play| {
+                // moves are only found for tiles on the rack
+                let leave = play.leave(rack).unwrap();
+                let equity = play.score as f64 + leave_value(&leave);
+                RankedMove {
+                    play,
+                    leave,
+                    equity,
+                }
+            })
+            .collect();
+        // stable, so equal equity keeps score order
+        ranked.sort_by(|a, b| b.equity.total_cmp(&a.equity));
+        ranked
+    }
+
+    fn transposed(&self) -> Board {
+        let mut board = self.clone();
+        for row in 0..self.size {
+            for col in 0..self.size {
+                board.premiums[col * self.size + row] = self.premium(row, col);
+                board.tiles[col * self.size + row] = self.tile(row, col);
+            }
+        }
+        board
+    }
+
+    // Tiles directly above and below a square, read downwards.
+    fn perpendicular(&self, row: usize, col: usize) -> (Vec<Tile>, Vec<Tile>) {
+        let above = (0..row)
+            .rev()
+            .map_while(|r| self.tile(r, col))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let below = (row + 1..self.size)
+            .map_while(|r| self.tile(r, col))
+            .collect();
+        (above, below)
+    }
+
+    fn is_anchor(&self, row: usize, col: usize) -> bool {
+        if self.tile(row, col).is_some() {
+            return false;
+        }
+        if self.is_empty() {
+            return row == self.size / 2 && col == self.size / 2;
+        }
+        let neighbours = [
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+        ];
+        neighbours
+            .iter()
+            .any(|&(r, c)| r < self.size && c < self.size && self.tile(r, c).is_some())
+    }
+}
+
+const ALL_LETTERS: u32 = (1 << ALPHABET_SIZE) - 1;
+
+// What a square constrains in the perpendicular direction.
+#[derive(Clone, Copy)]
+struct CrossCheck {
+    allowed: u32,
+    // Face value of the perpendicular tiles, when there are any.
+    score: Option<u32>,
+}
+
+// Generates across plays one row at a time; down plays are generated on the
+// transposed board and flipped back when recorded.
+struct Generator<'a> {
+    board: &'a Board,
+    dict: &'a Dictionary,
+    direction: Direction,
+    rack: Rack,
+    row: usize,
+    anchor: usize,
+    cross_checks: Vec<CrossCheck>,
+    word: Vec<NormalizedChar>,
+    // Tiles placed from the rack, by index into `word`.
+    placed: Vec<(usize, Tile)>,
+    moves: Vec<Move>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(board: &'a Board, dict: &'a Dictionary, rack: &Rack, direction: Direction) -> Self {
+        Generator {
+            board,
+            dict,
+            direction,
+            rack: rack.clone(),
+            row: 0,
+            anchor: 0,
+            cross_checks: vec![],
+            word: vec![],
+            placed: vec![],
+            moves: vec![],
+        }
+    }
+
+    fn generate(mut self) -> Vec<Move> {
+        let root = self.dict.trie();
+        for row in 0..self.board.size {
+            self.row = row;
+            self.cross_checks = (0..self.board.size)
+                .map(|col| self.cross_check(col))
+                .collect();
+
+            let mut free_left = 0;
+            for col in 0..self.board.size {
+                if !self.board.is_anchor(row, col) {
+                    free_left = match self.board.tile(row, col) {
+                        Some(_) => 0,
+                        None => free_left + 1,
+                    };
+                    continue;
+                }
+                self.anchor = col;
+                if col > 0 && self.board.tile(row, col - 1).is_some() {
+                    self.extend_existing(root, col);
+                } else {
+                    self.left_part(root, free_left.min(RACK_SIZE - 1));
+                }
+                free_left = 0;
+            }
+        }
+        self.moves
+    }
+
+    fn cross_check(&self, col: usize) -> CrossCheck {
+        let (above, below) = self.board.perpendicular(self.row, col);
+        if above.is_empty() && below.is_empty() {
+            return CrossCheck {
+                allowed: ALL_LETTERS,
+                score: None,
+            };
+        }
+
+        let mut allowed = 0;
+        for letter in NormalizedChar::all() {
+            let word: NormalizedWord = above
+                .iter()
+                .map(|t| t.letter)
+                .chain(Some(letter))
+                .chain(below.iter().map(|t| t.letter))
+                .collect();
+            if self.dict.find(&word).is_some() {
+                allowed |= 1 << letter as u32;
+            }
+        }
+        let score = above.iter().chain(below.iter()).map(|t| t.value()).sum();
+        CrossCheck {
+            allowed,
+            score: Some(score),
+        }
+    }
+
+    // Follows the tiles already left of the anchor, which every play through
+    // the anchor must extend.
+    fn extend_existing(&mut self, root: &'a Trie<DictEntry>, anchor: usize) {
+        let start = (0..anchor)
+            .rev()
+            .take_while(|&c| self.board.tile(self.row, c).is_some())
+            .last()
+            .unwrap_or(anchor);
+        let mut node = root;
+        for col in start..anchor {
+            let letter = self.board.tile(self.row, col).unwrap().letter;
+            match node.child(letter) {
+                Some(child) => node = child,
+                None => {
+                    self.word.clear();
+                    return;
+                }
+            }
+            self.word.push(letter);
+        }
+        self.extend_right(node, anchor);
+        self.word.clear();
+    }
+
+    // Places up to `limit` rack tiles on the free squares left of the anchor.
+    fn left_part(&mut self, node: &'a Trie<DictEntry>, limit: usize) {
+        self.extend_right(node, self.anchor);
+        if limit == 0 {
+            return;
+        }
+        for letter in NormalizedChar::all() {
+            let Some(child) = node.child(letter) else {
+                continue;
+            };
+            for tile in self.rack.tiles_for(letter).collect::<Vec<_>>() {
+                self.push(letter, Some(tile));
+                self.left_part(child, limit - 1);
+                self.pop();
+            }
+        }
+    }
+
+    fn extend_right(&mut self, node: &'a Trie<DictEntry>, col: usize) {
+        if col < self.board.size {
+            if let Some(tile) = self.board.tile(self.row, col) {
+                if let Some(child) = node.child(tile.letter) {
+                    self.push(tile.letter, None);
+                    self.extend_right(child, col + 1);
+                    self.pop();
+                }
+                return;
+            }
+        }
+
+        if node.is_terminal() && col > self.anchor && self.word.len() > 1 {
+            self.record(col);
+        }
+        if col == self.board.size {
+            return;
+        }
+
+        let allowed = self.cross_checks[col].allowed;
+        for letter in NormalizedChar::all() {
+            if allowed & (1 << letter as u32) == 0 {
+                continue;
+            }
+            let Some(child) = node.child(letter) else {
+                continue;
+            };
+            for tile in self.rack.tiles_for(letter).collect::<Vec<_>>() {
+                self.push(letter, Some(tile));
+                self.extend_right(child, col + 1);
+                self.pop();
+            }
+        }
+    }
+
+    fn push(&mut self, letter: NormalizedChar, tile: Option<Tile>) {
+        if let Some(tile) = tile {
+            // tiles come from `Rack::tiles_for`, so are always on the rack
+            self.rack.remove(tile);
+            self.placed.push((self.word.len(), tile));
+        }
+        self.word.push(letter);
+    }
+
+    fn pop(&mut self) {
+        self.word.pop();
+        if self
+            .placed
+            .last()
+            .is_some_and(|&(i, _)| i == self.word.len())
+        {
+            let (_, tile) = self.placed.pop().unwrap();
+            self.rack.add(tile);
+        }
+    }
+
+    // Records the word ending just before `end`.
+    fn record(&mut self, end: usize) {
+        let start = end - self.word.len();
+        let mut main = 0;
+        let mut multiplier = 1;
+        let mut cross_words = 0;
+        for i in 0..self.word.len() {
+            let col = start + i;
+            match self.placed.iter().find(|&&(j, _)| j == i) {
+                Some(&(_, tile)) => {
+                    let premium = self.board.premium(self.row, col);
+                    let value = tile.value() * premium.letter_multiplier();
+                    main += value;
+                    multiplier *= premium.word_multiplier();
+                    if let Some(score) = self.cross_checks[col].score {
+                        cross_words += (score + value) * premium.word_multiplier();
+                    }
+                }
+                None => main += self.board.tile(self.row, col).map_or(0, Tile::value),
+            }
+        }
+        let bingo = if self.placed.len() == RACK_SIZE {
+            BINGO_BONUS
+        } else {
+            0
+        };
+
+        // flip coordinates back for plays found on the transposed board
+        let at = |col: usize| match self.direction {
+            Direction::Across => (self.row, col),
+            Direction::Down => (col, self.row),
+        };
+        let (row, col) = at(start);
+        self.moves.push(Move {
+            row,
+            col,
+            direction: self.direction,
+            word: self.word.iter().copied().collect(),
+            placed: self
+                .placed
+                .iter()
+                .map(|&(i, tile)| {
+                    let (row, col) = at(start + i);
+                    (row, col, tile)
+                })
+                .collect(),
+            score: main * multiplier + cross_words + bingo,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NormalizedChar::*;
+
+    // Every run of two or more tiles in either direction is a word.
+    fn all_words_valid(board: &Board) -> bool {
+        let dict = dict();
+        [board.clone(), board.transposed()].iter().all(|b| {
+            (0..b.size()).all(|row| {
+                let line: String = (0..b.size())
+                    .map(|col| {
+                        b.tile(row, col)
+                            .map_or(' ', |t| (b'a' + t.letter as u8) as char)
+                    })
+                    .collect();
+                line.split(' ')
+                    .filter(|run| run.len() > 1)
+                    .all(|run| dict.find(&run.into()).is_some())
+            })
+        })
+    }
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec![
+            "act", "as", "at", "cat", "cats", "sat", "scat", "scats", "ta", "tas",
+        ])
+    }
+
+    #[test]
+    fn standard_board_premiums() {
+        let board = Board::standard();
+        assert_eq!(board.premium(0, 0), Premium::TripleWord);
+        assert_eq!(board.premium(7, 7), Premium::DoubleWord);
+        assert_eq!(board.premium(0, 11), Premium::DoubleLetter);
+        assert_eq!(board.premium(13, 9), Premium::TripleLetter);
+    }
+
+    #[test]
+    fn opening_moves_cover_the_centre() {
+        let moves = Board::standard().moves(&dict(), &"tac".into());
+
+        assert!(moves
+            .iter()
+            .all(|m| m.placed.iter().any(|&(r, c, _)| (r, c) == (7, 7))));
+        let best = &moves[0];
+        assert_eq!(best.word, "ACT".into());
+        assert_eq!(best.score, 10);
+        assert_eq!(
+            moves
+                .iter()
+                .filter(|m| m.direction == Direction::Down)
+                .count(),
+            moves
+                .iter()
+                .filter(|m| m.direction == Direction::Across)
+                .count()
+        );
+    }
+
+    #[test]
+    fn ranked_moves_value_the_leave() {
+        let mut board = Board::standard();
+        board.place_word(7, 6, Direction::Across, "cat");
+        let rack: Rack = "s?".into();
+
+        let ranked = board.ranked_moves(&dict(), &rack);
+
+        // keeping the blank beats the highest score
+        assert_eq!(ranked[0].leave, "?".into());
+        assert_eq!(ranked[0].play.word, "CATS".into());
+        assert!(ranked.windows(2).all(|w| w[0].equity >= w[1].equity));
+        assert!(ranked
+            .iter()
+            .all(|m| m.equity == m.play.score as f64 + leave_value(&m.leave)));
+        assert_eq!(ranked[0].play.leave(&"x".into()), None);
+    }
+
+    #[test]
+    fn removing_a_missing_tile_leaves_the_rack_alone() {
+        let mut rack: Rack = "a?".into();
+        let tile = |ch, blank| Tile {
+            letter: NormalizedChar::from_char(ch).unwrap(),
+            blank,
+        };
+
+        assert!(!rack.remove(tile('b', false)));
+        assert!(rack.remove(tile('b', true)));
+        assert!(!rack.remove(tile('c', true)));
+        assert!(rack.remove(tile('a', false)));
+        assert!(!rack.remove(tile('a', false)));
+        assert!(rack.is_empty());
+    }
+
+    #[test]
+    fn moves_respect_cross_checks() {
+        let mut board = Board::standard();
+        board.place_word(7, 6, Direction::Across, "cat");
+        let rack: Rack = "s?".into();
+
+        let moves = board.moves(&dict(), &rack);
+
+        // a blank A above an S that hooks SCAT: AS (1) + SCAT (6)
+        let best = &moves[0];
+        assert_eq!(
+            (best.row, best.col, best.direction),
+            (6, 5, Direction::Down)
+        );
+        assert_eq!(best.word, "AS".into());
+        assert_eq!(best.score, 7);
+        assert!(moves
+            .iter()
+            .any(|m| m.word == "CATS".into() && m.score == 6));
+        assert!(moves
+            .iter()
+            .any(|m| m.word == "SCATS".into() && m.score == 6));
+        for m in moves.iter() {
+            let mut after = board.clone();
+            after.play(m);
+            assert!(all_words_valid(&after), "{:?}", m);
+        }
+        // S below A spells AS downwards, and across is only the S
+        assert!(moves.iter().any(|m| {
+            m.word == "AS".into()
+                && m.placed
+                    == [(
+                        8,
+                        7,
+                        Tile {
+                            letter: S,
+                            blank: false,
+                        },
+                    )]
+        }));
+    }
+}
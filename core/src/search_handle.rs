@@ -0,0 +1,188 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cancel::CancellationToken;
+use crate::dictionary::ArcDictionary;
+use crate::normalized_word::NormalizedWord;
+use crate::query::DictQuery;
+
+/// A result sent from a search's worker thread, owning what it borrowed
+/// from the dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub normalized: NormalizedWord,
+    pub original: String,
+    pub score: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchState {
+    Running,
+    /// Every result has been found, though some may not have been polled.
+    Complete,
+    /// The search was cancelled before it finished.
+    Cancelled,
+}
+
+enum Message {
+    Result(SearchResult),
+    Done { cancelled: bool },
+}
+
+/// A query running on a worker thread, for frontends that must not block
+/// while searching, such as GUIs polling once a frame. Dropping the handle
+/// cancels the search.
+pub struct SearchHandle {
+    receiver: Receiver<Message>,
+    cancel: CancellationToken,
+    state: SearchState,
+    received: usize,
+    started: Instant,
+    elapsed: Option<Duration>,
+}
+
+impl SearchHandle {
+    /// Starts running `query` against `dict` on a new thread. A cancellable
+    /// query keeps its token, so the search can also be stopped through that.
+    pub fn spawn(dict: ArcDictionary, query: DictQuery) -> Self {
+        let cancel = query.cancellation().cloned().unwrap_or_default();
+        let query = query.cancellable(cancel.clone());
+        let (sender, receiver) = mpsc::channel();
+        let token = cancel.clone();
+        thread::spawn(move || {
+            for x in query.iter(&dict) {
+                let result = SearchResult {
+                    normalized: x.normalized,
+                    original: x.original.clone(),
+                    score: x.score,
+                };
+                // the handle was dropped, so nobody is listening
+                if sender.send(Message::Result(result)).is_err() {
+                    return;
+                }
+            }
+            let cancelled = token.is_cancelled();
+            let _ = sender.send(Message::Done { cancelled });
+        });
+        SearchHandle {
+            receiver,
+            cancel,
+            state: SearchState::Running,
+            received: 0,
+            started: Instant::now(),
+            elapsed: None,
+        }
+    }
+
+    /// Takes up to `max` of the results found so far without waiting.
+    pub fn poll(&mut self, max: usize) -> Vec<SearchResult> {
+        let mut batch = vec![];
+        while batch.len() < max {
+            match self.receiver.try_recv() {
+                Ok(Message::Result(result)) => batch.push(result),
+                Ok(Message::Done { cancelled }) => self.finish(cancelled),
+                Err(TryRecvError::Empty) => break,
+                // the worker panicked
+                Err(TryRecvError::Disconnected) => {
+                    self.finish(true);
+                    break;
+                }
+            }
+        }
+        self.received += batch.len();
+        batch
+    }
+
+    /// Blocks until the search ends, returning every result not yet polled.
+    pub fn wait(mut self) -> Vec<SearchResult> {
+        let mut rest = vec![];
+        while self.state == SearchState::Running {
+            match self.receiver.recv() {
+                Ok(Message::Result(result)) => rest.push(result),
+                Ok(Message::Done { cancelled }) => self.finish(cancelled),
+                Err(_) => self.finish(true),
+            }
+        }
+        rest
+    }
+
+    fn finish(&mut self, cancelled: bool) {
+        if self.state == SearchState::Running {
+            self.state = match cancelled {
+                true => SearchState::Cancelled,
+                false => SearchState::Complete,
+            };
+            self.elapsed = Some(self.started.elapsed());
+        }
+    }
+
+    /// Stops the search. Results already found can still be polled.
+    pub fn cancel(&self) {
+        self.cancel.cancel()
+    }
+
+    /// The state as of the last poll.
+    pub fn state(&self) -> SearchState {
+        self.state
+    }
+
+    /// Whether the search has ended and every result has been polled.
+    pub fn is_finished(&self) -> bool {
+        self.state != SearchState::Running
+    }
+
+    /// The number of results polled so far.
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// How long the search took, or has been running if not yet finished.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed.unwrap_or_else(|| self.started.elapsed())
+    }
+}
+
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        self.cancel.cancel()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+
+    fn dict() -> ArcDictionary {
+        Dictionary::from_iter(vec!["cat", "cot", "cut", "dog"]).into()
+    }
+
+    #[test]
+    fn polls_results_in_batches() {
+        let mut handle = SearchHandle::spawn(dict(), DictQuery::new().pattern("c?t"));
+
+        let mut found = vec![];
+        while !handle.is_finished() {
+            found.extend(handle.poll(2).into_iter().map(|x| x.original));
+        }
+
+        assert_eq!(found, ["cat", "cot", "cut"]);
+        assert_eq!(handle.state(), SearchState::Complete);
+        assert_eq!(handle.received(), 3);
+        assert!(handle.poll(10).is_empty());
+    }
+
+    #[test]
+    fn reports_cancellation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut handle = SearchHandle::spawn(dict(), DictQuery::new().cancellable(token));
+        while !handle.is_finished() {
+            handle.poll(usize::MAX);
+        }
+
+        assert_eq!(handle.state(), SearchState::Cancelled);
+        assert_eq!(handle.received(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::dictionary::{DictIterItem, Dictionary};
+use crate::fuzzy::FuzzyMatcher;
+use crate::normalized_word::NormalizedWord;
+use crate::phonetic::phonetic_code;
+
+/// Options for `Dictionary::suggest_with`: which words are candidates and
+/// how their signals are weighed against each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestOptions {
+    /// The most substitutions, insertions and deletions a correction may
+    /// need. Words sounding like the misspelling are candidates however
+    /// far they are.
+    pub max_distance: usize,
+    /// The weight of closeness in edits, from 1 for no edits to 0 for more
+    /// than `max_distance`.
+    pub distance_weight: f64,
+    /// The weight of sounding alike, 1 for sharing a `phonetic_code` and 0
+    /// otherwise.
+    pub phonetic_weight: f64,
+    /// The weight of commonness, by how a word's score ranks among the
+    /// dictionary's. Without scores it counts for nothing.
+    pub frequency_weight: f64,
+}
+
+impl Default for SuggestOptions {
+    fn default() -> Self {
+        SuggestOptions {
+            max_distance: 2,
+            distance_weight: 0.6,
+            phonetic_weight: 0.25,
+            frequency_weight: 0.15,
+        }
+    }
+}
+
+impl SuggestOptions {
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn with_weights(mut self, distance: f64, phonetic: f64, frequency: f64) -> Self {
+        self.distance_weight = distance;
+        self.phonetic_weight = phonetic;
+        self.frequency_weight = frequency;
+        self
+    }
+}
+
+/// Up to `k` corrections for `word`, best first, each with its weighted
+/// score: words within `max_distance` edits of it and words sounding like
+/// it, one entry per normalized word and none blocked. Ties are left in
+/// normalized order. A correctly spelled word is usually its own best
+/// suggestion.
+pub fn suggest<'a>(
+    dict: &'a Dictionary,
+    word: &str,
+    k: usize,
+    options: &SuggestOptions,
+) -> Vec<(DictIterItem<'a>, f64)> {
+    let normalized = NormalizedWord::from_str_safe(word);
+    if k == 0 || normalized.is_empty() {
+        return vec![];
+    }
+    let matcher = FuzzyMatcher::new(word, options.max_distance);
+    let code = phonetic_code(&normalized);
+
+    let mut candidates: Vec<DictIterItem> =
+        matcher.search(dict).into_iter().map(|(x, _)| x).collect();
+    for sounds_like in dict.sounds_like(&code) {
+        let entries = dict.find(sounds_like).into_iter().flatten();
+        candidates.extend(entries.map(|x| DictIterItem::from((sounds_like.clone(), x))));
+    }
+    // stable, so the first inserted entry of each word stays first
+    candidates.sort_by(|a, b| a.normalized.cmp(&b.normalized));
+    candidates.dedup_by(|a, b| a.normalized == b.normalized);
+    candidates.retain(|x| !dict.is_blocked(&x.normalized));
+
+    let max = options.max_distance as f64 + 1.0;
+    let mut scored: Vec<(DictIterItem, f64)> = candidates
+        .into_iter()
+        .map(|x| {
+            let closeness = matcher
+                .distance(&x.normalized)
+                .map_or(0.0, |d| 1.0 - d as f64 / max);
+            let alike = if phonetic_code(&x.normalized) == code {
+                1.0
+            } else {
+                0.0
+            };
+            let commonness = dict.score_rank(x.score).unwrap_or(0.0);
+            let score = options.distance_weight * closeness
+                + options.phonetic_weight * alike
+                + options.frequency_weight * commonness;
+            (x, score)
+        })
+        .collect();
+    scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scored.truncate(k);
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(found: &[(DictIterItem, f64)]) -> Vec<String> {
+        found.iter().map(|(x, _)| x.original.clone()).collect()
+    }
+
+    #[test]
+    fn ranks_corrections() {
+        let mut dict: Dictionary = Default::default();
+        for (word, score) in [
+            ("the", 90),
+            ("then", 60),
+            ("ten", 50),
+            ("tea", 40),
+            ("phonetic", 20),
+            ("frenetic", 10),
+            ("knight", 30),
+            ("night", 70),
+        ] {
+            dict.insert_scored(word, score);
+        }
+        dict.insert("The");
+        dict.insert("teh");
+        dict.block("teh");
+
+        // TEA sounds like TEH and is as close as TEN; THE takes two edits
+        assert_eq!(words(&dict.suggest("teh", 3)), ["tea", "ten", "the"]);
+        assert_eq!(words(&dict.suggest("fonetik", 2)), ["phonetic"]);
+        assert_eq!(words(&dict.suggest("nite", 2)), ["night", "knight"]);
+        assert_eq!(words(&dict.suggest("night", 1)), ["night"]);
+
+        // weighing commonness alone prefers the most frequent candidate
+        let options = SuggestOptions::default().with_weights(0.0, 0.0, 1.0);
+        assert_eq!(words(&dict.suggest_with("teh", 1, &options)), ["the"]);
+        let options = SuggestOptions::default()
+            .with_max_distance(1)
+            .with_weights(1.0, 0.0, 0.0);
+        assert_eq!(
+            words(&dict.suggest_with("tne", 2, &options)),
+            ["the", "ten"]
+        );
+
+        assert!(dict.suggest("teh", 0).is_empty());
+        assert!(dict.suggest("", 3).is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use rust_stemmers::{Algorithm, Stemmer};
+
+use crate::normalized_word::NormalizedWord;
+
+/// The English Porter stem of a word, after normalizing it.
+pub fn stem(word: &str) -> String {
+    let word = NormalizedWord::from_str_safe(word)
+        .to_string()
+        .to_lowercase();
+    Stemmer::create(Algorithm::English).stem(&word).into_owned()
+}
+
+/// Distinct words grouped by stem, for finding the inflections of a word
+/// without stemming every word again.
+#[derive(Debug, Default)]
+pub struct StemIndex {
+    by_stem: HashMap<String, Vec<NormalizedWord>>,
+}
+
+impl StemIndex {
+    /// Indexes `words`, which must be sorted and distinct.
+    pub fn build<'a, I: IntoIterator<Item = &'a NormalizedWord>>(words: I) -> Self {
+        let stemmer = Stemmer::create(Algorithm::English);
+        let mut by_stem: HashMap<String, Vec<NormalizedWord>> = HashMap::new();
+        for word in words {
+            let lowercase = word.to_string().to_lowercase();
+            by_stem
+                .entry(stemmer.stem(&lowercase).into_owned())
+                .or_default()
+                .push(word.clone());
+        }
+        StemIndex { by_stem }
+    }
+
+    /// The indexed words sharing a stem with `word`, in sorted order.
+    pub fn family(&self, word: &str) -> &[NormalizedWord] {
+        self.by_stem
+            .get(&stem(word))
+            .map_or(&[], |words| &words[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_words_by_stem() {
+        assert_eq!(stem("Running"), "run");
+
+        let words: Vec<NormalizedWord> = ["ran", "run", "running", "runs", "rung"]
+            .iter()
+            .map(|&x| x.into())
+            .collect();
+        let index = StemIndex::build(&words);
+
+        let family: Vec<String> = index.family("RUNS").iter().map(|x| x.to_string()).collect();
+        assert_eq!(family, ["RUN", "RUNNING", "RUNS"]);
+        assert!(index.family("walk").is_empty());
+    }
+}
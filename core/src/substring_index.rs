@@ -0,0 +1,89 @@
+use crate::normalized_word::{NormalizedWord, ALPHABET_SIZE};
+
+const GRAM: usize = 3;
+
+/// Trigram posting lists over a sorted list of distinct words, for finding
+/// words that contain a substring without scanning every word.
+#[derive(Debug, Default)]
+pub struct SubstringIndex {
+    words: Vec<NormalizedWord>,
+    postings: Vec<Vec<u32>>,
+}
+
+impl SubstringIndex {
+    /// Indexes `words`, which must be sorted and distinct.
+    pub fn build<'a, I: IntoIterator<Item = &'a NormalizedWord>>(words: I) -> Self {
+        let mut index = SubstringIndex {
+            words: vec![],
+            postings: vec![vec![]; ALPHABET_SIZE.pow(GRAM as u32)],
+        };
+        for word in words {
+            let id = index.words.len() as u32;
+            let mut grams: Vec<usize> = trigrams(word).collect();
+            grams.sort_unstable();
+            grams.dedup();
+            for gram in grams {
+                index.postings[gram].push(id);
+            }
+            index.words.push(word.clone());
+        }
+        index
+    }
+
+    /// Returns the indexed words containing `substring`, in sorted order, or
+    /// `None` when the substring is too short to narrow the search.
+    pub fn candidates(&self, substring: &NormalizedWord) -> Option<Vec<&NormalizedWord>> {
+        if substring.len() < GRAM {
+            return None;
+        }
+
+        let mut lists: Vec<&Vec<u32>> = trigrams(substring).map(|g| &self.postings[g]).collect();
+        lists.sort_by_key(|list| list.len());
+        let (shortest, rest) = lists.split_first()?;
+
+        let words = shortest
+            .iter()
+            .filter(|id| rest.iter().all(|list| list.binary_search(id).is_ok()))
+            .map(|&id| &self.words[id as usize])
+            .filter(|word| word.contains(substring))
+            .collect();
+        Some(words)
+    }
+}
+
+fn trigrams(word: &NormalizedWord) -> impl Iterator<Item = usize> + '_ {
+    let chars: Vec<usize> = word.iter_chars().map(|&ch| ch as usize).collect();
+    (0..chars.len().saturating_sub(GRAM - 1)).map(move |i| {
+        chars[i..i + GRAM]
+            .iter()
+            .fold(0, |acc, &ch| acc * ALPHABET_SIZE + ch)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_words_containing_substring() {
+        let words: Vec<NormalizedWord> = ["ACQUIRE", "QUACK", "QUEUE", "SQUAT", "SUQ"]
+            .iter()
+            .map(|&w| w.into())
+            .collect();
+        let index = SubstringIndex::build(&words);
+
+        let found = |s: &str| -> Vec<String> {
+            index
+                .candidates(&s.into())
+                .unwrap()
+                .iter()
+                .map(|w| w.to_string())
+                .collect()
+        };
+
+        assert_eq!(found("QUA"), vec!["QUACK", "SQUAT"]);
+        assert_eq!(found("QUEUE"), vec!["QUEUE"]);
+        assert!(found("QUU").is_empty());
+        assert_eq!(index.candidates(&"QU".into()), None);
+    }
+}
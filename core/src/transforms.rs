@@ -0,0 +1,308 @@
+use crate::char_map::CharMap;
+use crate::char_match::CharMatch;
+use crate::char_set::CharSet;
+use crate::dictionary::{Canonical, DictIterItem, DictSearch, Dictionary, WordPredicate};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::{TriePrefix, TrieSearch};
+
+/// A way of rewriting words, possibly in more than one way.
+pub trait Transform {
+    /// Every rewriting of `word`, which is the original spelling.
+    fn apply(&self, word: &str) -> Vec<String>;
+
+    /// For transforms rewriting letter for letter, the letters each letter
+    /// of `word` may become once its rewritings are read back as letters.
+    /// `survivors` searches the trie with these rather than rewriting.
+    fn letter_choices(&self, _word: &NormalizedWord) -> Option<Vec<CharSet>> {
+        None
+    }
+}
+
+/// A transform rewriting each letter as any of its alternatives. Letters
+/// without alternatives, and anything other than letters, are kept.
+#[derive(Debug, Clone, Default)]
+pub struct LetterMap {
+    alternatives: CharMap<Vec<String>>,
+    separator: String,
+}
+
+impl LetterMap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Rewrites `ch` as any of `alternatives`, which should include the
+    /// letter itself for it to be kept as an option.
+    pub fn with(mut self, ch: NormalizedChar, alternatives: &[&str]) -> Self {
+        self.alternatives
+            .set(ch, alternatives.iter().map(|x| x.to_string()).collect());
+        self
+    }
+
+    /// Puts `separator` between rewritten characters.
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Spells words out in the NATO phonetic alphabet.
+    pub fn nato() -> Self {
+        const WORDS: [&str; 26] = [
+            "Alfa", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India",
+            "Juliett", "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo",
+            "Sierra", "Tango", "Uniform", "Victor", "Whiskey", "X-ray", "Yankee", "Zulu",
+        ];
+        NormalizedChar::all()
+            .zip(WORDS)
+            .fold(LetterMap::new(), |map, (ch, word)| map.with(ch, &[word]))
+            .with_separator(" ")
+    }
+
+    /// Spells words out in the cells of uncontracted Braille.
+    pub fn braille() -> Self {
+        const CELLS: [&str; 26] = [
+            "⠁", "⠃", "⠉", "⠙", "⠑", "⠋", "⠛", "⠓", "⠊", "⠚", "⠅", "⠇", "⠍", "⠝", "⠕", "⠏", "⠟",
+            "⠗", "⠎", "⠞", "⠥", "⠧", "⠺", "⠭", "⠽", "⠵",
+        ];
+        NormalizedChar::all()
+            .zip(CELLS)
+            .fold(LetterMap::new(), |map, (ch, cell)| map.with(ch, &[cell]))
+    }
+
+    /// Leetspeak, with each letter kept or swapped for a look-alike.
+    pub fn leet() -> Self {
+        use NormalizedChar::*;
+        LetterMap::new()
+            .with(A, &["a", "4", "@"])
+            .with(B, &["b", "8"])
+            .with(E, &["e", "3"])
+            .with(G, &["g", "6", "9"])
+            .with(I, &["i", "1", "!"])
+            .with(L, &["l", "1"])
+            .with(O, &["o", "0"])
+            .with(S, &["s", "5", "$"])
+            .with(T, &["t", "7", "+"])
+            .with(Z, &["z", "2"])
+    }
+
+    /// Each letter kept or swapped for another letter written with the same
+    /// leetspeak character, as `1` stands for both I and L.
+    pub fn leet_lookalikes() -> Self {
+        let leet = LetterMap::leet();
+        let mut map = LetterMap::new();
+        for (ch, glyphs) in leet.alternatives.iter() {
+            let mut letters: Vec<String> = vec![ch.to_string().to_lowercase()];
+            for (other, other_glyphs) in leet.alternatives.iter() {
+                // each list starts with the letter itself
+                let shared = glyphs
+                    .iter()
+                    .skip(1)
+                    .any(|g| other_glyphs.iter().skip(1).any(|x| x == g));
+                if other != ch && shared {
+                    letters.push(other.to_string().to_lowercase());
+                }
+            }
+            map.alternatives.set(ch, letters);
+        }
+        map
+    }
+
+    // The letters an alternative reads as: itself if it is a letter, and
+    // otherwise every letter it is an alternative for, as `1` reads as I or L.
+    fn reads_as(&self, alternative: &str) -> CharSet {
+        let mut chars = alternative.chars();
+        match (
+            chars.next().and_then(NormalizedChar::from_char),
+            chars.next(),
+        ) {
+            (Some(letter), None) => CharSet::new().with(letter),
+            _ => self
+                .alternatives
+                .iter()
+                .filter(|(_, alternatives)| alternatives.iter().any(|x| x == alternative))
+                .map(|(ch, _)| ch)
+                .collect(),
+        }
+    }
+}
+
+impl Transform for LetterMap {
+    fn apply(&self, word: &str) -> Vec<String> {
+        let mut results = vec![String::new()];
+        for (i, ch) in word.chars().enumerate() {
+            let alternatives = match NormalizedChar::from_char(ch) {
+                Some(letter) if !self.alternatives.get(letter).is_empty() => {
+                    self.alternatives.get(letter).clone()
+                }
+                _ => vec![ch.to_string()],
+            };
+            let separator = if i == 0 { "" } else { &self.separator[..] };
+            results = results
+                .iter()
+                .flat_map(|done| {
+                    alternatives
+                        .iter()
+                        .map(move |alt| format!("{}{}{}", done, separator, alt))
+                })
+                .collect();
+        }
+        results
+    }
+
+    /// Letter for letter only when there is no separator and every
+    /// alternative is a single character.
+    fn letter_choices(&self, word: &NormalizedWord) -> Option<Vec<CharSet>> {
+        let mut alternatives = self.alternatives.iter_values().flatten();
+        if !self.separator.is_empty() || alternatives.any(|x| x.chars().count() != 1) {
+            return None;
+        }
+        let choices = word
+            .iter_chars()
+            .map(|&ch| match &self.alternatives.get(ch)[..] {
+                [] => CharSet::new().with(ch),
+                alternatives => alternatives
+                    .iter()
+                    .fold(CharSet::new(), |set, x| set.union(self.reads_as(x))),
+            });
+        Some(choices.collect())
+    }
+}
+
+/// Pig Latin: each word's leading consonants move to the end followed by
+/// "ay", and words starting with a vowel gain "way".
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PigLatin;
+
+impl Transform for PigLatin {
+    fn apply(&self, word: &str) -> Vec<String> {
+        let words: Vec<String> = word.split(' ').map(pig_latin).collect();
+        vec![words.join(" ")]
+    }
+}
+
+fn pig_latin(word: &str) -> String {
+    let is_vowel = |ch: char| "aeiouAEIOU".contains(ch);
+    let mut split = word.find(is_vowel).unwrap_or(word.len());
+    if split == 0 {
+        return format!("{}way", word);
+    }
+    // "qu" moves together, as in "quiet" to "ietquay"
+    if word[..split].ends_with(['q', 'Q']) && word[split..].starts_with(['u', 'U']) {
+        split += 1;
+    }
+    format!("{}{}ay", &word[split..], &word[..split])
+}
+
+/// Words with a rewriting that is a different word of the dictionary, once
+/// normalized, paired with that rewriting. Transforms with
+/// `letter_choices` are searched in the trie, position by position, so
+/// leetspeak's `1` finds words with I or L there. Otherwise rewritings
+/// containing anything but letters, spaces and punctuation are skipped.
+pub fn survivors<'a, T: Transform>(
+    dict: &'a Dictionary,
+    transform: &T,
+) -> Vec<(DictIterItem<'a>, String)> {
+    let mut results = vec![];
+    let mut last = None;
+    for item in dict.iter() {
+        if last.as_ref() == Some(&item.normalized) {
+            continue;
+        }
+        last = Some(item.normalized.clone());
+        if let Some(choices) = transform.letter_choices(&item.normalized) {
+            let len = choices.len();
+            let chars = choices.into_iter().map(|x| CharMatch::Not(x.complement()));
+            let search = DictSearch::new(
+                Some(TrieSearch::new(TriePrefix::new(chars.collect()), Some(len))),
+                WordPredicate::None,
+            )
+            .with_dedupe(Canonical::First);
+            for found in dict.iter_search(search) {
+                if found.normalized != item.normalized {
+                    results.push((item.clone(), found.original.clone()));
+                }
+            }
+            continue;
+        }
+        for rewriting in transform.apply(item.original) {
+            if rewriting
+                .chars()
+                .any(|ch| ch.is_numeric() || "@$+!".contains(ch))
+            {
+                continue;
+            }
+            let normalized = NormalizedWord::from_str_safe(&rewriting);
+            if normalized != item.normalized && dict.find(&normalized).is_some() {
+                results.push((item.clone(), rewriting));
+            }
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_letters() {
+        assert_eq!(LetterMap::nato().apply("Hi!"), ["Hotel India !"]);
+        assert_eq!(
+            LetterMap::leet().apply("at"),
+            ["at", "a7", "a+", "4t", "47", "4+", "@t", "@7", "@+"]
+        );
+        assert_eq!(
+            LetterMap::leet_lookalikes().apply("lit"),
+            ["lit", "llt", "iit", "ilt"]
+        );
+        assert_eq!(LetterMap::braille().apply("cab"), ["⠉⠁⠃"]);
+    }
+
+    #[test]
+    fn reads_rewritings_back_as_letters() {
+        let word = NormalizedWord::from("lit");
+        let sets = |map: LetterMap| map.letter_choices(&word).unwrap();
+
+        assert_eq!(
+            sets(LetterMap::leet()),
+            [CharSet::from("il"), CharSet::from("il"), CharSet::from("t")]
+        );
+        assert_eq!(sets(LetterMap::leet()), sets(LetterMap::leet_lookalikes()));
+        assert_eq!(
+            sets(LetterMap::braille()),
+            [CharSet::from("l"), CharSet::from("i"), CharSet::from("t")]
+        );
+        assert_eq!(LetterMap::nato().letter_choices(&word), None);
+    }
+
+    #[test]
+    fn speaks_pig_latin() {
+        assert_eq!(
+            PigLatin.apply("string quiet egg"),
+            ["ingstray ietquay eggway"]
+        );
+    }
+
+    #[test]
+    fn finds_words_surviving_a_transform() {
+        let dict = Dictionary::from_iter(vec!["ill", "lil", "sill", "tin"]);
+
+        let survivors: Vec<(String, String)> = survivors(&dict, &LetterMap::leet_lookalikes())
+            .into_iter()
+            .map(|(item, rewriting)| (item.original.clone(), rewriting))
+            .collect();
+
+        assert_eq!(
+            survivors,
+            [
+                ("ill".to_string(), "lil".to_string()),
+                ("lil".to_string(), "ill".to_string())
+            ]
+        );
+        assert_eq!(
+            survivors.len(),
+            super::survivors(&dict, &LetterMap::leet()).len()
+        );
+        assert!(super::survivors(&dict, &LetterMap::braille()).is_empty());
+    }
+}
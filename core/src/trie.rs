@@ -1,15 +1,40 @@
+use crate::cancel::CancellationToken;
 use crate::char_map::CharMap;
-use crate::char_match::CharMatch;
+use crate::char_match::{CharMatch, UnknownSearchChar};
+use crate::char_set::CharSet;
 use crate::normalized_word::*;
-use std::collections::VecDeque;
+use smallvec::SmallVec;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
+use std::iter::Peekable;
 use std::ops::RangeInclusive;
 
-#[derive(Debug, PartialEq)]
+/// A map from words to values, any number per word, supporting pattern
+/// searches and best-first completion. `Dictionary` is built on one, but it
+/// works as well for indexes of your own.
+///
+/// ```
+/// use wordplay_core::trie::Trie;
+///
+/// let mut rhymes: Trie<&str> = Default::default();
+/// rhymes.add_string("OW", "cow");
+/// rhymes.entry(&"OW".into()).push("now");
+/// rhymes.entry(&"EE".into()).or_insert("tree");
+///
+/// assert_eq!(rhymes.get(&"OW".into()), Some(&vec!["cow", "now"]));
+/// assert_eq!(rhymes.len(), 3);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
 pub struct Trie<T> {
     children: CharMap<Option<Box<Trie<T>>>>,
     terminals: Vec<T>,
+    count: usize,
+    best_score: Option<u32>,
+    min_len: usize,
 }
 
 impl<T> Trie<T> {
@@ -29,19 +54,249 @@ impl<T> Trie<T> {
     }
 
     pub fn add(&mut self, key: &NormalizedWord, value: T) {
+        self.add_scored(key, value, None)
+    }
+
+    /// Adds a value along with a score, which is cached on every node along
+    /// the key's path so that `iter_best_first` can skip weaker subtrees.
+    pub fn add_scored(&mut self, key: &NormalizedWord, value: T, score: Option<u32>) {
+        let len = key.len();
         let mut node: &mut Trie<T> = self;
+        node.record(len, score);
         for &ch in key.iter_chars() {
             node = node.get_or_create_mut(ch);
+            node.record(len, score);
         }
 
         node.terminals.push(value)
     }
 
+    fn record(&mut self, len: usize, score: Option<u32>) {
+        self.count += 1;
+        self.best_score = self.best_score.max(score);
+        self.min_len = self.min_len.min(len);
+    }
+
     pub fn add_string(&mut self, str: &str, value: T) {
         self.add(&NormalizedWord::from_str_safe(str), value)
     }
 
+    /// Returns the values stored under `key`, or `None` when no key ends
+    /// there, including when `key` is only a prefix of other keys.
     pub fn get(&self, key: &NormalizedWord) -> Option<&Vec<T>> {
+        self.get_node(key)
+            .map(|node| &node.terminals)
+            .filter(|terminals| !terminals.is_empty())
+    }
+
+    /// The values stored under `key`, which may be changed in place.
+    pub fn get_mut(&mut self, key: &NormalizedWord) -> Option<&mut [T]> {
+        self.get_node_mut(key)
+            .map(|node| &mut node.terminals[..])
+            .filter(|terminals| !terminals.is_empty())
+    }
+
+    pub fn contains_key(&self, key: &NormalizedWord) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The values under `key`, for adding to or filling in if absent.
+    pub fn entry(&mut self, key: &NormalizedWord) -> TrieEntry<'_, T> {
+        TrieEntry {
+            trie: self,
+            key: key.clone(),
+        }
+    }
+
+    /// The number of values, counting each value stored under a key.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Each key once, in lexicographic order.
+    pub fn keys(&self) -> impl Iterator<Item = NormalizedWord> + '_ {
+        self.nodes()
+            .filter(|(_, node)| node.is_terminal())
+            .map(|(word, _)| word)
+    }
+
+    /// Every value, in lexicographic key order.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.nodes().flat_map(|(_, node)| node.terminals.iter())
+    }
+
+    /// Keeps only the values for which `keep` returns true, removing nodes
+    /// left without any.
+    pub fn retain<F: FnMut(&NormalizedWord, &T) -> bool>(&mut self, mut keep: F) {
+        self.retain_from(&mut Default::default(), &mut keep);
+    }
+
+    fn retain_from<F>(&mut self, word: &mut NormalizedWord, keep: &mut F)
+    where
+        F: FnMut(&NormalizedWord, &T) -> bool,
+    {
+        self.terminals.retain(|value| keep(word, value));
+        self.count = self.terminals.len();
+        self.min_len = if self.is_terminal() {
+            word.len()
+        } else {
+            usize::MAX
+        };
+        for (ch, relation) in self.children.iter_mut() {
+            if let Some(child) = relation {
+                word.push(ch);
+                child.retain_from(word, keep);
+                word.pop();
+                if child.is_empty() {
+                    *relation = None;
+                    continue;
+                }
+                self.count += child.count;
+                self.min_len = self.min_len.min(child.min_len);
+            }
+        }
+        // scores aren't stored with values, so best_score stays as an upper
+        // bound, which best-first iteration tolerates
+        if self.is_empty() {
+            self.best_score = None;
+        }
+    }
+
+    // Every node in pre-order, which is lexicographic order of their paths.
+    fn nodes(&self) -> impl Iterator<Item = (NormalizedWord, &Trie<T>)> {
+        let mut stack = vec![(NormalizedWord::default(), self)];
+        std::iter::from_fn(move || {
+            let (word, node) = stack.pop()?;
+            stack.extend(node.children.iter().rev().filter_map(|(ch, child)| {
+                let mut child_word = word.clone();
+                child_word.push(ch);
+                Some((child_word, child.as_deref()?))
+            }));
+            Some((word, node))
+        })
+    }
+
+    /// The subtrie of keys continuing with `ch`, for walking the trie one
+    /// letter at a time.
+    pub fn child(&self, ch: NormalizedChar) -> Option<&Trie<T>> {
+        self.children.get(ch).as_deref()
+    }
+
+    /// Whether a key ends at this node.
+    pub fn is_terminal(&self) -> bool {
+        !self.terminals.is_empty()
+    }
+
+    /// The values stored under exactly this node's key.
+    pub fn terminals(&self) -> &[T] {
+        &self.terminals
+    }
+
+    /// Checks the counts, lengths and scores cached on each node against
+    /// the nodes below it, returning the first inconsistency in key order.
+    pub fn validate(&self) -> Result<(), TrieIssue> {
+        let mut seen = HashSet::new();
+        self.validate_from(&mut NormalizedWord::default(), &mut seen)
+    }
+
+    fn validate_from(
+        &self,
+        word: &mut NormalizedWord,
+        seen: &mut HashSet<*const Trie<T>>,
+    ) -> Result<(), TrieIssue> {
+        // owned boxes can't form cycles, but an arena of indexes could
+        if !seen.insert(self) {
+            return Err(TrieIssue::Cycle(word.clone()));
+        }
+        if !word.is_empty() && self.is_empty() {
+            return Err(TrieIssue::EmptyNode(word.clone()));
+        }
+        let mut count = self.terminals.len();
+        let mut min_len = if self.is_terminal() {
+            word.len()
+        } else {
+            usize::MAX
+        };
+        for (ch, child) in self.children.iter() {
+            let Some(child) = child else { continue };
+            word.push(ch);
+            child.validate_from(word, seen)?;
+            if child.best_score > self.best_score {
+                return Err(TrieIssue::ScoreBelowChild(word.clone()));
+            }
+            word.pop();
+            count += child.count;
+            min_len = min_len.min(child.min_len);
+        }
+        if count != self.count {
+            return Err(TrieIssue::CountMismatch {
+                key: word.clone(),
+                cached: self.count,
+                actual: count,
+            });
+        }
+        if min_len != self.min_len {
+            return Err(TrieIssue::MinLenMismatch {
+                key: word.clone(),
+                cached: self.min_len,
+                actual: min_len,
+            });
+        }
+        Ok(())
+    }
+
+    /// A Graphviz digraph of the nodes down to `max_depth` letters, each
+    /// labelled with its count of values. Nodes where keys end are double
+    /// circles, and nodes with children left out are dashed.
+    pub fn to_dot(&self, max_depth: usize) -> String {
+        let mut dot = String::from("digraph trie {\n");
+        let mut next_id = 0;
+        self.write_dot(&mut dot, &mut next_id, max_depth);
+        dot.push_str("}\n");
+        dot
+    }
+
+    // Writes this node and those below it, returning this node's id.
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize, depth_left: usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let shape = if self.is_terminal() {
+            "doublecircle"
+        } else {
+            "circle"
+        };
+        let cut = depth_left == 0 && self.children.iter().any(|(_, x)| x.is_some());
+        let style = if cut { ", style=dashed" } else { "" };
+        writeln!(
+            dot,
+            "  n{} [label=\"{}\", shape={}{}];",
+            id, self.count, shape, style
+        )
+        .unwrap();
+        if depth_left == 0 {
+            return id;
+        }
+        for (ch, child) in self.children.iter() {
+            let Some(child) = child else { continue };
+            let child_id = child.write_dot(dot, next_id, depth_left - 1);
+            writeln!(dot, "  n{} -> n{} [label=\"{}\"];", id, child_id, ch).unwrap();
+        }
+        id
+    }
+
+    fn get_node_mut(&mut self, key: &NormalizedWord) -> Option<&mut Trie<T>> {
+        let mut node: &mut Trie<T> = self;
+        for &ch in key.iter_chars() {
+            node = node.children.get_mut(ch).as_deref_mut()?;
+        }
+        Some(node)
+    }
+
+    fn get_node(&self, key: &NormalizedWord) -> Option<&Trie<T>> {
         let mut node: &Trie<T> = self;
         for &ch in key.iter_chars() {
             let child = node.children.get(ch);
@@ -51,24 +306,171 @@ impl<T> Trie<T> {
             }
         }
 
-        Some(&node.terminals)
+        Some(node)
     }
 
+    /// Iterates over every key and value in lexicographic key order.
+    ///
+    /// ```
+    /// use wordplay_core::trie::Trie;
+    ///
+    /// let trie: Trie<_> = vec![("BA", 1), ("A", 2), ("AB", 3)].into_iter().collect();
+    /// let keys: Vec<_> = trie.iter().map(|(k, _)| k).collect();
+    ///
+    /// assert_eq!(keys, ["A".into(), "AB".into(), "BA".into()]);
+    /// ```
     pub fn iter(&self) -> impl Iterator<Item = (NormalizedWord, &T)> {
         TrieIter::new(self, Default::default())
     }
 
-    pub fn iter_range(&self, range: RangeInclusive<usize>) -> TrieIter<T> {
-        let search = TrieSearch {
-            prefix: TriePrefix::any_with_length(*range.start()),
-            max_depth: Some(*range.end()),
-        };
+    pub fn iter_range(&self, range: RangeInclusive<usize>) -> TrieIter<'_, T> {
+        let search = TrieSearch::new(
+            TriePrefix::any_with_length(*range.start()),
+            Some(*range.end()),
+        );
         TrieIter::new(self, search)
     }
 
-    pub fn iter_search(&self, search: TrieSearch) -> TrieIter<T> {
+    /// Iterates over matching keys and values in the search's `TraversalOrder`,
+    /// which is lexicographic key order unless otherwise specified.
+    pub fn iter_search(&self, search: TrieSearch) -> TrieIter<'_, T> {
         TrieIter::new(self, search)
     }
+
+    /// Iterates over keys of exactly the pattern's length whose letters each
+    /// match it, in lexicographic order. Faster than `iter_search` for the
+    /// many small searches of a grid filler, as it allocates nothing for
+    /// keys of up to 24 letters besides the keys it yields.
+    pub fn iter_exact(&self, pattern: &[CharMatch]) -> ExactIter<'_, T> {
+        let mut iter = ExactIter {
+            pattern: SmallVec::from_slice(pattern),
+            stack: SmallVec::new(),
+            word: Default::default(),
+            terminals: [].iter(),
+        };
+        if pattern.is_empty() {
+            iter.terminals = self.terminals.iter();
+        } else if self.min_len <= pattern.len() {
+            iter.stack.push((self, 0));
+        }
+        iter
+    }
+
+    /// Iterates over keys starting with `prefix`, highest score first, then
+    /// shortest first, then in lexicographic order. `score_of` must agree with
+    /// the scores given to `add_scored`.
+    pub fn iter_best_first<F>(
+        &self,
+        prefix: &NormalizedWord,
+        score_of: F,
+    ) -> BestFirstIter<'_, T, F>
+    where
+        F: Fn(&T) -> Option<u32>,
+    {
+        let mut heap = BinaryHeap::new();
+        if let Some(node) = self.get_node(prefix) {
+            if node.count > 0 {
+                heap.push(BestFirstEntry {
+                    rank: node.rank(prefix),
+                    word: prefix.clone(),
+                    kind: BestFirstKind::Node(node),
+                });
+            }
+        }
+        BestFirstIter { heap, score_of }
+    }
+
+    fn rank(&self, word: &NormalizedWord) -> BestFirstRank {
+        (
+            self.best_score,
+            Reverse(self.min_len),
+            Reverse(word.clone()),
+        )
+    }
+
+    /// Counts the values matching a search without building their keys.
+    /// Subtrees past the prefix with no depth limit are counted in O(1).
+    pub fn count_search(&self, search: &TrieSearch) -> usize {
+        let mut total = 0;
+        self.walk_search(search, |node, whole_subtree| {
+            total += if whole_subtree {
+                node.count
+            } else {
+                node.terminals.len()
+            };
+            true
+        });
+        total
+    }
+
+    /// Returns whether any value matches a search, stopping at the first one.
+    pub fn exists_search(&self, search: &TrieSearch) -> bool {
+        let mut found = false;
+        self.walk_search(search, |node, whole_subtree| {
+            found = if whole_subtree {
+                node.count > 0
+            } else {
+                !node.terminals.is_empty()
+            };
+            !found
+        });
+        found
+    }
+
+    // Visits every node whose terminals match the search. `whole_subtree` is
+    // set when every descendant matches too, in which case the walk does not
+    // descend further. The visitor returns false to stop the walk.
+    fn walk_search<F: FnMut(&Trie<T>, bool) -> bool>(&self, search: &TrieSearch, mut visit: F) {
+        let prefix_len = search.prefix_len();
+        let mut stack: Vec<(usize, &Trie<T>, CharSet, CharSet)> = vec![];
+        if search.can_complete(0, search.containing, CharSet::new()) {
+            stack.push((0, self, search.containing, CharSet::new()));
+        }
+        while let Some((depth, node, missing, seen)) = stack.pop() {
+            if search.is_cancelled() {
+                return;
+            }
+            if prefix_len <= depth && missing.is_empty() && search.has_distinct_letters(seen) {
+                let whole_subtree = search.max_depth.is_none() && search.distinct_letters.is_none();
+                if !visit(node, whole_subtree) {
+                    return;
+                }
+                if whole_subtree {
+                    continue;
+                }
+            }
+
+            if search.below_max(depth) {
+                let char_restriction = search.get_char_restriction(depth);
+                stack.extend(
+                    node.children
+                        .iter()
+                        .filter(|(ch, _)| char_restriction.matches(ch))
+                        .filter_map(|(ch, child)| {
+                            Some((child.as_deref()?, missing.without(ch), seen.with(ch)))
+                        })
+                        .filter(|&(_, missing, seen)| search.can_complete(depth + 1, missing, seen))
+                        .map(|(child, missing, seen)| (depth + 1, child, missing, seen)),
+                );
+            }
+        }
+    }
+
+    /// Iterates over matching keys and values in no particular order.
+    pub fn iter_search_unordered(&self, search: TrieSearch) -> TrieIter<'_, T> {
+        TrieIter::new(self, search.with_order(TraversalOrder::Unordered))
+    }
+
+    /// Iterates over keys and values matching any of `searches`, each once,
+    /// in lexicographic key order. The searches must all be depth-first.
+    pub fn iter_union(&self, searches: Vec<TrieSearch>) -> UnionIter<'_, T> {
+        UnionIter {
+            iters: searches
+                .into_iter()
+                .map(|x| self.iter_search(x).peekable())
+                .collect(),
+        }
+    }
 }
 
 impl<'a, T> Extend<(&'a NormalizedWord, T)> for Trie<T> {
@@ -108,38 +510,197 @@ impl<T> Default for Trie<T> {
         Trie {
             children: Default::default(),
             terminals: Default::default(),
+            count: 0,
+            best_score: None,
+            min_len: usize::MAX,
         }
     }
 }
 
+/// A node of a `Trie` whose cached facts disagree with the nodes below it,
+/// found by `Trie::validate`. Each names the node by its key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieIssue {
+    /// The node was reached twice.
+    Cycle(NormalizedWord),
+    /// A node other than the root without any values below it.
+    EmptyNode(NormalizedWord),
+    CountMismatch {
+        key: NormalizedWord,
+        cached: usize,
+        actual: usize,
+    },
+    /// The cached length of the shortest key below the node is wrong.
+    MinLenMismatch {
+        key: NormalizedWord,
+        cached: usize,
+        actual: usize,
+    },
+    /// A child's best score is above its parent's, which best-first
+    /// iteration takes as an upper bound.
+    ScoreBelowChild(NormalizedWord),
+}
+
+impl fmt::Display for TrieIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrieIssue::Cycle(key) => write!(f, "node '{}' reached twice", key),
+            TrieIssue::EmptyNode(key) => write!(f, "node '{}' has no values", key),
+            TrieIssue::CountMismatch {
+                key,
+                cached,
+                actual,
+            } => write!(
+                f,
+                "node '{}' caches a count of {} but holds {}",
+                key, cached, actual
+            ),
+            TrieIssue::MinLenMismatch {
+                key,
+                cached,
+                actual,
+            } => write!(
+                f,
+                "node '{}' caches a shortest key of {} letters but it has {}",
+                key, cached, actual
+            ),
+            TrieIssue::ScoreBelowChild(key) => {
+                write!(f, "node '{}' scores above its parent", key)
+            }
+        }
+    }
+}
+
+/// The values under one key of a `Trie`, from `Trie::entry`.
+pub struct TrieEntry<'a, T> {
+    trie: &'a mut Trie<T>,
+    key: NormalizedWord,
+}
+
+impl<'a, T> TrieEntry<'a, T> {
+    pub fn key(&self) -> &NormalizedWord {
+        &self.key
+    }
+
+    /// Adds a value after any already under the key, returning it.
+    pub fn push(self, value: T) -> &'a mut T {
+        self.trie.add(&self.key, value);
+        self.trie
+            .get_node_mut(&self.key)
+            .and_then(|node| node.terminals.last_mut())
+            .unwrap()
+    }
+
+    /// The values under the key, first adding `value` if there are none.
+    pub fn or_insert(self, value: T) -> &'a mut [T] {
+        self.or_insert_with(|| value)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut [T] {
+        if !self.trie.contains_key(&self.key) {
+            self.trie.add(&self.key, default());
+        }
+        self.trie.get_mut(&self.key).unwrap()
+    }
+}
+
+impl<'a, T: Default> TrieEntry<'a, T> {
+    pub fn or_default(self) -> &'a mut [T] {
+        self.or_insert_with(Default::default)
+    }
+}
+
+/// One square of a pattern: a single letter, or a crossword rebus square
+/// holding several.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PrefixElement {
+    Char(CharMatch),
+    Rebus(NormalizedWord),
+}
+
 #[derive(Debug, PartialEq, Default, Clone)]
 pub struct TriePrefix {
+    // what each letter must match, with rebus squares spelled out so that
+    // they take one trie edge per letter
     chars: Vec<CharMatch>,
+    elements: Vec<PrefixElement>,
 }
 
 impl TriePrefix {
     pub fn new(chars: Vec<CharMatch>) -> Self {
-        Self { chars }
+        let elements = chars.iter().map(|&x| PrefixElement::Char(x)).collect();
+        Self { chars, elements }
+    }
+
+    pub fn from_elements(elements: Vec<PrefixElement>) -> Self {
+        let chars = elements
+            .iter()
+            .flat_map(|x| match x {
+                PrefixElement::Char(ch) => vec![*ch],
+                PrefixElement::Rebus(letters) => {
+                    letters.iter_chars().map(|&x| CharMatch::Only(x)).collect()
+                }
+            })
+            .collect();
+        Self { chars, elements }
     }
 
     pub fn any_with_length(len: usize) -> Self {
-        Self {
-            chars: vec![CharMatch::Any; len],
-        }
+        Self::new(vec![CharMatch::Any; len])
     }
 
+    /// The number of letters matched, counting every letter of a rebus.
     pub fn len(&self) -> usize {
         self.chars.len()
     }
 
+    /// What each letter must match, with rebus squares spelled out.
+    pub fn chars(&self) -> &[CharMatch] {
+        &self.chars
+    }
+
+    pub fn elements(&self) -> &[PrefixElement] {
+        &self.elements
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    pub fn from_pattern(str: &str) -> Self {
-        TriePrefix {
-            chars: str.chars().map(CharMatch::from).collect(),
+    /// Parses a pattern of letters and `?` wildcards, with the letters of a
+    /// rebus square in brackets, as `c[heart]d`.
+    pub fn from_pattern(str: &str) -> Result<Self, PatternError> {
+        let error = |position, kind| Err(PatternError { position, kind });
+        let mut elements = vec![];
+        let mut chars = str.chars().enumerate();
+        while let Some((i, ch)) = chars.next() {
+            if ch != '[' {
+                match CharMatch::try_from(ch) {
+                    Ok(x) => elements.push(PrefixElement::Char(x)),
+                    Err(UnknownSearchChar(ch)) => {
+                        return error(i, PatternErrorKind::UnknownChar(ch))
+                    }
+                }
+                continue;
+            }
+            let mut letters = NormalizedWord::default();
+            loop {
+                match chars.next() {
+                    Some((_, ']')) if !letters.is_empty() => break,
+                    Some((j, ']')) => return error(j, PatternErrorKind::EmptyRebus),
+                    Some((j, ch)) => match NormalizedChar::from_char(ch) {
+                        Some(x) => letters.push(x),
+                        None if CharMatch::try_from(ch).is_ok() => {
+                            return error(j, PatternErrorKind::WildcardInRebus(ch))
+                        }
+                        None => return error(j, PatternErrorKind::UnknownChar(ch)),
+                    },
+                    None => return error(i, PatternErrorKind::UnclosedRebus),
+                }
+            }
+            elements.push(PrefixElement::Rebus(letters));
         }
+        Ok(TriePrefix::from_elements(elements))
     }
 
     pub fn get_char_restriction(&self, depth: usize) -> CharMatch {
@@ -149,30 +710,126 @@ impl TriePrefix {
             CharMatch::Any
         }
     }
+
+    /// Whether `word` starts with letters matching the prefix.
+    pub fn is_prefix_of(&self, word: &NormalizedWord) -> bool {
+        word.len() >= self.len()
+            && self
+                .chars
+                .iter()
+                .zip(word.iter_chars())
+                .all(|(x, ch)| x.matches(ch))
+    }
+}
+
+impl fmt::Display for TriePrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&pattern(&self.elements))
+    }
+}
+
+/// Why a pattern could not be parsed, and where.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PatternError {
+    /// The offending character's index among the pattern's characters.
+    pub position: usize,
+    pub kind: PatternErrorKind,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PatternErrorKind {
+    /// Neither a letter nor a wildcard.
+    UnknownChar(char),
+    /// A rebus square opened with `[` and never closed.
+    UnclosedRebus,
+    /// A rebus square with no letters, as `[]`.
+    EmptyRebus,
+    /// A wildcard in a rebus square, which holds letters only.
+    WildcardInRebus(char),
+}
+
+impl PatternError {
+    /// The nearest valid syntax to what was probably meant.
+    pub fn suggestion(&self) -> &'static str {
+        match self.kind {
+            PatternErrorKind::UnknownChar('*' | '_' | '-' | '#') => "use ? for any one letter",
+            PatternErrorKind::UnknownChar('(' | ')' | '{' | '}') => {
+                "write a rebus square in square brackets, as c[heart]d"
+            }
+            PatternErrorKind::UnknownChar(']') => "open the rebus square with [",
+            PatternErrorKind::UnknownChar(_) => "use only letters and ? for any one letter",
+            PatternErrorKind::UnclosedRebus => "close the rebus square with ]",
+            PatternErrorKind::EmptyRebus => "put the square's letters in the brackets, as [heart]",
+            PatternErrorKind::WildcardInRebus(_) => "put wildcards outside the rebus square",
+        }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = self.position + 1;
+        match self.kind {
+            PatternErrorKind::UnknownChar(ch) => {
+                write!(f, "unexpected '{}' at character {}", ch, position)?
+            }
+            PatternErrorKind::UnclosedRebus => write!(f, "unclosed '[' at character {}", position)?,
+            PatternErrorKind::EmptyRebus => {
+                write!(f, "empty rebus square ending at character {}", position)?
+            }
+            PatternErrorKind::WildcardInRebus(ch) => write!(
+                f,
+                "wildcard '{}' in a rebus square at character {}",
+                ch, position
+            )?,
+        }
+        write!(f, "; {}", self.suggestion())
+    }
+}
+
+/// Order in which matching words are yielded by a `TrieIter`.
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub enum TraversalOrder {
+    /// Pre-order depth-first: words are yielded in lexicographic order,
+    /// with duplicate keys in insertion order.
+    #[default]
+    DepthFirst,
+    /// Breadth-first: shorter words are yielded before longer ones, and
+    /// words of the same length are yielded in lexicographic order.
+    BreadthFirst,
+    /// Whatever order is cheapest to produce; may change between versions.
+    Unordered,
 }
 
 #[derive(Debug, PartialEq, Default, Clone)]
 pub struct TrieSearch {
     prefix: TriePrefix,
     max_depth: Option<usize>,
+    order: TraversalOrder,
+    containing: CharSet,
+    distinct_letters: Option<RangeInclusive<u8>>,
+    cancel: Option<CancellationToken>,
 }
 
 impl TrieSearch {
     pub fn new(prefix: TriePrefix, max_depth: Option<usize>) -> Self {
-        Self { prefix, max_depth }
+        Self {
+            prefix,
+            max_depth,
+            ..Default::default()
+        }
     }
 
-    pub fn from_prefix(str: &str) -> Self {
-        TrieSearch {
-            prefix: TriePrefix::from_pattern(str),
+    pub fn from_prefix(str: &str) -> Result<Self, PatternError> {
+        Ok(TrieSearch {
+            prefix: TriePrefix::from_pattern(str)?,
             ..Default::default()
-        }
+        })
     }
 
-    pub fn exactly(str: &str) -> Self {
-        let search = TrieSearch::from_prefix(str);
+    pub fn exactly(str: &str) -> Result<Self, PatternError> {
+        let search = TrieSearch::from_prefix(str)?;
         let len = search.prefix.len();
-        search.with_max(len)
+        Ok(search.with_max(len))
     }
 
     pub fn with_max(&self, max: usize) -> Self {
@@ -182,8 +839,151 @@ impl TrieSearch {
         }
     }
 
+    pub fn with_order(&self, order: TraversalOrder) -> Self {
+        TrieSearch {
+            order,
+            ..self.clone()
+        }
+    }
+
+    /// Only matches keys containing each of `letters` somewhere. Branches
+    /// are pruned once too few letters remain before the maximum depth to
+    /// fit the letters not yet seen.
+    pub fn containing(&self, letters: CharSet) -> Self {
+        TrieSearch {
+            containing: letters,
+            ..self.clone()
+        }
+    }
+
+    /// Only matches keys with a number of different letters in `range`.
+    /// Branches are pruned once they have too many, or too few letters
+    /// remain before the maximum depth to reach enough.
+    pub fn with_distinct_letters(&self, range: RangeInclusive<u8>) -> Self {
+        TrieSearch {
+            distinct_letters: Some(range),
+            ..self.clone()
+        }
+    }
+
+    /// Narrows the search to keys of at least `min` letters and at most
+    /// `max`, containing each of `letters` as well as any already required.
+    pub fn narrowed(&self, min: usize, max: Option<usize>, letters: CharSet) -> Self {
+        let prefix = match min.checked_sub(self.prefix.len()) {
+            Some(padding) if padding > 0 => {
+                let mut elements = self.prefix.elements().to_vec();
+                elements.extend(vec![PrefixElement::Char(CharMatch::Any); padding]);
+                TriePrefix::from_elements(elements)
+            }
+            _ => self.prefix.clone(),
+        };
+        let max_depth = match (self.max_depth, max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        TrieSearch {
+            prefix,
+            max_depth,
+            containing: self.containing.union(letters),
+            ..self.clone()
+        }
+    }
+
+    /// The same search from a different prefix.
+    pub fn with_prefix(&self, prefix: TriePrefix) -> Self {
+        TrieSearch {
+            prefix,
+            ..self.clone()
+        }
+    }
+
+    /// Stops iteration and counting early once `token` is cancelled.
+    pub fn with_cancellation(&self, token: CancellationToken) -> Self {
+        TrieSearch {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|c| c.is_cancelled())
+    }
+
+    /// What the traversal prunes on, one constraint per line.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![];
+        let elements = self.prefix.elements();
+        // wildcards after the last fixed letter only set a minimum length
+        let fixed = elements
+            .iter()
+            .rposition(|x| *x != PrefixElement::Char(CharMatch::Any))
+            .map_or(0, |i| i + 1);
+        if fixed > 0 {
+            lines.push(format!("starting {}", pattern(&elements[..fixed])));
+        }
+        if fixed < elements.len() {
+            lines.push(format!("at least {}", letter_count(self.prefix.len())));
+        }
+        if let Some(max) = self.max_depth {
+            lines.push(format!("at most {}", letter_count(max)));
+        }
+        if !self.containing.is_empty() {
+            lines.push(format!("containing each of {}", self.containing));
+        }
+        if let Some(range) = &self.distinct_letters {
+            lines.push(match (range.start(), range.end()) {
+                (min, max) if min == max => format!("with {} different letters", min),
+                (min, max) => format!("with {} to {} different letters", min, max),
+            });
+        }
+        match self.order {
+            TraversalOrder::DepthFirst => {}
+            TraversalOrder::BreadthFirst => lines.push("shortest first".to_string()),
+            TraversalOrder::Unordered => lines.push("in no particular order".to_string()),
+        }
+        lines
+    }
+
+    pub fn order(&self) -> TraversalOrder {
+        self.order
+    }
+
+    /// Whether a key would be yielded by this search.
+    pub fn matches(&self, word: &NormalizedWord) -> bool {
+        word.len() >= self.prefix_len()
+            && self.max_depth.is_none_or(|max| word.len() <= max)
+            && word
+                .iter_chars()
+                .enumerate()
+                .all(|(depth, ch)| self.get_char_restriction(depth).matches(ch))
+            && self
+                .containing
+                .is_subset(&word.iter_chars().copied().collect())
+            && self.has_distinct_letters(word.iter_chars().copied().collect())
+    }
+
+    // Whether a path of length `depth` through the letters `seen`, still
+    // needing the letters in `missing`, has room for them and can end with
+    // an allowed number of different letters.
+    fn can_complete(&self, depth: usize, missing: CharSet, seen: CharSet) -> bool {
+        let room = self.max_depth.map(|max| max.saturating_sub(depth));
+        room.is_none_or(|room| missing.len() <= room)
+            && self.distinct_letters.as_ref().is_none_or(|range| {
+                seen.len() + missing.len() <= *range.end() as usize
+                    && room.is_none_or(|room| seen.len() + room >= *range.start() as usize)
+            })
+    }
+
+    // Whether a key with the letters `seen` has an allowed number of
+    // different letters.
+    fn has_distinct_letters(&self, seen: CharSet) -> bool {
+        self.distinct_letters
+            .as_ref()
+            .is_none_or(|range| range.contains(&(seen.len() as u8)))
+    }
+
     pub fn below_max(&self, depth: usize) -> bool {
-        self.max_depth.map_or(true, |m| depth < m)
+        self.max_depth.is_none_or(|m| depth < m)
     }
 
     pub fn get_char_restriction(&self, depth: usize) -> CharMatch {
@@ -195,16 +995,40 @@ impl TrieSearch {
     }
 }
 
+// Prefix elements as a pattern would spell them, with `?` for any letter.
+fn pattern(elements: &[PrefixElement]) -> String {
+    elements
+        .iter()
+        .map(|x| match x {
+            PrefixElement::Char(CharMatch::Only(ch)) => ch.to_string(),
+            PrefixElement::Char(CharMatch::Any) => "?".to_string(),
+            PrefixElement::Char(CharMatch::Not(letters)) => format!("[^{}]", letters),
+            PrefixElement::Rebus(letters) => format!("[{}]", letters),
+        })
+        .collect()
+}
+
+fn letter_count(n: usize) -> String {
+    match n {
+        1 => "1 letter".to_string(),
+        n => format!("{} letters", n),
+    }
+}
+
 pub struct TrieIter<'a, T> {
     search: TrieSearch,
-    node_queue: VecDeque<(NormalizedWord, &'a Trie<T>)>,
+    // each node's path, with the required letters the path lacks and the
+    // letters it has
+    node_queue: VecDeque<(NormalizedWord, &'a Trie<T>, CharSet, CharSet)>,
     terminal_queue: VecDeque<(NormalizedWord, &'a T)>,
 }
 
 impl<'a, T> TrieIter<'a, T> {
     fn new(root: &'a Trie<T>, search: TrieSearch) -> TrieIter<'a, T> {
         let mut node_queue: VecDeque<_> = Default::default();
-        node_queue.push_back((Default::default(), root));
+        if search.can_complete(0, search.containing, CharSet::new()) {
+            node_queue.push_back((Default::default(), root, search.containing, CharSet::new()));
+        }
 
         TrieIter {
             search,
@@ -213,35 +1037,50 @@ impl<'a, T> TrieIter<'a, T> {
         }
     }
 
-    fn visit(&mut self, word: NormalizedWord, node: &'a Trie<T>) {
+    fn visit(&mut self, word: NormalizedWord, node: &'a Trie<T>, missing: CharSet, seen: CharSet) {
         let depth = word.len();
 
         let prefix_len = self.search.prefix.len();
 
-        if prefix_len <= depth {
+        if prefix_len <= depth && missing.is_empty() && self.search.has_distinct_letters(seen) {
             self.terminal_queue
                 .extend(node.terminals.iter().map(|t| (word.clone(), t)));
         }
 
         if self.search.below_max(depth) {
             let char_restriction = self.search.get_char_restriction(depth);
+            let search = &self.search;
 
             let nodes = node
                 .children
                 .iter()
                 .filter(|(ch, _)| char_restriction.matches(ch))
+                .filter(|&(ch, _)| {
+                    search.can_complete(depth + 1, missing.without(ch), seen.with(ch))
+                })
                 .filter_map(|(ch, node_opt)| {
                     if let Some(x) = node_opt {
                         let mut child_word = word.clone();
                         child_word.push(ch);
-                        Some((child_word, x.as_ref()))
+                        Some((child_word, x.as_ref(), missing.without(ch), seen.with(ch)))
                     } else {
                         None
                     }
-                })
-                .rev();
+                });
+
+            match self.search.order {
+                TraversalOrder::DepthFirst => self.node_queue.extend(nodes.rev()),
+                TraversalOrder::BreadthFirst | TraversalOrder::Unordered => {
+                    self.node_queue.extend(nodes)
+                }
+            }
+        }
+    }
 
-            self.node_queue.extend(nodes);
+    fn next_node(&mut self) -> Option<(NormalizedWord, &'a Trie<T>, CharSet, CharSet)> {
+        match self.search.order {
+            TraversalOrder::DepthFirst | TraversalOrder::Unordered => self.node_queue.pop_back(),
+            TraversalOrder::BreadthFirst => self.node_queue.pop_front(),
         }
     }
 }
@@ -250,16 +1089,179 @@ impl<'a, T> Iterator for TrieIter<'a, T> {
     type Item = (NormalizedWord, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(term) = self.terminal_queue.pop_front() {
-            return Some(term);
+        loop {
+            if let Some(term) = self.terminal_queue.pop_front() {
+                return Some(term);
+            }
+
+            if self.search.is_cancelled() {
+                return None;
+            }
+            let (word, node, missing, seen) = self.next_node()?;
+            self.visit(word, node, missing, seen);
         }
+    }
+}
+
+/// Iterator returned by `Trie::iter_union`.
+pub struct UnionIter<'a, T> {
+    iters: Vec<Peekable<TrieIter<'a, T>>>,
+}
 
-        if let Some((word, node)) = self.node_queue.pop_back() {
-            self.visit(word, node);
-            return self.next();
+impl<'a, T> Iterator for UnionIter<'a, T> {
+    type Item = (NormalizedWord, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (word, value) = self
+            .iters
+            .iter_mut()
+            .filter_map(|x| x.peek())
+            .min_by(|(a, _), (b, _)| a.cmp(b))?
+            .clone();
+        // searches reaching the same key yield its values in the same order,
+        // so each value is at the front of every search that has it
+        for iter in self.iters.iter_mut() {
+            if iter
+                .peek()
+                .is_some_and(|(x, y)| *x == word && std::ptr::eq(*y, value))
+            {
+                iter.next();
+            }
         }
+        Some((word, value))
+    }
+}
 
-        None
+/// Iterator returned by `Trie::iter_exact`.
+pub struct ExactIter<'a, T> {
+    pattern: SmallVec<[CharMatch; 24]>,
+    // the nodes along `word`, each with the next child to try
+    stack: SmallVec<[(&'a Trie<T>, usize); 24]>,
+    word: NormalizedWord,
+    // values of `word` once it is a full key
+    terminals: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for ExactIter<'a, T> {
+    type Item = (NormalizedWord, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.pattern.len();
+        loop {
+            if let Some(value) = self.terminals.next() {
+                return Some((self.word.clone(), value));
+            }
+            // a full key's node isn't on the stack, only its letter in `word`
+            if len > 0 && self.word.len() == len {
+                self.word.pop();
+            }
+            let (node, next) = self.stack.last_mut()?;
+            let restriction = self.pattern[self.word.len()];
+            let found =
+                node.children
+                    .iter()
+                    .enumerate()
+                    .skip(*next)
+                    .find_map(|(i, (ch, child))| {
+                        let child = child.as_deref()?;
+                        (restriction.matches(&ch) && child.min_len <= len).then_some((i, ch, child))
+                    });
+            match found {
+                Some((i, ch, child)) => {
+                    *next = i + 1;
+                    self.word.push(ch);
+                    if self.word.len() == len {
+                        self.terminals = child.terminals.iter();
+                    } else {
+                        self.stack.push((child, 0));
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                }
+            }
+        }
+    }
+}
+
+type BestFirstRank = (Option<u32>, Reverse<usize>, Reverse<NormalizedWord>);
+
+enum BestFirstKind<'a, T> {
+    Node(&'a Trie<T>),
+    Terminal(&'a T),
+}
+
+struct BestFirstEntry<'a, T> {
+    rank: BestFirstRank,
+    word: NormalizedWord,
+    kind: BestFirstKind<'a, T>,
+}
+
+impl<'a, T> Ord for BestFirstEntry<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank.cmp(&other.rank)
+    }
+}
+
+impl<'a, T> PartialOrd for BestFirstEntry<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T> PartialEq for BestFirstEntry<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
+impl<'a, T> Eq for BestFirstEntry<'a, T> {}
+
+pub struct BestFirstIter<'a, T, F> {
+    heap: BinaryHeap<BestFirstEntry<'a, T>>,
+    score_of: F,
+}
+
+impl<'a, T, F> Iterator for BestFirstIter<'a, T, F>
+where
+    F: Fn(&T) -> Option<u32>,
+{
+    type Item = (NormalizedWord, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = self.heap.pop()?;
+            let node = match entry.kind {
+                BestFirstKind::Terminal(value) => return Some((entry.word, value)),
+                BestFirstKind::Node(node) => node,
+            };
+
+            let len = entry.word.len();
+            for value in node.terminals.iter() {
+                self.heap.push(BestFirstEntry {
+                    rank: (
+                        (self.score_of)(value),
+                        Reverse(len),
+                        Reverse(entry.word.clone()),
+                    ),
+                    word: entry.word.clone(),
+                    kind: BestFirstKind::Terminal(value),
+                });
+            }
+
+            for (ch, child) in node.children.iter() {
+                if let Some(child) = child {
+                    let mut word = entry.word.clone();
+                    word.push(ch);
+                    self.heap.push(BestFirstEntry {
+                        rank: child.rank(&word),
+                        word,
+                        kind: BestFirstKind::Node(child),
+                    });
+                }
+            }
+        }
     }
 }
 
@@ -299,6 +1301,246 @@ mod tests {
         assert_eq!(res, Some(&vec![1, 2]))
     }
 
+    #[test]
+    fn get_prefix_is_none() {
+        let trie = Trie::from_iter(vec![("ABC", 1)]);
+
+        assert_eq!(trie.get(&"AB".into()), None);
+        assert_eq!(trie.get(&"ABCD".into()), None);
+    }
+
+    #[test]
+    fn entry_adds_and_updates_values() {
+        let mut trie: Trie<i32> = Default::default();
+
+        *trie.entry(&"AB".into()).push(1) += 10;
+        trie.entry(&"AB".into()).or_insert(2)[0] += 1;
+        trie.entry(&"A".into()).or_default()[0] = 3;
+
+        assert_eq!(trie.get(&"AB".into()), Some(&vec![12]));
+        assert_eq!(trie.get_mut(&"A".into()), Some(&mut [3][..]));
+        assert!(trie.contains_key(&"A".into()) && !trie.contains_key(&"B".into()));
+        assert_eq!(trie.len(), 2);
+    }
+
+    #[test]
+    fn keys_and_values_in_key_order() {
+        let trie = Trie::from_iter(vec![("BA", 1), ("A", 2), ("AB", 3), ("A", 4)]);
+
+        let keys: Vec<NormalizedWord> = trie.keys().collect();
+        let values: Vec<&i32> = trie.values().collect();
+        assert_eq!(keys, ["A".into(), "AB".into(), "BA".into()]);
+        assert_eq!(values, [&2, &4, &3, &1]);
+    }
+
+    #[test]
+    fn retain_removes_values_and_empty_nodes() {
+        let mut trie = Trie::from_iter(vec![("A", 1), ("AB", 2), ("ABC", 3), ("BC", 4)]);
+
+        trie.retain(|key, &value| key.len() > 1 && value != 3);
+
+        assert_eq!(trie, Trie::from_iter(vec![("AB", 2), ("BC", 4)]));
+        trie.retain(|_, _| false);
+        assert!(trie.is_empty());
+        assert_eq!(trie, Default::default());
+    }
+
+    #[test]
+    fn pattern_rejects_unknown_chars() {
+        let error = |pattern| TriePrefix::from_pattern(pattern).unwrap_err();
+        use PatternErrorKind::*;
+
+        assert_eq!(TriePrefix::from_pattern("c?t").map(|p| p.len()), Ok(3));
+        assert_eq!(
+            error("c*t"),
+            PatternError {
+                position: 1,
+                kind: UnknownChar('*')
+            }
+        );
+        assert_eq!(error("ca[ab").kind, UnclosedRebus);
+        assert_eq!(error("ca[ab").position, 2);
+        assert_eq!(error("c[]").kind, EmptyRebus);
+        assert_eq!(error("c[a?]").kind, WildcardInRebus('?'));
+        assert_eq!(error("c[a1]").kind, UnknownChar('1'));
+        assert_eq!(
+            error("c*t").to_string(),
+            "unexpected '*' at character 2; use ? for any one letter"
+        );
+        assert_eq!(error("ca]").suggestion(), "open the rebus square with [");
+    }
+
+    #[test]
+    fn rebus_squares_match_several_letters() {
+        let trie = Trie::from_iter(vec![
+            ("CHEARTD", 1),
+            ("HEARTH", 2),
+            ("HEARTS", 3),
+            ("HEART", 4),
+            ("EARTHS", 5),
+        ]);
+        let prefix = TriePrefix::from_pattern("[heart]?").unwrap();
+        assert_eq!((prefix.len(), prefix.elements().len()), (6, 2));
+        assert_eq!(prefix.elements()[0], PrefixElement::Rebus("heart".into()));
+
+        let search = TrieSearch::exactly("[heart]?").unwrap();
+        let res: Vec<_> = trie.iter_search(search).map(|(_, &v)| v).collect();
+        assert_eq!(res, [2, 3]);
+        assert!(trie.exists_search(&TrieSearch::exactly("c[heart]d").unwrap()));
+    }
+
+    #[test]
+    fn cancellation_stops_iteration() {
+        let trie = Trie::from_iter(vec![("A", 1), ("AB", 2), ("B", 3), ("C", 4)]);
+        let token = CancellationToken::new();
+        let search = TrieSearch::default().with_cancellation(token.clone());
+
+        let mut iter = trie.iter_search(search.clone());
+        assert_eq!(iter.next().map(|(_, &v)| v), Some(1));
+        token.cancel();
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(trie.count_search(&search), 0);
+        token.reset();
+        assert_eq!(trie.count_search(&search), 4);
+    }
+
+    #[test]
+    fn containing_search_requires_every_letter() {
+        let trie = Trie::from_iter(vec![
+            ("TEA", 1),
+            ("EAT", 2),
+            ("TEAR", 3),
+            ("RATE", 4),
+            ("AT", 5),
+        ]);
+        let search = TrieSearch::default().containing("RE".into());
+
+        let res: Vec<_> = trie.iter_search(search.clone()).map(|(_, &v)| v).collect();
+        assert_eq!(res, [4, 3]);
+        assert_eq!(trie.count_search(&search), 2);
+        assert_eq!(trie.count_search(&search.with_max(3)), 0);
+        assert!(!trie.exists_search(&TrieSearch::from_prefix("A").unwrap().containing("E".into())));
+        assert!(search.matches(&"TEAR".into()) && !search.matches(&"TEA".into()));
+    }
+
+    #[test]
+    fn union_yields_each_key_once_in_order() {
+        let trie = Trie::from_iter(vec![
+            ("CAT", 1),
+            ("CAT", 2),
+            ("COT", 3),
+            ("CART", 4),
+            ("DOG", 5),
+            ("BAT", 6),
+        ]);
+        let searches = vec![
+            TrieSearch::from_prefix("D").unwrap(),
+            TrieSearch::exactly("C?T").unwrap(),
+            TrieSearch::from_prefix("CA").unwrap(),
+        ];
+
+        let res: Vec<_> = trie.iter_union(searches).map(|(_, &v)| v).collect();
+        assert_eq!(res, [4, 1, 2, 3, 5]);
+        assert_eq!(trie.iter_union(vec![]).count(), 0);
+    }
+
+    #[test]
+    fn distinct_letter_search_prunes_both_ways() {
+        let trie = Trie::from_iter(vec![
+            ("NOON", 1),
+            ("NONE", 2),
+            ("NOTE", 3),
+            ("ONE", 4),
+            ("TONNE", 5),
+            ("ON", 6),
+        ]);
+        let search = TrieSearch::default().with_distinct_letters(3..=3);
+
+        let res: Vec<_> = trie.iter_search(search.clone()).map(|(_, &v)| v).collect();
+        assert_eq!(res, [2, 4]);
+        assert_eq!(trie.count_search(&search), 2);
+        assert_eq!(
+            trie.count_search(&TrieSearch::default().with_distinct_letters(4..=4)),
+            2
+        );
+        assert_eq!(trie.count_search(&search.with_max(2)), 0);
+        assert!(search.matches(&"ONE".into()) && !search.matches(&"NOTE".into()));
+    }
+
+    #[test]
+    fn count_all() {
+        let trie = Trie::from_iter(vec![("A", 1), ("AB", 2), ("B", 3), ("AB", 4)]);
+
+        assert_eq!(trie.count_search(&Default::default()), 4)
+    }
+
+    #[test]
+    fn count_prefix_search() {
+        let trie = Trie::from_iter(vec![("BAT", ()), ("CAR", ()), ("CAT", ()), ("CA", ())]);
+
+        let search = TrieSearch::from_prefix("CA?").unwrap();
+
+        assert_eq!(trie.count_search(&search), 2)
+    }
+
+    #[test]
+    fn count_bounded_search() {
+        let trie = Trie::from_iter(vec![("A", 1), ("AB", 2), ("ABC", 3), ("BC", 4)]);
+
+        let search = TrieSearch::from_prefix("?").unwrap().with_max(2);
+
+        assert_eq!(trie.count_search(&search), 3)
+    }
+
+    #[test]
+    fn exists_search() {
+        let trie = Trie::from_iter(vec![("BAT", ()), ("CART", ())]);
+
+        assert!(trie.exists_search(&TrieSearch::from_prefix("CA").unwrap()));
+        assert!(!trie.exists_search(&TrieSearch::exactly("CAR").unwrap()));
+        assert!(!trie.exists_search(&TrieSearch::from_prefix("D").unwrap()));
+    }
+
+    #[test]
+    fn best_first_without_scores_is_shortest_first() {
+        let trie = Trie::from_iter(vec![("CATS", 1), ("CAB", 2), ("CAT", 3), ("DOG", 4)]);
+
+        let res: Vec<_> = trie.iter_best_first(&"CA".into(), |_| None).collect();
+
+        assert_eq!(
+            res,
+            [("CAB".into(), &2), ("CAT".into(), &3), ("CATS".into(), &1)]
+        )
+    }
+
+    #[test]
+    fn best_first_prefers_higher_scores() {
+        let mut trie: Trie<u32> = Default::default();
+        for (word, score) in [("CATS", 50), ("CAB", 10), ("CAT", 30), ("CATALOG", 50)] {
+            trie.add_scored(&word.into(), score, Some(score));
+        }
+
+        let res: Vec<_> = trie.iter_best_first(&"CA".into(), |&x| Some(x)).collect();
+
+        assert_eq!(
+            res,
+            [
+                ("CATS".into(), &50),
+                ("CATALOG".into(), &50),
+                ("CAT".into(), &30),
+                ("CAB".into(), &10)
+            ]
+        )
+    }
+
+    #[test]
+    fn best_first_missing_prefix_is_empty() {
+        let trie = Trie::from_iter(vec![("CAT", ())]);
+
+        assert_eq!(trie.iter_best_first(&"D".into(), |_| None).count(), 0)
+    }
+
     #[test]
     fn iterate_single() {
         let trie = Trie::from_iter(vec![("A", 1)]);
@@ -348,7 +1590,7 @@ mod tests {
     fn iterate_prefix_search() {
         let trie = Trie::from_iter(vec![("BAT", ()), ("CAR", ()), ("CAT", ())]);
 
-        let search = TrieSearch::from_prefix("CA");
+        let search = TrieSearch::from_prefix("CA").unwrap();
         let res: Vec<_> = trie.iter_search(search).collect();
 
         assert_eq!(res, [("CAR".into(), &()), ("CAT".into(), &())])
@@ -358,7 +1600,7 @@ mod tests {
     fn iterate_prefix_exclude_shorter() {
         let trie = Trie::from_iter(vec![("C", ()), ("CAR", ())]);
 
-        let search = TrieSearch::from_prefix("CA");
+        let search = TrieSearch::from_prefix("CA").unwrap();
         let res: Vec<_> = trie.iter_search(search).collect();
 
         assert_eq!(res, [("CAR".into(), &()),])
@@ -368,9 +1610,129 @@ mod tests {
     fn iterate_wildcard_match() {
         let trie = Trie::from_iter(vec![("BAT", ()), ("CAR", ()), ("COT", ())]);
 
-        let search = TrieSearch::from_prefix("?A");
+        let search = TrieSearch::from_prefix("?A").unwrap();
         let res: Vec<_> = trie.iter_search(search).collect();
 
         assert_eq!(res, [("BAT".into(), &()), ("CAR".into(), &())])
     }
+
+    #[test]
+    fn iterate_lexicographic_regardless_of_insertion_order() {
+        let words = ["CAT", "A", "CAB", "B", "CA", "AA", "C"];
+        let trie = Trie::from_iter(words.iter().map(|&w| (w, ())));
+
+        let res: Vec<NormalizedWord> = trie.iter().map(|(nw, _)| nw).collect();
+
+        let mut expected: Vec<NormalizedWord> = words.iter().map(|&w| w.into()).collect();
+        expected.sort();
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn iterate_unordered_yields_same_set() {
+        let trie = Trie::from_iter(vec![("CAT", 1), ("A", 2), ("CAB", 3), ("B", 4)]);
+
+        let mut res: Vec<_> = trie.iter_search_unordered(Default::default()).collect();
+        res.sort();
+
+        let expected: Vec<_> = trie.iter().collect();
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn iterate_breadth_first() {
+        let trie = Trie::from_iter(vec![("AB", 1), ("B", 2), ("A", 3), ("ABC", 4), ("BA", 5)]);
+
+        let search = TrieSearch::default().with_order(TraversalOrder::BreadthFirst);
+        let res: Vec<_> = trie.iter_search(search).collect();
+
+        assert_eq!(
+            res,
+            [
+                ("A".into(), &3),
+                ("B".into(), &2),
+                ("AB".into(), &1),
+                ("BA".into(), &5),
+                ("ABC".into(), &4),
+            ]
+        )
+    }
+
+    #[test]
+    fn iterate_long_chain() {
+        let word = "A".repeat(10_000);
+        let trie = Trie::from_iter(vec![(&word[..], 1)]);
+
+        let res: Vec<_> = trie.iter().map(|(nw, &v)| (nw.len(), v)).collect();
+
+        assert_eq!(res, [(10_000, 1)])
+    }
+
+    #[test]
+    fn validates_cached_counts() {
+        let mut trie = Trie::from_iter(vec![("AB", 1), ("ABC", 2), ("B", 3)]);
+        trie.add_scored(&"BA".into(), 4, Some(9));
+        assert_eq!(trie.validate(), Ok(()));
+
+        trie.retain(|_, &x| x != 3);
+        assert_eq!(trie.validate(), Ok(()));
+
+        let node = trie.get_node_mut(&"AB".into()).unwrap();
+        node.count += 1;
+        assert_eq!(
+            trie.validate(),
+            Err(TrieIssue::CountMismatch {
+                key: "AB".into(),
+                cached: 3,
+                actual: 2
+            })
+        );
+        trie.get_node_mut(&"AB".into()).unwrap().count -= 1;
+        assert_eq!(trie.validate(), Ok(()));
+
+        trie.get_node_mut(&"AB".into()).unwrap().best_score = Some(10);
+        let issue = trie.validate().unwrap_err();
+        assert_eq!(issue, TrieIssue::ScoreBelowChild("AB".into()));
+        assert_eq!(issue.to_string(), "node 'AB' scores above its parent");
+    }
+
+    #[test]
+    fn draws_nodes_to_depth() {
+        let trie = Trie::from_iter(vec![("A", 1), ("AB", 2), ("ABC", 3)]);
+
+        assert_eq!(
+            trie.to_dot(2),
+            "digraph trie {
+  n0 [label=\"3\", shape=circle];
+  n1 [label=\"3\", shape=doublecircle];
+  n2 [label=\"2\", shape=doublecircle, style=dashed];
+  n1 -> n2 [label=\"B\"];
+  n0 -> n1 [label=\"A\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn iterate_exact_length() {
+        let trie = Trie::from_iter(vec![
+            ("CAT", 1),
+            ("COT", 2),
+            ("CAT", 3),
+            ("CATS", 4),
+            ("AT", 5),
+            ("DOT", 6),
+        ]);
+        let pattern = TriePrefix::from_pattern("??T").unwrap();
+
+        let res: Vec<_> = trie.iter_exact(&pattern.chars).collect();
+        let search = TrieSearch::new(pattern.clone(), Some(3));
+        assert_eq!(res, trie.iter_search(search).collect::<Vec<_>>());
+        assert_eq!(res.len(), 4);
+
+        let pattern = TriePrefix::from_pattern("?O?").unwrap();
+        let res: Vec<_> = trie.iter_exact(&pattern.chars).map(|(_, &x)| x).collect();
+        assert_eq!(res, [2, 6]);
+        assert_eq!(trie.iter_exact(&[]).count(), 0);
+    }
 }
@@ -0,0 +1,131 @@
+use crate::dictionary::Dictionary;
+use crate::fuzzy::FuzzyMatcher;
+use crate::normalized_word::NormalizedWord;
+
+/// Why a word is or is not in the dictionary, as returned by
+/// `Dictionary::validate`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Validation<'a> {
+    /// An entry is spelled exactly as given.
+    pub exact: bool,
+    /// Entries with the same letters in the same order once case, accents
+    /// and punctuation are ignored, but spelled differently.
+    pub normalized: Vec<&'a str>,
+    /// Words one substitution, insertion or deletion away.
+    pub near_misses: Vec<&'a str>,
+    /// Other words using the same letters.
+    pub anagrams: Vec<&'a str>,
+}
+
+impl<'a> Validation<'a> {
+    pub fn of(dict: &'a Dictionary, word: &str) -> Self {
+        let normalized = NormalizedWord::from_str_safe(word);
+        if normalized.is_empty() {
+            return Default::default();
+        }
+
+        let entries = dict.find(&normalized).into_iter().flatten();
+        let (exact, others): (Vec<_>, Vec<_>) = entries.partition(|x| x.original == word);
+
+        Validation {
+            exact: !exact.is_empty(),
+            normalized: others.into_iter().map(|x| &x.original[..]).collect(),
+            near_misses: near_misses(dict, &normalized),
+            anagrams: dict
+                .anagrams(word)
+                .filter(|x| x.normalized != normalized)
+                .map(|x| &x.original[..])
+                .collect(),
+        }
+    }
+
+    /// Whether the word is in the dictionary, allowing for normalization.
+    pub fn is_valid(&self) -> bool {
+        self.exact || !self.normalized.is_empty()
+    }
+}
+
+fn near_misses<'a>(dict: &'a Dictionary, word: &NormalizedWord) -> Vec<&'a str> {
+    let mut found: Vec<_> = FuzzyMatcher::new(&word.to_string(), 1)
+        .search(dict)
+        .into_iter()
+        .map(|(x, _)| x)
+        .filter(|x| &x.normalized != word)
+        .collect();
+    found.dedup_by(|a, b| a.normalized == b.normalized);
+    found.into_iter().map(|x| &x.original[..]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explains_why_words_are_accepted() {
+        let dict = Dictionary::from_iter(vec![
+            "café", "cafe", "cafes", "face", "cage", "care", "cat", "safe",
+        ]);
+
+        let validation = dict.validate("cafe");
+        assert!(validation.exact);
+        assert_eq!(validation.normalized, vec!["café"]);
+        assert_eq!(
+            validation.near_misses,
+            vec!["cafes", "cage", "care", "safe"]
+        );
+        assert_eq!(validation.anagrams, vec!["face"]);
+
+        let validation = dict.validate("CAFE");
+        assert!(!validation.exact);
+        assert!(validation.is_valid());
+
+        let validation = dict.validate("cafx");
+        assert!(!validation.is_valid());
+        assert_eq!(validation.near_misses, vec!["café"]);
+        assert!(validation.anagrams.is_empty());
+
+        assert_eq!(dict.validate("123"), Default::default());
+    }
+
+    fn dict() -> Dictionary {
+        Dictionary::from_iter(vec!["café", "cafe", "cafes", "face", "cat", "straw"])
+    }
+
+    #[test]
+    fn rejects_words_far_from_any_entry() {
+        let dict = dict();
+        let validation = dict.validate("zebra");
+        assert!(!validation.exact);
+        assert!(!validation.is_valid());
+        assert_eq!(validation, Default::default());
+
+        // an anagram alone does not make a word valid
+        let validation = dict.validate("acef");
+        assert!(!validation.is_valid());
+        assert_eq!(validation.anagrams, vec!["café", "cafe", "face"]);
+    }
+
+    #[test]
+    fn rejects_words_of_the_wrong_length() {
+        let dict = dict();
+        let short = dict.validate("caf");
+        assert!(!short.is_valid());
+        assert_eq!(short.near_misses, vec!["café", "cat"]);
+
+        let long = dict.validate("cafess");
+        assert!(!long.is_valid());
+        assert_eq!(long.near_misses, vec!["cafes"]);
+        assert!(long.anagrams.is_empty());
+    }
+
+    #[test]
+    fn drops_letters_that_do_not_fold() {
+        let dict = dict();
+        // ß has no single-letter form, so STRAßE is checked as STRAE
+        let validation = dict.validate("straße");
+        assert!(!validation.is_valid());
+        assert_eq!(validation.near_misses, vec!["straw"]);
+
+        assert_eq!(dict.validate("ßø"), Default::default());
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use regex::Regex;
+
+/// A regular expression for `WordPredicate::OriginalRegex` and
+/// `WordPredicate::NormalizedRegex`, equal to any other with the same
+/// pattern.
+#[derive(Debug, Clone)]
+pub struct WordRegex(Regex);
+
+impl WordRegex {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(WordRegex)
+    }
+
+    /// Whether the expression matches anywhere in `text`, unless anchored
+    /// with `^` or `$`.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl From<Regex> for WordRegex {
+    fn from(regex: Regex) -> Self {
+        WordRegex(regex)
+    }
+}
+
+impl PartialEq for WordRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Display for WordRegex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}/", self.as_str())
+    }
+}
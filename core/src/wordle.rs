@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::char_freq::CharFreq;
+use crate::char_map::CharMap;
+use crate::char_match::CharMatch;
+use crate::char_set::CharSet;
+use crate::dictionary::{Canonical, DictSearch, Dictionary, WordPredicate};
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+use crate::trie::{TriePrefix, TrieSearch};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mark {
+    Absent,
+    Present,
+    Correct,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownMarkChar(pub char);
+
+impl TryFrom<char> for Mark {
+    type Error = UnknownMarkChar;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        match ch {
+            'g' | 'G' => Ok(Mark::Correct),
+            'y' | 'Y' => Ok(Mark::Present),
+            '.' | '-' | 'b' | 'B' => Ok(Mark::Absent),
+            _ => Err(UnknownMarkChar(ch)),
+        }
+    }
+}
+
+/// The marks a guess receives against an answer, one per letter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Feedback(pub Vec<Mark>);
+
+impl Feedback {
+    /// Scores `guess` against `answer` as Wordle does: exact matches first,
+    /// then misplaced letters up to the number remaining in the answer.
+    pub fn score(guess: &NormalizedWord, answer: &NormalizedWord) -> Feedback {
+        let mut marks = vec![Mark::Absent; guess.len()];
+        let mut unmatched: CharMap<u8> = Default::default();
+        for (i, (g, a)) in guess.iter_chars().zip(answer.iter_chars()).enumerate() {
+            if g == a {
+                marks[i] = Mark::Correct;
+            } else {
+                *unmatched.get_mut(*a) += 1;
+            }
+        }
+        for (i, &g) in guess.iter_chars().enumerate() {
+            let left = unmatched.get_mut(g);
+            if marks[i] == Mark::Absent && *left > 0 {
+                *left -= 1;
+                marks[i] = Mark::Present;
+            }
+        }
+        Feedback(marks)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.0.iter().all(|&m| m == Mark::Correct)
+    }
+}
+
+impl TryFrom<&str> for Feedback {
+    type Error = UnknownMarkChar;
+
+    /// Parses `g` (correct), `y` (present) and `.` (absent) marks.
+    fn try_from(str: &str) -> Result<Self, Self::Error> {
+        str.chars()
+            .map(Mark::try_from)
+            .collect::<Result<_, _>>()
+            .map(Feedback)
+    }
+}
+
+/// A guess, or its feedback, of a different length from the board.
+#[derive(Debug, PartialEq, Eq)]
+pub struct WrongLength {
+    pub expected: usize,
+    pub found: usize,
+}
+
+/// What previous guesses have revealed about a single answer.
+#[derive(Debug, Clone, Default)]
+pub struct Constraints {
+    correct: Vec<Option<NormalizedChar>>,
+    misplaced: Vec<(usize, NormalizedChar)>,
+    min_counts: CharMap<u8>,
+    max_counts: CharMap<Option<u8>>,
+}
+
+impl Constraints {
+    pub fn new(len: usize) -> Self {
+        Constraints {
+            correct: vec![None; len],
+            ..Default::default()
+        }
+    }
+
+    pub fn with(
+        mut self,
+        guess: &NormalizedWord,
+        feedback: &Feedback,
+    ) -> Result<Self, WrongLength> {
+        self.add(guess, feedback)?;
+        Ok(self)
+    }
+
+    /// Records `feedback` on `guess`, unless either is not as long as the
+    /// board.
+    pub fn add(&mut self, guess: &NormalizedWord, feedback: &Feedback) -> Result<(), WrongLength> {
+        self.check_length(guess, feedback)?;
+        let mut found: CharMap<u8> = Default::default();
+        for (i, (&ch, &mark)) in guess.iter_chars().zip(feedback.0.iter()).enumerate() {
+            match mark {
+                Mark::Correct => {
+                    self.correct[i] = Some(ch);
+                    *found.get_mut(ch) += 1;
+                }
+                Mark::Present => {
+                    self.misplaced.push((i, ch));
+                    *found.get_mut(ch) += 1;
+                }
+                Mark::Absent => self.misplaced.push((i, ch)),
+            }
+        }
+        for (&ch, &mark) in guess.iter_chars().zip(feedback.0.iter()) {
+            let count = *found.get(ch);
+            let min = self.min_counts.get_mut(ch);
+            *min = (*min).max(count);
+            if mark == Mark::Absent {
+                self.max_counts.set(ch, Some(count));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_length(&self, guess: &NormalizedWord, feedback: &Feedback) -> Result<(), WrongLength> {
+        let expected = self.correct.len();
+        match [guess.len(), feedback.0.len()]
+            .into_iter()
+            .find(|&x| x != expected)
+        {
+            Some(found) => Err(WrongLength { expected, found }),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether `word` could still be the answer.
+    pub fn allows(&self, word: &NormalizedWord) -> bool {
+        word.len() == self.correct.len()
+            && self.allows_hard_mode_guess(word)
+            && self.misplaced.iter().all(|&(i, ch)| word[i] != ch)
+            && self.max_counts.iter().all(|(ch, max)| {
+                max.is_none_or(|max| {
+                    word.iter_chars().filter(|&&x| x == ch).count() <= max as usize
+                })
+            })
+    }
+
+    /// A trie search pruning words the constraints rule out, apart from
+    /// letter counts above zero, which only `allows` checks.
+    pub fn to_search(&self) -> TrieSearch {
+        let absent: CharSet = self
+            .max_counts
+            .iter()
+            .filter(|&(_, &max)| max == Some(0))
+            .map(|(ch, _)| ch)
+            .collect();
+        let chars = self
+            .correct
+            .iter()
+            .enumerate()
+            .map(|(i, correct)| match correct {
+                Some(ch) => CharMatch::Only(*ch),
+                None => CharMatch::Not(
+                    self.misplaced
+                        .iter()
+                        .filter(|&&(j, _)| j == i)
+                        .map(|&(_, ch)| ch)
+                        .collect::<CharSet>()
+                        .union(absent),
+                ),
+            })
+            .collect();
+        let present = self
+            .min_counts
+            .iter()
+            .filter(|&(_, &min)| min > 0)
+            .map(|(ch, _)| ch)
+            .collect();
+        TrieSearch::new(TriePrefix::new(chars), Some(self.correct.len())).containing(present)
+    }
+
+    /// Whether `guess` is legal in hard mode: revealed letters stay in place
+    /// and misplaced letters are reused.
+    pub fn allows_hard_mode_guess(&self, guess: &NormalizedWord) -> bool {
+        let counts = CharFreq::from(guess);
+        self.correct
+            .iter()
+            .zip(guess.iter_chars())
+            .all(|(correct, ch)| correct.is_none_or(|x| x == *ch))
+            && self
+                .min_counts
+                .iter()
+                .all(|(ch, &min)| counts.get(ch) >= min)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Board {
+    candidates: Vec<NormalizedWord>,
+    constraints: Constraints,
+    solved: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedGuess {
+    pub word: NormalizedWord,
+    /// Expected information in bits, summed across unsolved boards.
+    pub bits: f64,
+    /// Whether the guess is a remaining candidate on some board.
+    pub could_solve: bool,
+}
+
+/// Several simultaneous Wordle boards sharing every guess, as in Quordle
+/// (four boards) or Octordle (eight).
+#[derive(Debug, Clone)]
+pub struct Game {
+    boards: Vec<Board>,
+    hard_mode: bool,
+}
+
+impl Game {
+    pub fn new(len: usize, candidates: Vec<NormalizedWord>, boards: usize) -> Self {
+        let board = Board {
+            candidates,
+            constraints: Constraints::new(len),
+            solved: false,
+        };
+        Game {
+            boards: vec![board; boards],
+            hard_mode: false,
+        }
+    }
+
+    /// A game whose answers are the dictionary's distinct words of `len` letters.
+    pub fn from_dictionary(dict: &Dictionary, len: usize, boards: usize) -> Self {
+        let search = DictSearch::new(
+            Some(TrieSearch::new(TriePrefix::any_with_length(len), Some(len))),
+            WordPredicate::None,
+        )
+        .with_dedupe(Canonical::First);
+        let candidates = dict.iter_search(search).map(|x| x.normalized).collect();
+        Game::new(len, candidates, boards)
+    }
+
+    pub fn with_hard_mode(mut self) -> Self {
+        self.hard_mode = true;
+        self
+    }
+
+    /// Records a guess with the feedback from each board, in board order.
+    /// Feedback for already solved boards is ignored. Nothing is recorded
+    /// if the guess or any feedback is not as long as the boards.
+    pub fn record(
+        &mut self,
+        guess: &NormalizedWord,
+        feedback: &[Feedback],
+    ) -> Result<(), WrongLength> {
+        for (board, feedback) in self.boards.iter().zip(feedback) {
+            board.constraints.check_length(guess, feedback)?;
+        }
+        for (board, feedback) in self.boards.iter_mut().zip(feedback) {
+            if board.solved {
+                continue;
+            }
+            board.constraints.add(guess, feedback)?;
+            board.solved = feedback.is_solved();
+            if board.solved {
+                board.candidates = vec![guess.clone()];
+            } else {
+                let constraints = &board.constraints;
+                board.candidates.retain(|word| constraints.allows(word));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn candidates(&self, board: usize) -> &[NormalizedWord] {
+        &self.boards[board].candidates
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.boards.iter().all(|b| b.solved)
+    }
+
+    /// Whether `guess` is allowed, which in hard mode means it respects the
+    /// hints of every unsolved board.
+    pub fn allows_guess(&self, guess: &NormalizedWord) -> bool {
+        !self.hard_mode
+            || self
+                .unsolved()
+                .all(|b| b.constraints.allows_hard_mode_guess(guess))
+    }
+
+    /// Ranks allowed guesses by expected information across unsolved boards,
+    /// treating the boards as independent. Ties favour guesses that could
+    /// solve a board.
+    pub fn rank_guesses<'a, I>(&self, guesses: I) -> Vec<RankedGuess>
+    where
+        I: IntoIterator<Item = &'a NormalizedWord>,
+    {
+        let mut ranked: Vec<RankedGuess> = guesses
+            .into_iter()
+            .filter(|guess| self.allows_guess(guess))
+            .map(|guess| RankedGuess {
+                word: guess.clone(),
+                bits: self
+                    .unsolved()
+                    .map(|b| expected_bits(guess, &b.candidates))
+                    .sum(),
+                could_solve: self.unsolved().any(|b| b.candidates.contains(guess)),
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.bits
+                .total_cmp(&a.bits)
+                .then(b.could_solve.cmp(&a.could_solve))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        ranked
+    }
+
+    fn unsolved(&self) -> impl Iterator<Item = &Board> {
+        self.boards.iter().filter(|b| !b.solved)
+    }
+}
+
+// Entropy of the feedback a guess would receive over equally likely answers.
+fn expected_bits(guess: &NormalizedWord, candidates: &[NormalizedWord]) -> f64 {
+    let mut partitions: HashMap<Feedback, usize> = HashMap::new();
+    for answer in candidates {
+        *partitions
+            .entry(Feedback::score(guess, answer))
+            .or_default() += 1;
+    }
+    let total = candidates.len() as f64;
+    partitions
+        .values()
+        .map(|&n| {
+            let p = n as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feedback(guess: &str, answer: &str) -> Feedback {
+        Feedback::score(&guess.into(), &answer.into())
+    }
+
+    #[test]
+    fn scores_repeated_letters() {
+        assert_eq!(
+            feedback("speed", "abide"),
+            Feedback::try_from("..y.y").unwrap()
+        );
+        assert_eq!(
+            feedback("eerie", "where"),
+            Feedback::try_from("y.y.g").unwrap()
+        );
+        assert!(feedback("crane", "crane").is_solved());
+        assert_eq!(Feedback::try_from("gy?"), Err(UnknownMarkChar('?')));
+    }
+
+    #[test]
+    fn constraints_agree_with_scoring() {
+        let words = ["abide", "aside", "bride", "eerie", "speed", "where"];
+        let guess = "eerie".into();
+        for answer in words {
+            let constraints = Constraints::new(5)
+                .with(&guess, &feedback("eerie", answer))
+                .unwrap();
+            for word in words {
+                let consistent = feedback("eerie", word) == feedback("eerie", answer);
+                assert_eq!(
+                    constraints.allows(&word.into()),
+                    consistent,
+                    "{answer} {word}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn search_finds_every_allowed_word() {
+        let words = ["abide", "aside", "bride", "eerie", "speed", "where"];
+        let dict = Dictionary::from_iter(words);
+        for answer in words {
+            let constraints = Constraints::new(5)
+                .with(&"eerie".into(), &feedback("eerie", answer))
+                .unwrap();
+            let search = DictSearch::new(Some(constraints.to_search()), WordPredicate::None);
+            let found: Vec<NormalizedWord> = dict
+                .iter_search(search)
+                .map(|x| x.normalized)
+                .filter(|word| constraints.allows(word))
+                .collect();
+            let allowed: Vec<NormalizedWord> = words
+                .iter()
+                .map(|&w| w.into())
+                .filter(|word| constraints.allows(word))
+                .collect();
+            assert_eq!(found, allowed, "{answer}");
+        }
+    }
+
+    #[test]
+    fn ranks_guesses_across_boards() {
+        let words: Vec<NormalizedWord> = ["bat", "cat", "cot", "hat", "hot", "mat"]
+            .iter()
+            .map(|&w| w.into())
+            .collect();
+        let mut game = Game::new(3, words.clone(), 2);
+
+        game.record(
+            &"hot".into(),
+            &[feedback("hot", "cat"), feedback("hot", "hot")],
+        )
+        .unwrap();
+        assert_eq!(game.candidates(0).len(), 3);
+        assert_eq!(game.candidates(1), &["hot".into()]);
+
+        let ranked = game.rank_guesses(&words);
+        assert_eq!(ranked[0].word, "bat".into());
+        assert!(ranked[0].could_solve);
+        assert!(ranked.last().unwrap().bits < ranked[0].bits);
+
+        let hard = game.clone().with_hard_mode();
+        assert!(hard.allows_guess(&"cot".into()));
+        assert!(!hard.allows_guess(&"cob".into()));
+
+        game.record(&"cat".into(), &[feedback("cat", "cat")])
+            .unwrap();
+        assert!(game.is_solved());
+    }
+
+    #[test]
+    fn rejects_guesses_of_the_wrong_length() {
+        let mut constraints = Constraints::new(3);
+        assert_eq!(
+            constraints.add(&"crane".into(), &feedback("crane", "cater")),
+            Err(WrongLength {
+                expected: 3,
+                found: 5
+            })
+        );
+        assert_eq!(
+            constraints.add(&"cat".into(), &Feedback::try_from("gg").unwrap()),
+            Err(WrongLength {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(constraints.allows(&"cot".into()));
+
+        let mut game = Game::new(3, vec!["cat".into()], 2);
+        let wrong = [feedback("cat", "cat"), feedback("cats", "cats")];
+        assert!(game.record(&"cat".into(), &wrong).is_err());
+        assert!(!game.is_solved());
+    }
+}
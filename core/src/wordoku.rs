@@ -0,0 +1,381 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::char_set::CharSet;
+use crate::dictionary::Dictionary;
+use crate::normalized_word::{NormalizedChar, NormalizedWord};
+
+const SIZE: usize = 9;
+const CELLS: usize = SIZE * SIZE;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WordokuError {
+    UnknownChar(char),
+    /// A row without exactly nine squares.
+    RaggedRow(usize),
+    /// The number of rows, when not nine.
+    RowCount(usize),
+    /// The givens use more than nine letters.
+    TooManyLetters(CharSet),
+}
+
+impl fmt::Display for WordokuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordokuError::UnknownChar(ch) => write!(f, "unknown character '{}'", ch),
+            WordokuError::RaggedRow(i) => write!(f, "row {} does not have 9 squares", i + 1),
+            WordokuError::RowCount(n) => write!(f, "expected 9 rows, found {}", n),
+            WordokuError::TooManyLetters(letters) => {
+                write!(f, "more than 9 letters given: {}", letters)
+            }
+        }
+    }
+}
+
+/// A row, column or 3×3 box of the grid, counting from zero. Boxes are
+/// numbered across then down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum House {
+    Row(usize),
+    Column(usize),
+    Box(usize),
+}
+
+impl House {
+    fn all() -> impl Iterator<Item = House> {
+        (0..SIZE).flat_map(|i| [House::Row(i), House::Column(i), House::Box(i)])
+    }
+
+    /// The squares of the house as indexes into the grid, read across then
+    /// down.
+    fn cells(self) -> [usize; SIZE] {
+        let mut cells = [0; SIZE];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = match self {
+                House::Row(row) => row * SIZE + i,
+                House::Column(col) => i * SIZE + col,
+                House::Box(b) => (b / 3 * 3 + i / 3) * SIZE + b % 3 * 3 + i % 3,
+            };
+        }
+        cells
+    }
+}
+
+/// A word sudoku: a 9×9 grid to fill with nine distinct letters so each
+/// row, column and box holds every one, with one of them spelling a word.
+///
+/// Parsed from nine lines of letters, with `.` or `?` for empty squares.
+/// Spaces and `|` within a line are ignored, and lines with neither
+/// letters nor squares, such as `------+------`, are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wordoku {
+    givens: Vec<Option<NormalizedChar>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordokuSolution {
+    cells: Vec<NormalizedChar>,
+    /// Houses spelling a dictionary word, with the word.
+    pub words: Vec<(House, NormalizedWord)>,
+}
+
+impl WordokuSolution {
+    pub fn cell(&self, row: usize, col: usize) -> NormalizedChar {
+        self.cells[row * SIZE + col]
+    }
+
+    pub fn read(&self, house: House) -> NormalizedWord {
+        house.cells().iter().map(|&i| self.cells[i]).collect()
+    }
+}
+
+impl fmt::Display for WordokuSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(SIZE) {
+            for ch in row {
+                write!(f, "{}", ch)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Wordoku {
+    /// The letters already placed.
+    pub fn given_letters(&self) -> CharSet {
+        self.givens.iter().flatten().copied().collect()
+    }
+
+    /// Solves the grid, taking its letters from the givens when all nine
+    /// appear, and otherwise trying each nine-letter word without repeated
+    /// letters that has them all. Returns the first solution where a house
+    /// spells a dictionary word.
+    pub fn solve(&self, dict: &Dictionary) -> Option<WordokuSolution> {
+        self.alphabets(dict).into_iter().find_map(|alphabet| {
+            let mut found = None;
+            self.solutions(alphabet, &mut |cells| {
+                let words: Vec<(House, NormalizedWord)> = House::all()
+                    .map(|house| {
+                        let word = house.cells().iter().map(|&i| cells[i]).collect();
+                        (house, word)
+                    })
+                    .filter(|(_, word)| dict.find(word).is_some())
+                    .collect();
+                if words.is_empty() {
+                    return false;
+                }
+                found = Some(WordokuSolution {
+                    cells: cells.to_vec(),
+                    words,
+                });
+                true
+            });
+            found
+        })
+    }
+
+    /// Calls `visit` with each way of filling the grid from `alphabet`
+    /// until it returns true, returning whether it did.
+    pub fn solutions(
+        &self,
+        alphabet: CharSet,
+        visit: &mut dyn FnMut(&[NormalizedChar]) -> bool,
+    ) -> bool {
+        let mut domains = [alphabet; CELLS];
+        for (i, given) in self.givens.iter().enumerate() {
+            if let Some(ch) = given {
+                if !alphabet.contains(*ch) {
+                    return false;
+                }
+                domains[i] = CharSet::from_iter([*ch]);
+            }
+        }
+        let peers = peers();
+        propagate(&mut domains, &peers) && search(domains, &peers, visit)
+    }
+
+    fn alphabets(&self, dict: &Dictionary) -> Vec<CharSet> {
+        let given = self.given_letters();
+        if given.len() == SIZE {
+            return vec![given];
+        }
+        let mut alphabets: Vec<CharSet> = vec![];
+        for item in dict.candidates(SIZE, &[]) {
+            let letters = item.letter_mask();
+            if letters.len() == SIZE && given.is_subset(&letters) && !alphabets.contains(&letters) {
+                alphabets.push(letters);
+            }
+        }
+        alphabets
+    }
+}
+
+// For each square, the houses it lies in.
+fn peers() -> Vec<[House; 3]> {
+    (0..CELLS)
+        .map(|i| {
+            let (row, col) = (i / SIZE, i % SIZE);
+            [
+                House::Row(row),
+                House::Column(col),
+                House::Box(row / 3 * 3 + col / 3),
+            ]
+        })
+        .collect()
+}
+
+// Removes placed letters from the rest of their houses, and places letters
+// with only one square left in a house, until nothing changes. Returns false
+// if a square or a letter runs out of places.
+fn propagate(domains: &mut [CharSet; CELLS], peers: &[[House; 3]]) -> bool {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..CELLS {
+            if domains[i].len() != 1 {
+                continue;
+            }
+            let placed = domains[i];
+            for house in peers[i] {
+                for j in house.cells() {
+                    if j == i || domains[j].intersection(placed).is_empty() {
+                        continue;
+                    }
+                    let ch = placed.iter().next().unwrap();
+                    domains[j].remove(ch);
+                    if domains[j].is_empty() {
+                        return false;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        for house in House::all() {
+            let cells = house.cells();
+            let letters = cells
+                .iter()
+                .fold(CharSet::new(), |acc, &i| acc.union(domains[i]));
+            if letters.len() < SIZE {
+                return false;
+            }
+            for ch in letters.iter() {
+                let mut places = cells.iter().filter(|&&i| domains[i].contains(ch));
+                if let (Some(&only), None) = (places.next(), places.next()) {
+                    if domains[only].len() > 1 {
+                        domains[only] = CharSet::from_iter([ch]);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+fn search(
+    domains: [CharSet; CELLS],
+    peers: &[[House; 3]],
+    visit: &mut dyn FnMut(&[NormalizedChar]) -> bool,
+) -> bool {
+    let open = (0..CELLS)
+        .filter(|&i| domains[i].len() > 1)
+        .min_by_key(|&i| domains[i].len());
+    let Some(i) = open else {
+        let cells: Vec<NormalizedChar> = domains.iter().map(|x| x.iter().next().unwrap()).collect();
+        return visit(&cells);
+    };
+    for ch in domains[i].iter() {
+        let mut next = domains;
+        next[i] = CharSet::from_iter([ch]);
+        if propagate(&mut next, peers) && search(next, peers, visit) {
+            return true;
+        }
+    }
+    false
+}
+
+impl FromStr for Wordoku {
+    type Err = WordokuError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let mut givens = vec![];
+        let mut rows = 0;
+        for line in str.lines() {
+            let squares: Vec<char> = line
+                .chars()
+                .filter(|ch| !ch.is_whitespace() && *ch != '|')
+                .collect();
+            if !squares
+                .iter()
+                .any(|ch| ch.is_alphabetic() || matches!(ch, '.' | '?'))
+            {
+                continue;
+            }
+            if squares.len() != SIZE {
+                return Err(WordokuError::RaggedRow(rows));
+            }
+            for ch in squares {
+                givens.push(match ch {
+                    '.' | '?' => None,
+                    _ => Some(NormalizedChar::from_char(ch).ok_or(WordokuError::UnknownChar(ch))?),
+                });
+            }
+            rows += 1;
+        }
+        if rows != SIZE {
+            return Err(WordokuError::RowCount(rows));
+        }
+        let wordoku = Wordoku { givens };
+        let letters = wordoku.given_letters();
+        if letters.len() > SIZE {
+            return Err(WordokuError::TooManyLetters(letters));
+        }
+        Ok(wordoku)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "
+        CO..T....
+        N..EIC...
+        .IR....N.
+        R...N...O
+        U..R.O..E
+        T...S...N
+        .N....SR.
+        ...UEI..C
+        ....R..TI";
+
+    const SOLUTION: &str = "\
+        COUNTRIES\nNTSEICOUR\nEIROUSCNT\nRCITNEUSO\nUSNRCOTIE\n\
+        TEOISURCN\nINECOTSRU\nSRTUEINOC\nOUCSRNETI\n";
+
+    #[test]
+    fn solves_and_finds_the_hidden_word() {
+        let dict = Dictionary::from_iter(vec!["countries", "something"]);
+        let wordoku: Wordoku = PUZZLE.parse().unwrap();
+
+        let solution = wordoku.solve(&dict).unwrap();
+        assert_eq!(solution.to_string(), SOLUTION.replace(' ', ""));
+        assert_eq!(
+            solution.words,
+            [(House::Row(0), NormalizedWord::from("countries"))]
+        );
+        assert_eq!(solution.read(House::Box(8)).to_string(), "SRUNOCETI");
+    }
+
+    #[test]
+    fn takes_missing_letters_from_the_dictionary() {
+        let dict = Dictionary::from_iter(vec!["countries", "something"]);
+        let wordoku: Wordoku = PUZZLE.replace('S', ".").parse().unwrap();
+        assert_eq!(wordoku.given_letters().len(), 8);
+
+        let solution = wordoku.solve(&dict).unwrap();
+        assert_eq!(
+            solution.words,
+            [(House::Row(0), NormalizedWord::from("countries"))]
+        );
+        assert!((0..9).all(|col| solution
+            .read(House::Column(col))
+            .iter_chars()
+            .any(|x| *x == NormalizedChar::S)));
+        assert!(wordoku
+            .solve(&Dictionary::from_iter(vec!["something"]))
+            .is_none());
+    }
+
+    #[test]
+    fn parses_text_grids() {
+        let framed = PUZZLE
+            .lines()
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .map(|x| format!("{} | {} | {}", &x[..3], &x[3..6], &x[6..]))
+            .collect::<Vec<_>>()
+            .join("\n------+-------+------\n");
+        assert_eq!(framed.parse::<Wordoku>(), PUZZLE.parse());
+
+        assert_eq!("ABC".parse::<Wordoku>(), Err(WordokuError::RaggedRow(0)));
+        assert_eq!(
+            "ABCDEFGHI\n".parse::<Wordoku>(),
+            Err(WordokuError::RowCount(1))
+        );
+        assert!(matches!(
+            "ABCDEFGHI\nJ........\n".repeat(5).parse::<Wordoku>(),
+            Err(WordokuError::RowCount(10))
+        ));
+        let too_many = format!("ABCDEFGHI\nJ........\n{}", ".........\n".repeat(7));
+        assert!(matches!(
+            too_many.parse::<Wordoku>(),
+            Err(WordokuError::TooManyLetters(_))
+        ));
+        assert_eq!(
+            PUZZLE.replace('C', "1").parse::<Wordoku>(),
+            Err(WordokuError::UnknownChar('1'))
+        );
+    }
+}
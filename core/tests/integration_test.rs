@@ -2,7 +2,10 @@
 extern crate lazy_static;
 use std::fs::File;
 
-use wordplay_core::dictionary::{DictSearch, Dictionary};
+use wordplay_core::{
+    dictionary::{DictSearch, Dictionary},
+    scrabble::{Board, Direction},
+};
 
 #[cfg(test)]
 mod enable_tests {
@@ -10,7 +13,7 @@ mod enable_tests {
 
     lazy_static! {
         static ref ENABLE: Dictionary = {
-            let file = File::open("data/enable.txt").unwrap();
+            let file = File::open("../data/enable.txt").unwrap();
             Dictionary::from_file(file)
         };
     }
@@ -21,9 +24,17 @@ mod enable_tests {
         assert_eq!(dict_size, 172820)
     }
 
+    #[test]
+    fn count_agrees_with_iteration() {
+        let search = || DictSearch::from_pattern("?ana??").unwrap();
+
+        assert_eq!(ENABLE.count(search()), ENABLE.iter_search(search()).count());
+        assert_eq!(ENABLE.count(Default::default()), 172820);
+    }
+
     #[test]
     fn find_matching_words() {
-        let search = DictSearch::from_pattern("?ana??");
+        let search = DictSearch::from_pattern("?ana??").unwrap();
 
         let mut iter = ENABLE.iter_search(search).map(|x| &x.original[..]);
 
@@ -36,4 +47,59 @@ mod enable_tests {
             assert!(x.anag_num.is_some());
         }
     }
+
+    #[test]
+    fn scrabble_moves_include_bingos() {
+        let mut board = Board::standard();
+        board.place_word(7, 5, Direction::Across, "quiz");
+
+        let moves = board.moves(&ENABLE, &"retains".into());
+
+        let best = &moves[0];
+        assert_eq!(best.placed.len(), 7);
+        assert!(best.score >= 50);
+        assert!(moves.windows(2).all(|w| w[0].score >= w[1].score));
+        assert!(moves.iter().all(|m| ENABLE.find(&m.word).is_some()));
+    }
+
+    #[test]
+    fn concurrent_searches_agree() {
+        let patterns = ["?ana??", "c?t", "??????e", "q", "z?z"];
+        let expected: Vec<usize> = patterns
+            .iter()
+            .map(|p| {
+                ENABLE
+                    .iter_search(DictSearch::from_pattern(p).unwrap())
+                    .count()
+            })
+            .collect();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..5 {
+                        for (pattern, &count) in patterns.iter().zip(expected.iter()) {
+                            let search = DictSearch::from_pattern(pattern).unwrap();
+                            assert_eq!(ENABLE.iter_search(search).count(), count);
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_load_matches_blocking_load() {
+        let file = tokio::fs::File::open("../data/enable.txt").await.unwrap();
+        let dict = Dictionary::from_async_reader(tokio::io::BufReader::new(file))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            dict.count(Default::default()),
+            ENABLE.count(Default::default())
+        );
+        assert!(dict.anagrams("listen").eq(ENABLE.anagrams("listen")));
+    }
 }
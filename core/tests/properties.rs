@@ -0,0 +1,197 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use proptest::prelude::*;
+use wordplay_core::{
+    anagram_number::{AnagramComparison, AnagramNumber},
+    char_freq::{CharFreq, CharFreqComparisonResult},
+    char_match::CharMatch,
+    char_set::CharSet,
+    dictionary::{DictSearch, Dictionary, WordPredicate},
+    fuzzy::FuzzyMatcher,
+    normalized_word::{NormalizedChar, NormalizedWord},
+    trie::{TriePrefix, TrieSearch},
+};
+
+fn normalized_char() -> impl Strategy<Value = NormalizedChar> {
+    // weighted towards a few letters so that shared prefixes and anagrams are common
+    prop_oneof![
+        3 => prop::sample::select(vec!['a', 'e', 's', 't']),
+        1 => prop::char::range('a', 'z'),
+    ]
+    .prop_map(|ch| NormalizedChar::from_char(ch).unwrap())
+}
+
+fn normalized_word(max_len: usize) -> impl Strategy<Value = NormalizedWord> {
+    prop::collection::vec(normalized_char(), 0..=max_len).prop_map(NormalizedWord::new)
+}
+
+fn char_freq() -> impl Strategy<Value = CharFreq> {
+    normalized_word(8).prop_map(|word| CharFreq::from(&word))
+}
+
+fn word_set() -> impl Strategy<Value = BTreeSet<String>> {
+    prop::collection::btree_set(
+        normalized_word(8).prop_map(|word| word.to_string().to_lowercase()),
+        0..60,
+    )
+}
+
+fn pattern() -> impl Strategy<Value = Vec<CharMatch>> {
+    prop::collection::vec(
+        prop_oneof![
+            1 => Just(CharMatch::Any),
+            3 => normalized_char().prop_map(CharMatch::Only),
+            1 => prop::collection::vec(normalized_char(), 1..3)
+                .prop_map(|chars| CharMatch::Not(chars.into_iter().collect())),
+        ],
+        0..4,
+    )
+}
+
+fn edit_distance(a: &NormalizedWord, b: &NormalizedWord) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter_chars().enumerate() {
+        let mut next = vec![i + 1];
+        for (j, y) in b.iter_chars().enumerate() {
+            next.push(
+                (row[j] + usize::from(x != y))
+                    .min(row[j + 1] + 1)
+                    .min(next[j] + 1),
+            );
+        }
+        row = next;
+    }
+    row[b.len()]
+}
+
+fn matches_prefix(word: &NormalizedWord, prefix: &[CharMatch]) -> bool {
+    word.len() >= prefix.len()
+        && prefix
+            .iter()
+            .zip(word.iter_chars())
+            .all(|(m, &ch)| match m {
+                CharMatch::Any => true,
+                &CharMatch::Only(x) => x == ch,
+                CharMatch::Not(excluded) => !excluded.contains(ch),
+            })
+}
+
+proptest! {
+    #[test]
+    fn get_returns_everything_added(words in word_set()) {
+        let dict = Dictionary::from_iter(words.iter().map(|w| &w[..]));
+
+        for word in words.iter() {
+            let entries = dict.find(&NormalizedWord::from(&word[..]));
+            prop_assert!(entries.is_some_and(|e| e.iter().any(|x| &x.original == word)));
+        }
+    }
+
+    #[test]
+    fn iter_is_sorted_and_complete(words in word_set()) {
+        let dict = Dictionary::from_iter(words.iter().map(|w| &w[..]));
+
+        let iterated: Vec<String> = dict.iter().map(|x| x.original.clone()).collect();
+        let expected: Vec<String> = words.into_iter().collect();
+        prop_assert_eq!(iterated, expected);
+    }
+
+    #[test]
+    fn iter_search_agrees_with_filter(words in word_set(), prefix in pattern()) {
+        let dict = Dictionary::from_iter(words.iter().map(|w| &w[..]));
+        let search = || DictSearch::new(
+            Some(TrieSearch::new(TriePrefix::new(prefix.clone()), None)),
+            WordPredicate::None,
+        );
+
+        let searched: Vec<_> = dict.iter_search(search()).map(|x| x.normalized).collect();
+        let filtered: Vec<_> = dict
+            .iter()
+            .map(|x| x.normalized)
+            .filter(|w| matches_prefix(w, &prefix))
+            .collect();
+        prop_assert_eq!(dict.count(search()), searched.len());
+        prop_assert_eq!(searched, filtered);
+    }
+
+    #[test]
+    fn containing_search_agrees_with_filter(
+        words in word_set(),
+        letters in normalized_word(3),
+        max in 0..8usize,
+    ) {
+        let dict = Dictionary::from_iter(words.iter().map(|w| &w[..]));
+        let letters: CharSet = letters.iter_chars().copied().collect();
+        let search = || DictSearch::new(
+            Some(TrieSearch::default().with_max(max).containing(letters)),
+            WordPredicate::None,
+        );
+
+        let searched: Vec<_> = dict.iter_search(search()).map(|x| x.normalized).collect();
+        let filtered: Vec<_> = dict
+            .iter()
+            .map(|x| x.normalized)
+            .filter(|w| w.len() <= max && letters.iter().all(|ch| w.iter_chars().any(|&c| c == ch)))
+            .collect();
+        prop_assert_eq!(dict.count(search()), searched.len());
+        prop_assert_eq!(searched, filtered);
+    }
+
+    #[test]
+    fn fuzzy_matcher_agrees_with_edit_distance(
+        words in word_set(),
+        query in normalized_word(6),
+        max in 0usize..3,
+    ) {
+        let dict = Dictionary::from_iter(words.iter().map(|w| &w[..]));
+        let matcher = FuzzyMatcher::new(&query.to_string(), max);
+
+        let found: Vec<(String, usize)> = matcher
+            .search(&dict)
+            .into_iter()
+            .map(|(x, distance)| (x.original.clone(), distance))
+            .collect();
+        let expected: Vec<(String, usize)> = dict
+            .iter()
+            .map(|x| (x.original.clone(), edit_distance(&x.normalized, &query)))
+            .filter(|&(_, distance)| distance <= max)
+            .collect();
+        prop_assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn anagram_index_agrees_with_predicate(words in word_set(), letters in normalized_word(6)) {
+        let dict = Dictionary::from_iter(words.iter().map(|w| &w[..]));
+        let letters = letters.to_string();
+
+        let indexed: Vec<_> = dict.anagrams(&letters).map(|x| x.original.clone()).collect();
+        let searched: Vec<_> = dict
+            .iter_search(DictSearch::anagram_of(&letters))
+            .map(|x| x.original.clone())
+            .collect();
+        prop_assert_eq!(indexed, searched);
+    }
+
+    #[test]
+    fn anagram_number_comparison_agrees_with_char_freq(a in char_freq(), b in char_freq()) {
+        let word = |freq: &CharFreq| {
+            let mut word = NormalizedWord::default();
+            for ch in NormalizedChar::all() {
+                for _ in 0..freq.get(ch) {
+                    word.push(ch);
+                }
+            }
+            AnagramNumber::try_from(&word).unwrap()
+        };
+
+        // AnagramNumber describes `b` relative to `a`; CharFreq describes `a` relative to `b`
+        let expected = match a.clone().compare(&b) {
+            CharFreqComparisonResult::Same => AnagramComparison::Exact,
+            CharFreqComparisonResult::Unrelated => AnagramComparison::Unrelated,
+            CharFreqComparisonResult::Subset { .. } => AnagramComparison::Superset,
+            CharFreqComparisonResult::Superset { .. } => AnagramComparison::Subset,
+        };
+        prop_assert_eq!(word(&a).compare(word(&b)), expected);
+    }
+}
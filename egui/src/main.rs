@@ -0,0 +1,107 @@
+//! A search box whose results fill in as a `SearchHandle` finds them,
+//! polled once a frame so the window never blocks on a search. Typing
+//! again cancels the running search and starts another.
+//!
+//! Run with `cargo run -p wordplay-egui`.
+
+use std::fs::File;
+
+use eframe::egui;
+use wordplay_core::dictionary::{ArcDictionary, Dictionary};
+use wordplay_core::query::DictQuery;
+use wordplay_core::search_handle::{SearchHandle, SearchState};
+
+const BATCH: usize = 200;
+
+fn main() -> eframe::Result {
+    let dict = ArcDictionary::new(Dictionary::from_file(
+        File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/../data/enable.txt")).unwrap(),
+    ));
+    eframe::run_native(
+        "wordplay search",
+        eframe::NativeOptions::default(),
+        Box::new(|_| Ok(Box::new(SearchApp::new(dict)))),
+    )
+}
+
+struct SearchApp {
+    dict: ArcDictionary,
+    letters: String,
+    search: Option<SearchHandle>,
+    results: Vec<String>,
+}
+
+impl SearchApp {
+    fn new(dict: ArcDictionary) -> Self {
+        SearchApp {
+            dict,
+            letters: String::new(),
+            search: None,
+            results: vec![],
+        }
+    }
+
+    // Replaces the running search, dropping the old handle to cancel it.
+    fn restart(&mut self) {
+        self.results.clear();
+        self.search = (!self.letters.trim().is_empty()).then(|| {
+            let query = DictQuery::new().contains(&self.letters);
+            SearchHandle::spawn(self.dict.clone(), query)
+        });
+    }
+
+    fn status(&self) -> String {
+        let Some(search) = &self.search else {
+            return "Type letters to find words containing them".to_string();
+        };
+        let state = match search.state() {
+            SearchState::Running => "searching",
+            SearchState::Complete => "complete",
+            SearchState::Cancelled => "cancelled",
+        };
+        format!(
+            "{} words in {:?} ({})",
+            self.results.len(),
+            search.elapsed(),
+            state
+        )
+    }
+}
+
+impl eframe::App for SearchApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(search) = &mut self.search {
+            let batch = search.poll(BATCH);
+            self.results.extend(batch.into_iter().map(|x| x.original));
+            if !search.is_finished() {
+                ctx.request_repaint();
+            }
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Contains:");
+                if ui.text_edit_singleline(&mut self.letters).changed() {
+                    self.restart();
+                }
+                let running = self.search.as_ref().is_some_and(|x| !x.is_finished());
+                if ui
+                    .add_enabled(running, egui::Button::new("Cancel"))
+                    .clicked()
+                {
+                    if let Some(search) = &self.search {
+                        search.cancel();
+                    }
+                }
+            });
+            ui.label(self.status());
+            ui.separator();
+            let height = ui.text_style_height(&egui::TextStyle::Body);
+            egui::ScrollArea::vertical().show_rows(ui, height, self.results.len(), |ui, rows| {
+                for word in &self.results[rows] {
+                    ui.label(word);
+                }
+            });
+        });
+    }
+}
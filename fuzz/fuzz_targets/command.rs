@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The CLI is a binary crate, so its parser is compiled in directly.
+#[allow(dead_code)]
+#[path = "../../cli/src/command.rs"]
+mod command;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(line) = std::str::from_utf8(data) {
+        let _ = command::parse_line(line);
+    }
+});
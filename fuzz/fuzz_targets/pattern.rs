@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wordplay_core::{
+    dictionary::{DictSearch, Dictionary},
+    query::DictQuery,
+    trie::TriePrefix,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(pattern) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(prefix) = TriePrefix::from_pattern(pattern) {
+        assert_eq!(prefix.len(), pattern.chars().count());
+    }
+
+    let dict = Dictionary::from_iter(vec!["cat", "cart", "banana", "a"]);
+    if let Ok(search) = DictSearch::from_pattern(pattern) {
+        dict.iter_search(search).count();
+    }
+    dict.query(&DictQuery::new().prefix(pattern));
+});
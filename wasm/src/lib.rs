@@ -0,0 +1,150 @@
+use wasm_bindgen::prelude::*;
+use wordplay_core::{
+    dictionary::{DictIterItem, Dictionary, Sort, SortAspect, SortDirection},
+    normalized_word::NormalizedWord,
+    query::DictQuery,
+};
+
+/// A search result, with plain fields so it reads naturally from JS.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct WordResult {
+    pub word: String,
+    pub normalized: String,
+    pub length: usize,
+    pub score: Option<u32>,
+}
+
+impl<'a> From<DictIterItem<'a>> for WordResult {
+    fn from(item: DictIterItem<'a>) -> Self {
+        WordResult {
+            word: item.original.clone(),
+            normalized: item.normalized.to_string(),
+            length: item.normalized.len(),
+            score: item.score,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = Dictionary)]
+pub struct WasmDictionary {
+    dict: Dictionary,
+}
+
+#[wasm_bindgen(js_class = Dictionary)]
+impl WasmDictionary {
+    /// Loads a newline-separated word list, e.g. the bytes of a fetched file.
+    #[wasm_bindgen(constructor)]
+    pub fn new(words: &[u8]) -> WasmDictionary {
+        WasmDictionary {
+            dict: Dictionary::from_bytes(words),
+        }
+    }
+
+    pub fn has(&self, word: &str) -> bool {
+        self.dict
+            .find(&NormalizedWord::from_str_safe(word))
+            .is_some()
+    }
+
+    /// Words matching a pattern such as `c?t`, where `?` is any letter.
+    /// Throws where and why a pattern does not parse.
+    pub fn pattern(&self, pattern: &str, limit: usize) -> Result<Vec<WordResult>, String> {
+        let query = DictQuery::new().try_pattern(pattern);
+        Ok(self.run(query.map_err(|err| err.to_string())?.limit(limit)))
+    }
+
+    #[wasm_bindgen(js_name = startingWith)]
+    pub fn starting_with(&self, prefix: &str, limit: usize) -> Result<Vec<WordResult>, String> {
+        let query = DictQuery::new().try_prefix(prefix);
+        Ok(self.run(query.map_err(|err| err.to_string())?.limit(limit)))
+    }
+
+    pub fn anagrams(&self, letters: &str, limit: usize) -> Vec<WordResult> {
+        self.dict
+            .anagrams(letters)
+            .take(limit)
+            .map(WordResult::from)
+            .collect()
+    }
+
+    /// Words formable from some of the letters, longest first.
+    pub fn subanagrams(&self, letters: &str, limit: usize) -> Vec<WordResult> {
+        let sort = Sort(SortAspect::Length, SortDirection::Descending);
+        self.run(
+            DictQuery::new()
+                .formable_from(letters)
+                .sort(sort)
+                .limit(limit),
+        )
+    }
+
+    /// Words using all of the letters plus others, shortest first.
+    pub fn superanagrams(&self, letters: &str, limit: usize) -> Vec<WordResult> {
+        let sort = Sort(SortAspect::Length, SortDirection::Ascending);
+        self.run(
+            DictQuery::new()
+                .containing_letters(letters)
+                .sort(sort)
+                .limit(limit),
+        )
+    }
+
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<WordResult> {
+        self.dict
+            .complete(prefix, limit)
+            .into_iter()
+            .map(WordResult::from)
+            .collect()
+    }
+
+    fn run(&self, query: DictQuery) -> Vec<WordResult> {
+        self.dict
+            .query(&query)
+            .into_iter()
+            .map(WordResult::from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(results: Vec<WordResult>) -> Vec<String> {
+        results.into_iter().map(|r| r.word).collect()
+    }
+
+    #[test]
+    fn loads_from_bytes() {
+        let dict = WasmDictionary::new(b"cat\r\nact\n\ntac\ndog\n");
+
+        assert!(dict.has("CAT"));
+        assert!(!dict.has("cow"));
+        assert_eq!(words(dict.anagrams("tca", 10)), vec!["act", "cat", "tac"]);
+    }
+
+    #[test]
+    fn searches_return_plain_results() {
+        let dict = WasmDictionary::new(b"cat\ncart\ncoat\ndog");
+
+        assert_eq!(
+            words(dict.pattern("c??t", 10).unwrap()),
+            vec!["cart", "coat"]
+        );
+        assert_eq!(
+            dict.pattern("c*t", 10),
+            Err("unexpected '*' at character 2; use ? for any one letter".to_string())
+        );
+        assert_eq!(
+            dict.starting_with("ca", 1).unwrap(),
+            vec![WordResult {
+                word: "cart".to_string(),
+                normalized: "CART".to_string(),
+                length: 4,
+                score: None,
+            }]
+        );
+        assert_eq!(words(dict.subanagrams("tacr", 10)), vec!["cart", "cat"]);
+    }
+}